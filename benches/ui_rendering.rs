@@ -39,6 +39,8 @@ fn create_mock_commits(count: usize) -> Vec<GitCommit> {
                 vec![format!("commit_{:08x}", i - 1)]
             },
             tree_id: format!("tree_{:08x}", i),
+            is_signed: false,
+            notes: vec![],
         });
     }
 
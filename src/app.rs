@@ -1,13 +1,42 @@
-use crate::git::GitRepository;
+use crate::git::{
+    CommitExportConfig, CommitExportField, CommitExportFormat, CommitExportJob, GitRepository,
+    WATCH_DEBOUNCE, WatchChanges,
+};
 use crate::state::{AppConfig, AppState};
 use crate::ui::MainWindow;
 use eframe::egui;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 pub struct GitkApp {
-    state: AppState,
+    state: AppState, // The active tab's session
     config: AppConfig,
     main_window: MainWindow,
+    tabs: Vec<RepositoryTab>, // The active entry's session is parked in `state`/`main_window`
+    active_tab: usize,
+    next_tab_id: u64,
+    last_auto_refresh: Instant,
+    show_commit_export_dialog: bool,
+    commit_export_config: CommitExportConfig, // Kept between exports
+}
+
+/// One open repository with its own state, views and graph caches
+struct RepositoryTab {
+    id: u64,               // Keeps each tab's widget state, e.g. scroll offsets, apart
+    path: Option<PathBuf>, // As opened; restored tabs load on first activation
+    state: AppState,
+    main_window: MainWindow,
+}
+
+impl RepositoryTab {
+    fn new(id: u64, path: Option<PathBuf>) -> Self {
+        Self {
+            id,
+            path,
+            state: AppState::new(),
+            main_window: MainWindow::new(),
+        }
+    }
 }
 
 impl GitkApp {
@@ -16,17 +45,75 @@ impl GitkApp {
         let state = AppState::new();
         let main_window = MainWindow::new();
 
-        Self {
+        let mut app = Self {
             state,
             config,
             main_window,
+            tabs: vec![RepositoryTab::new(0, None)],
+            active_tab: 0,
+            next_tab_id: 1,
+            last_auto_refresh: Instant::now(),
+            show_commit_export_dialog: false,
+            commit_export_config: CommitExportConfig::default(),
+        };
+        app.restore_tabs();
+        app
+    }
+
+    /// Reopen the recent repositories in tabs, most recently used first and
+    /// active; the others load on first activation
+    fn restore_tabs(&mut self) {
+        let paths: Vec<PathBuf> = self
+            .config
+            .recent_repositories
+            .iter()
+            .filter(|path| path.exists())
+            .cloned()
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        self.tabs = paths
+            .into_iter()
+            .enumerate()
+            .map(|(index, path)| RepositoryTab::new(index as u64, Some(path)))
+            .collect();
+        self.next_tab_id = self.tabs.len() as u64;
+        self.active_tab = 0;
+
+        if let Some(path) = self.tabs[0].path.clone() {
+            self.load_repository(path);
         }
     }
 
+    /// Open a repository, switching to its tab if it is already open and
+    /// adding a new tab unless the current one is empty
     fn open_repository(&mut self, path: PathBuf) {
+        if let Some(index) = self
+            .tabs
+            .iter()
+            .position(|tab| tab.path.as_ref() == Some(&path))
+        {
+            self.switch_tab(index);
+            return;
+        }
+
+        if self.state.has_repository() {
+            self.add_tab();
+        }
+        self.load_repository(path);
+    }
+
+    /// Load a repository into the active tab
+    fn load_repository(&mut self, path: PathBuf) {
         match GitRepository::discover(&path) {
             Ok(repo) => {
-                self.config.add_recent_repository(path);
+                self.config.add_recent_repository(path.clone());
+                self.tabs[self.active_tab].path = Some(path);
+                self.state.notes_refs = self.config.notes_refs.clone();
+                self.state.start_commit = None;
+                self.state.parent_sessions.clear();
                 self.state.set_repository(repo);
                 let _ = self.config.save();
             }
@@ -36,6 +123,165 @@ impl GitkApp {
         }
     }
 
+    /// Pick a .patch or .diff file and show it in the active tab
+    fn open_patch_file(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Patches", &["patch", "diff"])
+            .add_filter("All files", &["*"])
+            .pick_file()
+        {
+            self.state.open_patch_file(&path);
+        }
+    }
+
+    fn add_tab(&mut self) {
+        self.tabs.push(RepositoryTab::new(self.next_tab_id, None));
+        self.next_tab_id += 1;
+        self.switch_tab(self.tabs.len() - 1);
+    }
+
+    fn switch_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+
+        self.swap_active_tab();
+        self.active_tab = index;
+        self.swap_active_tab();
+
+        // Tabs restored from the last session load when first shown; the
+        // active tab's repository moves to the front of the recent list
+        if let Some(path) = self.tabs[index].path.clone() {
+            if self.state.has_repository() {
+                self.config.add_recent_repository(path);
+                let _ = self.config.save();
+            } else {
+                self.load_repository(path);
+            }
+        }
+    }
+
+    /// Exchange the active session with the one parked in its tab entry
+    fn swap_active_tab(&mut self) {
+        let tab = &mut self.tabs[self.active_tab];
+        std::mem::swap(&mut self.state, &mut tab.state);
+        std::mem::swap(&mut self.main_window, &mut tab.main_window);
+    }
+
+    /// Close a tab; its repository leaves the recent list so it isn't
+    /// reopened on the next start
+    fn close_tab(&mut self, index: usize) {
+        if index >= self.tabs.len() {
+            return;
+        }
+
+        if let Some(ref path) = self.tabs[index].path {
+            self.config.recent_repositories.retain(|p| p != path);
+            let _ = self.config.save();
+        }
+
+        if self.tabs.len() == 1 {
+            // The last tab is emptied rather than removed
+            self.state = AppState::new();
+            self.main_window = MainWindow::new();
+            self.tabs[0].path = None;
+        } else {
+            if index == self.active_tab {
+                let next = if index + 1 < self.tabs.len() {
+                    index + 1
+                } else {
+                    index - 1
+                };
+                self.switch_tab(next);
+            }
+            self.tabs.remove(index);
+            if index < self.active_tab {
+                self.active_tab -= 1;
+            }
+        }
+    }
+
+    fn tab_title(&self, index: usize) -> String {
+        let tab = &self.tabs[index];
+        let state = if index == self.active_tab {
+            &self.state
+        } else {
+            &tab.state
+        };
+
+        state
+            .repository_info()
+            .map(|info| info.name.clone())
+            .or_else(|| {
+                tab.path
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .or_else(|| {
+                state
+                    .opened_patch
+                    .as_ref()
+                    .and_then(|patch| patch.path.file_name())
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| "New tab".to_string())
+    }
+
+    fn show_tab_bar(&mut self, ctx: &egui::Context) {
+        if self.tabs.len() < 2 && !self.state.has_repository() {
+            return;
+        }
+
+        let mut switch_to = None;
+        let mut close = None;
+        let mut open = None;
+
+        egui::TopBottomPanel::top("tab_bar").show(ctx, |ui| {
+            egui::ScrollArea::horizontal().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for index in 0..self.tabs.len() {
+                        let title = self.tab_title(index);
+                        let hover = self.tabs[index]
+                            .path
+                            .as_ref()
+                            .map_or_else(|| title.clone(), |path| path.display().to_string());
+
+                        if ui
+                            .selectable_label(index == self.active_tab, &title)
+                            .on_hover_text(hover)
+                            .clicked()
+                        {
+                            switch_to = Some(index);
+                        }
+                        if ui.small_button("×").on_hover_text("Close tab").clicked() {
+                            close = Some(index);
+                        }
+                        ui.separator();
+                    }
+
+                    if ui
+                        .small_button("+")
+                        .on_hover_text("Open a repository in a new tab")
+                        .clicked()
+                    {
+                        open = rfd::FileDialog::new().pick_folder();
+                    }
+                });
+            });
+        });
+
+        if let Some(index) = switch_to {
+            self.switch_tab(index);
+        }
+        if let Some(index) = close {
+            self.close_tab(index);
+        }
+        if let Some(path) = open {
+            self.open_repository(path);
+        }
+    }
+
     fn show_menu_bar(&mut self, ctx: &egui::Context) {
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
@@ -51,6 +297,50 @@ impl GitkApp {
                         // Will be implemented in main_window
                     }
 
+                    if ui
+                        .button("Open Patch...")
+                        .on_hover_text("View a .patch or .diff file")
+                        .clicked()
+                    {
+                        self.open_patch_file();
+                        ui.close();
+                    }
+
+                    if ui
+                        .add_enabled(
+                            self.state.has_repository(),
+                            egui::Button::new("Apply Patches..."),
+                        )
+                        .on_hover_text("Preview and apply an mbox or patch file")
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Patches", &["patch", "diff", "mbox", "eml"])
+                            .add_filter("All files", &["*"])
+                            .pick_file()
+                        {
+                            self.state.load_patches(&path);
+                        }
+                        ui.close();
+                    }
+
+                    if ui
+                        .add_enabled(
+                            self.state.has_repository(),
+                            egui::Button::new("Export Commits..."),
+                        )
+                        .on_hover_text("Save the commits in the current view as CSV or JSON")
+                        .clicked()
+                    {
+                        self.show_commit_export_dialog = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Close Tab").clicked() {
+                        self.close_tab(self.active_tab);
+                        ui.close();
+                    }
+
                     ui.separator();
 
                     if ui.button("Quit").clicked() {
@@ -72,6 +362,14 @@ impl GitkApp {
                     if ui.button("Refresh").clicked() {
                         self.state.start_streaming_commits();
                     }
+                    if ui
+                        .add_enabled(self.state.has_repository(), egui::Button::new("Statistics"))
+                        .on_hover_text("Authors, churn and activity of the current view")
+                        .clicked()
+                    {
+                        self.main_window.open_statistics(&mut self.state);
+                        ui.close();
+                    }
                     ui.separator();
                     if ui.button("Settings").clicked() {
                         self.state.show_settings_dialog = true;
@@ -105,10 +403,34 @@ impl GitkApp {
                     }
                     ui.separator();
                     ui.label(format!("Commits: {}", self.state.commits.len()));
+                    if let Some(status) = self.state.working_tree_status {
+                        ui.separator();
+                        if status.is_clean() {
+                            ui.weak("Working tree clean");
+                        } else {
+                            let mut parts = Vec::new();
+                            for (count, label) in [
+                                (status.conflicted, "conflicted"),
+                                (status.staged, "staged"),
+                                (status.unstaged, "modified"),
+                                (status.untracked, "untracked"),
+                            ] {
+                                if count > 0 {
+                                    parts.push(format!("{count} {label}"));
+                                }
+                            }
+                            ui.label(format!("Local changes: {}", parts.join(", ")));
+                        }
+                    }
                 } else {
                     ui.label("No repository opened");
                 }
 
+                if let Some(ref message) = self.state.status_message {
+                    ui.separator();
+                    ui.weak(message);
+                }
+
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if self.state.loading {
                         ui.spinner();
@@ -144,22 +466,44 @@ impl eframe::App for GitkApp {
         // Update window size in config for persistence
         self.update_window_size(frame);
 
-        // Poll commit stream for new commits
-        if self.state.poll_commit_stream() {
-            ctx.request_repaint(); // Request repaint when new commits arrive
+        // Poll every tab's background jobs so hidden tabs keep loading
+        if self.state.poll_background_jobs() {
+            ctx.request_repaint();
+        }
+        for (index, tab) in self.tabs.iter_mut().enumerate() {
+            if index != self.active_tab && tab.state.poll_background_jobs() {
+                ctx.request_repaint();
+            }
         }
 
-        // Continue polling if we're still streaming
-        if self.state.is_streaming() {
+        // Refresh after other programs change refs, the index or the worktree
+        if self.state.poll_repository_changes() {
+            ctx.request_repaint();
+        }
+        if self.state.is_watching() {
+            ctx.request_repaint_after(WATCH_DEBOUNCE);
+        }
+        self.auto_refresh(ctx);
+
+        // Continue polling while any tab is still streaming or searching
+        if self.state.has_background_jobs()
+            || self
+                .tabs
+                .iter()
+                .enumerate()
+                .any(|(index, tab)| index != self.active_tab && tab.state.has_background_jobs())
+        {
             ctx.request_repaint_after(std::time::Duration::from_millis(16)); // ~60 FPS
         }
 
         self.show_menu_bar(ctx);
+        self.show_tab_bar(ctx);
         self.show_status_bar(ctx);
         self.show_error_dialog(ctx);
         self.show_shortcuts_dialog(ctx);
         self.show_about_dialog(ctx);
         self.show_settings_dialog(ctx);
+        self.show_commit_export_dialog(ctx);
 
         // Show modal dialogs
         if self.state.has_repository() {
@@ -169,7 +513,14 @@ impl eframe::App for GitkApp {
         // Main content area
         egui::CentralPanel::default().show(ctx, |ui| {
             if self.state.has_repository() {
-                self.main_window.show(ui, &mut self.state, &self.config);
+                ui.push_id(self.tabs[self.active_tab].id, |ui| {
+                    self.main_window.show(ui, &mut self.state, &self.config);
+                });
+            } else if self.state.opened_patch.is_some() {
+                ui.push_id(self.tabs[self.active_tab].id, |ui| {
+                    self.main_window
+                        .show_patch_viewer(ui, &mut self.state, &self.config);
+                });
             } else {
                 self.show_welcome_screen(ui);
             }
@@ -182,6 +533,27 @@ impl eframe::App for GitkApp {
 }
 
 impl GitkApp {
+    /// Periodic full refresh for filesystems where change notifications
+    /// don't arrive, e.g. network mounts
+    fn auto_refresh(&mut self, ctx: &egui::Context) {
+        let Some(seconds) = self.config.auto_refresh_interval else {
+            return;
+        };
+        let interval = Duration::from_secs(seconds.max(1));
+
+        if self.last_auto_refresh.elapsed() >= interval {
+            self.last_auto_refresh = Instant::now();
+            if self.state.has_repository() && !self.state.is_streaming() {
+                self.state.apply_repository_changes(WatchChanges {
+                    refs: true,
+                    index: true,
+                    worktree: true,
+                });
+            }
+        }
+        ctx.request_repaint_after(interval.saturating_sub(self.last_auto_refresh.elapsed()));
+    }
+
     fn handle_keyboard_shortcuts(&mut self, ctx: &egui::Context) {
         // Global shortcuts
         if ctx.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::O)) {
@@ -196,6 +568,43 @@ impl GitkApp {
             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
         }
 
+        // Tab shortcuts; Ctrl+Shift+Tab must be checked before Ctrl+Tab
+        let tab_count = self.tabs.len();
+        if ctx.input_mut(|i| {
+            i.consume_key(
+                egui::Modifiers::CTRL | egui::Modifiers::SHIFT,
+                egui::Key::Tab,
+            ) || i.consume_key(egui::Modifiers::CTRL, egui::Key::PageUp)
+        }) {
+            self.switch_tab((self.active_tab + tab_count - 1) % tab_count);
+        } else if ctx.input_mut(|i| {
+            i.consume_key(egui::Modifiers::CTRL, egui::Key::Tab)
+                || i.consume_key(egui::Modifiers::CTRL, egui::Key::PageDown)
+        }) {
+            self.switch_tab((self.active_tab + 1) % tab_count);
+        }
+
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::W)) {
+            self.close_tab(self.active_tab);
+        }
+
+        let tab_keys = [
+            egui::Key::Num1,
+            egui::Key::Num2,
+            egui::Key::Num3,
+            egui::Key::Num4,
+            egui::Key::Num5,
+            egui::Key::Num6,
+            egui::Key::Num7,
+            egui::Key::Num8,
+            egui::Key::Num9,
+        ];
+        for (index, key) in tab_keys.into_iter().enumerate() {
+            if ctx.input_mut(|i| i.consume_key(egui::Modifiers::ALT, key)) {
+                self.switch_tab(index);
+            }
+        }
+
         if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F5)) {
             // Refresh repository
             if self.state.has_repository() {
@@ -278,6 +687,14 @@ impl GitkApp {
                     ui.label("F5: Refresh");
                     ui.label("Ctrl+F: Focus Search");
 
+                    ui.add_space(10.0);
+                    ui.heading("Tabs");
+                    ui.separator();
+                    ui.label("Ctrl+Tab / Ctrl+Page Down: Next tab");
+                    ui.label("Ctrl+Shift+Tab / Ctrl+Page Up: Previous tab");
+                    ui.label("Alt+1..9: Go to tab");
+                    ui.label("Ctrl+W: Close tab");
+
                     ui.add_space(10.0);
                     ui.heading("Navigation");
                     ui.separator();
@@ -329,6 +746,107 @@ impl GitkApp {
         }
     }
 
+    fn show_commit_export_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_commit_export_dialog || !self.state.has_repository() {
+            return;
+        }
+
+        let commit_count = self.state.get_filtered_commits().len();
+        let mut keep_open = true;
+        let mut target = None;
+
+        egui::Window::new("Export Commits")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{commit_count} commits in the current view"));
+                ui.separator();
+
+                ui.label("Fields:");
+                let fields = &mut self.commit_export_config.fields;
+                egui::Grid::new("commit_export_fields")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (index, field) in CommitExportField::ALL.into_iter().enumerate() {
+                            let mut selected = fields.contains(&field);
+                            if ui.checkbox(&mut selected, field.name()).changed() {
+                                if selected {
+                                    fields.push(field);
+                                    // Keep columns in the canonical order
+                                    fields.sort_by_key(|field| {
+                                        CommitExportField::ALL.iter().position(|f| f == field)
+                                    });
+                                } else {
+                                    fields.retain(|f| *f != field);
+                                }
+                            }
+                            if index % 3 == 2 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    let format = &mut self.commit_export_config.format;
+                    ui.radio_value(format, CommitExportFormat::Csv, "CSV");
+                    ui.radio_value(format, CommitExportFormat::Json, "JSON");
+                });
+
+                ui.separator();
+
+                if let Some(ref job) = self.state.commit_export_job {
+                    if !job.is_complete() {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(format!(
+                                "Exporting {}/{} commits",
+                                job.scanned_count(),
+                                job.total_count().unwrap_or(0)
+                            ));
+                            if ui.button("Cancel").clicked() {
+                                job.cancel();
+                            }
+                        });
+                        return;
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    let can_export = !self.commit_export_config.fields.is_empty();
+                    if ui
+                        .add_enabled(can_export, egui::Button::new("💾 Export..."))
+                        .clicked()
+                    {
+                        let extension = self.commit_export_config.format.extension();
+                        target = rfd::FileDialog::new()
+                            .add_filter(extension.to_uppercase(), &[extension])
+                            .set_file_name(format!("commits.{extension}"))
+                            .save_file();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        keep_open = false;
+                    }
+                });
+            });
+
+        if let Some(path) = target {
+            self.state
+                .start_commit_export(&path, &self.commit_export_config);
+        }
+        // Close once the export finishes; the status bar reports the outcome
+        if self
+            .state
+            .commit_export_job
+            .as_ref()
+            .is_some_and(CommitExportJob::is_complete)
+        {
+            self.state.commit_export_job = None;
+            keep_open = false;
+        }
+        self.show_commit_export_dialog = keep_open;
+    }
+
     fn show_settings_dialog(&mut self, ctx: &egui::Context) {
         if self.state.show_settings_dialog {
             egui::Window::new("Settings")
@@ -377,6 +895,23 @@ impl GitkApp {
                         ui.checkbox(&mut self.config.show_relative_dates, "Show relative dates");
                         ui.checkbox(&mut self.config.compact_view, "Compact view");
 
+                        ui.horizontal(|ui| {
+                            let mut enabled = self.config.auto_refresh_interval.is_some();
+                            if ui
+                                .checkbox(&mut enabled, "Also refresh every")
+                                .on_hover_text(
+                                    "Changes are picked up automatically; polling helps on \
+                                     network filesystems that don't report them",
+                                )
+                                .changed()
+                            {
+                                self.config.auto_refresh_interval = enabled.then_some(30);
+                            }
+                            if let Some(ref mut seconds) = self.config.auto_refresh_interval {
+                                ui.add(egui::Slider::new(seconds, 5..=600).suffix(" s"));
+                            }
+                        });
+
                         ui.add_space(20.0);
 
                         // Performance Settings
@@ -516,6 +1051,12 @@ impl GitkApp {
                 }
             }
 
+            ui.add_space(10.0);
+
+            if ui.button("Open Patch File").clicked() {
+                self.open_patch_file();
+            }
+
             ui.add_space(20.0);
 
             if !self.config.recent_repositories.is_empty() {
@@ -0,0 +1,420 @@
+//! Headless subcommands, run instead of the GUI when the first argument
+//! names one, e.g. `gitk-rs log --graph` over SSH or in scripts.
+
+use crate::git::{
+    CommitExportConfig, CommitExportField, CommitExportFormat, GitRef, GitRepository, LaneLayout,
+    RefManager, RefType,
+};
+use crate::models::GitCommit;
+use anyhow::{Result, anyhow, bail};
+use serde_json::json;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+
+const LOG_USAGE: &str = "\
+usage: gitk-rs log [<options>] [<revision>]
+
+    --graph             draw the commit graph next to the log
+    --json              print commits and their graph layout as JSON
+    --color[=<when>]    color lanes and refs: always, never or auto
+    --no-color          same as --color=never
+    -n, --max-count <n> show at most <n> commits
+    -C <path>           run in <path> instead of the current directory";
+
+const EXPORT_USAGE: &str = "\
+usage: gitk-rs export [<options>] [<revision>]
+
+    --format <format>   csv (default) or json
+    --fields <list>     comma separated columns, from: sha, short_sha, author,
+                        author_email, author_date, committer, committer_email,
+                        commit_date, summary, message, parents, refs, diffstat,
+                        trailers
+    -o, --output <file> write to <file> instead of standard output
+    -n, --max-count <n> export at most <n> commits
+    -C <path>           run in <path> instead of the current directory";
+
+// Lane colors, in the order git uses for its graph
+const LANE_COLORS: [&str; 6] = [
+    "\x1b[31m", "\x1b[32m", "\x1b[33m", "\x1b[34m", "\x1b[35m", "\x1b[36m",
+];
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Run the subcommand named by `args`, if any, and return its exit code
+#[must_use]
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let result = match command.as_str() {
+        "log" => LogOptions::parse(rest).and_then(|options| run_log(&options)),
+        "export" => ExportOptions::parse(rest).and_then(|options| run_export(&options)),
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok(()) => 0,
+        // Output piped into `head` and closed early
+        Err(e)
+            if e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            0
+        }
+        Err(e) => {
+            eprintln!("gitk-rs: {e}");
+            1
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorWhen {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorWhen {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => bail!("invalid color mode '{value}', expected always, never or auto"),
+        }
+    }
+
+    fn enabled(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+/// Options for `gitk-rs log`
+#[derive(Debug, Clone)]
+pub struct LogOptions {
+    pub graph: bool,
+    pub json: bool,
+    pub color: ColorWhen,
+    pub max_count: Option<usize>,
+    pub repo_path: PathBuf,
+    pub revision: Option<String>, // Walk from here instead of HEAD
+    pub help: bool,
+}
+
+impl LogOptions {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut options = Self {
+            graph: false,
+            json: false,
+            color: ColorWhen::Auto,
+            max_count: None,
+            repo_path: PathBuf::from("."),
+            revision: None,
+            help: false,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--graph" => options.graph = true,
+                "--json" => options.json = true,
+                "--color" => options.color = ColorWhen::Always,
+                "--no-color" => options.color = ColorWhen::Never,
+                "-h" | "--help" => options.help = true,
+                "-n" | "--max-count" => {
+                    let count = args.next().ok_or_else(|| anyhow!("{arg} needs a count"))?;
+                    options.max_count = Some(parse_count(count)?);
+                }
+                "-C" => {
+                    let path = args.next().ok_or_else(|| anyhow!("-C needs a path"))?;
+                    options.repo_path = PathBuf::from(path);
+                }
+                _ => {
+                    if let Some(when) = arg.strip_prefix("--color=") {
+                        options.color = ColorWhen::parse(when)?;
+                    } else if let Some(count) = arg.strip_prefix("--max-count=") {
+                        options.max_count = Some(parse_count(count)?);
+                    } else if let Some(count) = arg.strip_prefix("-n") {
+                        options.max_count = Some(parse_count(count)?);
+                    } else if arg.starts_with('-') {
+                        bail!("unknown option '{arg}'\n\n{LOG_USAGE}");
+                    } else if options.revision.is_some() {
+                        bail!("only one revision can be given\n\n{LOG_USAGE}");
+                    } else {
+                        options.revision = Some(arg.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Options for `gitk-rs export`
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub config: CommitExportConfig,
+    pub output: Option<PathBuf>, // Standard output when None
+    pub max_count: Option<usize>,
+    pub repo_path: PathBuf,
+    pub revision: Option<String>,
+    pub help: bool,
+}
+
+impl ExportOptions {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut options = Self {
+            config: CommitExportConfig::default(),
+            output: None,
+            max_count: None,
+            repo_path: PathBuf::from("."),
+            revision: None,
+            help: false,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| anyhow!("{arg} needs {name}"));
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--format" => {
+                    options.config.format = CommitExportFormat::parse(value("a format")?)?;
+                }
+                "--fields" => {
+                    options.config.fields = CommitExportField::parse_list(value("a list")?)?;
+                }
+                "-o" | "--output" => options.output = Some(PathBuf::from(value("a file")?)),
+                "-n" | "--max-count" => options.max_count = Some(parse_count(value("a count")?)?),
+                "-C" => options.repo_path = PathBuf::from(value("a path")?),
+                _ => {
+                    if let Some(format) = arg.strip_prefix("--format=") {
+                        options.config.format = CommitExportFormat::parse(format)?;
+                    } else if let Some(fields) = arg.strip_prefix("--fields=") {
+                        options.config.fields = CommitExportField::parse_list(fields)?;
+                    } else if let Some(output) = arg.strip_prefix("--output=") {
+                        options.output = Some(PathBuf::from(output));
+                    } else if let Some(count) = arg.strip_prefix("--max-count=") {
+                        options.max_count = Some(parse_count(count)?);
+                    } else if let Some(count) = arg.strip_prefix("-n") {
+                        options.max_count = Some(parse_count(count)?);
+                    } else if arg.starts_with('-') {
+                        bail!("unknown option '{arg}'\n\n{EXPORT_USAGE}");
+                    } else if options.revision.is_some() {
+                        bail!("only one revision can be given\n\n{EXPORT_USAGE}");
+                    } else {
+                        options.revision = Some(arg.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_count(count: &str) -> Result<usize> {
+    count
+        .parse()
+        .map_err(|_| anyhow!("invalid commit count '{count}'"))
+}
+
+fn run_log(options: &LogOptions) -> Result<()> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if options.help {
+        writeln!(out, "{LOG_USAGE}")?;
+        return Ok(out.flush()?);
+    }
+
+    let repo = GitRepository::discover(&options.repo_path)?;
+    let commits = repo.get_history(options.revision.as_deref(), options.max_count)?;
+    let refs = repo.get_ref_manager()?;
+    let layout = LaneLayout::compute(&commits);
+
+    if options.json {
+        write_json(&mut out, &commits, &layout, &refs)?;
+    } else {
+        let color = options.color.enabled();
+        write_text(&mut out, &commits, &layout, &refs, options.graph, color)?;
+    }
+    Ok(out.flush()?)
+}
+
+/// Streams straight from a revision walk, so memory use stays flat however
+/// long the history is
+fn run_export(options: &ExportOptions) -> Result<()> {
+    if options.help {
+        println!("{EXPORT_USAGE}");
+        return Ok(());
+    }
+
+    let repo = GitRepository::discover(&options.repo_path)?;
+    let revision = options.revision.as_deref();
+    let config = options.config.clone();
+    if let Some(ref path) = options.output {
+        let file = io::BufWriter::new(std::fs::File::create(path)?);
+        let count = repo.export_history(revision, options.max_count, file, config)?;
+        eprintln!("Exported {} commits to {}", count, path.display());
+    } else {
+        let out = io::BufWriter::new(io::stdout().lock());
+        repo.export_history(revision, options.max_count, out, config)?;
+    }
+    Ok(())
+}
+
+fn write_text(
+    out: &mut impl Write,
+    commits: &[GitCommit],
+    layout: &LaneLayout,
+    refs: &RefManager,
+    graph: bool,
+    color: bool,
+) -> Result<()> {
+    if !graph {
+        for commit in commits {
+            writeln!(out, "{}", describe_commit(commit, refs, color))?;
+        }
+        return Ok(());
+    }
+
+    for text_row in layout.text_rows() {
+        let mut text = String::new();
+        for cell in &text_row.cells {
+            match cell.lane {
+                Some(lane) if color && cell.ch != ' ' => {
+                    text.push_str(LANE_COLORS[lane % LANE_COLORS.len()]);
+                    text.push(cell.ch);
+                    text.push_str(RESET);
+                }
+                _ => text.push(cell.ch),
+            }
+        }
+        if let Some(row) = text_row.commit_row {
+            text.push_str("  ");
+            text.push_str(&describe_commit(&commits[row], refs, color));
+        }
+        writeln!(out, "{text}")?;
+    }
+    Ok(())
+}
+
+/// `abc1234 (HEAD -> main, tag: v1.0) Summary`, like `git log --oneline`
+fn describe_commit(commit: &GitCommit, refs: &RefManager, color: bool) -> String {
+    let paint = |code: &str, text: &str| {
+        if color {
+            format!("{code}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+
+    let decorations: Vec<String> = decorations(refs, &commit.id)
+        .into_iter()
+        .map(|(code, text)| paint(code, &text))
+        .collect();
+    let decorations = if decorations.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", decorations.join(", "))
+    };
+
+    format!(
+        "{}{} {}",
+        paint(YELLOW, &commit.short_id),
+        decorations,
+        commit.summary
+    )
+}
+
+/// A ref name and the ANSI color `git log` decorates it with
+type Decoration = (&'static str, String);
+
+/// Ref names pointing at a commit with git's decoration colors: HEAD first,
+/// then local branches, remote branches and tags
+fn decorations(refs: &RefManager, commit_id: &str) -> Vec<Decoration> {
+    let mut commit_refs: Vec<&GitRef> = refs.get_refs_for_commit(commit_id);
+    commit_refs.sort_by_key(|git_ref| (ref_order(git_ref), git_ref.name.clone()));
+
+    let head_branch = commit_refs
+        .iter()
+        .find(|git_ref| git_ref.full_name == "HEAD" && git_ref.ref_type == RefType::LocalBranch)
+        .map(|head| head.name.clone());
+    commit_refs
+        .into_iter()
+        .filter(|git_ref| {
+            git_ref.full_name == "HEAD"
+                || git_ref.ref_type != RefType::LocalBranch
+                || Some(&git_ref.name) != head_branch.as_ref()
+        })
+        .map(|git_ref| match git_ref.ref_type {
+            _ if git_ref.full_name == "HEAD" => (
+                "\x1b[1;36m",
+                head_branch
+                    .as_ref()
+                    .map_or_else(|| "HEAD".to_string(), |branch| format!("HEAD -> {branch}")),
+            ),
+            RefType::LocalBranch => ("\x1b[1;32m", git_ref.name.clone()),
+            RefType::RemoteBranch => ("\x1b[1;31m", git_ref.name.clone()),
+            RefType::Tag => ("\x1b[1;33m", format!("tag: {}", git_ref.name)),
+            RefType::Head | RefType::Other => ("\x1b[1m", git_ref.name.clone()),
+        })
+        .collect()
+}
+
+fn ref_order(git_ref: &GitRef) -> u8 {
+    if git_ref.full_name == "HEAD" {
+        return 0;
+    }
+    match git_ref.ref_type {
+        RefType::LocalBranch => 1,
+        RefType::RemoteBranch => 2,
+        RefType::Tag => 3,
+        RefType::Head | RefType::Other => 4,
+    }
+}
+
+/// The commits with their layout rows, for tooling
+fn write_json(
+    out: &mut impl Write,
+    commits: &[GitCommit],
+    layout: &LaneLayout,
+    refs: &RefManager,
+) -> Result<()> {
+    let rows: Vec<serde_json::Value> = commits
+        .iter()
+        .zip(&layout.rows)
+        .map(|(commit, row)| {
+            let mut commit_refs: Vec<&str> = refs
+                .get_refs_for_commit(&commit.id)
+                .into_iter()
+                .map(|git_ref| git_ref.full_name.as_str())
+                .collect();
+            commit_refs.sort_unstable();
+            json!({
+                "id": commit.id,
+                "short_id": commit.short_id,
+                "summary": commit.summary,
+                "author": commit.author.name,
+                "email": commit.author.email,
+                "date": commit.author.when.to_rfc3339(),
+                "refs": commit_refs,
+                "lane": row.lane,
+                "pass_through": row.pass_through,
+                "parents": row.parents,
+            })
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(
+        &mut *out,
+        &json!({
+            "lane_count": layout.lane_count,
+            "commits": rows,
+        }),
+    )?;
+    writeln!(out)?;
+    Ok(())
+}
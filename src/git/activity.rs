@@ -0,0 +1,288 @@
+use crate::git::{GitRepository, ViewFilter, commit_time};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Weeks shown in the calendar, a year like GitHub's contribution graph
+pub const ACTIVITY_WEEKS: usize = 53;
+/// Shades of the heatmap besides the empty one
+pub const ACTIVITY_LEVELS: usize = 4;
+
+/// Whose and which commits the calendar counts; empty fields match all
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivityScope {
+    pub author: String, // Substring of the author name or email
+    pub path: String,   // Pathspec the commits must touch
+}
+
+impl ActivityScope {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.author.trim().is_empty() && self.path.trim().is_empty()
+    }
+
+    /// A view of the scope's commits committed from the start of `first`
+    /// through the end of `last`
+    #[must_use]
+    pub fn to_filter(&self, name: &str, first: NaiveDate, last: NaiveDate) -> ViewFilter {
+        let non_empty = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let description = if first == last {
+            format!("Commits on {first}")
+        } else {
+            format!("Commits from {first} to {last}")
+        };
+
+        ViewFilter {
+            name: name.to_string(),
+            description,
+            author_filter: non_empty(&self.author),
+            file_filter: non_empty(&self.path),
+            date_from: Some(format!("{first}T00:00:00Z")), // UTC for git too
+            date_to: Some(format!("{last}T23:59:59Z")),
+            max_commits: None,
+            include_merges: true,
+            ..ViewFilter::default()
+        }
+    }
+}
+
+/// Commits per day by committer date (UTC), the date view filters compare
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivityCalendar {
+    days: BTreeMap<NaiveDate, usize>,
+    last_day: Option<NaiveDate>, // Right edge of the calendar
+}
+
+impl ActivityCalendar {
+    fn add_day(&mut self, day: NaiveDate) {
+        *self.days.entry(day).or_default() += 1;
+    }
+
+    /// End the calendar on `day` instead of the last day with commits
+    #[must_use]
+    pub const fn ending_on(mut self, day: NaiveDate) -> Self {
+        self.last_day = Some(day);
+        self
+    }
+
+    #[must_use]
+    pub fn count(&self, day: NaiveDate) -> usize {
+        self.days.get(&day).copied().unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.days.values().sum()
+    }
+
+    #[must_use]
+    pub fn max(&self) -> usize {
+        self.days.values().copied().max().unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn last_day(&self) -> Option<NaiveDate> {
+        self.last_day
+            .or_else(|| self.days.keys().next_back().copied())
+    }
+
+    /// Shade for a day: 0 without commits, else 1 to `ACTIVITY_LEVELS`
+    /// relative to the busiest day
+    #[must_use]
+    pub fn level(&self, day: NaiveDate) -> usize {
+        let count = self.count(day);
+        if count == 0 {
+            return 0;
+        }
+        (count * ACTIVITY_LEVELS)
+            .div_ceil(self.max())
+            .clamp(1, ACTIVITY_LEVELS)
+    }
+
+    /// Monday of each column, oldest first, so the last column holds
+    /// `last_day()`
+    #[must_use]
+    pub fn week_starts(&self, weeks: usize) -> Vec<NaiveDate> {
+        let Some(last_day) = self.last_day() else {
+            return Vec::new();
+        };
+        let last_monday =
+            last_day - Duration::days(i64::from(last_day.weekday().num_days_from_monday()));
+        let mut starts: Vec<NaiveDate> = std::iter::successors(Some(last_monday), |monday| {
+            monday.checked_sub_signed(Duration::weeks(1))
+        })
+        .take(weeks)
+        .collect();
+        starts.reverse();
+        starts
+    }
+}
+
+impl GitRepository {
+    /// Commits per day from HEAD for the `ACTIVITY_WEEKS` weeks up to
+    /// `last_day`, narrowed to `scope`
+    pub fn activity_calendar(
+        &self,
+        scope: &ActivityScope,
+        last_day: NaiveDate,
+    ) -> Result<ActivityCalendar> {
+        let mut calendar = ActivityCalendar::default().ending_on(last_day);
+        let first_day = calendar.week_starts(ACTIVITY_WEEKS)[0];
+        let filter = scope.to_filter("Activity", first_day, last_day);
+
+        // rev-list does the author, path and date filtering; only the
+        // committer dates are read back
+        let args = filter.to_git_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        for line in self.commands().rev_list(&args)?.lines() {
+            let commit = self.repo().find_commit(git2::Oid::from_str(line.trim())?)?;
+            calendar.add_day(commit_time(&commit).date_naive());
+        }
+        Ok(calendar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git_with_env;
+    use crate::models::{GitCommit, GitSignature};
+    use chrono::{DateTime, Utc};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn commit_on(when: &str) -> GitCommit {
+        let signature = GitSignature {
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+            when: DateTime::parse_from_rfc3339(when)
+                .unwrap()
+                .with_timezone(&Utc),
+        };
+        GitCommit {
+            id: when.to_string(),
+            short_id: when.to_string(),
+            author: signature.clone(),
+            committer: signature,
+            message: "Change".to_string(),
+            summary: "Change".to_string(),
+            parent_ids: Vec::new(),
+            tree_id: String::new(),
+            is_signed: false,
+            notes: Vec::new(),
+        }
+    }
+
+    fn day(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_counts_and_levels() {
+        let commits = vec![
+            commit_on("2024-03-05T10:00:00Z"),
+            commit_on("2024-03-05T23:30:00Z"),
+            commit_on("2024-03-05T08:00:00Z"),
+            commit_on("2024-03-05T09:00:00Z"),
+            commit_on("2024-03-07T12:00:00Z"),
+        ];
+        let mut calendar = ActivityCalendar::default();
+        for commit in &commits {
+            calendar.add_day(commit.committer.when.date_naive());
+        }
+
+        assert_eq!(calendar.count(day("2024-03-05")), 4);
+        assert_eq!(calendar.count(day("2024-03-06")), 0);
+        assert_eq!(calendar.total(), 5);
+        assert_eq!(calendar.level(day("2024-03-05")), ACTIVITY_LEVELS);
+        assert_eq!(calendar.level(day("2024-03-06")), 0);
+        assert_eq!(calendar.level(day("2024-03-07")), 1);
+        assert_eq!(calendar.last_day(), Some(day("2024-03-07")));
+    }
+
+    #[test]
+    fn test_week_starts_end_with_last_day() {
+        // 2024-03-07 is a Thursday
+        let calendar = ActivityCalendar::default().ending_on(day("2024-03-07"));
+        let weeks = calendar.week_starts(3);
+
+        assert_eq!(
+            weeks,
+            vec![day("2024-02-19"), day("2024-02-26"), day("2024-03-04")]
+        );
+        assert!(ActivityCalendar::default().week_starts(3).is_empty());
+    }
+
+    #[test]
+    fn test_scope_filter_covers_whole_days() {
+        let scope = ActivityScope {
+            author: " alice ".to_string(),
+            path: String::new(),
+        };
+        let filter = scope.to_filter("Day", day("2024-03-05"), day("2024-03-05"));
+
+        assert_eq!(filter.author_filter.as_deref(), Some("alice"));
+        assert_eq!(filter.file_filter, None);
+        let range = filter.date_range().unwrap();
+        assert!(range.contains(commit_on("2024-03-05T00:00:00Z").committer.when));
+        assert!(range.contains(commit_on("2024-03-05T23:59:59Z").committer.when));
+        assert!(!range.contains(commit_on("2024-03-06T00:00:00Z").committer.when));
+    }
+
+    fn git(repo_path: &Path, args: &[&str], author: &str, date: &str) -> Result<String> {
+        let email = format!("{}@example.com", author);
+        let env = [
+            ("GIT_AUTHOR_NAME", author),
+            ("GIT_AUTHOR_EMAIL", email.as_str()),
+            ("GIT_COMMITTER_NAME", author),
+            ("GIT_COMMITTER_EMAIL", email.as_str()),
+            ("GIT_AUTHOR_DATE", date),
+            ("GIT_COMMITTER_DATE", date),
+        ];
+        git_with_env(repo_path, args, &env)
+    }
+
+    #[test]
+    fn test_activity_calendar_for_author_and_path() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"], "alice", "2024-03-01T12:00:00Z")?;
+
+        for (file, author, date) in [
+            ("a.txt", "alice", "2023-01-10T12:00:00Z"), // Before the calendar
+            ("a.txt", "alice", "2024-03-01T12:00:00Z"),
+            ("b.txt", "bob", "2024-03-01T13:00:00Z"),
+            ("a.txt", "bob", "2024-03-04T09:00:00Z"),
+        ] {
+            fs::write(path.join(file), date)?;
+            git(path, &["add", file], author, date)?;
+            git(path, &["commit", "-m", date], author, date)?;
+        }
+
+        let repo = GitRepository::open(path)?;
+        let last_day = day("2024-03-04");
+
+        let everyone = repo.activity_calendar(&ActivityScope::default(), last_day)?;
+        assert_eq!(everyone.total(), 3);
+        assert_eq!(everyone.count(day("2024-03-01")), 2);
+
+        let bob = ActivityScope {
+            author: "bob".to_string(),
+            path: String::new(),
+        };
+        assert_eq!(repo.activity_calendar(&bob, last_day)?.total(), 2);
+
+        let a_txt = ActivityScope {
+            author: String::new(),
+            path: "a.txt".to_string(),
+        };
+        let calendar = repo.activity_calendar(&a_txt, last_day)?;
+        assert_eq!(calendar.count(day("2024-03-01")), 1);
+        assert_eq!(calendar.count(day("2024-03-04")), 1);
+        Ok(())
+    }
+}
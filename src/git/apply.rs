@@ -0,0 +1,1459 @@
+use crate::git::{ConflictInfo, ConflictType, GitRepository, collect_git_diffs};
+use crate::models::GitDiff;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, FixedOffset};
+use git2::build::CheckoutBuilder;
+use git2::{
+    ApplyLocation, Delta, Diff, FileMode, Index, IndexEntry, IndexTime, Oid, Repository, Tree,
+};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Where patches go, like `git apply`, `git apply --cached` and `git am`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchApplyTarget {
+    WorkingTree, // Change files only
+    Index,       // Stage the changes without touching files
+    Commit,      // One commit per patch, keeping the mail's author and date
+}
+
+/// Configuration for applying patches
+#[derive(Debug, Clone)]
+pub struct PatchApplyConfig {
+    pub target: PatchApplyTarget,
+    pub three_way: bool, // Merge from the blobs named in the patch when it does not apply
+}
+
+impl Default for PatchApplyConfig {
+    fn default() -> Self {
+        Self {
+            target: PatchApplyTarget::Commit,
+            three_way: true,
+        }
+    }
+}
+
+/// Author of a mailed patch, from its From: header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+/// One patch read from a mailbox or a plain patch file
+#[derive(Debug, Clone)]
+pub struct MailPatch {
+    pub author: Option<PatchAuthor>,
+    pub date: Option<DateTime<FixedOffset>>,
+    pub subject: String, // Without the "[PATCH n/m]" prefix
+    pub message: String, // Subject and body, as the commit message
+    pub diff: Vec<u8>,   // The git diff, signature removed
+}
+
+/// Outcome of applying a list of patches in order
+#[derive(Debug, Clone, Default)]
+pub struct PatchApplyResult {
+    pub applied: usize,               // Patches applied before stopping
+    pub new_commits: Vec<String>,     // Created in `Commit` mode
+    pub stopped_at: Option<usize>,    // Index of the patch that failed
+    pub conflicts: Vec<ConflictInfo>, // Rejected or conflicted paths of that patch
+    pub merged: bool,                 // The failed patch was merged, leaving conflict markers
+    pub message: String,
+}
+
+impl PatchApplyResult {
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        self.stopped_at.is_none()
+    }
+}
+
+/// How a single patch went
+enum PatchOutcome {
+    Applied,
+    Conflicted(Vec<ConflictInfo>), // Three-way merge left conflicts in the worktree
+    Rejected(Vec<ConflictInfo>),   // Nothing was changed
+}
+
+impl MailPatch {
+    /// The patch as a libgit2 diff, ready to apply
+    pub fn to_diff(&self) -> Result<Diff<'static>> {
+        Ok(Diff::from_buffer(&self.diff)?)
+    }
+
+    /// The files and hunks of the patch, for previewing in the diff viewer
+    pub fn diffs(&self) -> Result<Vec<GitDiff>> {
+        collect_git_diffs(&self.to_diff()?)
+    }
+}
+
+/// One line of raw mail text, line ending included
+type Line<'a> = &'a [u8];
+
+/// Mail header names and values, in message order
+type MailHeaders = Vec<(String, String)>;
+
+/// Text of a decoded "=?charset?...?=" word and the input length it used
+type DecodedWord = (String, usize);
+
+/// Split an mbox, a single mail or a bare git diff into patches. Messages
+/// without a diff, such as a series' cover letter, are skipped.
+pub fn parse_mailbox(data: &[u8]) -> Result<Vec<MailPatch>> {
+    let lines: Vec<Line> = data.split_inclusive(|&byte| byte == b'\n').collect();
+
+    // Messages start at "From " separator lines following a blank line
+    let mut starts = vec![0];
+    for (index, line) in lines.iter().enumerate().skip(1) {
+        if line.starts_with(b"From ") && trim_eol(lines[index - 1]).is_empty() {
+            starts.push(index);
+        }
+    }
+    starts.push(lines.len());
+
+    let mut patches = Vec::new();
+    for range in starts.windows(2) {
+        if let Some(patch) = parse_message(&lines[range[0]..range[1]]) {
+            patches.push(patch);
+        } else {
+            debug!("Skipping message without a diff at line {}", range[0] + 1);
+        }
+    }
+
+    if patches.is_empty() {
+        return Err(anyhow!("No patches found"));
+    }
+    Ok(patches)
+}
+
+impl GitRepository {
+    /// Apply patches in order, stopping at the first one that does not
+    /// apply. A three-way merge, like `git apply --3way`, also stages its
+    /// result and writes conflict markers for the paths it could not merge.
+    pub fn apply_patches(
+        &self,
+        patches: &[MailPatch],
+        config: &PatchApplyConfig,
+    ) -> Result<PatchApplyResult> {
+        let repo = self.repo();
+
+        // Other programs may have changed the index since it was loaded
+        repo.index()?.read(false)?;
+
+        if config.target == PatchApplyTarget::Commit && has_staged_changes(repo)? {
+            return Err(anyhow!(
+                "The index has staged changes; commit or stash them before applying patches as commits"
+            ));
+        }
+
+        let mut result = PatchApplyResult::default();
+        for (index, patch) in patches.iter().enumerate() {
+            if config.target == PatchApplyTarget::Commit && patch.subject.is_empty() {
+                result.stopped_at = Some(index);
+                result.message =
+                    "Patch has no commit message; apply it to the working tree instead".to_string();
+                return Ok(result);
+            }
+
+            let diff = match patch.to_diff() {
+                Ok(diff) => diff,
+                Err(e) => {
+                    result.stopped_at = Some(index);
+                    result.message = format!("\"{}\" could not be parsed: {}", patch.subject, e);
+                    return Ok(result);
+                }
+            };
+
+            match self.apply_patch(patch, &diff, config)? {
+                PatchOutcome::Applied => {
+                    if config.target == PatchApplyTarget::Commit {
+                        let commit_id = self.commit_patch(patch)?;
+                        result.new_commits.push(commit_id.to_string());
+                    }
+                    result.applied += 1;
+                }
+                PatchOutcome::Conflicted(conflicts) => {
+                    result.stopped_at = Some(index);
+                    result.merged = true;
+                    result.message = format!(
+                        "\"{}\" was merged with conflicts in {} file(s)",
+                        patch.subject,
+                        conflicts.len()
+                    );
+                    result.conflicts = conflicts;
+                    return Ok(result);
+                }
+                PatchOutcome::Rejected(conflicts) => {
+                    result.stopped_at = Some(index);
+                    result.message = format!("\"{}\" does not apply", patch.subject);
+                    result.conflicts = conflicts;
+                    return Ok(result);
+                }
+            }
+        }
+
+        info!("Applied {} patches", result.applied);
+        result.message = match config.target {
+            PatchApplyTarget::Commit => format!("Committed {} patch(es)", result.applied),
+            _ => format!("Applied {} patch(es)", result.applied),
+        };
+        Ok(result)
+    }
+
+    /// Resume after `patches[0]` stopped, like `git am --continue`: once its
+    /// conflicts are resolved and staged it is committed with the mail's
+    /// author and date (in `Commit` mode), then the rest are applied
+    pub fn continue_patches(
+        &self,
+        patches: &[MailPatch],
+        config: &PatchApplyConfig,
+    ) -> Result<PatchApplyResult> {
+        let Some((resolved, rest)) = patches.split_first() else {
+            return Err(anyhow!("No patch to continue"));
+        };
+        let repo = self.repo();
+        let mut index = repo.index()?;
+        index.read(false)?;
+        if index.has_conflicts() {
+            return Err(anyhow!(
+                "Resolve the conflicts and stage the files before continuing"
+            ));
+        }
+
+        let mut new_commits = Vec::new();
+        if config.target == PatchApplyTarget::Commit {
+            if !has_staged_changes(repo)? {
+                return Err(anyhow!(
+                    "No changes staged for \"{}\"; skip the patch instead",
+                    resolved.subject
+                ));
+            }
+            new_commits.push(self.commit_patch(resolved)?.to_string());
+        }
+
+        let mut result = self.apply_patches(rest, config)?;
+        result.applied += 1;
+        result.new_commits.splice(0..0, new_commits);
+        result.stopped_at = result.stopped_at.map(|index| index + 1);
+        Ok(result)
+    }
+
+    /// Drop `patches[0]` and apply the rest, like `git am --skip`. In
+    /// `Commit` mode the paths it touched go back to HEAD; otherwise its
+    /// conflicted paths go back to what they were before the merge.
+    pub fn skip_patch(
+        &self,
+        patches: &[MailPatch],
+        config: &PatchApplyConfig,
+    ) -> Result<PatchApplyResult> {
+        let Some((skipped, rest)) = patches.split_first() else {
+            return Err(anyhow!("No patch to skip"));
+        };
+        let repo = self.repo();
+        let paths = skipped
+            .to_diff()
+            .map(|diff| patch_paths(&diff))
+            .unwrap_or_default();
+
+        if !paths.is_empty() {
+            if config.target == PatchApplyTarget::Commit {
+                let head = repo.head()?.peel_to_commit()?;
+                repo.reset_default(Some(head.as_object()), &paths)?;
+                let mut checkout = CheckoutBuilder::new();
+                checkout.force();
+                for path in &paths {
+                    checkout.path(path.as_path());
+                }
+                repo.checkout_head(Some(&mut checkout))?;
+            } else {
+                restore_ours(repo, &paths)?;
+            }
+        }
+        info!("Skipped patch \"{}\"", skipped.subject);
+
+        let mut result = self.apply_patches(rest, config)?;
+        result.stopped_at = result.stopped_at.map(|index| index + 1);
+        Ok(result)
+    }
+
+    fn apply_patch(
+        &self,
+        patch: &MailPatch,
+        diff: &Diff,
+        config: &PatchApplyConfig,
+    ) -> Result<PatchOutcome> {
+        let repo = self.repo();
+        let location = match config.target {
+            PatchApplyTarget::WorkingTree => ApplyLocation::WorkDir,
+            PatchApplyTarget::Index => ApplyLocation::Index,
+            PatchApplyTarget::Commit => ApplyLocation::Both,
+        };
+
+        let error = match repo.apply(diff, location, None) {
+            Ok(()) => return Ok(PatchOutcome::Applied),
+            Err(e) => e,
+        };
+        debug!("Patch does not apply cleanly: {}", error);
+
+        // The index matches HEAD when committing, so this is "ours" either way
+        let ours = repo.find_tree(repo.index()?.write_tree()?)?;
+        if config.three_way {
+            if let Some(merged) = three_way_merge(repo, &patch.diff, diff, &ours)? {
+                let conflicts = ConflictInfo::from_index(&merged)?;
+                check_out_merge(repo, &merged, &patch_paths(diff))?;
+                return Ok(if conflicts.is_empty() {
+                    PatchOutcome::Applied
+                } else {
+                    PatchOutcome::Conflicted(conflicts)
+                });
+            }
+        }
+        Ok(PatchOutcome::Rejected(rejected_paths(
+            repo, diff, location, &ours,
+        )))
+    }
+
+    /// Commit the index with the mail's author and date
+    fn commit_patch(&self, patch: &MailPatch) -> Result<Oid> {
+        let repo = self.repo();
+        let committer = repo.signature()?;
+        let author = match patch.author {
+            Some(ref author) => {
+                let when = patch.date.map_or_else(
+                    || committer.when(),
+                    |date| git2::Time::new(date.timestamp(), date.offset().local_minus_utc() / 60),
+                );
+                git2::Signature::new(&author.name, &author.email, &when)?
+            }
+            None => committer.clone(),
+        };
+
+        let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+        let parent = repo.head()?.peel_to_commit()?;
+        Ok(repo.commit(
+            Some("HEAD"),
+            &author,
+            &committer,
+            &patch.message,
+            &tree,
+            &[&parent],
+        )?)
+    }
+}
+
+fn has_staged_changes(repo: &Repository) -> Result<bool> {
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let diff = repo.diff_tree_to_index(Some(&head_tree), None, None)?;
+    Ok(diff.deltas().len() > 0)
+}
+
+/// Rebuild the patch's preimage from the blob ids on its "index" lines,
+/// apply the patch to it and merge the result into `ours`. `None` when a
+/// preimage blob is not in this repository.
+fn three_way_merge(
+    repo: &Repository,
+    patch: &[u8],
+    diff: &Diff,
+    ours: &Tree,
+) -> Result<Option<Index>> {
+    let preimages = preimage_ids(patch);
+    if preimages.len() != diff.deltas().len() {
+        return Ok(None);
+    }
+
+    let mut base = Index::new()?;
+    base.read_tree(ours)?;
+    for (delta, preimage) in diff.deltas().zip(preimages) {
+        if delta.status() == Delta::Added {
+            if let Some(path) = delta.new_file().path() {
+                base.remove_path(path)?;
+            }
+            continue;
+        }
+        let Some(old_path) = delta.old_file().path() else {
+            continue;
+        };
+
+        let blob = preimage
+            .and_then(|id| repo.revparse_single(&id).ok())
+            .and_then(|object| object.peel_to_blob().ok());
+        match blob {
+            Some(blob) => {
+                let mode = ours.get_path(old_path).map_or_else(
+                    |_| u32::from(FileMode::Blob),
+                    |entry| entry.filemode().cast_unsigned(),
+                );
+                base.add(&index_entry(old_path, blob.id(), mode))?;
+            }
+            // A pure rename has no index line; its content is ours
+            None if delta.status() == Delta::Renamed => {}
+            None => {
+                debug!("Preimage of {} is not available", old_path.display());
+                return Ok(None);
+            }
+        }
+    }
+    let base_tree = repo.find_tree(base.write_tree_to(repo)?)?;
+
+    let mut theirs = match repo.apply_to_tree(&base_tree, diff, None) {
+        Ok(index) => index,
+        Err(e) => {
+            debug!("Patch does not apply to its own preimage: {}", e);
+            return Ok(None);
+        }
+    };
+    let their_tree = repo.find_tree(theirs.write_tree_to(repo)?)?;
+
+    Ok(Some(repo.merge_trees(
+        &base_tree,
+        ours,
+        &their_tree,
+        None,
+    )?))
+}
+
+/// The abbreviated preimage blob id from each file's "index" line, in order
+fn preimage_ids(patch: &[u8]) -> Vec<Option<String>> {
+    let mut ids = Vec::new();
+    let mut in_header = false;
+    for line in patch.split(|&byte| byte == b'\n') {
+        if line.starts_with(b"diff --git ") {
+            ids.push(None);
+            in_header = true;
+        } else if line.starts_with(b"@@") || line.starts_with(b"GIT binary patch") {
+            in_header = false;
+        } else if in_header && line.starts_with(b"index ") {
+            let text = String::from_utf8_lossy(&line[b"index ".len()..]);
+            let old_id = text
+                .split_once("..")
+                .map(|(old_id, _)| old_id.to_string())
+                .filter(|old_id| old_id.bytes().any(|byte| byte != b'0'));
+            if let Some(last) = ids.last_mut() {
+                *last = old_id;
+            }
+        }
+    }
+    ids
+}
+
+fn index_entry(path: &Path, id: Oid, mode: u32) -> IndexEntry {
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id,
+        flags: 0, // git2 fills in the path length
+        flags_extended: 0,
+        path: path.to_string_lossy().into_owned().into_bytes(),
+    }
+}
+
+/// Every path a patch touches, both sides of renames included
+fn patch_paths(diff: &Diff) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = diff
+        .deltas()
+        .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+        .flatten()
+        .map(Path::to_path_buf)
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Put a merge result for `paths` into the repository index, conflicts
+/// included, and write those paths to the working tree with conflict markers
+fn check_out_merge(repo: &Repository, merged: &Index, paths: &[PathBuf]) -> Result<()> {
+    let dirty = git2::Status::WT_MODIFIED | git2::Status::WT_DELETED | git2::Status::WT_TYPECHANGE;
+    for path in paths {
+        if repo
+            .status_file(path)
+            .is_ok_and(|status| status.intersects(dirty))
+        {
+            return Err(anyhow!(
+                "{} has local changes; commit or stash them first",
+                path.display()
+            ));
+        }
+    }
+
+    let mut index = repo.index()?;
+    for path in paths {
+        index.remove_path(path)?;
+    }
+    for entry in merged.iter() {
+        let stage = (entry.flags >> 12) & 0x3;
+        let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+        if stage == 0 && paths.contains(&path) {
+            index.add(&entry)?;
+        }
+    }
+    // git2 has no conflict_add; an entry's stage lives in bits 12-13 of its flags
+    for conflict in merged.conflicts()? {
+        let conflict = conflict?;
+        let stages = [
+            (1, conflict.ancestor),
+            (2, conflict.our),
+            (3, conflict.their),
+        ];
+        for (stage, entry) in stages {
+            if let Some(mut entry) = entry {
+                entry.flags = (entry.flags & !0x3000) | (stage << 12);
+                index.add(&entry)?;
+            }
+        }
+    }
+    index.write()?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout
+        .force()
+        .allow_conflicts(true)
+        .conflict_style_merge(true);
+    for path in paths {
+        checkout.path(path.as_path());
+    }
+    repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+    Ok(())
+}
+
+/// Replace the conflicts among `paths` with their "ours" side, in the index
+/// and the working tree, undoing a three-way merge of those paths
+fn restore_ours(repo: &Repository, paths: &[PathBuf]) -> Result<()> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+
+    let mut restored = Vec::new();
+    let conflicts: Vec<git2::IndexConflict> = index.conflicts()?.collect::<Result<_, _>>()?;
+    for conflict in conflicts {
+        let Some(entry) = conflict
+            .our
+            .as_ref()
+            .or(conflict.their.as_ref())
+            .or(conflict.ancestor.as_ref())
+        else {
+            continue;
+        };
+        let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+        if !paths.contains(&path) {
+            continue;
+        }
+
+        index.conflict_remove(&path)?;
+        match conflict.our {
+            Some(mut ours) => {
+                ours.flags &= !0x3000;
+                index.add(&ours)?;
+            }
+            None => {
+                // Added by the patch; nothing to go back to
+                if let Some(workdir) = repo.workdir() {
+                    let _ = std::fs::remove_file(workdir.join(&path));
+                }
+            }
+        }
+        restored.push(path);
+    }
+    index.write()?;
+
+    if !restored.is_empty() {
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force();
+        for path in &restored {
+            checkout.path(path.as_path());
+        }
+        repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+    }
+    Ok(())
+}
+
+/// Report each file of a patch that does not apply on its own as a conflict
+fn rejected_paths(
+    repo: &Repository,
+    diff: &Diff,
+    location: ApplyLocation,
+    ours: &Tree,
+) -> Vec<ConflictInfo> {
+    let mut conflicts = Vec::new();
+    for delta in diff.deltas() {
+        let Some(path) = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(Path::to_path_buf)
+        else {
+            continue;
+        };
+
+        let wanted = path.clone();
+        let mut options = git2::ApplyOptions::new();
+        options.check(true);
+        options.delta_callback(move |delta| {
+            delta.is_some_and(|delta| {
+                delta.new_file().path().or_else(|| delta.old_file().path())
+                    == Some(wanted.as_path())
+            })
+        });
+        if repo.apply(diff, location, Some(&mut options)).is_ok() {
+            continue;
+        }
+
+        let our_id = delta
+            .old_file()
+            .path()
+            .and_then(|old_path| ours.get_path(old_path).ok())
+            .map(|entry| entry.id().to_string());
+        let conflict_type = match (delta.status(), our_id.is_some()) {
+            (Delta::Added, true) => ConflictType::AddAdd,
+            (Delta::Added, false) => ConflictType::Content,
+            (_, false) => ConflictType::DeleteModify,
+            _ => ConflictType::Content,
+        };
+        conflicts.push(ConflictInfo {
+            path: path.to_string_lossy().to_string(),
+            ancestor_id: None,
+            our_id,
+            their_id: None,
+            conflict_type,
+        });
+    }
+    conflicts
+}
+
+fn parse_message(lines: &[Line]) -> Option<MailPatch> {
+    let mut lines = lines;
+    if lines.first().is_some_and(|line| line.starts_with(b"From ")) {
+        lines = &lines[1..];
+    }
+
+    let mut headers = Vec::new();
+    if lines.first().is_some_and(|line| is_header_line(line)) {
+        let end = lines
+            .iter()
+            .position(|line| trim_eol(line).is_empty())
+            .unwrap_or(lines.len());
+        headers = parse_headers(&lines[..end]);
+        lines = &lines[(end + 1).min(lines.len())..];
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+
+    let decoded;
+    let body: Vec<Line> = match header("Content-Transfer-Encoding")
+        .map(|encoding| encoding.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("quoted-printable") => {
+            decoded = decode_quoted_printable(&lines.concat());
+            decoded.split_inclusive(|&byte| byte == b'\n').collect()
+        }
+        Some("base64") => {
+            decoded = decode_base64(&lines.concat())?;
+            decoded.split_inclusive(|&byte| byte == b'\n').collect()
+        }
+        _ => lines.to_vec(),
+    };
+
+    let mut author = header("From").and_then(parse_address);
+    let mut date = header("Date").and_then(parse_date);
+    let mut subject = header("Subject").map(decode_rfc2047).unwrap_or_default();
+
+    // Headers at the top of the body override the mail's, as in `git am`
+    let mut body = &body[..];
+    while body.first().is_some_and(|line| trim_eol(line).is_empty()) {
+        body = &body[1..];
+    }
+    let mut in_body_headers = false;
+    while let Some(line) = body.first() {
+        let text = String::from_utf8_lossy(trim_eol(line));
+        if let Some(value) = text.strip_prefix("From: ") {
+            author = parse_address(value).or(author);
+        } else if let Some(value) = text.strip_prefix("Date: ") {
+            date = parse_date(value).or(date);
+        } else if let Some(value) = text.strip_prefix("Subject: ") {
+            subject = decode_rfc2047(value);
+        } else {
+            break;
+        }
+        in_body_headers = true;
+        body = &body[1..];
+    }
+    if in_body_headers {
+        while body.first().is_some_and(|line| trim_eol(line).is_empty()) {
+            body = &body[1..];
+        }
+    }
+
+    // A git diff, or a plain unified diff's "---"/"+++" pair as `git am` accepts
+    let is_diff_start = |index: usize| {
+        let line = body[index];
+        line.starts_with(b"diff --git ")
+            || (line.starts_with(b"--- ")
+                && body
+                    .get(index + 1)
+                    .is_some_and(|next| next.starts_with(b"+++ ")))
+    };
+    let message_end = (0..body.len())
+        .find(|&index| trim_eol(body[index]) == b"---" || is_diff_start(index))
+        .unwrap_or(body.len());
+    let diff_start = (message_end..body.len()).find(|&index| is_diff_start(index))?;
+    let diff_lines = &body[diff_start..];
+    let diff = with_git_headers(&diff_lines[..signature_start(diff_lines)]);
+
+    let subject = strip_subject_prefix(&subject);
+    let description = String::from_utf8_lossy(&body[..message_end].concat())
+        .trim_end()
+        .to_string();
+    let message = [subject.as_str(), description.as_str()]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let message = if message.is_empty() {
+        message
+    } else {
+        message + "\n"
+    };
+
+    Some(MailPatch {
+        author,
+        date,
+        subject,
+        message,
+        diff,
+    })
+}
+
+fn trim_eol(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+fn is_header_line(line: &[u8]) -> bool {
+    line.iter()
+        .position(|&byte| byte == b':')
+        .is_some_and(|end| {
+            end > 0
+                && line[..end]
+                    .iter()
+                    .all(|&byte| byte.is_ascii_alphanumeric() || byte == b'-')
+        })
+}
+
+/// Header names and values, with folded lines joined
+fn parse_headers(lines: &[Line]) -> MailHeaders {
+    let mut headers = MailHeaders::new();
+    for line in lines {
+        let line = String::from_utf8_lossy(trim_eol(line));
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.to_string(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+/// Where the "-- " signature after the last hunk starts, if there is one
+fn signature_start(lines: &[Line]) -> usize {
+    lines
+        .iter()
+        .rposition(|line| trim_eol(line) == b"-- ")
+        .filter(|&separator| {
+            // A removed "- " line looks the same; hunk lines never follow a signature
+            lines[separator + 1..].iter().all(|line| {
+                !line.starts_with(b"diff ")
+                    && !matches!(line.first(), Some(b'+' | b'-' | b' ' | b'@' | b'\\'))
+            })
+        })
+        .unwrap_or(lines.len())
+}
+
+/// The diff with a `diff --git` header added before each plain "---"/"+++"
+/// file header, which libgit2 can't parse on its own. Paths are taken with
+/// one leading component stripped, like `git am -p1`, and timestamps dropped.
+fn with_git_headers(lines: &[Line]) -> Vec<u8> {
+    let mut diff = Vec::new();
+    let mut in_git_header = false;
+    let mut hunk_remaining: (usize, usize) = (0, 0); // Old and new lines left in the current hunk
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        if hunk_remaining != (0, 0) {
+            match line.first() {
+                Some(b' ') => {
+                    hunk_remaining.0 = hunk_remaining.0.saturating_sub(1);
+                    hunk_remaining.1 = hunk_remaining.1.saturating_sub(1);
+                }
+                Some(b'-') => hunk_remaining.0 = hunk_remaining.0.saturating_sub(1),
+                Some(b'+') => hunk_remaining.1 = hunk_remaining.1.saturating_sub(1),
+                Some(b'\\') => {}
+                _ => hunk_remaining = (0, 0),
+            }
+            diff.extend_from_slice(line);
+            index += 1;
+            continue;
+        }
+
+        if line.starts_with(b"diff --git ") {
+            in_git_header = true;
+        } else if line.starts_with(b"@@ ") {
+            in_git_header = false;
+            hunk_remaining = hunk_lengths(line);
+        } else if !in_git_header
+            && line.starts_with(b"--- ")
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.starts_with(b"+++ "))
+        {
+            let old_path = header_path(line);
+            let new_path = header_path(lines[index + 1]);
+            let name = if new_path == b"/dev/null" {
+                old_path
+            } else {
+                new_path
+            };
+            let name = name
+                .iter()
+                .position(|&byte| byte == b'/')
+                .map_or(name, |slash| &name[slash + 1..]);
+
+            diff.extend_from_slice(b"diff --git a/");
+            diff.extend_from_slice(name);
+            diff.extend_from_slice(b" b/");
+            diff.extend_from_slice(name);
+            diff.push(b'\n');
+            if old_path == b"/dev/null" {
+                diff.extend_from_slice(b"new file mode 100644\n");
+            } else if new_path == b"/dev/null" {
+                diff.extend_from_slice(b"deleted file mode 100644\n");
+            }
+            for (prefix, path) in [(b"--- ", old_path), (b"+++ ", new_path)] {
+                diff.extend_from_slice(prefix);
+                diff.extend_from_slice(path);
+                diff.push(b'\n');
+            }
+            index += 2;
+            continue;
+        }
+
+        diff.extend_from_slice(line);
+        index += 1;
+    }
+    diff
+}
+
+/// The path of a "---" or "+++" line, without a trailing timestamp
+fn header_path(line: &[u8]) -> &[u8] {
+    let path = trim_eol(&line[b"--- ".len()..]);
+    path.iter()
+        .position(|&byte| byte == b'\t')
+        .map_or(path, |tab| &path[..tab])
+}
+
+/// Old and new line counts from a hunk header such as "@@ -2,3 +2,4 @@"
+fn hunk_lengths(line: &[u8]) -> (usize, usize) {
+    let text = String::from_utf8_lossy(line);
+    let mut ranges = text.split_whitespace().skip(1).take(2).map(|range| {
+        range[1..]
+            .split_once(',')
+            .map_or(Some(1), |(_, count)| count.parse().ok())
+            .unwrap_or(0)
+    });
+    (ranges.next().unwrap_or(0), ranges.next().unwrap_or(0))
+}
+
+fn parse_date(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc2822(value.trim()).ok()
+}
+
+/// "Name <email>", "email (Name)" or a bare address
+fn parse_address(value: &str) -> Option<PatchAuthor> {
+    let value = decode_rfc2047(value.trim());
+    let (name, email) = match (value.rfind('<'), value.rfind('>')) {
+        (Some(open), Some(close)) if open < close => {
+            let name = value[..open].trim();
+            let name = name
+                .strip_prefix('"')
+                .and_then(|name| name.strip_suffix('"'))
+                .map_or_else(
+                    || name.to_string(),
+                    |name| name.replace("\\\"", "\"").replace("\\\\", "\\"),
+                );
+            (name, value[open + 1..close].trim().to_string())
+        }
+        _ => match (value.find('('), value.rfind(')')) {
+            (Some(open), Some(close)) if open < close => (
+                value[open + 1..close].trim().to_string(),
+                value[..open].trim().to_string(),
+            ),
+            _ => (String::new(), value.trim().to_string()),
+        },
+    };
+
+    if email.is_empty() {
+        return None;
+    }
+    Some(PatchAuthor {
+        name: if name.is_empty() { email.clone() } else { name },
+        email,
+    })
+}
+
+/// Drop "Re:" and bracketed prefixes such as "[PATCH v2 1/3]", like `git am`
+fn strip_subject_prefix(subject: &str) -> String {
+    let mut subject = subject.trim();
+    loop {
+        if subject
+            .get(..3)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("re:"))
+        {
+            subject = subject[3..].trim_start();
+        } else if subject.starts_with('[') {
+            match subject.find(']') {
+                Some(end) => subject = subject[end + 1..].trim_start(),
+                None => break,
+            }
+        } else {
+            break;
+        }
+    }
+    subject.to_string()
+}
+
+/// Decode RFC 2047 encoded words such as "=?UTF-8?q?Jos=C3=A9?="
+fn decode_rfc2047(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        if let Some((text, consumed)) = decode_encoded_word(&rest[start..]) {
+            // Whitespace between adjacent encoded words is not part of the text
+            let between = &rest[..start];
+            if !(after_word && between.trim().is_empty()) {
+                decoded.push_str(between);
+            }
+            decoded.push_str(&text);
+            rest = &rest[start + consumed..];
+            after_word = true;
+        } else {
+            decoded.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_word = false;
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// One "=?charset?encoding?text?=" word: its text and the length consumed
+fn decode_encoded_word(word: &str) -> Option<DecodedWord> {
+    let mut parts = word[2..].splitn(3, '?');
+    let charset = parts.next()?;
+    let encoding = parts.next()?;
+    let encoded = parts.next()?;
+    let end = encoded.find("?=")?;
+    let encoded = &encoded[..end];
+
+    let bytes = match encoding {
+        "q" | "Q" => decode_quoted_printable(encoded.replace('_', " ").as_bytes()),
+        "b" | "B" => decode_base64(encoded.as_bytes())?,
+        _ => return None,
+    };
+    let text = if charset.eq_ignore_ascii_case("iso-8859-1") {
+        bytes.iter().map(|&byte| byte as char).collect()
+    } else {
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+    let consumed = 2 + charset.len() + 1 + encoding.len() + 1 + end + 2;
+    Some((text, consumed))
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(input.len());
+    let mut index = 0;
+    while index < input.len() {
+        let byte = input[index];
+        if byte == b'=' {
+            let rest = &input[index + 1..];
+            // Soft line break
+            if rest.starts_with(b"\r\n") {
+                index += 3;
+                continue;
+            }
+            if rest.starts_with(b"\n") {
+                index += 2;
+                continue;
+            }
+            let value = rest
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+            if let Some(value) = value {
+                decoded.push(value);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(byte);
+        index += 1;
+    }
+    decoded
+}
+
+fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in input {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ if byte.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits).to_le_bytes()[0]);
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use crate::git::{FormatPatchOptions, patches_to_mbox};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_file(repo_path: &Path, file: &str, content: &str, message: &str) -> Result<String> {
+        fs::write(repo_path.join(file), content)?;
+        git(repo_path, &["add", file])?;
+        git(repo_path, &["commit", "-m", message])?;
+        git(repo_path, &["rev-parse", "HEAD"])
+    }
+
+    fn numbered_lines(changes: &[(usize, &str)]) -> String {
+        (1..=10)
+            .map(|number| {
+                changes
+                    .iter()
+                    .find(|(line, _)| *line == number)
+                    .map(|(_, text)| format!("{}\n", text))
+                    .unwrap_or_else(|| format!("line {}\n", number))
+            })
+            .collect()
+    }
+
+    /// A base commit on main and a "topic" branch with two commits by
+    /// another author, exported as an mbox. HEAD is back on main.
+    fn create_test_repo() -> Result<(TempDir, Vec<u8>)> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+        commit_file(path, "lines.txt", &numbered_lines(&[]), "Initial commit")?;
+
+        git(path, &["checkout", "-b", "topic"])?;
+        git(path, &["config", "user.name", "José Contributor"])?;
+        git(path, &["config", "user.email", "jose@example.com"])?;
+        let first = commit_file(
+            path,
+            "lines.txt",
+            &numbered_lines(&[(5, "line five")]),
+            "Spell out line five\n\nNumbers are hard to read.",
+        )?;
+        let second = commit_file(path, "notes.txt", "notes\n", "Add notes")?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+        git(path, &["checkout", "main"])?;
+
+        let repo = GitRepository::open(path)?;
+        let options = FormatPatchOptions {
+            cover_letter: true,
+            ..FormatPatchOptions::default()
+        };
+        let patches = repo.format_patches(&[first, second], &options)?;
+        Ok((temp_dir, patches_to_mbox(&patches)))
+    }
+
+    #[test]
+    fn test_parse_mailbox() -> Result<()> {
+        let (_temp_dir, mbox) = create_test_repo()?;
+        let patches = parse_mailbox(&mbox)?;
+
+        // The cover letter has no diff
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].subject, "Spell out line five");
+        assert_eq!(
+            patches[0].message,
+            "Spell out line five\n\nNumbers are hard to read.\n"
+        );
+        assert_eq!(
+            patches[0].author,
+            Some(PatchAuthor {
+                name: "José Contributor".to_string(),
+                email: "jose@example.com".to_string(),
+            })
+        );
+        assert!(patches[0].date.is_some());
+        assert!(
+            patches[0]
+                .diff
+                .starts_with(b"diff --git a/lines.txt b/lines.txt\n")
+        );
+        assert!(!String::from_utf8_lossy(&patches[0].diff).contains("gitk-rs"));
+        assert_eq!(patches[1].subject, "Add notes");
+
+        let diffs = patches[0].diffs()?;
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].new_file.as_deref(), Some("lines.txt"));
+        assert_eq!(diffs[0].stats.insertions, 1);
+        assert_eq!(diffs[0].stats.deletions, 1);
+
+        // A bare diff is a patch without metadata
+        let bare = parse_mailbox(&patches[1].diff)?;
+        assert_eq!(bare.len(), 1);
+        assert!(bare[0].author.is_none());
+        assert!(bare[0].message.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_plain_unified_diff() -> Result<()> {
+        let (temp_dir, _) = create_test_repo()?;
+        let path = temp_dir.path();
+        let mail = "From: Jane Doe <jane@example.com>\n\
+                    Subject: [PATCH] Spell out line three\n\
+                    \n\
+                    Made with diff -u rather than git.\n\
+                    \n\
+                    --- a/lines.txt\t2026-01-01 12:00:00\n\
+                    +++ b/lines.txt\t2026-01-02 12:00:00\n\
+                    @@ -2,3 +2,3 @@\n \
+                    line 2\n\
+                    -line 3\n\
+                    +line three\n \
+                    line 4\n";
+
+        let patches = parse_mailbox(mail.as_bytes())?;
+        assert_eq!(patches.len(), 1);
+        assert_eq!(
+            patches[0].message,
+            "Spell out line three\n\nMade with diff -u rather than git.\n"
+        );
+        assert!(patches[0].diff.starts_with(
+            b"diff --git a/lines.txt b/lines.txt\n--- a/lines.txt\n+++ b/lines.txt\n"
+        ));
+
+        let repo = GitRepository::open(path)?;
+        let config = PatchApplyConfig {
+            target: PatchApplyTarget::WorkingTree,
+            three_way: false,
+        };
+        assert!(repo.apply_patches(&patches, &config)?.is_success());
+        assert_eq!(
+            fs::read_to_string(path.join("lines.txt"))?,
+            numbered_lines(&[(3, "line three")])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_decoding() {
+        assert_eq!(
+            decode_rfc2047("=?UTF-8?q?Jos=C3=A9_Mar=C3=ADa?= <jm@example.com>"),
+            "José María <jm@example.com>"
+        );
+        assert_eq!(decode_rfc2047("=?utf-8?B?w7E=?= =?utf-8?B?YQ==?="), "ña");
+        assert_eq!(decode_rfc2047("=?iso-8859-1?q?caf=E9?="), "café");
+        assert_eq!(decode_rfc2047("plain =? text"), "plain =? text");
+
+        assert_eq!(
+            parse_address("\"Doe, Jane\" <jane@example.com>"),
+            Some(PatchAuthor {
+                name: "Doe, Jane".to_string(),
+                email: "jane@example.com".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_address("jane@example.com (Jane Doe)").map(|author| author.name),
+            Some("Jane Doe".to_string())
+        );
+
+        assert_eq!(strip_subject_prefix("Re: [PATCH v2 3/7] Fix it"), "Fix it");
+        assert_eq!(strip_subject_prefix("[RFC][PATCH] Fix [it]"), "Fix [it]");
+        assert_eq!(decode_quoted_printable(b"a=3Db=\nc"), b"a=bc");
+    }
+
+    #[test]
+    fn test_apply_as_commits_keeps_author_and_date() -> Result<()> {
+        let (temp_dir, mbox) = create_test_repo()?;
+        let path = temp_dir.path();
+        let repo = GitRepository::open(path)?;
+
+        let patches = parse_mailbox(&mbox)?;
+        let result = repo.apply_patches(&patches, &PatchApplyConfig::default())?;
+        assert!(result.is_success(), "{}", result.message);
+        assert_eq!(result.applied, 2);
+        assert_eq!(result.new_commits.len(), 2);
+
+        // Same trees as the original branch, same author and author date
+        assert_eq!(
+            git(path, &["rev-parse", "HEAD^{tree}"])?,
+            git(path, &["rev-parse", "topic^{tree}"])?
+        );
+        assert_eq!(
+            git(path, &["log", "-1", "--format=%an <%ae> %ad", "HEAD~1"])?,
+            git(path, &["log", "-1", "--format=%an <%ae> %ad", "topic~1"])?
+        );
+        assert_eq!(
+            git(path, &["log", "-1", "--format=%B", "HEAD~1"])?,
+            "Spell out line five\n\nNumbers are hard to read."
+        );
+        assert_eq!(git(path, &["status", "--porcelain"])?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_to_worktree_and_index() -> Result<()> {
+        let (temp_dir, mbox) = create_test_repo()?;
+        let path = temp_dir.path();
+        let repo = GitRepository::open(path)?;
+        let patches = parse_mailbox(&mbox)?;
+
+        let worktree = PatchApplyConfig {
+            target: PatchApplyTarget::WorkingTree,
+            three_way: false,
+        };
+        assert!(repo.apply_patches(&patches[..1], &worktree)?.is_success());
+        assert_eq!(git(path, &["status", "--porcelain"])?, "M lines.txt");
+
+        let index = PatchApplyConfig {
+            target: PatchApplyTarget::Index,
+            three_way: false,
+        };
+        assert!(repo.apply_patches(&patches[1..], &index)?.is_success());
+        assert_eq!(
+            git(path, &["diff", "--cached", "--name-only"])?,
+            "notes.txt"
+        );
+        assert!(!path.join("notes.txt").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejected_patch_reports_paths() -> Result<()> {
+        let (temp_dir, mbox) = create_test_repo()?;
+        let path = temp_dir.path();
+        commit_file(
+            path,
+            "lines.txt",
+            &numbered_lines(&[(3, "line three")]),
+            "Spell out line three",
+        )?;
+        let repo = GitRepository::open(path)?;
+        let patches = parse_mailbox(&mbox)?;
+
+        let config = PatchApplyConfig {
+            three_way: false,
+            ..PatchApplyConfig::default()
+        };
+        let result = repo.apply_patches(&patches, &config)?;
+        assert_eq!(result.stopped_at, Some(0));
+        assert!(!result.merged);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "lines.txt");
+        assert_eq!(result.conflicts[0].conflict_type, ConflictType::Content);
+        assert_eq!(git(path, &["status", "--porcelain"])?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_three_way_fallback() -> Result<()> {
+        let (temp_dir, mbox) = create_test_repo()?;
+        let path = temp_dir.path();
+        let patches = parse_mailbox(&mbox)?;
+
+        // Line three changed: the context no longer matches but merges cleanly
+        commit_file(
+            path,
+            "lines.txt",
+            &numbered_lines(&[(3, "line three")]),
+            "Spell out line three",
+        )?;
+        let repo = GitRepository::open(path)?;
+        let result = repo.apply_patches(&patches, &PatchApplyConfig::default())?;
+        assert!(result.is_success(), "{}", result.message);
+        assert_eq!(
+            fs::read_to_string(path.join("lines.txt"))?,
+            numbered_lines(&[(3, "line three"), (5, "line five")])
+        );
+        assert_eq!(git(path, &["status", "--porcelain"])?, "");
+
+        // Line five changed differently: a real conflict
+        git(path, &["reset", "--hard", "HEAD~2"])?;
+        commit_file(
+            path,
+            "lines.txt",
+            &numbered_lines(&[(5, "LINE FIVE")]),
+            "Shout line five",
+        )?;
+        let result = repo.apply_patches(&patches, &PatchApplyConfig::default())?;
+        assert_eq!(result.stopped_at, Some(0));
+        assert!(result.merged);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "lines.txt");
+        assert!(fs::read_to_string(path.join("lines.txt"))?.contains("<<<<<<<"));
+        assert_eq!(git(path, &["status", "--porcelain"])?, "UU lines.txt");
+        Ok(())
+    }
+
+    #[test]
+    fn test_continue_and_skip_after_conflict() -> Result<()> {
+        let (temp_dir, mbox) = create_test_repo()?;
+        let path = temp_dir.path();
+        let patches = parse_mailbox(&mbox)?;
+        let shouted = numbered_lines(&[(5, "LINE FIVE")]);
+        commit_file(path, "lines.txt", &shouted, "Shout line five")?;
+
+        let repo = GitRepository::open(path)?;
+        let config = PatchApplyConfig::default();
+        assert_eq!(repo.apply_patches(&patches, &config)?.stopped_at, Some(0));
+        assert!(repo.continue_patches(&patches, &config).is_err());
+
+        // Resolve and stage, then commit it as the mail's author
+        let resolved = numbered_lines(&[(5, "Line Five")]);
+        fs::write(path.join("lines.txt"), &resolved)?;
+        git(path, &["add", "lines.txt"])?;
+        let result = repo.continue_patches(&patches, &config)?;
+        assert!(result.is_success(), "{}", result.message);
+        assert_eq!(result.applied, 2);
+        assert_eq!(result.new_commits.len(), 2);
+        assert_eq!(
+            git(path, &["log", "-2", "--format=%an|%s"])?,
+            "José Contributor|Add notes\nJosé Contributor|Spell out line five"
+        );
+        assert_eq!(fs::read_to_string(path.join("lines.txt"))?, resolved);
+        assert_eq!(git(path, &["status", "--porcelain"])?, "");
+
+        // Skipping drops the conflicted patch and applies the next one
+        git(path, &["reset", "--hard", "HEAD~2"])?;
+        assert_eq!(repo.apply_patches(&patches, &config)?.stopped_at, Some(0));
+        let result = repo.skip_patch(&patches, &config)?;
+        assert!(result.is_success(), "{}", result.message);
+        assert_eq!(result.applied, 1);
+        assert_eq!(git(path, &["log", "-1", "--format=%s"])?, "Add notes");
+        assert_eq!(fs::read_to_string(path.join("lines.txt"))?, shouted);
+        assert_eq!(git(path, &["status", "--porcelain"])?, "");
+
+        // Outside commit mode only the merge is undone
+        git(path, &["reset", "--hard", "HEAD~1"])?;
+        let worktree = PatchApplyConfig {
+            target: PatchApplyTarget::WorkingTree,
+            three_way: true,
+        };
+        assert_eq!(repo.apply_patches(&patches, &worktree)?.stopped_at, Some(0));
+        let result = repo.skip_patch(&patches, &worktree)?;
+        assert!(result.is_success(), "{}", result.message);
+        assert_eq!(fs::read_to_string(path.join("lines.txt"))?, shouted);
+        assert_eq!(git(path, &["status", "--porcelain"])?, "?? notes.txt");
+        Ok(())
+    }
+
+    #[test]
+    fn test_three_way_conflict_is_staged_with_markers() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+        fs::write(path.join("other.txt"), numbered_lines(&[]))?;
+        commit_file(path, "lines.txt", &numbered_lines(&[]), "Initial commit")?;
+        git(path, &["add", "other.txt"])?;
+        git(path, &["commit", "--amend", "--no-edit"])?;
+
+        // One patch touching both files; only lines.txt conflicts
+        git(path, &["checkout", "-b", "topic"])?;
+        fs::write(path.join("other.txt"), numbered_lines(&[(2, "line two")]))?;
+        git(path, &["add", "other.txt"])?;
+        let topic = commit_file(
+            path,
+            "lines.txt",
+            &numbered_lines(&[(5, "line five")]),
+            "Spell out lines",
+        )?;
+        git(path, &["checkout", "main"])?;
+        fs::write(path.join("other.txt"), numbered_lines(&[(9, "line nine")]))?;
+        git(path, &["add", "other.txt"])?;
+        commit_file(
+            path,
+            "lines.txt",
+            &numbered_lines(&[(5, "LINE FIVE")]),
+            "Shout line five",
+        )?;
+
+        let repo = GitRepository::open(path)?;
+        let patches = repo.format_patches(&[topic], &FormatPatchOptions::default())?;
+        let patches = parse_mailbox(&patches_to_mbox(&patches))?;
+        let result = repo.apply_patches(&patches, &PatchApplyConfig::default())?;
+        assert_eq!(result.stopped_at, Some(0));
+        assert!(result.merged);
+
+        assert_eq!(result.conflicts.len(), 1);
+        let conflict = &result.conflicts[0];
+        assert_eq!(conflict.path, "lines.txt");
+        assert_eq!(conflict.conflict_type, ConflictType::Content);
+        assert!(conflict.ancestor_id.is_some());
+        assert_eq!(
+            conflict.our_id.as_deref(),
+            Some(git(path, &["rev-parse", "HEAD:lines.txt"])?.as_str())
+        );
+        assert_eq!(
+            conflict.their_id.as_deref(),
+            Some(git(path, &["rev-parse", "topic:lines.txt"])?.as_str())
+        );
+
+        // All three stages are in the index, as after `git apply --3way`
+        let stages: Vec<String> = git(path, &["ls-files", "-u"])?
+            .lines()
+            .map(|line| line.split_whitespace().nth(2).unwrap_or("").to_string())
+            .collect();
+        assert_eq!(stages, ["1", "2", "3"]);
+
+        let content = fs::read_to_string(path.join("lines.txt"))?;
+        assert!(content.contains("<<<<<<< "));
+        assert!(content.contains("LINE FIVE\n=======\nline five\n"));
+        assert!(content.contains(">>>>>>> "));
+        assert_eq!(
+            fs::read_to_string(path.join("other.txt"))?,
+            numbered_lines(&[(2, "line two"), (9, "line nine")])
+        );
+        assert_eq!(
+            git(path, &["status", "--porcelain"])?,
+            "UU lines.txt\nM  other.txt"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,514 @@
+use crate::git::GitRepository;
+use crate::models::GitCommit;
+use anyhow::{Result, anyhow};
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday,
+};
+
+/// Commits this much older than `since` are walked before giving up, so a
+/// little clock skew between parents and children doesn't cut history short
+const DATE_SLOP: usize = 5;
+
+/// Parse a date the way git's `--since`/`--until` do.
+///
+/// Accepts absolute forms (`2026-01-01`, `2026-01-01 12:30`, RFC 3339/2822,
+/// `@<unix time>`) or approximate ones such as `2.weeks.ago`, `yesterday`,
+/// `last friday`, `noon`, `5pm` and `jan 5`.
+///
+/// Times without a zone are taken as UTC. Like git, relative dates keep the
+/// current time of day unless one is given; a bare date means its midnight.
+pub fn parse_approxidate(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(anyhow!("Empty date"));
+    }
+
+    if let Some(date) = parse_absolute(input) {
+        return Ok(date);
+    }
+    parse_relative(&input.to_lowercase(), now).map_err(|e| anyhow!("Invalid date '{input}': {e}"))
+}
+
+/// `parse_approxidate` relative to the current time
+pub fn approxidate(input: &str) -> Result<DateTime<Utc>> {
+    parse_approxidate(input, Utc::now())
+}
+
+fn parse_absolute(input: &str) -> Option<DateTime<Utc>> {
+    if let Some(seconds) = input.strip_prefix('@') {
+        return DateTime::from_timestamp(seconds.parse().ok()?, 0);
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(input) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(input) {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%Y.%m.%d %H:%M:%S",
+    ] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(input, format) {
+            return Some(date.and_utc());
+        }
+    }
+    for format in ["%Y-%m-%d", "%Y.%m.%d", "%Y/%m/%d", "%m/%d/%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(input, format) {
+            return Some(date.and_time(NaiveTime::MIN).and_utc());
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DateToken {
+    Number(u32),
+    Word(String),
+}
+
+fn tokenize(input: &str) -> Vec<DateToken> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut digits = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                digits.push(c);
+                chars.next();
+            }
+            // Absurdly long numbers saturate and are rejected as out of range later
+            tokens.push(DateToken::Number(digits.parse().unwrap_or(u32::MAX)));
+        } else if c.is_alphabetic() {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_alphabetic()) {
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(DateToken::Word(word));
+        } else {
+            // Dots, commas, dashes and spaces all separate tokens
+            chars.next();
+        }
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+fn parse_unit(word: &str) -> Option<Unit> {
+    let singular = word.strip_suffix('s').unwrap_or(word);
+    match singular {
+        "second" | "sec" => Some(Unit::Second),
+        "minute" | "min" => Some(Unit::Minute),
+        "hour" => Some(Unit::Hour),
+        "day" => Some(Unit::Day),
+        "week" => Some(Unit::Week),
+        "month" => Some(Unit::Month),
+        "year" => Some(Unit::Year),
+        _ => None,
+    }
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ]
+    .into_iter()
+    .find(|day| {
+        let name = full_weekday(*day);
+        word == name || word == &name[..3]
+    })
+}
+
+const fn full_weekday(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    (1..)
+        .zip(MONTHS)
+        .find(|(_, month)| word.len() >= 3 && month.starts_with(word))
+        .map(|(number, _)| number)
+}
+
+fn subtract(date: DateTime<Utc>, amount: u32, unit: Unit) -> Result<DateTime<Utc>> {
+    let result = match unit {
+        Unit::Second => date.checked_sub_signed(Duration::seconds(i64::from(amount))),
+        Unit::Minute => date.checked_sub_signed(Duration::minutes(i64::from(amount))),
+        Unit::Hour => date.checked_sub_signed(Duration::hours(i64::from(amount))),
+        Unit::Day => date.checked_sub_signed(Duration::days(i64::from(amount))),
+        Unit::Week => date.checked_sub_signed(Duration::weeks(i64::from(amount))),
+        Unit::Month => date.checked_sub_months(Months::new(amount)),
+        Unit::Year => amount
+            .checked_mul(12)
+            .and_then(|months| date.checked_sub_months(Months::new(months))),
+    };
+    result.ok_or_else(|| anyhow!("date out of range"))
+}
+
+/// Move to `hour:00` on the same day, or the day before if that is still ahead of `now`
+fn at_hour(date: DateTime<Utc>, hour: u32, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let time = NaiveTime::from_hms_opt(hour, 0, 0).ok_or_else(|| anyhow!("invalid hour"))?;
+    let result = date.date_naive().and_time(time).and_utc();
+    Ok(if result > now {
+        result - Duration::days(1)
+    } else {
+        result
+    })
+}
+
+fn parse_relative(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let tokens = tokenize(input);
+    if tokens.is_empty() {
+        return Err(anyhow!("no date found"));
+    }
+
+    let mut date = now;
+    let mut month: Option<u32> = None;
+    let mut day: Option<u32> = None;
+    let mut year: Option<i32> = None;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let next_word = match tokens.get(i + 1) {
+            Some(DateToken::Word(word)) => Some(word.as_str()),
+            _ => None,
+        };
+
+        match &tokens[i] {
+            DateToken::Number(n) => {
+                if let Some(unit) = next_word.and_then(parse_unit) {
+                    date = subtract(date, *n, unit)?;
+                    i += 1;
+                } else if let Some(meridiem @ ("am" | "pm")) = next_word {
+                    if !(1..=12).contains(n) {
+                        return Err(anyhow!("invalid hour {n}"));
+                    }
+                    let hour = n % 12 + if meridiem == "pm" { 12 } else { 0 };
+                    date = at_hour(date, hour, now)?;
+                    i += 1;
+                } else if month.is_some() || next_word.and_then(parse_month).is_some() {
+                    if *n > 31 {
+                        year = Some(i32::try_from(*n)?);
+                    } else if day.is_none() {
+                        day = Some(*n);
+                    } else {
+                        return Err(anyhow!("unexpected number {n}"));
+                    }
+                } else {
+                    return Err(anyhow!("number {n} without a unit"));
+                }
+            }
+            DateToken::Word(word) => {
+                let word = word.as_str();
+                if let Some(unit) = parse_unit(word) {
+                    date = subtract(date, 1, unit)?;
+                } else if let Some(weekday) = parse_weekday(word) {
+                    // The most recent one before today, a week back if today is that day
+                    let today = date.weekday().num_days_from_monday();
+                    let back = match (today + 7 - weekday.num_days_from_monday()) % 7 {
+                        0 => 7,
+                        days => days,
+                    };
+                    date = subtract(date, back, Unit::Day)?;
+                } else if let Some(m) = parse_month(word) {
+                    month = Some(m);
+                } else {
+                    match word {
+                        "ago" | "now" | "today" | "at" => {}
+                        "yesterday" => date = subtract(date, 1, Unit::Day)?,
+                        "midnight" => date = at_hour(date, 0, now)?,
+                        "noon" => date = at_hour(date, 12, now)?,
+                        "tea" => date = at_hour(date, 17, now)?,
+                        // "last week" and "a day ago" are one unit back; "last friday" is "friday"
+                        "last" | "a" | "an"
+                            if next_word.is_some_and(|next| {
+                                parse_unit(next).is_some() || parse_weekday(next).is_some()
+                            }) => {}
+                        _ => return Err(anyhow!("unrecognized word '{word}'")),
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+
+    match month {
+        Some(month) => {
+            let day = day.unwrap_or(1);
+            let on = |year: i32| {
+                NaiveDate::from_ymd_opt(year, month, day)
+                    .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+                    .ok_or_else(|| anyhow!("no such day {year}-{month:02}-{day:02}"))
+            };
+            if let Some(year) = year {
+                on(year)
+            } else {
+                let this_year = on(now.year())?;
+                if this_year > now {
+                    on(now.year() - 1)
+                } else {
+                    Ok(this_year)
+                }
+            }
+        }
+        None if day.is_some() || year.is_some() => Err(anyhow!("day or year without a month")),
+        None => Ok(date),
+    }
+}
+
+/// A committer-date window, as given by `--since`/`--until`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    /// Parse both ends with `parse_approxidate`; `None` leaves that end open
+    pub fn parse(since: Option<&str>, until: Option<&str>, now: DateTime<Utc>) -> Result<Self> {
+        let parse = |value: Option<&str>| match value.map(str::trim) {
+            Some(value) if !value.is_empty() => parse_approxidate(value, now).map(Some),
+            _ => Ok(None),
+        };
+        Ok(Self {
+            since: parse(since)?,
+            until: parse(until)?,
+        })
+    }
+
+    #[must_use]
+    pub const fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    #[must_use]
+    pub fn contains(&self, when: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| when >= since) && self.until.is_none_or(|until| when <= until)
+    }
+
+    /// Whether a commit at `when` is older than the window
+    #[must_use]
+    pub fn is_before(&self, when: DateTime<Utc>) -> bool {
+        self.since.is_some_and(|since| when < since)
+    }
+}
+
+/// Stops a time-sorted walk once it has gone `DATE_SLOP` commits past `since`
+#[derive(Debug, Clone, Default)]
+pub struct DateCutoff {
+    range: DateRange,
+    old_in_a_row: usize,
+}
+
+impl DateCutoff {
+    #[must_use]
+    pub const fn new(range: DateRange) -> Self {
+        Self {
+            range,
+            old_in_a_row: 0,
+        }
+    }
+
+    /// Feed the next commit's committer date; `None` once the walk should stop,
+    /// otherwise whether the commit is inside the range
+    pub fn check(&mut self, when: DateTime<Utc>) -> Option<bool> {
+        if self.range.is_before(when) {
+            self.old_in_a_row += 1;
+            return (self.old_in_a_row < DATE_SLOP).then_some(false);
+        }
+        self.old_in_a_row = 0;
+        Some(self.range.contains(when))
+    }
+}
+
+/// Committer time of a git2 commit in UTC
+#[must_use]
+pub fn commit_time(commit: &git2::Commit) -> DateTime<Utc> {
+    DateTime::from_timestamp(commit.committer().when().seconds(), 0).unwrap_or_default()
+}
+
+impl GitRepository {
+    /// Up to `max_count` commits from HEAD committed inside the range,
+    /// stopping the walk once history is older than `since`
+    pub fn get_commits_in_date_range(
+        &self,
+        range: DateRange,
+        max_count: Option<usize>,
+    ) -> Result<Vec<GitCommit>> {
+        let mut revwalk = self.repo().revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        revwalk.push_head()?;
+
+        let limit = max_count.unwrap_or(1000);
+        let mut cutoff = DateCutoff::new(range);
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            if commits.len() >= limit {
+                break;
+            }
+            let commit = self.repo().find_commit(oid?)?;
+            match cutoff.check(commit_time(&commit)) {
+                None => break,
+                Some(true) => commits.push(GitCommit::new(&commit)?),
+                Some(false) => {}
+            }
+        }
+
+        Ok(commits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::{self, git_with_env};
+    use chrono::TimeZone;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    // Wednesday 2026-03-18 15:30:00 UTC
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 18, 15, 30, 0).unwrap()
+    }
+
+    fn parse(input: &str) -> DateTime<Utc> {
+        parse_approxidate(input, now()).unwrap()
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_absolute_dates() {
+        assert_eq!(parse("2026-01-01"), at(2026, 1, 1, 0, 0));
+        assert_eq!(parse("2026-01-01 12:30"), at(2026, 1, 1, 12, 30));
+        assert_eq!(parse("2026-01-01T12:30:00+02:00"), at(2026, 1, 1, 10, 30));
+        assert_eq!(parse("@1767225600"), at(2026, 1, 1, 0, 0));
+        assert_eq!(parse("01/02/2026"), at(2026, 1, 2, 0, 0));
+    }
+
+    #[test]
+    fn test_relative_dates() {
+        assert_eq!(parse("30.days.ago"), at(2026, 2, 16, 15, 30));
+        assert_eq!(parse("2 weeks ago"), at(2026, 3, 4, 15, 30));
+        assert_eq!(parse("2.weeks.3.days.ago"), at(2026, 3, 1, 15, 30));
+        assert_eq!(parse("1.month.ago"), at(2026, 2, 18, 15, 30));
+        assert_eq!(parse("last year"), at(2025, 3, 18, 15, 30));
+        assert_eq!(parse("yesterday"), at(2026, 3, 17, 15, 30));
+        assert_eq!(parse("yesterday noon"), at(2026, 3, 17, 12, 0));
+        assert_eq!(parse("midnight"), at(2026, 3, 18, 0, 0));
+        assert_eq!(parse("5pm"), at(2026, 3, 17, 17, 0));
+        assert_eq!(parse("last friday"), at(2026, 3, 13, 15, 30));
+        assert_eq!(parse("wednesday"), at(2026, 3, 11, 15, 30));
+        assert_eq!(parse("jan 5"), at(2026, 1, 5, 0, 0));
+        assert_eq!(parse("5 december"), at(2025, 12, 5, 0, 0));
+        assert_eq!(parse("dec 5 2024"), at(2024, 12, 5, 0, 0));
+    }
+
+    #[test]
+    fn test_invalid_dates() {
+        for input in ["", "soon", "3", "feb 30 2026", "13pm"] {
+            assert!(parse_approxidate(input, now()).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_date_cutoff_allows_slop() {
+        let range = DateRange::parse(Some("2026-03-01"), Some("2026-03-10"), now()).unwrap();
+        let mut cutoff = DateCutoff::new(range);
+
+        assert_eq!(cutoff.check(at(2026, 3, 15, 0, 0)), Some(false));
+        assert_eq!(cutoff.check(at(2026, 3, 5, 0, 0)), Some(true));
+        for _ in 1..DATE_SLOP {
+            assert_eq!(cutoff.check(at(2026, 2, 1, 0, 0)), Some(false));
+        }
+        // A skewed commit inside the window resets the count
+        assert_eq!(cutoff.check(at(2026, 3, 2, 0, 0)), Some(true));
+        for _ in 1..DATE_SLOP {
+            assert_eq!(cutoff.check(at(2026, 2, 1, 0, 0)), Some(false));
+        }
+        assert_eq!(cutoff.check(at(2026, 2, 1, 0, 0)), None);
+    }
+
+    fn git(repo_path: &Path, args: &[&str], date: Option<&str>) -> Result<String> {
+        match date {
+            Some(date) => git_with_env(
+                repo_path,
+                args,
+                &[("GIT_AUTHOR_DATE", date), ("GIT_COMMITTER_DATE", date)],
+            ),
+            None => test_support::git(repo_path, args),
+        }
+    }
+
+    #[test]
+    fn test_get_commits_in_date_range() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"], None)?;
+        git(path, &["config", "user.name", "Test User"], None)?;
+        git(path, &["config", "user.email", "test@example.com"], None)?;
+        for (message, date) in [
+            ("january", "2026-01-15T12:00:00Z"),
+            ("february", "2026-02-15T12:00:00Z"),
+            ("march", "2026-03-15T12:00:00Z"),
+        ] {
+            git(
+                path,
+                &["commit", "--allow-empty", "-m", message],
+                Some(date),
+            )?;
+        }
+
+        let repo = GitRepository::open(path)?;
+        let range = DateRange::parse(Some("2026-02-01"), Some("2026-03-01"), now())?;
+        let commits = repo.get_commits_in_date_range(range, None)?;
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["february"]);
+
+        Ok(())
+    }
+}
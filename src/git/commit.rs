@@ -1,6 +1,23 @@
 use crate::git::GitRepository;
 use crate::models::GitCommit;
 use anyhow::Result;
+use std::path::Path;
+
+/// Changed lines of one file, keyed by its new path
+pub type FileStat = (String, crate::models::GitDiffStats);
+
+/// Map a raw tree entry mode to `git2::FileMode`
+const fn filemode_from_raw(mode: i32) -> git2::FileMode {
+    match mode {
+        0o040_000 => git2::FileMode::Tree,
+        0o100_664 => git2::FileMode::BlobGroupWritable,
+        0o100_755 => git2::FileMode::BlobExecutable,
+        0o120_000 => git2::FileMode::Link,
+        0o160_000 => git2::FileMode::Commit,
+        0o100_644 => git2::FileMode::Blob,
+        _ => git2::FileMode::Unreadable,
+    }
+}
 
 impl GitRepository {
     pub fn get_commit_parents(&self, commit: &GitCommit) -> Result<Vec<GitCommit>> {
@@ -36,7 +53,7 @@ impl GitRepository {
                 name: entry.name().unwrap_or("").to_string(),
                 path: full_path,
                 id: entry.id().to_string(),
-                filemode: git2::FileMode::Tree, // Simplified for now
+                filemode: filemode_from_raw(entry.filemode()),
                 is_tree: entry.filemode() == 0o040000, // Tree mode in git
             });
 
@@ -47,6 +64,101 @@ impl GitRepository {
     }
 
     pub fn get_commit_diff_stats(&self, commit_id: &str) -> Result<crate::models::GitDiffStats> {
+        let diff = self.first_parent_diff(commit_id)?;
+        let stats = diff.stats()?;
+
+        Ok(crate::models::GitDiffStats {
+            files_changed: stats.files_changed(),
+            insertions: stats.insertions(),
+            deletions: stats.deletions(),
+        })
+    }
+
+    /// Per-file insertions and deletions of the same diff as
+    /// `get_commit_diff_stats`, keyed by the file's new path
+    pub fn get_commit_file_stats(&self, commit_id: &str) -> Result<Vec<FileStat>> {
+        let diff = self.first_parent_diff(commit_id)?;
+
+        let mut files = Vec::with_capacity(diff.deltas().len());
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(&diff, index)? else {
+                continue; // Binary or unchanged
+            };
+            let delta = patch.delta();
+            let file_path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let (_, insertions, deletions) = patch.line_stats()?;
+            files.push((
+                file_path,
+                crate::models::GitDiffStats {
+                    files_changed: 1,
+                    insertions,
+                    deletions,
+                },
+            ));
+        }
+
+        Ok(files)
+    }
+
+    /// Entries directly inside `directory` ("" for the root) at a commit,
+    /// directories first and each group by name, for browsing the tree
+    /// one level at a time
+    pub fn get_tree_directory(
+        &self,
+        commit_id: &str,
+        directory: &str,
+    ) -> Result<Vec<crate::models::TreeEntry>> {
+        let oid = git2::Oid::from_str(commit_id)?;
+        let root = self.repo().find_commit(oid)?.tree()?;
+        let tree = if directory.is_empty() {
+            root
+        } else {
+            let entry = root.get_path(Path::new(directory))?;
+            self.repo().find_tree(entry.id())?
+        };
+
+        let mut entries: Vec<crate::models::TreeEntry> = tree
+            .iter()
+            .map(|entry| {
+                let name = entry.name().unwrap_or("").to_string();
+                crate::models::TreeEntry {
+                    path: if directory.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{directory}/{name}")
+                    },
+                    name,
+                    id: entry.id().to_string(),
+                    filemode: filemode_from_raw(entry.filemode()),
+                    is_tree: entry.filemode() == 0o040_000,
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| b.is_tree.cmp(&a.is_tree).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(entries)
+    }
+
+    /// Paths the commit added, modified or deleted relative to its first
+    /// parent
+    pub fn get_commit_changed_paths(&self, commit_id: &str) -> Result<Vec<String>> {
+        let diff = self.first_parent_diff(commit_id)?;
+
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    /// The commit's changes against its first parent, or against the
+    /// empty tree for a root commit
+    fn first_parent_diff(&self, commit_id: &str) -> Result<git2::Diff<'_>> {
         let oid = git2::Oid::from_str(commit_id)?;
         let commit = self.repo().find_commit(oid)?;
 
@@ -57,16 +169,82 @@ impl GitRepository {
             None
         };
 
-        let diff = self
+        Ok(self
             .repo()
-            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?)
+    }
+}
 
-        let stats = diff.stats()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git_with_env;
+    use std::fs;
+    use tempfile::TempDir;
 
-        Ok(crate::models::GitDiffStats {
-            files_changed: stats.files_changed(),
-            insertions: stats.insertions(),
-            deletions: stats.deletions(),
-        })
+    const IDENTITY: &[(&str, &str)] = &[
+        ("GIT_AUTHOR_NAME", "Test User"),
+        ("GIT_AUTHOR_EMAIL", "test@example.com"),
+        ("GIT_COMMITTER_NAME", "Test User"),
+        ("GIT_COMMITTER_EMAIL", "test@example.com"),
+    ];
+
+    fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+        git_with_env(repo_path, args, IDENTITY)
+    }
+
+    fn names(entries: &[crate::models::TreeEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tree_directory_lists_one_level() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        fs::create_dir_all(path.join("src/ui"))?;
+        fs::write(path.join("README"), "readme\n")?;
+        fs::write(path.join("src/lib.rs"), "mod ui;\n")?;
+        fs::write(path.join("src/ui/mod.rs"), "\n")?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Initial"])?;
+        let head = git(path, &["rev-parse", "HEAD"])?;
+        let repo = GitRepository::open(path)?;
+
+        let root = repo.get_tree_directory(&head, "")?;
+        assert_eq!(names(&root), vec!["src", "README"]);
+        assert!(root[0].is_tree);
+
+        let src = repo.get_tree_directory(&head, "src")?;
+        assert_eq!(names(&src), vec!["src/ui", "src/lib.rs"]);
+        assert!(repo.get_tree_directory(&head, "missing").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_changed_paths_against_first_parent() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        fs::create_dir(path.join("dir"))?;
+        fs::write(path.join("a.txt"), "a\n")?;
+        fs::write(path.join("dir/b.txt"), "b\n")?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Initial"])?;
+        let first = git(path, &["rev-parse", "HEAD"])?;
+
+        fs::write(path.join("dir/b.txt"), "b\nb\n")?;
+        fs::write(path.join("c.txt"), "c\n")?;
+        git(path, &["rm", "-q", "a.txt"])?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Change"])?;
+        let second = git(path, &["rev-parse", "HEAD"])?;
+        let repo = GitRepository::open(path)?;
+
+        let mut changed = repo.get_commit_changed_paths(&second)?;
+        changed.sort();
+        assert_eq!(changed, vec!["a.txt", "c.txt", "dir/b.txt"]);
+        assert_eq!(repo.get_commit_changed_paths(&first)?.len(), 2);
+        Ok(())
     }
 }
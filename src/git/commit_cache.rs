@@ -0,0 +1,603 @@
+use crate::git::{CommitScan, GitRepository, ScanEvent};
+use crate::models::{GitCommit, GitSignature};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// Bumped whenever the on-disk layout changes; older files are ignored
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// HEAD's first-page history, persisted between launches so the commit list
+/// can be shown before anything is walked.
+///
+/// The cache records the ref tips it was built from. If HEAD has not moved
+/// the commits are reused as-is; if HEAD only moved forward, just the new
+/// commits are walked and merged in by date. Anything else (reset, rebase, a larger
+/// commit limit than the cache was built with) discards the cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitCache {
+    version: u32,
+    repo_path: PathBuf,
+    limit: usize,
+    tips: RefTips, // Ref name -> target, including "HEAD"
+    commits: Vec<CachedCommit>,
+}
+
+/// Author or committer name, email and Unix time
+type SignatureFields = (String, String, i64);
+
+/// Ref targets by ref name
+type RefTips = BTreeMap<String, String>;
+
+/// Commits restored from a cache, newest first
+type CachedHistory = Vec<GitCommit>;
+
+/// Compact commit metadata; parents inside the cache are stored by index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedCommit {
+    id: String,
+    short_id: String,
+    author: SignatureFields,
+    committer: SignatureFields,
+    summary: String,
+    message: String,
+    tree_id: String,
+    parents: Vec<ParentLink>,
+    #[serde(default)]
+    is_signed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ParentLink {
+    Index(u32), // Position of the parent within the cache
+    Id(String), // Parent outside the cached range
+}
+
+/// How a cache relates to the repository's current refs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheStatus {
+    Current,          // Every ref tip matches
+    HeadUnchanged,    // Other refs moved; HEAD's history is the same
+    FastForward(Oid), // HEAD moved forward from the cached tip
+    Stale,            // History was rewritten or the cache is too short
+}
+
+impl CommitCache {
+    /// Snapshot `commits` (HEAD's history, newest first) with the current ref tips
+    pub fn new(repo: &GitRepository, commits: &[GitCommit], limit: usize) -> Result<Self> {
+        let positions: HashMap<&str, u32> = (0..)
+            .zip(commits)
+            .map(|(index, commit)| (commit.id.as_str(), index))
+            .collect();
+
+        let commits = commits
+            .iter()
+            .map(|commit| CachedCommit::new(commit, &positions))
+            .collect();
+
+        Ok(Self {
+            version: CACHE_FORMAT_VERSION,
+            repo_path: repo_key(repo),
+            limit,
+            tips: collect_tips(repo.repo())?,
+            commits,
+        })
+    }
+
+    /// Where the cache for `repo` lives under the user's data directory
+    pub fn default_path(repo: &GitRepository) -> Option<PathBuf> {
+        let key = repo_key(repo);
+        let name = format!("{:016x}.json", fnv1a(key.to_string_lossy().as_bytes()));
+        dirs::data_dir().map(|dir| dir.join("gitk-rust").join("commit-cache").join(name))
+    }
+
+    /// Read a cache file, ignoring files from another repository or format
+    pub fn read(path: &Path, repo: &GitRepository) -> Result<Option<Self>> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let cache: Self = serde_json::from_slice(&content)?;
+        if cache.version != CACHE_FORMAT_VERSION || cache.repo_path != repo_key(repo) {
+            debug!(
+                "Ignoring commit cache at {} built for another repository",
+                path.display()
+            );
+            return Ok(None);
+        }
+        Ok(Some(cache))
+    }
+
+    /// Write the cache, replacing any previous file atomically
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // A unique temp file, so two windows on one repository never share it
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut temp_file = tempfile::NamedTempFile::new_in(dir)?;
+        temp_file.write_all(&serde_json::to_vec(self)?)?;
+        temp_file.persist(path)?;
+        Ok(())
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.commits.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.commits.is_empty()
+    }
+
+    /// Compare the cached ref tips against the repository
+    pub fn status(&self, repo: &GitRepository, limit: usize) -> Result<CacheStatus> {
+        // A truncated cache cannot serve a longer history
+        if limit > self.limit && self.commits.len() >= self.limit {
+            return Ok(CacheStatus::Stale);
+        }
+
+        let tips = collect_tips(repo.repo())?;
+        if tips == self.tips {
+            return Ok(CacheStatus::Current);
+        }
+
+        let (Some(old_head), Some(new_head)) = (self.tips.get("HEAD"), tips.get("HEAD")) else {
+            return Ok(CacheStatus::Stale);
+        };
+        if old_head == new_head {
+            return Ok(CacheStatus::HeadUnchanged);
+        }
+
+        let old_head = Oid::from_str(old_head)?;
+        let new_head = Oid::from_str(new_head)?;
+        if repo.repo().graph_descendant_of(new_head, old_head)? {
+            Ok(CacheStatus::FastForward(old_head))
+        } else {
+            Ok(CacheStatus::Stale)
+        }
+    }
+
+    /// Bring the cache up to date with `repo`, walking only commits that are
+    /// not cached yet. Returns the number of commits walked, or `None` when the
+    /// cache could not be reused and must be rebuilt from a full walk.
+    pub fn refresh(&mut self, repo: &GitRepository, limit: usize) -> Result<Option<usize>> {
+        let walked = match self.status(repo, limit)? {
+            CacheStatus::Current => 0,
+            CacheStatus::HeadUnchanged => {
+                self.tips = collect_tips(repo.repo())?;
+                0
+            }
+            CacheStatus::FastForward(old_head) => {
+                let new_commits = walk_new_commits(repo.repo(), old_head, limit)?;
+                let walked = new_commits.len();
+                // A merge can bring in commits older than cached ones
+                let mut commits = merge_by_date(new_commits, self.commits());
+                commits.truncate(limit);
+                *self = Self::new(repo, &commits, limit)?;
+                walked
+            }
+            CacheStatus::Stale => return Ok(None),
+        };
+
+        if self.commits.len() > limit {
+            let commits: Vec<GitCommit> = self.commits().into_iter().take(limit).collect();
+            *self = Self::new(repo, &commits, limit)?;
+        }
+        self.limit = limit;
+        Ok(Some(walked))
+    }
+
+    /// Rebuild the cached commits, newest first
+    #[must_use]
+    pub fn commits(&self) -> Vec<GitCommit> {
+        self.commits
+            .iter()
+            .map(|cached| cached.to_commit(&self.commits))
+            .collect()
+    }
+}
+
+impl CachedCommit {
+    fn new(commit: &GitCommit, positions: &HashMap<&str, u32>) -> Self {
+        let parents = commit
+            .parent_ids
+            .iter()
+            .map(|id| match positions.get(id.as_str()) {
+                Some(&index) => ParentLink::Index(index),
+                None => ParentLink::Id(id.clone()),
+            })
+            .collect();
+
+        Self {
+            id: commit.id.clone(),
+            short_id: commit.short_id.clone(),
+            author: signature_fields(&commit.author),
+            committer: signature_fields(&commit.committer),
+            summary: commit.summary.clone(),
+            message: commit.message.clone(),
+            tree_id: commit.tree_id.clone(),
+            parents,
+            is_signed: commit.is_signed,
+        }
+    }
+
+    fn to_commit(&self, commits: &[Self]) -> GitCommit {
+        let parent_ids = self
+            .parents
+            .iter()
+            .map(|parent| match parent {
+                ParentLink::Index(index) => commits
+                    .get(*index as usize)
+                    .map(|commit| commit.id.clone())
+                    .unwrap_or_default(),
+                ParentLink::Id(id) => id.clone(),
+            })
+            .collect();
+
+        GitCommit {
+            id: self.id.clone(),
+            short_id: self.short_id.clone(),
+            author: signature_from_fields(&self.author),
+            committer: signature_from_fields(&self.committer),
+            message: self.message.clone(),
+            summary: self.summary.clone(),
+            parent_ids,
+            tree_id: self.tree_id.clone(),
+            is_signed: self.is_signed,
+            notes: Vec::new(),
+        }
+    }
+}
+
+fn signature_fields(signature: &GitSignature) -> SignatureFields {
+    (
+        signature.name.clone(),
+        signature.email.clone(),
+        signature.when.timestamp(),
+    )
+}
+
+fn signature_from_fields((name, email, when): &SignatureFields) -> GitSignature {
+    GitSignature {
+        name: name.clone(),
+        email: email.clone(),
+        when: DateTime::from_timestamp(*when, 0).unwrap_or_else(Utc::now),
+    }
+}
+
+/// Identify a repository by its canonical git directory
+fn repo_key(repo: &GitRepository) -> PathBuf {
+    let path = repo.repo().path();
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// FNV-1a, stable across builds unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Current targets of HEAD and every ref that points at an object
+fn collect_tips(repo: &Repository) -> Result<RefTips> {
+    let mut tips = BTreeMap::new();
+    if let Ok(head) = repo.head() {
+        if let Some(target) = head.target() {
+            tips.insert("HEAD".to_string(), target.to_string());
+        }
+    }
+    for reference in repo.references()?.flatten() {
+        if let (Some(name), Some(target)) = (reference.name(), reference.target()) {
+            tips.insert(name.to_string(), target.to_string());
+        }
+    }
+    Ok(tips)
+}
+
+/// Commits reachable from HEAD but not from `old_head`, newest first
+fn walk_new_commits(repo: &Repository, old_head: Oid, limit: usize) -> Result<Vec<GitCommit>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME)?;
+    revwalk.push_head()?;
+    revwalk.hide(old_head)?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk.take(limit) {
+        commits.push(GitCommit::new(&repo.find_commit(oid?)?)?);
+    }
+    Ok(commits)
+}
+
+/// Merge two newest-first lists into the order of a time-sorted walk; on
+/// equal dates the new commits, usually descendants, come first
+fn merge_by_date(new: Vec<GitCommit>, cached: Vec<GitCommit>) -> Vec<GitCommit> {
+    let mut merged = Vec::with_capacity(new.len() + cached.len());
+    let mut new = new.into_iter().peekable();
+    let mut cached = cached.into_iter().peekable();
+    while let (Some(new_commit), Some(cached_commit)) = (new.peek(), cached.peek()) {
+        let next = if new_commit.committer.when >= cached_commit.committer.when {
+            new.next()
+        } else {
+            cached.next()
+        };
+        merged.extend(next);
+    }
+    merged.extend(new);
+    merged.extend(cached);
+    merged
+}
+
+/// Incremental output of a background cache load; the one item is HEAD's
+/// history, absent when there is no usable cache
+pub type CommitCacheEvent = ScanEvent<Vec<GitCommit>>;
+
+/// A commit cache load running on a background thread
+pub type CommitCacheLoad = CommitScan<Vec<GitCommit>>;
+
+impl GitRepository {
+    /// Load HEAD's history from the on-disk cache on a background thread,
+    /// walking only commits added since it was written
+    pub fn start_cached_commit_load(&self, limit: usize) -> Result<CommitCacheLoad> {
+        self.start_cached_commit_load_from(self.commit_cache_path()?, limit)
+    }
+
+    fn start_cached_commit_load_from(
+        &self,
+        path: PathBuf,
+        limit: usize,
+    ) -> Result<CommitCacheLoad> {
+        let repo_path = self.repo().path().to_path_buf();
+        CommitScan::start("commit-cache-load", (), None, move |scan| {
+            let repo = Self::open(&repo_path)?;
+            if let Some(commits) = repo.load_cached_commits(&path, limit)? {
+                scan.report(commits);
+            }
+            Ok(())
+        })
+    }
+
+    fn load_cached_commits(&self, path: &Path, limit: usize) -> Result<Option<CachedHistory>> {
+        let Some(mut cache) = CommitCache::read(path, self)? else {
+            return Ok(None);
+        };
+
+        let Some(walked) = cache.refresh(self, limit)? else {
+            debug!("Commit cache for {} is stale", self.repo().path().display());
+            return Ok(None);
+        };
+        if walked > 0 {
+            info!("Walked {} new commits on top of the commit cache", walked);
+            if let Err(e) = cache.write(path) {
+                warn!("Failed to update commit cache: {}", e);
+            }
+        }
+        Ok(Some(cache.commits()))
+    }
+
+    /// Persist HEAD's history on a background thread so the next launch can
+    /// skip the walk
+    pub fn start_commit_cache_save(&self, commits: Vec<GitCommit>, limit: usize) -> Result<()> {
+        let path = self.commit_cache_path()?;
+        let repo_path = self.repo().path().to_path_buf();
+        std::thread::Builder::new()
+            .name("commit-cache-save".to_string())
+            .spawn(move || {
+                let saved = Self::open(&repo_path)
+                    .and_then(|repo| CommitCache::new(&repo, &commits, limit)?.write(&path));
+                if let Err(e) = saved {
+                    warn!("Failed to write commit cache: {}", e);
+                }
+            })?;
+        Ok(())
+    }
+
+    fn commit_cache_path(&self) -> Result<PathBuf> {
+        CommitCache::default_path(self).ok_or_else(|| anyhow!("No data directory available"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::{git, git_with_env};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn commit_file(repo_path: &Path, file: &str, content: &str) -> Result<String> {
+        fs::write(repo_path.join(file), content)?;
+        git(repo_path, &["add", file])?;
+        git(repo_path, &["commit", "-m", &format!("Change {}", file)])?;
+        git(repo_path, &["rev-parse", "HEAD"])
+    }
+
+    fn create_test_repo() -> Result<TempDir> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        commit_file(path, "a.txt", "a")?;
+        commit_file(path, "b.txt", "b")?;
+        commit_file(path, "c.txt", "c")?;
+        Ok(temp_dir)
+    }
+
+    fn head_history(repo: &GitRepository) -> Result<Vec<GitCommit>> {
+        let mut revwalk = repo.repo().revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        revwalk.push_head()?;
+        revwalk
+            .map(|oid| GitCommit::new(&repo.repo().find_commit(oid?)?))
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip_preserves_commits() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let commits = head_history(&repo)?;
+        let cache_path = temp_dir.path().join("cache.json");
+
+        CommitCache::new(&repo, &commits, 100)?.write(&cache_path)?;
+        let cache = CommitCache::read(&cache_path, &repo)?.expect("cache should load");
+        assert_eq!(cache.status(&repo, 100)?, CacheStatus::Current);
+
+        let restored = cache.commits();
+        assert_eq!(restored.len(), 3);
+        for (original, restored) in commits.iter().zip(&restored) {
+            assert_eq!(original.id, restored.id);
+            assert_eq!(original.parent_ids, restored.parent_ids);
+            assert_eq!(original.summary, restored.summary);
+            assert_eq!(original.author.when, restored.author.when);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_walks_only_new_commits() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let mut cache = CommitCache::new(&repo, &head_history(&repo)?, 100)?;
+
+        let new_head = commit_file(temp_dir.path(), "d.txt", "d")?;
+        assert!(matches!(
+            cache.status(&repo, 100)?,
+            CacheStatus::FastForward(_)
+        ));
+        assert_eq!(cache.refresh(&repo, 100)?, Some(1));
+
+        let commits = cache.commits();
+        assert_eq!(commits.len(), 4);
+        assert_eq!(commits[0].id, new_head);
+        assert_eq!(commits[0].parent_ids, vec![commits[1].id.clone()]);
+        assert_eq!(cache.status(&repo, 100)?, CacheStatus::Current);
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_merges_older_commits_by_date() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        let commit_on = |file: &str, date: &str| -> Result<()> {
+            fs::write(path.join(file), file)?;
+            git(path, &["add", file])?;
+            let dates = [("GIT_AUTHOR_DATE", date), ("GIT_COMMITTER_DATE", date)];
+            git_with_env(path, &["commit", "-m", file], &dates)?;
+            Ok(())
+        };
+        commit_on("base.txt", "2024-01-01T12:00:00")?;
+        git(path, &["checkout", "-b", "topic"])?;
+        commit_on("topic.txt", "2024-01-02T12:00:00")?;
+        git(path, &["checkout", "main"])?;
+        commit_on("main.txt", "2024-01-03T12:00:00")?;
+
+        let repo = GitRepository::open(path)?;
+        let mut cache = CommitCache::new(&repo, &head_history(&repo)?, 100)?;
+
+        // The merge brings in a commit older than the cached main commit
+        let dates = [
+            ("GIT_AUTHOR_DATE", "2024-01-04T12:00:00"),
+            ("GIT_COMMITTER_DATE", "2024-01-04T12:00:00"),
+        ];
+        git_with_env(
+            path,
+            &["merge", "--no-ff", "-m", "Merge topic", "topic"],
+            &dates,
+        )?;
+        assert_eq!(cache.refresh(&repo, 100)?, Some(2));
+
+        let ids = |commits: Vec<GitCommit>| -> Vec<String> {
+            commits.into_iter().map(|commit| commit.id).collect()
+        };
+        assert_eq!(ids(cache.commits()), ids(head_history(&repo)?));
+
+        Ok(())
+    }
+
+    fn wait_for_load(load: &mut CommitCacheLoad) -> Result<Option<Vec<GitCommit>>> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut commits = None;
+        while Instant::now() < deadline {
+            match load.try_next() {
+                Some(ScanEvent::Item(loaded)) => commits = Some(loaded),
+                Some(ScanEvent::Finished { .. }) => return Ok(commits),
+                Some(ScanEvent::Failed(e)) => return Err(anyhow!(e)),
+                Some(ScanEvent::Progress { .. }) => {}
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        Err(anyhow!("Cache load did not finish"))
+    }
+
+    #[test]
+    fn test_background_load_refreshes_and_rewrites_cache() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let cache_dir = TempDir::new()?;
+        let cache_path = cache_dir.path().join("cache.json");
+
+        let mut load = repo.start_cached_commit_load_from(cache_path.clone(), 100)?;
+        assert!(wait_for_load(&mut load)?.is_none());
+
+        CommitCache::new(&repo, &head_history(&repo)?, 100)?.write(&cache_path)?;
+        let new_head = commit_file(temp_dir.path(), "d.txt", "d")?;
+        let mut load = repo.start_cached_commit_load_from(cache_path.clone(), 100)?;
+        let commits = wait_for_load(&mut load)?.expect("cache should load");
+        assert_eq!(commits.len(), 4);
+        assert_eq!(commits[0].id, new_head);
+
+        // The refreshed cache replaced the file without leaving temp files
+        let cache = CommitCache::read(&cache_path, &repo)?.expect("cache should load");
+        assert_eq!(cache.status(&repo, 100)?, CacheStatus::Current);
+        assert_eq!(fs::read_dir(cache_dir.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewritten_history_is_stale() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let mut cache = CommitCache::new(&repo, &head_history(&repo)?, 100)?;
+
+        git(temp_dir.path(), &["reset", "--hard", "HEAD~1"])?;
+        commit_file(temp_dir.path(), "other.txt", "other")?;
+        assert_eq!(cache.status(&repo, 100)?, CacheStatus::Stale);
+        assert_eq!(cache.refresh(&repo, 100)?, None);
+
+        // A truncated cache cannot serve a longer limit either
+        let truncated = CommitCache::new(&repo, &head_history(&repo)?[..2], 2)?;
+        assert_eq!(truncated.status(&repo, 10)?, CacheStatus::Stale);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_for_other_repository_is_ignored() -> Result<()> {
+        let first = create_test_repo()?;
+        let second = create_test_repo()?;
+        let first_repo = GitRepository::open(first.path())?;
+        let second_repo = GitRepository::open(second.path())?;
+        let cache_path = first.path().join("cache.json");
+
+        CommitCache::new(&first_repo, &head_history(&first_repo)?, 100)?.write(&cache_path)?;
+        assert!(CommitCache::read(&cache_path, &second_repo)?.is_none());
+        assert!(CommitCache::read(&first.path().join("missing.json"), &first_repo)?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,611 @@
+use crate::git::{CommitScan, GitRepository, ScanContext, ScanEvent};
+use crate::models::GitCommit;
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use git2::{Oid, Time};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A column of a commit export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommitExportField {
+    Sha,
+    ShortSha,
+    Author,
+    AuthorEmail,
+    AuthorDate,
+    Committer,
+    CommitterEmail,
+    CommitDate,
+    Summary,
+    Message,
+    Parents,
+    Refs,
+    Diffstat, // Files changed, insertions and deletions against the first parent
+    Trailers,
+}
+
+impl CommitExportField {
+    pub const ALL: [Self; 14] = [
+        Self::Sha,
+        Self::ShortSha,
+        Self::Author,
+        Self::AuthorEmail,
+        Self::AuthorDate,
+        Self::Committer,
+        Self::CommitterEmail,
+        Self::CommitDate,
+        Self::Summary,
+        Self::Message,
+        Self::Parents,
+        Self::Refs,
+        Self::Diffstat,
+        Self::Trailers,
+    ];
+
+    /// Name used for the column header, JSON key and `--fields` list
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Sha => "sha",
+            Self::ShortSha => "short_sha",
+            Self::Author => "author",
+            Self::AuthorEmail => "author_email",
+            Self::AuthorDate => "author_date",
+            Self::Committer => "committer",
+            Self::CommitterEmail => "committer_email",
+            Self::CommitDate => "commit_date",
+            Self::Summary => "summary",
+            Self::Message => "message",
+            Self::Parents => "parents",
+            Self::Refs => "refs",
+            Self::Diffstat => "diffstat",
+            Self::Trailers => "trailers",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name() == name)
+            .ok_or_else(|| anyhow!("unknown export field '{name}'"))
+    }
+
+    /// Comma separated field names, e.g. "`sha,author,author_date`"
+    pub fn parse_list(names: &str) -> Result<Vec<Self>> {
+        let fields = names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(Self::parse)
+            .collect::<Result<Vec<_>>>()?;
+        if fields.is_empty() {
+            bail!("no export fields given");
+        }
+        Ok(fields)
+    }
+
+    /// CSV header cells; the diffstat spans three columns
+    fn csv_headers(self) -> Vec<&'static str> {
+        match self {
+            Self::Diffstat => vec!["files_changed", "insertions", "deletions"],
+            field => vec![field.name()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitExportFormat {
+    Csv,
+    Json, // An array with one object per commit
+}
+
+impl CommitExportFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => bail!("unknown export format '{name}', expected csv or json"),
+        }
+    }
+
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitExportConfig {
+    pub fields: Vec<CommitExportField>, // Columns in output order
+    pub format: CommitExportFormat,
+}
+
+impl Default for CommitExportConfig {
+    fn default() -> Self {
+        Self {
+            fields: vec![
+                CommitExportField::Sha,
+                CommitExportField::Author,
+                CommitExportField::AuthorEmail,
+                CommitExportField::AuthorDate,
+                CommitExportField::Summary,
+                CommitExportField::Refs,
+            ],
+            format: CommitExportFormat::Csv,
+        }
+    }
+}
+
+/// Full ref names by target commit
+type RefsByCommit = HashMap<String, Vec<String>>;
+
+/// Files changed, insertions and deletions
+type Diffstat = (usize, usize, usize);
+
+/// Writes commits one at a time so exports of any size run in constant
+/// memory. Call `finish` after the last commit to close the JSON array.
+pub struct CommitExporter<'a, W: Write> {
+    repo: &'a GitRepository,
+    writer: W,
+    config: CommitExportConfig,
+    refs: RefsByCommit,
+    written: usize,
+}
+
+impl<'a, W: Write> CommitExporter<'a, W> {
+    pub fn new(repo: &'a GitRepository, mut writer: W, config: CommitExportConfig) -> Result<Self> {
+        let mut refs = RefsByCommit::new();
+        if config.fields.contains(&CommitExportField::Refs) {
+            for git_ref in repo.get_ref_manager()?.get_all_refs() {
+                refs.entry(git_ref.target.clone())
+                    .or_default()
+                    .push(git_ref.full_name.clone());
+            }
+            for names in refs.values_mut() {
+                names.sort_unstable();
+            }
+        }
+
+        match config.format {
+            CommitExportFormat::Csv => {
+                let headers: Vec<&str> = config
+                    .fields
+                    .iter()
+                    .flat_map(|field| field.csv_headers())
+                    .collect();
+                writeln!(writer, "{}", headers.join(","))?;
+            }
+            CommitExportFormat::Json => write!(writer, "[")?,
+        }
+
+        Ok(Self {
+            repo,
+            writer,
+            config,
+            refs,
+            written: 0,
+        })
+    }
+
+    pub fn write_commit(&mut self, commit: &GitCommit) -> Result<()> {
+        let raw = self.repo.repo().find_commit(Oid::from_str(&commit.id)?)?;
+        let values: Vec<_> = self
+            .config
+            .fields
+            .iter()
+            .map(|&field| Ok((field, self.field_value(field, commit, &raw)?)))
+            .collect::<Result<_>>()?;
+
+        match self.config.format {
+            CommitExportFormat::Csv => {
+                let cells: Vec<String> = values
+                    .iter()
+                    .flat_map(|(field, value)| csv_cells(*field, value))
+                    .map(|cell| csv_quote(&cell))
+                    .collect();
+                writeln!(self.writer, "{}", cells.join(","))?;
+            }
+            CommitExportFormat::Json => {
+                let object: Map<String, Value> = values
+                    .into_iter()
+                    .map(|(field, value)| (field.name().to_string(), value))
+                    .collect();
+                let separator = if self.written == 0 { "\n" } else { ",\n" };
+                write!(self.writer, "{separator}  ")?;
+                serde_json::to_writer(&mut self.writer, &object)?;
+            }
+        }
+
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Close the output and return the number of commits written
+    pub fn finish(mut self) -> Result<usize> {
+        if self.config.format == CommitExportFormat::Json {
+            writeln!(self.writer, "\n]")?;
+        }
+        self.writer.flush()?;
+        Ok(self.written)
+    }
+
+    fn field_value(
+        &self,
+        field: CommitExportField,
+        commit: &GitCommit,
+        raw: &git2::Commit,
+    ) -> Result<Value> {
+        Ok(match field {
+            CommitExportField::Sha => json!(commit.id),
+            CommitExportField::ShortSha => json!(commit.short_id),
+            CommitExportField::Author => json!(commit.author.name),
+            CommitExportField::AuthorEmail => json!(commit.author.email),
+            CommitExportField::AuthorDate => json!(local_date(raw.author().when())),
+            CommitExportField::Committer => json!(commit.committer.name),
+            CommitExportField::CommitterEmail => json!(commit.committer.email),
+            CommitExportField::CommitDate => json!(local_date(raw.committer().when())),
+            CommitExportField::Summary => json!(commit.summary),
+            CommitExportField::Message => json!(commit.message),
+            CommitExportField::Parents => json!(commit.parent_ids),
+            CommitExportField::Refs => {
+                json!(self.refs.get(&commit.id).cloned().unwrap_or_default())
+            }
+            CommitExportField::Diffstat => {
+                let (files_changed, insertions, deletions) = diffstat(self.repo, raw)?;
+                json!({
+                    "files_changed": files_changed,
+                    "insertions": insertions,
+                    "deletions": deletions,
+                })
+            }
+            CommitExportField::Trailers => {
+                let trailers: Vec<Value> = git2::message_trailers_strs(&commit.message)
+                    .map(|trailers| {
+                        trailers
+                            .iter()
+                            .map(|(key, value)| json!({ "key": key, "value": value }))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Value::Array(trailers)
+            }
+        })
+    }
+}
+
+impl GitRepository {
+    /// Stream up to `max_count` commits reachable from `revision` (HEAD when
+    /// None), newest first, into an export; returns the number written
+    pub fn export_history<W: Write>(
+        &self,
+        revision: Option<&str>,
+        max_count: Option<usize>,
+        writer: W,
+        config: CommitExportConfig,
+    ) -> Result<usize> {
+        let mut revwalk = self.repo().revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        match revision {
+            Some(revision) => {
+                let start = self.repo().revparse_single(revision)?.peel_to_commit()?;
+                revwalk.push(start.id())?;
+            }
+            None => revwalk.push_head()?,
+        }
+
+        let mut exporter = CommitExporter::new(self, writer, config)?;
+        for oid in revwalk.take(max_count.unwrap_or(usize::MAX)) {
+            let commit = self.repo().find_commit(oid?)?;
+            exporter.write_commit(&GitCommit::new(&commit)?)?;
+        }
+        exporter.finish()
+    }
+
+    /// Export an already loaded commit list, e.g. the current view
+    pub fn export_commits<W: Write>(
+        &self,
+        commits: &[GitCommit],
+        writer: W,
+        config: CommitExportConfig,
+    ) -> Result<usize> {
+        let mut exporter = CommitExporter::new(self, writer, config)?;
+        for commit in commits {
+            exporter.write_commit(commit)?;
+        }
+        exporter.finish()
+    }
+
+    /// Export a loaded commit list to `path` on a background thread
+    pub fn start_commit_export(
+        &self,
+        commits: Vec<GitCommit>,
+        path: &Path,
+        config: CommitExportConfig,
+    ) -> Result<CommitExportJob> {
+        let repo_path = self.repo().path().to_path_buf();
+        let output = path.to_path_buf();
+        let total = commits.len();
+        CommitScan::start("commit-export", output.clone(), Some(total), move |scan| {
+            run_export(&repo_path, &commits, &output, config, scan)
+        })
+    }
+}
+
+/// Incremental output of a background commit export; the one item is the
+/// number of commits written
+pub type CommitExportEvent = ScanEvent<usize>;
+
+/// A commit export running on a background thread, keyed by its output file
+pub type CommitExportJob = CommitScan<usize, PathBuf>;
+
+fn run_export(
+    repo_path: &Path,
+    commits: &[GitCommit],
+    output: &Path,
+    config: CommitExportConfig,
+    scan: &mut ScanContext<usize>,
+) -> Result<()> {
+    let repo = GitRepository::open(repo_path)?;
+    let writer = std::io::BufWriter::new(std::fs::File::create(output)?);
+    let mut exporter = CommitExporter::new(&repo, writer, config)?;
+
+    for commit in commits {
+        if !scan.next_commit() {
+            drop(exporter);
+            // Don't leave a truncated export behind
+            let _ = std::fs::remove_file(output);
+            return Ok(());
+        }
+        exporter.write_commit(commit)?;
+    }
+
+    scan.report(exporter.finish()?);
+    Ok(())
+}
+
+/// RFC 3339 in the timezone the date was recorded in
+fn local_date(time: Time) -> String {
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60).unwrap_or_else(|| Utc.fix());
+    DateTime::from_timestamp(time.seconds(), 0)
+        .unwrap_or_default()
+        .with_timezone(&offset)
+        .to_rfc3339()
+}
+
+/// Files changed, insertions and deletions against the first parent, or
+/// against the empty tree for a root commit
+fn diffstat(repo: &GitRepository, commit: &git2::Commit) -> Result<Diffstat> {
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    let diff = repo
+        .repo()
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?;
+    Ok((stats.files_changed(), stats.insertions(), stats.deletions()))
+}
+
+/// Flatten a field value into its CSV cells; lists go one entry per line
+fn csv_cells(field: CommitExportField, value: &Value) -> Vec<String> {
+    match (field, value) {
+        (CommitExportField::Diffstat, Value::Object(stats)) => field
+            .csv_headers()
+            .iter()
+            .map(|key| stats.get(*key).map(Value::to_string).unwrap_or_default())
+            .collect(),
+        (CommitExportField::Trailers, Value::Array(trailers)) => {
+            let lines: Vec<String> = trailers
+                .iter()
+                .map(|trailer| {
+                    format!(
+                        "{}: {}",
+                        trailer["key"].as_str().unwrap_or_default(),
+                        trailer["value"].as_str().unwrap_or_default()
+                    )
+                })
+                .collect();
+            vec![lines.join("\n")]
+        }
+        (_, Value::Array(items)) => {
+            let items: Vec<&str> = items.iter().filter_map(Value::as_str).collect();
+            vec![items.join(" ")]
+        }
+        (_, Value::String(text)) => vec![text.clone()],
+        (_, other) => vec![other.to_string()],
+    }
+}
+
+/// Quote a cell when it holds a separator, quote or line break (RFC 4180)
+fn csv_quote(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::{git, git_with_env};
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    const DATE_ENV: [(&str, &str); 2] = [
+        ("GIT_AUTHOR_DATE", "2024-03-01T12:00:00+0530"),
+        ("GIT_COMMITTER_DATE", "2024-03-01T12:00:00+0530"),
+    ];
+
+    /// Two commits; the second has a quoted subject and a trailer
+    fn create_test_repo() -> Result<(TempDir, String)> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        fs::write(path.join("a.txt"), "one\ntwo\n")?;
+        git(path, &["add", "a.txt"])?;
+        git_with_env(path, &["commit", "-m", "Initial commit"], &DATE_ENV)?;
+
+        fs::write(path.join("a.txt"), "one\nthree\nfour\n")?;
+        fs::write(path.join("b.txt"), "new\n")?;
+        git(path, &["add", "."])?;
+        git_with_env(
+            path,
+            &[
+                "commit",
+                "-m",
+                "Say \"hi\", politely\n\nBody text.\n\nSigned-off-by: Test User <test@example.com>",
+            ],
+            &DATE_ENV,
+        )?;
+        git(path, &["tag", "v1.0"])?;
+        let head = git(path, &["rev-parse", "HEAD"])?;
+        Ok((temp_dir, head))
+    }
+
+    fn config(fields: &str, format: CommitExportFormat) -> Result<CommitExportConfig> {
+        Ok(CommitExportConfig {
+            fields: CommitExportField::parse_list(fields)?,
+            format,
+        })
+    }
+
+    #[test]
+    fn test_csv_export_quotes_and_expands_diffstat() -> Result<()> {
+        let (temp_dir, head) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let mut output = Vec::new();
+        let config = config("sha,summary,author_date,diffstat", CommitExportFormat::Csv)?;
+        let written = repo.export_history(None, None, &mut output, config)?;
+        assert_eq!(written, 2);
+
+        let text = String::from_utf8(output)?;
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "sha,summary,author_date,files_changed,insertions,deletions"
+        );
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},\"Say \"\"hi\"\", politely\",2024-03-01T12:00:00+05:30,2,3,1",
+                head
+            )
+        );
+        assert!(lines[2].ends_with(",Initial commit,2024-03-01T12:00:00+05:30,1,2,0"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_export_has_refs_and_trailers() -> Result<()> {
+        let (temp_dir, head) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let mut output = Vec::new();
+        let config = config("sha,refs,trailers,parents", CommitExportFormat::Json)?;
+        repo.export_history(Some("HEAD"), Some(1), &mut output, config)?;
+
+        let commits: Value = serde_json::from_slice(&output)?;
+        let commits = commits.as_array().ok_or_else(|| anyhow!("not an array"))?;
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0]["sha"], json!(head));
+        assert_eq!(
+            commits[0]["refs"],
+            json!(["HEAD", "refs/heads/main", "refs/tags/v1.0"])
+        );
+        assert_eq!(
+            commits[0]["trailers"],
+            json!([{ "key": "Signed-off-by", "value": "Test User <test@example.com>" }])
+        );
+        assert_eq!(commits[0]["parents"].as_array().map(Vec::len), Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_json_export_is_valid() -> Result<()> {
+        let (temp_dir, _) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let mut output = Vec::new();
+        let config = config("sha", CommitExportFormat::Json)?;
+        assert_eq!(repo.export_commits(&[], &mut output, config)?, 0);
+        assert_eq!(serde_json::from_slice::<Value>(&output)?, json!([]));
+        Ok(())
+    }
+
+    fn wait_for_export(job: &mut CommitExportJob) -> Result<usize> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            match job.try_next() {
+                Some(CommitExportEvent::Item(written)) => return Ok(written),
+                Some(CommitExportEvent::Failed(e)) => return Err(anyhow!(e)),
+                Some(_) => {}
+                None if job.is_complete() => bail!("export finished without a count"),
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        bail!("export timed out")
+    }
+
+    #[test]
+    fn test_background_export_round_trips_loaded_view() -> Result<()> {
+        let (temp_dir, _) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let commits = repo.get_commits(None)?;
+
+        let json_path = temp_dir.path().join("commits.json");
+        let json_config = config("sha,author,summary,parents", CommitExportFormat::Json)?;
+        let mut job = repo.start_commit_export(commits.clone(), &json_path, json_config)?;
+        assert_eq!(wait_for_export(&mut job)?, commits.len());
+        assert_eq!(job.query(), &json_path);
+
+        let exported: Vec<Value> = serde_json::from_slice(&fs::read(&json_path)?)?;
+        assert_eq!(exported.len(), commits.len());
+        for (object, commit) in exported.iter().zip(&commits) {
+            assert_eq!(object["sha"], json!(commit.id));
+            assert_eq!(object["author"], json!(commit.author.name));
+            assert_eq!(object["summary"], json!(commit.summary));
+            assert_eq!(object["parents"], json!(commit.parent_ids));
+        }
+
+        let csv_path = temp_dir.path().join("commits.csv");
+        let csv_config = config("sha,summary", CommitExportFormat::Csv)?;
+        let mut job = repo.start_commit_export(commits.clone(), &csv_path, csv_config)?;
+        assert_eq!(wait_for_export(&mut job)?, commits.len());
+
+        let text = fs::read_to_string(&csv_path)?;
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("sha,summary"));
+        let rows: Vec<&str> = lines.collect();
+        assert_eq!(rows.len(), commits.len());
+        for (row, commit) in rows.iter().zip(&commits) {
+            let expected = format!("{},{}", commit.id, csv_quote(&commit.summary));
+            assert_eq!(*row, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_field_list() {
+        assert_eq!(
+            CommitExportField::parse_list("sha, author_date").unwrap(),
+            vec![CommitExportField::Sha, CommitExportField::AuthorDate]
+        );
+        assert!(CommitExportField::parse_list("sha,bogus").is_err());
+        assert!(CommitExportField::parse_list("").is_err());
+        assert_eq!(csv_quote("plain"), "plain");
+        assert_eq!(csv_quote("a\nb"), "\"a\nb\"");
+    }
+}
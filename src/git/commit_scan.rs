@@ -0,0 +1,273 @@
+use anyhow::Result;
+use git2::{Oid, Repository, Revwalk};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use tracing::{debug, warn};
+
+/// Commits scanned between progress reports
+const PROGRESS_INTERVAL: usize = 100;
+
+/// Incremental output of a background commit scan
+#[derive(Debug)]
+pub enum ScanEvent<T> {
+    Item(T), // A commit the scan looked for, or the result of the whole scan
+    Progress { scanned: usize },
+    Finished { scanned: usize, cancelled: bool },
+    Failed(String),
+}
+
+/// The worker thread's side of a scan: counts commits, reports items and
+/// tells the scan when to stop
+pub struct ScanContext<T> {
+    sender: Sender<ScanEvent<T>>,
+    cancel: Arc<AtomicBool>,
+    scanned: usize,
+}
+
+impl<T> ScanContext<T> {
+    /// Count the next commit; `false` once the scan was cancelled
+    pub fn next_commit(&mut self) -> bool {
+        if self.cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        self.scanned += 1;
+        if self.scanned.is_multiple_of(PROGRESS_INTERVAL) {
+            let _ = self.sender.send(ScanEvent::Progress {
+                scanned: self.scanned,
+            });
+        }
+        true
+    }
+
+    /// Send an item to the UI; `false` once the scan was dropped
+    pub fn report(&self, item: T) -> bool {
+        self.sender.send(ScanEvent::Item(item)).is_ok()
+    }
+}
+
+/// A scan over commits running on a background thread. `query` is what
+/// it was started with, kept for display.
+pub struct CommitScan<T, Q = ()> {
+    query: Q,
+    receiver: Receiver<ScanEvent<T>>,
+    cancel: Arc<AtomicBool>,
+    total: Option<usize>, // Commits to scan, when known up front
+    scanned: usize,
+    matched: usize,
+    is_complete: bool,
+}
+
+impl<T: Send + 'static, Q> CommitScan<T, Q> {
+    /// Run `scan` on a thread called `name`. It calls `next_commit` before
+    /// each commit and `report` for what it finds; an error ends the scan
+    /// with `Failed`.
+    pub fn start<F>(name: &str, query: Q, total: Option<usize>, scan: F) -> Result<Self>
+    where
+        F: FnOnce(&mut ScanContext<T>) -> Result<()> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut context = ScanContext {
+            sender,
+            cancel: Arc::clone(&cancel),
+            scanned: 0,
+        };
+
+        let thread_name = name.to_string();
+        thread::Builder::new()
+            .name(thread_name.clone())
+            .spawn(move || {
+                let event = match scan(&mut context) {
+                    Ok(()) => {
+                        let cancelled = context.cancel.load(Ordering::Relaxed);
+                        if cancelled {
+                            debug!(
+                                "{} cancelled after {} commits",
+                                thread_name, context.scanned
+                            );
+                        }
+                        ScanEvent::Finished {
+                            scanned: context.scanned,
+                            cancelled,
+                        }
+                    }
+                    Err(e) => {
+                        warn!("{} failed: {}", thread_name, e);
+                        ScanEvent::Failed(e.to_string())
+                    }
+                };
+                let _ = context.sender.send(event);
+            })?;
+
+        Ok(Self {
+            query,
+            receiver,
+            cancel,
+            total,
+            scanned: 0,
+            matched: 0,
+            is_complete: false,
+        })
+    }
+}
+
+impl<T, Q> CommitScan<T, Q> {
+    /// Next event from the scan thread, without blocking
+    pub fn try_next(&mut self) -> Option<ScanEvent<T>> {
+        match self.receiver.try_recv() {
+            Ok(event) => {
+                match event {
+                    ScanEvent::Item(_) => self.matched += 1,
+                    ScanEvent::Progress { scanned } => self.scanned = scanned,
+                    ScanEvent::Finished { scanned, .. } => {
+                        self.scanned = scanned;
+                        self.is_complete = true;
+                    }
+                    ScanEvent::Failed(_) => self.is_complete = true,
+                }
+                Some(event)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.is_complete = true;
+                None
+            }
+        }
+    }
+
+    /// Ask the scan thread to stop; it finishes after the current commit
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub const fn query(&self) -> &Q {
+        &self.query
+    }
+
+    pub const fn total_count(&self) -> Option<usize> {
+        self.total
+    }
+
+    pub const fn scanned_count(&self) -> usize {
+        self.scanned
+    }
+
+    pub const fn matched_count(&self) -> usize {
+        self.matched
+    }
+
+    pub const fn is_complete(&self) -> bool {
+        self.is_complete
+    }
+}
+
+impl<T, Q> Drop for CommitScan<T, Q> {
+    fn drop(&mut self) {
+        // The detached thread notices on its next commit; joining could stall the UI
+        self.cancel();
+    }
+}
+
+impl<T, Q: std::fmt::Debug> std::fmt::Debug for CommitScan<T, Q> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommitScan")
+            .field("query", &self.query)
+            .field("scanned", &self.scanned)
+            .field("matched", &self.matched)
+            .field("is_complete", &self.is_complete)
+            .finish_non_exhaustive()
+    }
+}
+
+/// History from `start` (HEAD when `None`), newest commits first
+pub(crate) fn walk_history(repo: &Repository, start: Option<Oid>) -> Result<Revwalk<'_>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME)?;
+    match start {
+        Some(start) => revwalk.push(start)?,
+        None => revwalk.push_head()?,
+    }
+    Ok(revwalk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn drain<T, Q>(scan: &mut CommitScan<T, Q>) -> Vec<ScanEvent<T>> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut events = Vec::new();
+        while !scan.is_complete() && Instant::now() < deadline {
+            match scan.try_next() {
+                Some(event) => events.push(event),
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_scan_reports_items_progress_and_finish() -> Result<()> {
+        let mut scan = CommitScan::start("test-scan", "evens", Some(250), |scan| {
+            for number in 0..250 {
+                if !scan.next_commit() {
+                    break;
+                }
+                if number % 2 == 0 {
+                    scan.report(number);
+                }
+            }
+            Ok(())
+        })?;
+
+        let events = drain(&mut scan);
+        assert!(scan.is_complete());
+        assert_eq!(*scan.query(), "evens");
+        assert_eq!(scan.total_count(), Some(250));
+        assert_eq!(scan.scanned_count(), 250);
+        assert_eq!(scan.matched_count(), 125);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, ScanEvent::Progress { .. }))
+                .count(),
+            2
+        );
+        assert!(matches!(
+            events.last(),
+            Some(ScanEvent::Finished {
+                scanned: 250,
+                cancelled: false
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel_and_failure() -> Result<()> {
+        let mut scan: CommitScan<()> = CommitScan::start("test-scan", (), None, |scan| {
+            while scan.next_commit() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            Ok(())
+        })?;
+        scan.cancel();
+        let events = drain(&mut scan);
+        assert!(matches!(
+            events.last(),
+            Some(ScanEvent::Finished {
+                cancelled: true,
+                ..
+            })
+        ));
+
+        let mut scan: CommitScan<()> =
+            CommitScan::start("test-scan", (), None, |_| Err(anyhow::anyhow!("broken")))?;
+        let events = drain(&mut scan);
+        assert!(matches!(events.last(), Some(ScanEvent::Failed(e)) if e == "broken"));
+        Ok(())
+    }
+}
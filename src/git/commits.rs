@@ -1,6 +1,6 @@
 use crate::git::operations::{OperationRecord, OperationType};
 use crate::git::{ErrorReporter, GitRepository, InputSanitizer, InputValidator};
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use git2::{CherrypickOptions, Oid, Repository, ResetType, RevertOptions};
 use tracing::{error, info, warn};
 
@@ -91,6 +91,67 @@ pub enum ConflictType {
     Mode,         // File mode conflicts
 }
 
+impl ConflictInfo {
+    /// Collect the conflicted entries of an index, e.g. after a merge
+    pub fn from_index(index: &git2::Index) -> Result<Vec<Self>> {
+        let mut conflicts = Vec::new();
+
+        for conflict_data in index.conflicts()?.flatten() {
+            let path = conflict_data
+                .ancestor
+                .as_ref()
+                .or(conflict_data.our.as_ref())
+                .or(conflict_data.their.as_ref())
+                .and_then(|entry| std::str::from_utf8(&entry.path).ok())
+                .unwrap_or("unknown")
+                .to_string();
+
+            conflicts.push(Self {
+                path,
+                ancestor_id: conflict_data.ancestor.as_ref().map(|e| e.id.to_string()),
+                our_id: conflict_data.our.as_ref().map(|e| e.id.to_string()),
+                their_id: conflict_data.their.as_ref().map(|e| e.id.to_string()),
+                conflict_type: ConflictType::classify(
+                    &conflict_data.ancestor,
+                    &conflict_data.our,
+                    &conflict_data.their,
+                ),
+            });
+        }
+
+        Ok(conflicts)
+    }
+}
+
+impl ConflictType {
+    /// Classify the type of conflict from the entries present on each side
+    #[must_use]
+    pub const fn classify(
+        ancestor: &Option<git2::IndexEntry>,
+        our: &Option<git2::IndexEntry>,
+        their: &Option<git2::IndexEntry>,
+    ) -> Self {
+        match (ancestor.is_some(), our.is_some(), their.is_some()) {
+            (false, true, true) => Self::AddAdd,
+            (true, false, true) => Self::DeleteModify,
+            (true, true, false) => Self::ModifyDelete,
+            (true, true, true) => {
+                // Check if it's a mode conflict
+                if let (Some(our_entry), Some(their_entry)) = (our, their) {
+                    if our_entry.mode == their_entry.mode {
+                        Self::Content
+                    } else {
+                        Self::Mode
+                    }
+                } else {
+                    Self::Content
+                }
+            }
+            _ => Self::Content,
+        }
+    }
+}
+
 impl CommitOperations {
     /// Create a new commit operations manager
     pub fn new(git_repo: &GitRepository) -> Result<Self> {
@@ -112,34 +173,12 @@ impl CommitOperations {
         // Validate input
         if let Err(e) = InputValidator::validate_commit_id(commit_id) {
             ErrorReporter::log_error(&e, "cherry-pick validation");
-            return Ok(CommitOperationResult {
-                success: false,
-                operation: OperationType::CommitCherryPick,
-                commit_id: None,
-                new_commit_id: None,
-                message: format!("Invalid commit ID: {}", e),
-                conflicts: vec![],
-                modified_files: vec![],
-                reverted_files: vec![],
-            });
+            return Err(anyhow!("Invalid commit ID: {e}"));
         }
 
         // Sanitize input
-        let sanitized_commit = match InputSanitizer::sanitize_commit_id(commit_id) {
-            Ok(commit) => commit,
-            Err(e) => {
-                return Ok(CommitOperationResult {
-                    success: false,
-                    operation: OperationType::CommitCherryPick,
-                    commit_id: None,
-                    new_commit_id: None,
-                    message: format!("Failed to sanitize commit ID: {}", e),
-                    conflicts: vec![],
-                    modified_files: vec![],
-                    reverted_files: vec![],
-                });
-            }
-        };
+        let sanitized_commit = InputSanitizer::sanitize_commit_id(commit_id)
+            .map_err(|e| anyhow!("Failed to sanitize commit ID: {e}"))?;
 
         // Get current HEAD for operation record
         let original_head = self
@@ -151,37 +190,13 @@ impl CommitOperations {
 
         // Perform cherry-pick in separate scope
         let cherry_pick_result = {
-            let target_oid = match Oid::from_str(&sanitized_commit) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Ok(CommitOperationResult {
-                        success: false,
-                        operation: OperationType::CommitCherryPick,
-                        commit_id: Some(sanitized_commit),
-                        new_commit_id: None,
-                        message: format!("Invalid commit OID: {}", e),
-                        conflicts: vec![],
-                        modified_files: vec![],
-                        reverted_files: vec![],
-                    });
-                }
-            };
+            let target_oid =
+                Oid::from_str(&sanitized_commit).map_err(|e| anyhow!("Invalid commit OID: {e}"))?;
 
-            let target_commit = match self.repo.find_commit(target_oid) {
-                Ok(commit) => commit,
-                Err(e) => {
-                    return Ok(CommitOperationResult {
-                        success: false,
-                        operation: OperationType::CommitCherryPick,
-                        commit_id: Some(sanitized_commit),
-                        new_commit_id: None,
-                        message: format!("Commit not found: {}", e),
-                        conflicts: vec![],
-                        modified_files: vec![],
-                        reverted_files: vec![],
-                    });
-                }
-            };
+            let target_commit = self
+                .repo
+                .find_commit(target_oid)
+                .map_err(|e| anyhow!("Commit not found: {e}"))?;
 
             // Set up cherry-pick options
             let mut cherrypick_opts = CherrypickOptions::new();
@@ -293,34 +308,12 @@ impl CommitOperations {
         // Validate input
         if let Err(e) = InputValidator::validate_commit_id(commit_id) {
             ErrorReporter::log_error(&e, "revert validation");
-            return Ok(CommitOperationResult {
-                success: false,
-                operation: OperationType::CommitRevert,
-                commit_id: None,
-                new_commit_id: None,
-                message: format!("Invalid commit ID: {}", e),
-                conflicts: vec![],
-                modified_files: vec![],
-                reverted_files: vec![],
-            });
+            return Err(anyhow!("Invalid commit ID: {e}"));
         }
 
         // Sanitize input
-        let sanitized_commit = match InputSanitizer::sanitize_commit_id(commit_id) {
-            Ok(commit) => commit,
-            Err(e) => {
-                return Ok(CommitOperationResult {
-                    success: false,
-                    operation: OperationType::CommitRevert,
-                    commit_id: None,
-                    new_commit_id: None,
-                    message: format!("Failed to sanitize commit ID: {}", e),
-                    conflicts: vec![],
-                    modified_files: vec![],
-                    reverted_files: vec![],
-                });
-            }
-        };
+        let sanitized_commit = InputSanitizer::sanitize_commit_id(commit_id)
+            .map_err(|e| anyhow!("Failed to sanitize commit ID: {e}"))?;
 
         // Get current HEAD for operation record
         let original_head = self
@@ -332,37 +325,13 @@ impl CommitOperations {
 
         // Perform revert in separate scope
         let revert_result = {
-            let target_oid = match Oid::from_str(&sanitized_commit) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Ok(CommitOperationResult {
-                        success: false,
-                        operation: OperationType::CommitRevert,
-                        commit_id: Some(sanitized_commit),
-                        new_commit_id: None,
-                        message: format!("Invalid commit OID: {}", e),
-                        conflicts: vec![],
-                        modified_files: vec![],
-                        reverted_files: vec![],
-                    });
-                }
-            };
+            let target_oid =
+                Oid::from_str(&sanitized_commit).map_err(|e| anyhow!("Invalid commit OID: {e}"))?;
 
-            let target_commit = match self.repo.find_commit(target_oid) {
-                Ok(commit) => commit,
-                Err(e) => {
-                    return Ok(CommitOperationResult {
-                        success: false,
-                        operation: OperationType::CommitRevert,
-                        commit_id: Some(sanitized_commit),
-                        new_commit_id: None,
-                        message: format!("Commit not found: {}", e),
-                        conflicts: vec![],
-                        modified_files: vec![],
-                        reverted_files: vec![],
-                    });
-                }
-            };
+            let target_commit = self
+                .repo
+                .find_commit(target_oid)
+                .map_err(|e| anyhow!("Commit not found: {e}"))?;
 
             // Set up revert options
             let mut revert_opts = RevertOptions::new();
@@ -479,34 +448,12 @@ impl CommitOperations {
         // Validate input
         if let Err(e) = InputValidator::validate_commit_id(commit_id) {
             ErrorReporter::log_error(&e, "reset validation");
-            return Ok(CommitOperationResult {
-                success: false,
-                operation: OperationType::CommitReset,
-                commit_id: None,
-                new_commit_id: None,
-                message: format!("Invalid commit ID: {}", e),
-                conflicts: vec![],
-                modified_files: vec![],
-                reverted_files: vec![],
-            });
+            return Err(anyhow!("Invalid commit ID: {e}"));
         }
 
         // Sanitize input
-        let sanitized_commit = match InputSanitizer::sanitize_commit_id(commit_id) {
-            Ok(commit) => commit,
-            Err(e) => {
-                return Ok(CommitOperationResult {
-                    success: false,
-                    operation: OperationType::CommitReset,
-                    commit_id: None,
-                    new_commit_id: None,
-                    message: format!("Failed to sanitize commit ID: {}", e),
-                    conflicts: vec![],
-                    modified_files: vec![],
-                    reverted_files: vec![],
-                });
-            }
-        };
+        let sanitized_commit = InputSanitizer::sanitize_commit_id(commit_id)
+            .map_err(|e| anyhow!("Failed to sanitize commit ID: {e}"))?;
 
         // Get current HEAD for operation record
         let original_head = self
@@ -518,37 +465,13 @@ impl CommitOperations {
 
         // Perform reset in separate scope
         let reset_result = {
-            let target_oid = match Oid::from_str(&sanitized_commit) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Ok(CommitOperationResult {
-                        success: false,
-                        operation: OperationType::CommitReset,
-                        commit_id: Some(sanitized_commit),
-                        new_commit_id: None,
-                        message: format!("Invalid commit OID: {}", e),
-                        conflicts: vec![],
-                        modified_files: vec![],
-                        reverted_files: vec![],
-                    });
-                }
-            };
+            let target_oid =
+                Oid::from_str(&sanitized_commit).map_err(|e| anyhow!("Invalid commit OID: {e}"))?;
 
-            let target_commit = match self.repo.find_commit(target_oid) {
-                Ok(commit) => commit,
-                Err(e) => {
-                    return Ok(CommitOperationResult {
-                        success: false,
-                        operation: OperationType::CommitReset,
-                        commit_id: Some(sanitized_commit),
-                        new_commit_id: None,
-                        message: format!("Commit not found: {}", e),
-                        conflicts: vec![],
-                        modified_files: vec![],
-                        reverted_files: vec![],
-                    });
-                }
-            };
+            let target_commit = self
+                .repo
+                .find_commit(target_oid)
+                .map_err(|e| anyhow!("Commit not found: {e}"))?;
 
             // Convert reset type
             let reset_type = match config.reset_type {
@@ -611,39 +534,7 @@ impl CommitOperations {
 
     /// Get current conflicts in the repository
     fn get_conflicts(&self) -> Result<Vec<ConflictInfo>> {
-        let mut conflicts = Vec::new();
-
-        let index = self.repo.index()?;
-        let conflict_iter = index.conflicts()?;
-
-        for conflict in conflict_iter {
-            if let Ok(conflict_data) = conflict {
-                let path = conflict_data
-                    .ancestor
-                    .as_ref()
-                    .or(conflict_data.our.as_ref())
-                    .or(conflict_data.their.as_ref())
-                    .and_then(|entry| std::str::from_utf8(&entry.path).ok())
-                    .unwrap_or("unknown")
-                    .to_string();
-
-                let conflict_info = ConflictInfo {
-                    path,
-                    ancestor_id: conflict_data.ancestor.as_ref().map(|e| e.id.to_string()),
-                    our_id: conflict_data.our.as_ref().map(|e| e.id.to_string()),
-                    their_id: conflict_data.their.as_ref().map(|e| e.id.to_string()),
-                    conflict_type: self.classify_conflict_type(
-                        &conflict_data.ancestor,
-                        &conflict_data.our,
-                        &conflict_data.their,
-                    ),
-                };
-
-                conflicts.push(conflict_info);
-            }
-        }
-
-        Ok(conflicts)
+        ConflictInfo::from_index(&self.repo.index()?)
     }
 
     /// Get list of modified files
@@ -817,33 +708,6 @@ impl CommitOperations {
             .map_err(Into::into)
     }
 
-    /// Classify the type of conflict
-    fn classify_conflict_type(
-        &self,
-        ancestor: &Option<git2::IndexEntry>,
-        our: &Option<git2::IndexEntry>,
-        their: &Option<git2::IndexEntry>,
-    ) -> ConflictType {
-        match (ancestor.is_some(), our.is_some(), their.is_some()) {
-            (false, true, true) => ConflictType::AddAdd,
-            (true, false, true) => ConflictType::DeleteModify,
-            (true, true, false) => ConflictType::ModifyDelete,
-            (true, true, true) => {
-                // Check if it's a mode conflict
-                if let (Some(our_entry), Some(their_entry)) = (our, their) {
-                    if our_entry.mode != their_entry.mode {
-                        ConflictType::Mode
-                    } else {
-                        ConflictType::Content
-                    }
-                } else {
-                    ConflictType::Content
-                }
-            }
-            _ => ConflictType::Content,
-        }
-    }
-
     /// Record an operation in the history
     fn record_operation(&mut self, record: OperationRecord) {
         let operation_type = record.operation_type.clone();
@@ -1144,6 +1008,7 @@ mod tests {
             mainline: None,
             no_commit: false,
             edit_message: false,
+            sign_off: false,
             strategy: MergeStrategy::Recursive,
         };
 
@@ -1237,9 +1102,10 @@ mod tests {
 
         assert_eq!(config.mainline, None);
         assert_eq!(config.no_commit, false);
-        assert_eq!(config.allow_empty, false);
-        assert_eq!(config.allow_empty_message, false);
-        assert_eq!(config.strategy, None);
+        assert_eq!(config.edit_message, false);
+        assert_eq!(config.sign_off, false);
+        assert_eq!(config.strategy, MergeStrategy::Recursive);
+        assert!(config.strategy_options.is_empty());
 
         Ok(())
     }
@@ -1251,7 +1117,8 @@ mod tests {
         assert_eq!(config.mainline, None);
         assert_eq!(config.no_commit, false);
         assert_eq!(config.edit_message, false);
-        assert_eq!(config.strategy, None);
+        assert_eq!(config.sign_off, false);
+        assert_eq!(config.strategy, MergeStrategy::Recursive);
 
         Ok(())
     }
@@ -0,0 +1,177 @@
+use crate::git::{GitRepository, collect_git_diffs};
+use crate::models::GitDiff;
+use anyhow::Result;
+
+/// One side of a comparison: a commit's tree, the index or the working tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffEndpoint {
+    Commit(String),
+    Index,
+    WorkingTree,
+}
+
+impl DiffEndpoint {
+    /// Short form for headers, e.g. "1a2b3c4" or "Working tree"
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self {
+            Self::Commit(id) => id.chars().take(7).collect(),
+            Self::Index => "Index".to_string(),
+            Self::WorkingTree => "Working tree".to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn commit_id(&self) -> Option<&str> {
+        match self {
+            Self::Commit(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    // Order in which git can diff two endpoints: trees before the index
+    // before the working tree
+    const fn rank(&self) -> u8 {
+        match self {
+            Self::Commit(_) => 0,
+            Self::Index => 1,
+            Self::WorkingTree => 2,
+        }
+    }
+}
+
+impl GitRepository {
+    /// Changes that turn `from` into `to`, like `git diff <from> <to>`;
+    /// `git diff --cached <commit>` and `git diff <commit>` are the index
+    /// and working tree cases
+    pub fn diff_endpoints(&self, from: &DiffEndpoint, to: &DiffEndpoint) -> Result<Vec<GitDiff>> {
+        // libgit2 only diffs "older" sources against "newer" ones, so the
+        // other direction is the same diff reversed
+        let reverse = from.rank() > to.rank();
+        let (old, new) = if reverse { (to, from) } else { (from, to) };
+        let mut options = git2::DiffOptions::new();
+        options.reverse(reverse);
+
+        let repo = self.repo();
+        let tree = |id: &str| -> Result<git2::Tree> {
+            Ok(repo.find_commit(git2::Oid::from_str(id)?)?.tree()?)
+        };
+
+        let diff = match (old, new) {
+            (DiffEndpoint::Commit(old_id), DiffEndpoint::Commit(new_id)) => repo
+                .diff_tree_to_tree(
+                    Some(&tree(old_id)?),
+                    Some(&tree(new_id)?),
+                    Some(&mut options),
+                )?,
+            (DiffEndpoint::Commit(old_id), DiffEndpoint::Index) => {
+                repo.diff_tree_to_index(Some(&tree(old_id)?), None, Some(&mut options))?
+            }
+            (DiffEndpoint::Commit(old_id), DiffEndpoint::WorkingTree) => {
+                repo.diff_tree_to_workdir_with_index(Some(&tree(old_id)?), Some(&mut options))?
+            }
+            (DiffEndpoint::Index, DiffEndpoint::WorkingTree) => {
+                repo.diff_index_to_workdir(None, Some(&mut options))?
+            }
+            // The index or the working tree against itself
+            _ => return Ok(Vec::new()),
+        };
+
+        collect_git_diffs(&diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git_with_env;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    const IDENTITY: &[(&str, &str)] = &[
+        ("GIT_AUTHOR_NAME", "Test User"),
+        ("GIT_AUTHOR_EMAIL", "test@example.com"),
+        ("GIT_COMMITTER_NAME", "Test User"),
+        ("GIT_COMMITTER_EMAIL", "test@example.com"),
+    ];
+
+    fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+        git_with_env(repo_path, args, IDENTITY)
+    }
+
+    fn commit_file(repo_path: &Path, file: &str, content: &str) -> Result<DiffEndpoint> {
+        fs::write(repo_path.join(file), content)?;
+        git(repo_path, &["add", file])?;
+        git(repo_path, &["commit", "-m", file])?;
+        Ok(DiffEndpoint::Commit(git(
+            repo_path,
+            &["rev-parse", "HEAD"],
+        )?))
+    }
+
+    fn changed_paths(diffs: &[GitDiff]) -> Vec<String> {
+        diffs
+            .iter()
+            .filter_map(|diff| diff.new_file.clone().or_else(|| diff.old_file.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_between_commits_both_ways() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        let first = commit_file(path, "a.txt", "one\n")?;
+        commit_file(path, "b.txt", "unrelated\n")?;
+        let third = commit_file(path, "a.txt", "one\ntwo\n")?;
+        let repo = GitRepository::open(path)?;
+
+        // Spans more than one commit, unlike the first-parent diff
+        let forward = repo.diff_endpoints(&first, &third)?;
+        assert_eq!(changed_paths(&forward), vec!["a.txt", "b.txt"]);
+        let a_txt = &forward[0];
+        assert_eq!((a_txt.stats.insertions, a_txt.stats.deletions), (1, 0));
+
+        let backward = repo.diff_endpoints(&third, &first)?;
+        assert_eq!(changed_paths(&backward), vec!["a.txt", "b.txt"]);
+        assert_eq!(
+            (backward[0].stats.insertions, backward[0].stats.deletions),
+            (0, 1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_commit_against_index_and_working_tree() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        let head = commit_file(path, "a.txt", "one\n")?;
+        fs::write(path.join("a.txt"), "one\nstaged\n")?;
+        git(path, &["add", "a.txt"])?;
+        fs::write(path.join("a.txt"), "one\nstaged\nunstaged\n")?;
+        let repo = GitRepository::open(path)?;
+
+        let staged = repo.diff_endpoints(&head, &DiffEndpoint::Index)?;
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].stats.insertions, 1);
+
+        let working = repo.diff_endpoints(&head, &DiffEndpoint::WorkingTree)?;
+        assert_eq!(working[0].stats.insertions, 2);
+
+        let unstaged = repo.diff_endpoints(&DiffEndpoint::Index, &DiffEndpoint::WorkingTree)?;
+        assert_eq!(unstaged[0].stats.insertions, 1);
+
+        let reverted = repo.diff_endpoints(&DiffEndpoint::WorkingTree, &head)?;
+        assert_eq!(
+            (reverted[0].stats.insertions, reverted[0].stats.deletions),
+            (0, 2)
+        );
+        assert!(
+            repo.diff_endpoints(&DiffEndpoint::Index, &DiffEndpoint::Index)?
+                .is_empty()
+        );
+        Ok(())
+    }
+}
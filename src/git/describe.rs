@@ -0,0 +1,145 @@
+use crate::git::{CommitGraphIndex, GitRepository, RefManager};
+use anyhow::Result;
+use git2::{DescribeFormatOptions, DescribeOptions, ErrorClass, ErrorCode, Oid};
+use std::collections::HashMap;
+
+/// Where a commit sits among branches and tags, as in gitk's commit details
+#[derive(Debug, Clone, Default)]
+pub struct CommitRefContext {
+    pub branches: Vec<String>, // Local and remote branches containing the commit
+    pub follows: Vec<String>,  // Nearest tags among the commit's ancestors
+    pub precedes: Vec<String>, // Nearest tags whose history contains the commit
+    pub describe: Option<String>, // `git describe --tags` name
+}
+
+/// Tag names grouped by the commit they peel to
+type TagsByCommit = HashMap<Oid, Vec<String>>;
+
+impl GitRepository {
+    /// `git describe --tags` for a commit; `None` when no tag reaches it
+    pub fn describe_commit(&self, commit_id: &str) -> Result<Option<String>> {
+        let oid = Oid::from_str(commit_id)?;
+        let commit = self.repo().find_commit(oid)?;
+
+        let mut options = DescribeOptions::new();
+        options.describe_tags();
+        let describe = match commit.as_object().describe(&options) {
+            Ok(describe) => describe,
+            // libgit2 reports "no reference found" as a generic describe error
+            Err(e) if e.code() == ErrorCode::NotFound || e.class() == ErrorClass::Describe => {
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut format = DescribeFormatOptions::new();
+        format.abbreviated_size(7);
+        Ok(Some(describe.format(Some(&format))?))
+    }
+}
+
+impl RefManager {
+    /// Containing branches and nearest tags around a commit
+    pub fn get_commit_ref_context(
+        &self,
+        repo: &GitRepository,
+        index: &CommitGraphIndex,
+        commit_id: &str,
+    ) -> Result<CommitRefContext> {
+        let oid = Oid::from_str(commit_id)?;
+
+        // Annotated tags point at tag objects; the graph needs their commits
+        let mut tags_by_commit = TagsByCommit::new();
+        for tag in self.get_tags() {
+            let Ok(commit) = repo
+                .repo()
+                .find_reference(&tag.full_name)
+                .and_then(|reference| reference.peel_to_commit())
+            else {
+                continue;
+            };
+            tags_by_commit
+                .entry(commit.id())
+                .or_default()
+                .push(tag.name.clone());
+        }
+        let is_tagged = |id: Oid| tags_by_commit.contains_key(&id);
+        let tag_names = |ids: Vec<Oid>| -> Vec<String> {
+            let mut names: Vec<String> = ids
+                .iter()
+                .filter_map(|id| tags_by_commit.get(id))
+                .flatten()
+                .cloned()
+                .collect();
+            names.sort();
+            names
+        };
+
+        let mut branches: Vec<String> = self
+            .get_branches_containing(index, commit_id)?
+            .into_iter()
+            .map(|branch| branch.name.clone())
+            .collect();
+        branches.sort();
+
+        Ok(CommitRefContext {
+            branches,
+            follows: tag_names(index.nearest_ancestors_matching(oid, is_tagged)),
+            precedes: tag_names(index.nearest_descendants_matching(oid, is_tagged)),
+            describe: repo.describe_commit(commit_id)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_commit_ref_context() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        git(path, &["commit", "--allow-empty", "-m", "first"])?;
+        git(path, &["tag", "-a", "v1.0", "-m", "Release 1.0"])?;
+        git(path, &["commit", "--allow-empty", "-m", "second"])?;
+        let middle = git(path, &["rev-parse", "HEAD"])?;
+        git(path, &["branch", "topic"])?;
+        git(path, &["commit", "--allow-empty", "-m", "third"])?;
+        git(path, &["tag", "v2.0"])?;
+
+        let repo = GitRepository::open(path)?;
+        let index = repo.build_commit_graph_index()?;
+        let ref_manager = repo.get_ref_manager()?;
+
+        let context = ref_manager.get_commit_ref_context(&repo, &index, &middle)?;
+        assert_eq!(context.branches, vec!["main", "topic"]);
+        assert_eq!(context.follows, vec!["v1.0"]);
+        assert_eq!(context.precedes, vec!["v2.0"]);
+        let describe = context.describe.unwrap();
+        assert!(describe.starts_with("v1.0-1-g"), "{}", describe);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe_without_tags() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+        git(path, &["commit", "--allow-empty", "-m", "untagged"])?;
+
+        let repo = GitRepository::open(path)?;
+        let head = git(path, &["rev-parse", "HEAD"])?;
+        assert_eq!(repo.describe_commit(&head)?, None);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::git::GitRepository;
-use crate::models::{DiffStatus, GitDiff, GitDiffLine, GitDiffStats, GitHunk};
+use crate::models::{DiffStatus, GitDiff, GitDiffLine, GitDiffStats, GitHunk, SubmoduleChange};
 use anyhow::Result;
 
 /// Convert git2::Delta to our DiffStatus
@@ -17,6 +17,27 @@ fn delta_to_status(delta: git2::Delta) -> DiffStatus {
     }
 }
 
+/// Describe a gitlink (submodule) delta; `None` for ordinary files
+fn submodule_change(delta: &git2::DiffDelta) -> Option<SubmoduleChange> {
+    let is_gitlink = |file: &git2::DiffFile| file.mode() == git2::FileMode::Commit;
+    let commit_of = |file: &git2::DiffFile| {
+        (is_gitlink(file) && !file.id().is_zero()).then(|| file.id().to_string())
+    };
+
+    let old_file = delta.old_file();
+    let new_file = delta.new_file();
+    if !is_gitlink(&old_file) && !is_gitlink(&new_file) {
+        return None;
+    }
+
+    let path = new_file.path().or_else(|| old_file.path())?;
+    Some(SubmoduleChange {
+        path: path.to_string_lossy().to_string(),
+        old_commit: commit_of(&old_file),
+        new_commit: commit_of(&new_file),
+    })
+}
+
 impl GitRepository {
     pub fn get_commit_diff(&self, commit_id: &str) -> Result<Vec<GitDiff>> {
         let oid = git2::Oid::from_str(commit_id)?;
@@ -62,6 +83,7 @@ impl GitRepository {
                 is_binary,
                 status,
                 similarity,
+                submodule: submodule_change(&delta),
             };
 
             diffs.push(git_diff);
@@ -102,6 +124,7 @@ impl GitRepository {
             is_binary: false,
             status: DiffStatus::Modified,
             similarity: None,
+            submodule: None,
         };
 
         // Get basic file info from deltas
@@ -118,6 +141,7 @@ impl GitRepository {
             result_diff.status = delta_to_status(delta.status());
             result_diff.similarity = None; // Similarity detection requires more complex processing
             result_diff.is_binary = delta.old_file().is_binary() || delta.new_file().is_binary();
+            result_diff.submodule = submodule_change(&delta);
         }
 
         // Generate patch and parse it for hunk information
@@ -188,94 +212,98 @@ impl GitRepository {
             .repo()
             .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
 
-        let mut diffs = Vec::new();
+        collect_git_diffs(&diff)
+    }
+}
 
-        for delta in diff.deltas() {
-            let old_file = delta
-                .old_file()
-                .path()
-                .map(|p| p.to_string_lossy().to_string());
-            let new_file = delta
-                .new_file()
-                .path()
-                .map(|p| p.to_string_lossy().to_string());
-            let status = delta_to_status(delta.status());
-            let similarity = None; // Similarity detection requires more complex processing
-            let is_binary = delta.old_file().is_binary() || delta.new_file().is_binary();
+/// Convert every delta of a diff, hunks included, into our model types
+pub(crate) fn collect_git_diffs(diff: &git2::Diff) -> Result<Vec<GitDiff>> {
+    let mut diffs = Vec::new();
+
+    for delta in diff.deltas() {
+        let old_file = delta
+            .old_file()
+            .path()
+            .map(|p| p.to_string_lossy().to_string());
+        let new_file = delta
+            .new_file()
+            .path()
+            .map(|p| p.to_string_lossy().to_string());
+        let status = delta_to_status(delta.status());
+        let similarity = None; // Similarity detection requires more complex processing
+        let is_binary = delta.old_file().is_binary() || delta.new_file().is_binary();
+        let submodule = submodule_change(&delta);
+
+        let mut hunks = Vec::new();
+        let mut insertions = 0;
+        let mut deletions = 0;
+
+        // Only process hunks for non-binary files; gitlinks have no content to diff
+        if !is_binary && submodule.is_none() {
+            if let Ok(Some(patch)) = git2::Patch::from_diff(diff, diffs.len()) {
+                let num_hunks = patch.num_hunks();
 
-            let mut hunks = Vec::new();
-            let mut insertions = 0;
-            let mut deletions = 0;
-
-            // Only process hunks for non-binary files
-            if !is_binary {
-                if let Ok(patch) = git2::Patch::from_diff(&diff, diffs.len()) {
-                    if let Some(patch) = patch {
-                        let num_hunks = patch.num_hunks();
-
-                        for hunk_idx in 0..num_hunks {
-                            if let Ok((hunk, _)) = patch.hunk(hunk_idx) {
-                                let mut lines = Vec::new();
-                                let num_lines = patch.num_lines_in_hunk(hunk_idx)?;
-
-                                for line_idx in 0..num_lines {
-                                    if let Ok(line) = patch.line_in_hunk(hunk_idx, line_idx) {
-                                        let content =
-                                            String::from_utf8_lossy(line.content()).to_string();
-                                        let origin = line.origin();
-
-                                        match origin {
-                                            '+' => insertions += 1,
-                                            '-' => deletions += 1,
-                                            _ => {}
-                                        }
-
-                                        lines.push(GitDiffLine {
-                                            origin,
-                                            content,
-                                            old_lineno: line.old_lineno(),
-                                            new_lineno: line.new_lineno(),
-                                        });
-                                    }
+                for hunk_idx in 0..num_hunks {
+                    if let Ok((hunk, _)) = patch.hunk(hunk_idx) {
+                        let mut lines = Vec::new();
+                        let num_lines = patch.num_lines_in_hunk(hunk_idx)?;
+
+                        for line_idx in 0..num_lines {
+                            if let Ok(line) = patch.line_in_hunk(hunk_idx, line_idx) {
+                                let content = String::from_utf8_lossy(line.content()).to_string();
+                                let origin = line.origin();
+
+                                match origin {
+                                    '+' => insertions += 1,
+                                    '-' => deletions += 1,
+                                    _ => {}
                                 }
 
-                                hunks.push(GitHunk {
-                                    old_start: hunk.old_start(),
-                                    old_lines: hunk.old_lines(),
-                                    new_start: hunk.new_start(),
-                                    new_lines: hunk.new_lines(),
-                                    header: format!(
-                                        "@@ -{},{} +{},{} @@",
-                                        hunk.old_start(),
-                                        hunk.old_lines(),
-                                        hunk.new_start(),
-                                        hunk.new_lines()
-                                    ),
-                                    lines,
+                                lines.push(GitDiffLine {
+                                    origin,
+                                    content,
+                                    old_lineno: line.old_lineno(),
+                                    new_lineno: line.new_lineno(),
                                 });
                             }
                         }
+
+                        hunks.push(GitHunk {
+                            old_start: hunk.old_start(),
+                            old_lines: hunk.old_lines(),
+                            new_start: hunk.new_start(),
+                            new_lines: hunk.new_lines(),
+                            header: format!(
+                                "@@ -{},{} +{},{} @@",
+                                hunk.old_start(),
+                                hunk.old_lines(),
+                                hunk.new_start(),
+                                hunk.new_lines()
+                            ),
+                            lines,
+                        });
                     }
                 }
             }
-
-            let git_diff = GitDiff {
-                old_file,
-                new_file,
-                hunks,
-                stats: GitDiffStats {
-                    files_changed: 1,
-                    insertions,
-                    deletions,
-                },
-                is_binary,
-                status,
-                similarity,
-            };
-
-            diffs.push(git_diff);
         }
 
-        Ok(diffs)
+        let git_diff = GitDiff {
+            old_file,
+            new_file,
+            hunks,
+            stats: GitDiffStats {
+                files_changed: 1,
+                insertions,
+                deletions,
+            },
+            is_binary,
+            status,
+            similarity,
+            submodule,
+        };
+
+        diffs.push(git_diff);
     }
+
+    Ok(diffs)
 }
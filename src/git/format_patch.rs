@@ -0,0 +1,560 @@
+use crate::git::GitRepository;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use git2::{
+    Commit, Diff, DiffFindOptions, DiffFormat, DiffOptions, DiffStatsFormat, Oid, Repository,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The fixed date git puts on the mbox "From " separator line
+const MBOX_FROM_DATE: &str = "Mon Sep 17 00:00:00 2001";
+/// Longest sanitized subject used in a patch file name, as in git
+const FILE_NAME_SUBJECT_MAX: usize = 52;
+
+/// Options mirroring the `git format-patch` flags we support
+#[derive(Debug, Clone)]
+pub struct FormatPatchOptions {
+    pub subject_prefix: String,    // "PATCH" gives "[PATCH 1/3]"
+    pub numbered: bool,            // Number a single patch too, like `-n`
+    pub start_number: usize,       // Like `--start-number`
+    pub cover_letter: bool,        // Prepend a 0/N cover letter
+    pub signature: Option<String>, // Text after the "-- " line; None omits it
+}
+
+impl Default for FormatPatchOptions {
+    fn default() -> Self {
+        Self {
+            subject_prefix: "PATCH".to_string(),
+            numbered: false,
+            start_number: 1,
+            cover_letter: false,
+            signature: Some(format!("gitk-rs {}", env!("CARGO_PKG_VERSION"))),
+        }
+    }
+}
+
+/// Commit summaries grouped by author name, as in `git shortlog`
+type Shortlog = BTreeMap<String, Vec<String>>;
+
+/// One message of a patch series and the file name format-patch would give it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchFile {
+    pub file_name: String,
+    pub contents: Vec<u8>, // Raw bytes; file contents need not be UTF-8
+}
+
+/// Join a series into a single mbox, ready for `git am`
+#[must_use]
+pub fn patches_to_mbox(patches: &[PatchFile]) -> Vec<u8> {
+    patches
+        .iter()
+        .flat_map(|patch| patch.contents.iter().copied())
+        .collect()
+}
+
+impl GitRepository {
+    /// Format commits as a `git format-patch` series, oldest first. Merge
+    /// commits are skipped like format-patch does.
+    pub fn format_patches(
+        &self,
+        commit_ids: &[String],
+        options: &FormatPatchOptions,
+    ) -> Result<Vec<PatchFile>> {
+        let repo = self.repo();
+        let ids = commit_ids
+            .iter()
+            .map(|id| Ok(repo.revparse_single(id)?.peel_to_commit()?.id()))
+            .collect::<Result<Vec<Oid>>>()?;
+
+        let commits = order_oldest_first(repo, &ids)?
+            .into_iter()
+            .map(|id| repo.find_commit(id))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let commits: Vec<Commit> = commits
+            .into_iter()
+            .filter(|commit| commit.parent_count() <= 1)
+            .collect();
+        if commits.is_empty() {
+            return Err(anyhow!("Merge commits cannot be exported as patches"));
+        }
+
+        let last_number = options.start_number + commits.len() - 1;
+        let numbered = options.numbered || options.cover_letter || commits.len() > 1;
+        let prefix = |number: usize| subject_prefix(options, numbered, number, last_number);
+
+        let mut patches = Vec::new();
+        if options.cover_letter {
+            patches.push(PatchFile {
+                file_name: "0000-cover-letter.patch".to_string(),
+                contents: format_cover_letter(repo, &commits, &prefix(0), options)?,
+            });
+        }
+        for (index, commit) in commits.iter().enumerate() {
+            let number = options.start_number + index;
+            patches.push(PatchFile {
+                file_name: patch_file_name(number, commit.summary().unwrap_or("")),
+                contents: format_commit(repo, commit, &prefix(number), options)?,
+            });
+        }
+        Ok(patches)
+    }
+
+    /// Write a series as numbered files into `dir`, like `format-patch -o`
+    pub fn write_patch_series(
+        &self,
+        commit_ids: &[String],
+        options: &FormatPatchOptions,
+        dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        self.format_patches(commit_ids, options)?
+            .into_iter()
+            .map(|patch| {
+                let path = dir.join(&patch.file_name);
+                fs::write(&path, &patch.contents)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Write a series as one mbox file, like `format-patch --stdout`
+    pub fn write_patch_mbox(
+        &self,
+        commit_ids: &[String],
+        options: &FormatPatchOptions,
+        path: &Path,
+    ) -> Result<()> {
+        let patches = self.format_patches(commit_ids, options)?;
+        fs::write(path, patches_to_mbox(&patches))?;
+        Ok(())
+    }
+}
+
+/// Sort the selected commits so ancestors come before their descendants
+fn order_oldest_first(repo: &Repository, ids: &[Oid]) -> Result<Vec<Oid>> {
+    let wanted: HashSet<Oid> = ids.iter().copied().collect();
+    if wanted.len() <= 1 {
+        return Ok(wanted.into_iter().collect());
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    for id in &wanted {
+        revwalk.push(*id)?;
+    }
+
+    let mut ordered = Vec::with_capacity(wanted.len());
+    for oid in revwalk {
+        let oid = oid?;
+        if wanted.contains(&oid) {
+            ordered.push(oid);
+            if ordered.len() == wanted.len() {
+                break;
+            }
+        }
+    }
+    ordered.reverse();
+    Ok(ordered)
+}
+
+fn subject_prefix(
+    options: &FormatPatchOptions,
+    numbered: bool,
+    number: usize,
+    last_number: usize,
+) -> String {
+    let prefix = options.subject_prefix.trim();
+    if numbered {
+        let width = last_number.to_string().len();
+        let numbering = format!("{number:0width$}/{last_number}");
+        if prefix.is_empty() {
+            format!("[{numbering}] ")
+        } else {
+            format!("[{prefix} {numbering}] ")
+        }
+    } else if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("[{prefix}] ")
+    }
+}
+
+fn format_commit(
+    repo: &Repository,
+    commit: &Commit,
+    prefix: &str,
+    options: &FormatPatchOptions,
+) -> Result<Vec<u8>> {
+    let summary = commit.summary().unwrap_or("");
+    let body = commit.body().unwrap_or("").trim_end();
+
+    let mut out = Vec::new();
+    out.extend_from_slice(
+        email_headers(
+            &commit.id().to_string(),
+            &commit.author(),
+            &format!("{}{}", prefix, encode_header_text(summary)),
+            !summary.is_ascii() || !body.is_ascii(),
+        )
+        .as_bytes(),
+    );
+    if !body.is_empty() {
+        out.extend_from_slice(body.as_bytes());
+        out.push(b'\n');
+    }
+    out.extend_from_slice(b"---\n");
+
+    let parent_tree = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(0)?.tree()?),
+    };
+    let diff = tree_diff(repo, parent_tree.as_ref(), &commit.tree()?)?;
+    out.extend_from_slice(diff_stat(&diff)?.as_bytes());
+    out.push(b'\n');
+    out.extend_from_slice(&diff_patch(&diff)?);
+    push_signature(&mut out, options);
+    Ok(out)
+}
+
+/// The 0/N message: shortlog of the series and its combined diffstat
+fn format_cover_letter(
+    repo: &Repository,
+    commits: &[Commit],
+    prefix: &str,
+    options: &FormatPatchOptions,
+) -> Result<Vec<u8>> {
+    let sender = repo
+        .signature()
+        .unwrap_or_else(|_| commits[0].author().to_owned());
+
+    let mut out = email_headers(
+        &Oid::zero().to_string(),
+        &sender,
+        &format!("{prefix}*** SUBJECT HERE ***"),
+        false,
+    );
+    out.push_str("*** BLURB HERE ***\n\n");
+
+    // Shortlog: summaries grouped by author
+    let mut by_author = Shortlog::new();
+    for commit in commits {
+        by_author
+            .entry(commit.author().name().unwrap_or("").to_string())
+            .or_default()
+            .push(commit.summary().unwrap_or("").to_string());
+    }
+    for (author, summaries) in &by_author {
+        let _ = writeln!(out, "{author} ({}):", summaries.len());
+        for summary in summaries {
+            let _ = writeln!(out, "  {summary}");
+        }
+        out.push('\n');
+    }
+
+    let first = &commits[0];
+    let base_tree = match first.parent_count() {
+        0 => None,
+        _ => Some(first.parent(0)?.tree()?),
+    };
+    let last_tree = commits[commits.len() - 1].tree()?;
+    out.push_str(&diff_stat(&tree_diff(
+        repo,
+        base_tree.as_ref(),
+        &last_tree,
+    )?)?);
+    out.push('\n');
+
+    let mut out = out.into_bytes();
+    push_signature(&mut out, options);
+    Ok(out)
+}
+
+/// Mbox separator, From/Date/Subject and, for non-ASCII text, MIME headers,
+/// followed by the blank line that ends the header block
+fn email_headers(id: &str, author: &git2::Signature, subject: &str, utf8_body: bool) -> String {
+    let mut headers = format!("From {id} {MBOX_FROM_DATE}\n");
+    let _ = writeln!(headers, "From: {}", format_address(author));
+    let _ = writeln!(headers, "Date: {}", format_date(author.when()));
+    let _ = writeln!(headers, "Subject: {subject}");
+    if utf8_body {
+        headers.push_str("MIME-Version: 1.0\n");
+        headers.push_str("Content-Type: text/plain; charset=UTF-8\n");
+        headers.push_str("Content-Transfer-Encoding: 8bit\n");
+    }
+    headers.push('\n');
+    headers
+}
+
+fn push_signature(out: &mut Vec<u8>, options: &FormatPatchOptions) {
+    if let Some(ref signature) = options.signature {
+        out.extend_from_slice(format!("-- \n{signature}\n\n").as_bytes());
+    }
+}
+
+fn tree_diff<'r>(
+    repo: &'r Repository,
+    old_tree: Option<&git2::Tree>,
+    new_tree: &git2::Tree,
+) -> Result<Diff<'r>> {
+    let mut options = DiffOptions::new();
+    options.show_binary(true);
+    let mut diff = repo.diff_tree_to_tree(old_tree, Some(new_tree), Some(&mut options))?;
+
+    let mut find_options = DiffFindOptions::new();
+    find_options.renames(true);
+    diff.find_similar(Some(&mut find_options))?;
+    Ok(diff)
+}
+
+fn diff_stat(diff: &Diff) -> Result<String> {
+    let stats = diff.stats()?;
+    let buf = stats.to_buf(DiffStatsFormat::FULL | DiffStatsFormat::INCLUDE_SUMMARY, 72)?;
+    Ok(buf.as_str().unwrap_or("").to_string())
+}
+
+fn diff_patch(diff: &Diff) -> Result<Vec<u8>> {
+    let mut patch = Vec::new();
+    diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin() as u8);
+        }
+        patch.extend_from_slice(line.content());
+        true
+    })?;
+    Ok(patch)
+}
+
+/// RFC 2822 date in the signature's own time zone
+fn format_date(time: git2::Time) -> String {
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60).unwrap_or_else(|| Utc.fix());
+    DateTime::from_timestamp(time.seconds(), 0)
+        .map(|date| {
+            date.with_timezone(&offset)
+                .format("%a, %-d %b %Y %H:%M:%S %z")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// "Name <email>", quoting or encoding the name when mail needs it
+fn format_address(signature: &git2::Signature) -> String {
+    let name = signature.name().unwrap_or("");
+    let email = signature.email().unwrap_or("");
+
+    let name = if !name.is_ascii() {
+        encode_rfc2047(name)
+    } else if name.chars().any(|c| "()<>[]:;@\\,.\"".contains(c)) {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        name.to_string()
+    };
+    format!("{name} <{email}>")
+}
+
+fn encode_header_text(text: &str) -> String {
+    if text.is_ascii() {
+        text.to_string()
+    } else {
+        encode_rfc2047(text)
+    }
+}
+
+/// Q-encode a header word as UTF-8, as git does for non-ASCII names and subjects
+fn encode_rfc2047(text: &str) -> String {
+    let mut encoded = String::from("=?UTF-8?q?");
+    for byte in text.bytes() {
+        match byte {
+            b' ' => encoded.push('_'),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'!' | b'*' | b'+' | b'-' | b'/' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                let _ = write!(encoded, "={byte:02X}");
+            }
+        }
+    }
+    encoded.push_str("?=");
+    encoded
+}
+
+/// "0001-Fix-the-thing.patch" from a commit summary, like format-patch
+fn patch_file_name(number: usize, summary: &str) -> String {
+    let mut slug = String::new();
+    let mut pending_dash = false;
+    for ch in summary.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '.' || ch == '_' {
+            if pending_dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_dash = false;
+            slug.push(ch);
+        } else {
+            pending_dash = true;
+        }
+    }
+
+    let slug = slug.trim_start_matches('.');
+    let slug = &slug[..slug.len().min(FILE_NAME_SUBJECT_MAX)];
+    let slug = slug.trim_end_matches(['.', '-']);
+    format!("{number:04}-{slug}.patch")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use tempfile::TempDir;
+
+    fn configure(path: &Path) -> Result<()> {
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+        Ok(())
+    }
+
+    fn commit_file(repo_path: &Path, file: &str, content: &[u8], message: &str) -> Result<String> {
+        fs::write(repo_path.join(file), content)?;
+        git(repo_path, &["add", file])?;
+        git(repo_path, &["commit", "-m", message])?;
+        git(repo_path, &["rev-parse", "HEAD"])
+    }
+
+    /// Base commit plus a text change, a binary file and a non-ASCII subject
+    fn create_test_repo() -> Result<(TempDir, String, Vec<String>)> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        configure(path)?;
+
+        let base = commit_file(path, "README.md", b"readme\n", "Initial commit")?;
+        let commits = vec![
+            commit_file(
+                path,
+                "README.md",
+                b"readme\nmore\n",
+                "Update the readme\n\nExplain a bit more.",
+            )?,
+            commit_file(path, "logo.bin", &[0, 1, 2, 255, 0, 7], "Add binary logo")?,
+            commit_file(path, "notes.txt", "ñandú\n".as_bytes(), "Añadir notas")?,
+        ];
+        Ok((temp_dir, base, commits))
+    }
+
+    #[test]
+    fn test_format_patch_headers_and_numbering() -> Result<()> {
+        let (temp_dir, _base, commits) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        // Selection order must not matter
+        let selection = vec![commits[1].clone(), commits[0].clone()];
+        let patches = repo.format_patches(&selection, &FormatPatchOptions::default())?;
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].file_name, "0001-Update-the-readme.patch");
+        assert_eq!(patches[1].file_name, "0002-Add-binary-logo.patch");
+
+        let first = String::from_utf8(patches[0].contents.clone())?;
+        assert!(first.starts_with(&format!("From {} {}\n", commits[0], MBOX_FROM_DATE)));
+        assert!(first.contains("From: Test User <test@example.com>\n"));
+        assert!(
+            first.contains("Subject: [PATCH 1/2] Update the readme\n\nExplain a bit more.\n---\n")
+        );
+        assert!(first.contains(" README.md | 1 +\n 1 file changed, 1 insertion(+)\n"));
+        assert!(first.contains("diff --git a/README.md b/README.md\n"));
+        assert!(first.ends_with(&format!("-- \ngitk-rs {}\n\n", env!("CARGO_PKG_VERSION"))));
+
+        let second = String::from_utf8_lossy(&patches[1].contents);
+        assert!(second.contains("GIT binary patch"));
+
+        // A single patch is unnumbered
+        let single = repo.format_patches(&commits[..1], &FormatPatchOptions::default())?;
+        assert!(String::from_utf8_lossy(&single[0].contents).contains("Subject: [PATCH] Update"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_series_applies_with_git_am() -> Result<()> {
+        let (temp_dir, base, commits) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let mbox_path = temp_dir.path().join("series.mbox");
+        repo.write_patch_mbox(&commits, &FormatPatchOptions::default(), &mbox_path)?;
+
+        let clone_dir = TempDir::new()?;
+        let source = temp_dir.path().to_string_lossy().to_string();
+        git(clone_dir.path(), &["clone", "-q", &source, "."])?;
+        configure(clone_dir.path())?;
+        git(clone_dir.path(), &["reset", "-q", "--hard", &base])?;
+        git(
+            clone_dir.path(),
+            &["am", "-q", &mbox_path.to_string_lossy()],
+        )?;
+
+        let tree = |path: &Path| git(path, &["rev-parse", "HEAD^{tree}"]);
+        assert_eq!(tree(clone_dir.path())?, tree(temp_dir.path())?);
+
+        let log = |path: &Path| git(path, &["log", "-3", "--format=%an <%ae> %at %B"]);
+        assert_eq!(log(clone_dir.path())?, log(temp_dir.path())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cover_letter_and_file_output() -> Result<()> {
+        let (temp_dir, _base, commits) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let options = FormatPatchOptions {
+            cover_letter: true,
+            signature: None,
+            ..FormatPatchOptions::default()
+        };
+
+        let out_dir = temp_dir.path().join("outgoing");
+        let written = repo.write_patch_series(&commits[..2], &options, &out_dir)?;
+        assert_eq!(written.len(), 3);
+        assert!(written[0].ends_with("0000-cover-letter.patch"));
+
+        let cover = fs::read_to_string(&written[0])?;
+        assert!(cover.contains("Subject: [PATCH 0/2] *** SUBJECT HERE ***\n"));
+        assert!(cover.contains("Test User (2):\n  Update the readme\n  Add binary logo\n"));
+        assert!(cover.contains(" 2 files changed"));
+        assert!(!cover.contains("-- \n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_commits_are_skipped() -> Result<()> {
+        let (temp_dir, base, _commits) = create_test_repo()?;
+        let path = temp_dir.path();
+        git(path, &["checkout", "-q", "-b", "side", &base])?;
+        commit_file(path, "side.txt", b"side\n", "Side change")?;
+        git(path, &["checkout", "-q", "-"])?;
+        git(
+            path,
+            &["merge", "-q", "--no-ff", "-m", "Merge side", "side"],
+        )?;
+        let merge = git(path, &["rev-parse", "HEAD"])?;
+
+        let repo = GitRepository::open(path)?;
+        assert!(
+            repo.format_patches(&[merge], &FormatPatchOptions::default())
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_patch_file_name_and_encoding() {
+        assert_eq!(
+            patch_file_name(3, "Fix: crash when  opening [repo]..."),
+            "0003-Fix-crash-when-opening-repo.patch"
+        );
+        assert_eq!(
+            patch_file_name(12, "Añadir notas"),
+            "0012-A-adir-notas.patch"
+        );
+        assert_eq!(
+            encode_rfc2047("Añadir notas"),
+            "=?UTF-8?q?A=C3=B1adir_notas?="
+        );
+    }
+}
@@ -0,0 +1,860 @@
+use crate::git::GitRepository;
+use anyhow::{Result, anyhow};
+use git2::{Oid, Repository};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use tracing::{debug, info};
+
+/// Parent slot value for "no parent" in the commit-graph file
+const GRAPH_PARENT_NONE: u32 = 0x7000_0000;
+/// Marks an index into the extra edge list (and the last edge in that list)
+const GRAPH_EDGE_FLAG: u32 = 0x8000_0000;
+/// SHA-1 object id length, the only hash version we read
+const GRAPH_HASH_LEN: usize = 20;
+
+/// A caller's tip paired with its index position
+type IndexedTip<T> = (T, u32);
+/// Commit-graph chunk ids mapped to their file offsets
+type ChunkOffsets = HashMap<[u8; 4], usize>;
+
+/// In-memory commit DAG with generation numbers for fast ancestry queries.
+///
+/// Commits are immutable, so the index only ever grows: `update` adds the
+/// commits reachable from new ref tips and leaves the rest untouched.
+#[derive(Debug, Clone, Default)]
+pub struct CommitGraphIndex {
+    ids: Vec<Oid>,
+    positions: HashMap<Oid, u32>,
+    parents: Vec<Vec<u32>>,
+    children: Vec<Vec<u32>>,
+    generations: Vec<u32>, // 1 for root commits, 1 + max(parent generations) otherwise
+    commit_times: Vec<i64>,
+}
+
+/// Commits read from `objects/info/commit-graph`, indexed by file position
+struct CommitGraphFile {
+    ids: Vec<Oid>,
+    parents: Vec<Vec<u32>>,
+    commit_times: Vec<i64>,
+}
+
+/// Commit found while walking from the ref tips, not yet linked
+struct PendingCommit {
+    id: Oid,
+    parents: Vec<Oid>,
+    time: i64,
+}
+
+impl CommitGraphIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index every commit reachable from the repository's refs and HEAD
+    pub fn build(repo: &GitRepository) -> Result<Self> {
+        let mut index = Self::new();
+        index.update(repo)?;
+        Ok(index)
+    }
+
+    /// Add commits reachable from the current refs that are not indexed yet.
+    /// Returns the number of commits added.
+    pub fn update(&mut self, repo: &GitRepository) -> Result<usize> {
+        self.update_from(repo.repo())
+    }
+
+    fn update_from(&mut self, repo: &Repository) -> Result<usize> {
+        let first_new = self.next_position()?;
+
+        // Seed an empty index from git's own commit-graph file when available
+        if self.ids.is_empty() {
+            if let Some(graph) = read_commit_graph_file(repo.commondir()) {
+                self.import_graph_file(graph)?;
+            }
+        }
+
+        let tips = collect_tips(repo)?;
+        self.index_from_tips(repo, tips)?;
+        self.compute_generations(first_new);
+
+        let added = self.ids.len() - first_new as usize;
+        if added > 0 {
+            info!(
+                "Indexed {} commits ({} total) for ancestry queries",
+                added,
+                self.ids.len()
+            );
+        }
+        Ok(added)
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    #[must_use]
+    pub fn contains(&self, id: Oid) -> bool {
+        self.positions.contains_key(&id)
+    }
+
+    /// Generation number of a commit; `None` if it is not indexed
+    #[must_use]
+    pub fn generation(&self, id: Oid) -> Option<u32> {
+        self.position(id).map(|pos| self.generations[pos as usize])
+    }
+
+    /// Committer time of an indexed commit (seconds since the epoch)
+    #[must_use]
+    pub fn commit_time(&self, id: Oid) -> Option<i64> {
+        self.position(id).map(|pos| self.commit_times[pos as usize])
+    }
+
+    #[must_use]
+    pub fn parents(&self, id: Oid) -> Vec<Oid> {
+        self.position(id)
+            .map(|pos| self.ids_of(&self.parents[pos as usize]))
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn children(&self, id: Oid) -> Vec<Oid> {
+        self.position(id)
+            .map(|pos| self.ids_of(&self.children[pos as usize]))
+            .unwrap_or_default()
+    }
+
+    /// Whether `ancestor` is reachable from `descendant` (a commit is its own ancestor)
+    #[must_use]
+    pub fn is_ancestor(&self, ancestor: Oid, descendant: Oid) -> bool {
+        let (Some(target), Some(start)) = (self.position(ancestor), self.position(descendant))
+        else {
+            return false;
+        };
+        if target == start {
+            return true;
+        }
+
+        // Anything at or below the ancestor's generation cannot lead to it
+        let floor = self.generations[target as usize];
+        if self.generations[start as usize] <= floor {
+            return false;
+        }
+
+        let mut visited = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            for &parent in &self.parents[pos as usize] {
+                if parent == target {
+                    return true;
+                }
+                if self.generations[parent as usize] > floor && visited.insert(parent) {
+                    stack.push(parent);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Best common ancestors of two commits, highest generation first
+    #[must_use]
+    pub fn merge_bases(&self, a: Oid, b: Oid) -> Vec<Oid> {
+        const PARENT1: u8 = 1;
+        const PARENT2: u8 = 2;
+        const STALE: u8 = 4;
+        const RESULT: u8 = 8;
+
+        let (Some(a), Some(b)) = (self.position(a), self.position(b)) else {
+            return Vec::new();
+        };
+        if a == b {
+            return vec![self.ids[a as usize]];
+        }
+
+        // Paint down from both sides in generation order; commits painted by
+        // both are common ancestors, and everything below them goes stale
+        let mut flags: HashMap<u32, u8> = HashMap::from([(a, PARENT1), (b, PARENT2)]);
+        let mut queue = BinaryHeap::from([
+            (self.generations[a as usize], a),
+            (self.generations[b as usize], b),
+        ]);
+        let mut candidates = Vec::new();
+
+        // Queue entries per commit and how many of them are not stale; the
+        // walk is over once only stale entries are left
+        let mut queued: HashMap<u32, usize> = HashMap::from([(a, 1), (b, 1)]);
+        let mut non_stale = 2;
+
+        while non_stale > 0 {
+            let Some((_, pos)) = queue.pop() else {
+                break;
+            };
+            *queued.entry(pos).or_default() -= 1;
+            if flags[&pos] & STALE == 0 {
+                non_stale -= 1;
+            }
+            let mut paint = flags[&pos] & (PARENT1 | PARENT2 | STALE);
+            if paint == PARENT1 | PARENT2 {
+                let entry = flags.entry(pos).or_default();
+                if *entry & RESULT == 0 {
+                    *entry |= RESULT;
+                    candidates.push(pos);
+                }
+                paint |= STALE;
+            }
+
+            for &parent in &self.parents[pos as usize] {
+                let entry = flags.entry(parent).or_default();
+                if *entry & paint == paint {
+                    continue;
+                }
+                let was_stale = *entry & STALE != 0;
+                *entry |= paint;
+                let is_stale = *entry & STALE != 0;
+
+                let count = queued.entry(parent).or_default();
+                if is_stale && !was_stale {
+                    non_stale -= *count;
+                }
+                *count += 1;
+                if !is_stale {
+                    non_stale += 1;
+                }
+                queue.push((self.generations[parent as usize], parent));
+            }
+        }
+
+        // Drop candidates that are ancestors of other candidates
+        let mut bases: Vec<u32> = candidates
+            .iter()
+            .copied()
+            .filter(|&pos| {
+                !candidates.iter().any(|&other| {
+                    other != pos
+                        && self.is_ancestor(self.ids[pos as usize], self.ids[other as usize])
+                })
+            })
+            .collect();
+        bases.sort_by_key(|&pos| Reverse(self.generations[pos as usize]));
+        self.ids_of(&bases)
+    }
+
+    /// The best common ancestor of two commits
+    #[must_use]
+    pub fn merge_base(&self, a: Oid, b: Oid) -> Option<Oid> {
+        self.merge_bases(a, b).into_iter().next()
+    }
+
+    /// The tips (e.g. branch heads) from which `commit` is reachable
+    pub fn tips_containing<T>(
+        &self,
+        commit: Oid,
+        tips: impl IntoIterator<Item = (T, Oid)>,
+    ) -> Vec<T> {
+        let Some(start) = self.position(commit) else {
+            return Vec::new();
+        };
+        let floor = self.generations[start as usize];
+
+        let tips: Vec<IndexedTip<T>> = tips
+            .into_iter()
+            .filter_map(|(tip, id)| self.position(id).map(|pos| (tip, pos)))
+            .filter(|&(_, pos)| self.generations[pos as usize] >= floor)
+            .collect();
+        let Some(ceiling) = tips
+            .iter()
+            .map(|&(_, pos)| self.generations[pos as usize])
+            .max()
+        else {
+            return Vec::new();
+        };
+
+        // Walk up through children once; descendants above the highest tip
+        // cannot be ancestors of any tip
+        let mut descendants = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            for &child in &self.children[pos as usize] {
+                if self.generations[child as usize] <= ceiling && descendants.insert(child) {
+                    stack.push(child);
+                }
+            }
+        }
+
+        tips.into_iter()
+            .filter(|(_, pos)| descendants.contains(pos))
+            .map(|(tip, _)| tip)
+            .collect()
+    }
+
+    /// Ancestors of a commit (including itself), newest generations first,
+    /// stopping after `limit` commits
+    #[must_use]
+    pub fn ancestors(&self, id: Oid, limit: usize) -> Vec<Oid> {
+        let Some(start) = self.position(id) else {
+            return Vec::new();
+        };
+
+        let mut result = Vec::new();
+        let mut seen = HashSet::from([start]);
+        let mut queue = BinaryHeap::from([(self.generations[start as usize], start)]);
+        while let Some((_, pos)) = queue.pop() {
+            if result.len() >= limit {
+                break;
+            }
+            result.push(self.ids[pos as usize]);
+            for &parent in &self.parents[pos as usize] {
+                if seen.insert(parent) {
+                    queue.push((self.generations[parent as usize], parent));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Closest strict ancestors matching `is_marked`; the walk does not go
+    /// past a match, and matches behind other matches are dropped
+    pub fn nearest_ancestors_matching(&self, id: Oid, is_marked: impl Fn(Oid) -> bool) -> Vec<Oid> {
+        let Some(start) = self.position(id) else {
+            return Vec::new();
+        };
+
+        let mut found: Vec<u32> = Vec::new();
+        let mut seen = HashSet::from([start]);
+        let mut queue = BinaryHeap::from([(self.generations[start as usize], start)]);
+        while let Some((_, pos)) = queue.pop() {
+            if pos != start && is_marked(self.ids[pos as usize]) {
+                found.push(pos);
+                continue;
+            }
+            for &parent in &self.parents[pos as usize] {
+                if seen.insert(parent) {
+                    queue.push((self.generations[parent as usize], parent));
+                }
+            }
+        }
+
+        let nearest: Vec<u32> = found
+            .iter()
+            .copied()
+            .filter(|&pos| {
+                !found.iter().any(|&other| {
+                    other != pos
+                        && self.is_ancestor(self.ids[pos as usize], self.ids[other as usize])
+                })
+            })
+            .collect();
+        self.ids_of(&nearest)
+    }
+
+    /// Closest strict descendants matching `is_marked`; the walk does not go
+    /// past a match, and matches beyond other matches are dropped
+    pub fn nearest_descendants_matching(
+        &self,
+        id: Oid,
+        is_marked: impl Fn(Oid) -> bool,
+    ) -> Vec<Oid> {
+        let Some(start) = self.position(id) else {
+            return Vec::new();
+        };
+
+        let mut found: Vec<u32> = Vec::new();
+        let mut seen = HashSet::from([start]);
+        let mut queue = BinaryHeap::from([(Reverse(self.generations[start as usize]), start)]);
+        while let Some((_, pos)) = queue.pop() {
+            if pos != start && is_marked(self.ids[pos as usize]) {
+                found.push(pos);
+                continue;
+            }
+            for &child in &self.children[pos as usize] {
+                if seen.insert(child) {
+                    queue.push((Reverse(self.generations[child as usize]), child));
+                }
+            }
+        }
+
+        let nearest: Vec<u32> = found
+            .iter()
+            .copied()
+            .filter(|&pos| {
+                !found.iter().any(|&other| {
+                    other != pos
+                        && self.is_ancestor(self.ids[other as usize], self.ids[pos as usize])
+                })
+            })
+            .collect();
+        self.ids_of(&nearest)
+    }
+
+    fn position(&self, id: Oid) -> Option<u32> {
+        self.positions.get(&id).copied()
+    }
+
+    fn ids_of(&self, positions: &[u32]) -> Vec<Oid> {
+        positions
+            .iter()
+            .map(|&pos| self.ids[pos as usize])
+            .collect()
+    }
+
+    /// Position the next pushed commit will get
+    fn next_position(&self) -> Result<u32> {
+        u32::try_from(self.ids.len()).map_err(|_| anyhow!("Commit index is full"))
+    }
+
+    fn push_commit(&mut self, id: Oid, time: i64) -> Result<u32> {
+        let pos = self.next_position()?;
+        self.ids.push(id);
+        self.positions.insert(id, pos);
+        self.parents.push(Vec::new());
+        self.children.push(Vec::new());
+        self.generations.push(0);
+        self.commit_times.push(time);
+        Ok(pos)
+    }
+
+    fn link(&mut self, child: u32, parents: Vec<u32>) {
+        for &parent in &parents {
+            self.children[parent as usize].push(child);
+        }
+        self.parents[child as usize] = parents;
+    }
+
+    fn import_graph_file(&mut self, graph: CommitGraphFile) -> Result<()> {
+        // File positions map one-to-one onto index positions
+        for (&id, &time) in graph.ids.iter().zip(&graph.commit_times) {
+            self.push_commit(id, time)?;
+        }
+        for (pos, parents) in (0..).zip(graph.parents) {
+            self.link(pos, parents);
+        }
+        debug!("Loaded {} commits from commit-graph file", self.ids.len());
+        Ok(())
+    }
+
+    /// Walk parents from the tips until reaching already indexed commits
+    fn index_from_tips(&mut self, repo: &Repository, tips: Vec<Oid>) -> Result<()> {
+        let mut pending = Vec::new();
+        let mut queued: HashSet<Oid> = HashSet::new();
+        let mut stack: Vec<Oid> = tips.into_iter().filter(|id| !self.contains(*id)).collect();
+
+        while let Some(id) = stack.pop() {
+            if self.contains(id) || !queued.insert(id) {
+                continue;
+            }
+            // Parents missing from a shallow clone end the walk
+            let Ok(commit) = repo.find_commit(id) else {
+                continue;
+            };
+            let parents: Vec<Oid> = commit.parent_ids().collect();
+            stack.extend(
+                parents
+                    .iter()
+                    .copied()
+                    .filter(|parent| !self.contains(*parent)),
+            );
+            pending.push(PendingCommit {
+                id,
+                parents,
+                time: commit.time().seconds(),
+            });
+        }
+
+        let first = self.next_position()?;
+        for commit in &pending {
+            self.push_commit(commit.id, commit.time)?;
+        }
+        for (pos, commit) in (first..).zip(pending) {
+            let parents = commit
+                .parents
+                .iter()
+                .filter_map(|parent| self.position(*parent))
+                .collect();
+            self.link(pos, parents);
+        }
+        Ok(())
+    }
+
+    /// Assign generation numbers to commits added from `first` onwards
+    fn compute_generations(&mut self, first: u32) {
+        for start in (first..).take(self.ids.len() - first as usize) {
+            if self.generations[start as usize] != 0 {
+                continue;
+            }
+
+            // Iterative post-order so deep histories don't overflow the stack
+            let mut stack = vec![start];
+            while let Some(&pos) = stack.last() {
+                let unresolved: Vec<u32> = self.parents[pos as usize]
+                    .iter()
+                    .copied()
+                    .filter(|&parent| self.generations[parent as usize] == 0)
+                    .collect();
+                if unresolved.is_empty() {
+                    let generation = self.parents[pos as usize]
+                        .iter()
+                        .map(|&parent| self.generations[parent as usize])
+                        .max()
+                        .unwrap_or(0);
+                    self.generations[pos as usize] = generation + 1;
+                    stack.pop();
+                } else {
+                    stack.extend(unresolved);
+                }
+            }
+        }
+    }
+}
+
+/// Commits the index must cover: every ref and HEAD
+fn collect_tips(repo: &Repository) -> Result<Vec<Oid>> {
+    let mut tips = Vec::new();
+    for reference in repo.references()? {
+        // Refs to trees/blobs and broken refs are simply skipped
+        if let Ok(commit) = reference?.peel_to_commit() {
+            tips.push(commit.id());
+        }
+    }
+    if let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) {
+        tips.push(head.id());
+    }
+    Ok(tips)
+}
+
+/// Parse a single-file, SHA-1 commit-graph. Split graph chains and anything
+/// malformed are ignored; the index then falls back to reading objects.
+fn read_commit_graph_file(common_dir: &Path) -> Option<CommitGraphFile> {
+    let data = fs::read(common_dir.join("objects/info/commit-graph")).ok()?;
+    let graph = parse_commit_graph(&data);
+    if graph.is_none() {
+        debug!("Ignoring unreadable commit-graph file");
+    }
+    graph
+}
+
+fn parse_commit_graph(data: &[u8]) -> Option<CommitGraphFile> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let bytes = data.get(offset..offset + 4)?;
+        Some(u32::from_be_bytes(bytes.try_into().ok()?))
+    };
+    let read_u64 = |offset: usize| -> Option<u64> {
+        let bytes = data.get(offset..offset + 8)?;
+        Some(u64::from_be_bytes(bytes.try_into().ok()?))
+    };
+
+    // Header: signature, version 1, hash version 1 (SHA-1), chunk count, base graphs
+    if data.get(0..4)? != b"CGPH" || *data.get(4)? != 1 || *data.get(5)? != 1 {
+        return None;
+    }
+    let chunk_count = *data.get(6)? as usize;
+    if *data.get(7)? != 0 {
+        return None;
+    }
+
+    let mut chunks = ChunkOffsets::new();
+    for i in 0..chunk_count {
+        let entry = 8 + i * 12;
+        let id: [u8; 4] = data.get(entry..entry + 4)?.try_into().ok()?;
+        chunks.insert(id, usize::try_from(read_u64(entry + 4)?).ok()?);
+    }
+
+    let fanout = *chunks.get(b"OIDF")?;
+    let lookup = *chunks.get(b"OIDL")?;
+    let commit_data = *chunks.get(b"CDAT")?;
+    let extra_edges = chunks.get(b"EDGE").copied();
+
+    let count = read_u32(fanout + 255 * 4)? as usize;
+    let record_len = GRAPH_HASH_LEN + 16;
+    data.get(lookup..lookup + count * GRAPH_HASH_LEN)?;
+    data.get(commit_data..commit_data + count * record_len)?;
+
+    let mut ids = Vec::with_capacity(count);
+    for i in 0..count {
+        let start = lookup + i * GRAPH_HASH_LEN;
+        ids.push(Oid::from_bytes(&data[start..start + GRAPH_HASH_LEN]).ok()?);
+    }
+
+    let valid = |pos: u32| (pos as usize) < count;
+    let mut parents = Vec::with_capacity(count);
+    let mut commit_times = Vec::with_capacity(count);
+    for i in 0..count {
+        let record = commit_data + i * record_len + GRAPH_HASH_LEN;
+        let first = read_u32(record)?;
+        let second = read_u32(record + 4)?;
+        let time_high = u64::from(read_u32(record + 8)? & 0x3);
+        let time_low = u64::from(read_u32(record + 12)?);
+
+        let mut commit_parents = Vec::new();
+        if first != GRAPH_PARENT_NONE {
+            commit_parents.push(valid(first).then_some(first)?);
+        }
+        if second & GRAPH_EDGE_FLAG != 0 {
+            // Octopus merge: the remaining parents live in the EDGE chunk
+            let mut edge = extra_edges? + ((second & !GRAPH_EDGE_FLAG) as usize) * 4;
+            loop {
+                let value = read_u32(edge)?;
+                let parent = value & !GRAPH_EDGE_FLAG;
+                commit_parents.push(valid(parent).then_some(parent)?);
+                if value & GRAPH_EDGE_FLAG != 0 {
+                    break;
+                }
+                edge += 4;
+            }
+        } else if second != GRAPH_PARENT_NONE {
+            commit_parents.push(valid(second).then_some(second)?);
+        }
+
+        parents.push(commit_parents);
+        commit_times.push(((time_high << 32) | time_low).cast_signed());
+    }
+
+    Some(CommitGraphFile {
+        ids,
+        parents,
+        commit_times,
+    })
+}
+
+/// A `CommitGraphIndex::update` running on a background thread
+pub struct CommitIndexUpdate {
+    receiver: Receiver<Result<CommitGraphIndex>>,
+}
+
+impl CommitIndexUpdate {
+    /// Grow `index` with the commits of the repository at `repo_path`
+    pub fn start(repo_path: PathBuf, mut index: CommitGraphIndex) -> Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("commit-index".to_string())
+            .spawn(move || {
+                let result = Repository::open(&repo_path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|repo| index.update_from(&repo))
+                    .map(|_| index);
+                // The receiver is gone when the update was dropped
+                let _ = sender.send(result);
+            })?;
+
+        Ok(Self { receiver })
+    }
+
+    /// The updated index once the thread is done, without blocking
+    #[must_use]
+    pub fn try_finish(&self) -> Option<Result<CommitGraphIndex>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!("Commit index thread stopped"))),
+        }
+    }
+}
+
+impl std::fmt::Debug for CommitIndexUpdate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommitIndexUpdate").finish_non_exhaustive()
+    }
+}
+
+impl GitRepository {
+    /// Build a commit graph index covering all refs
+    pub fn build_commit_graph_index(&self) -> Result<CommitGraphIndex> {
+        CommitGraphIndex::build(self)
+    }
+
+    /// Update a copy of `index` in the background; queries keep using the
+    /// original until the result arrives
+    pub fn start_commit_index_update(&self, index: CommitGraphIndex) -> Result<CommitIndexUpdate> {
+        CommitIndexUpdate::start(self.repo().path().to_path_buf(), index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn commit(repo_path: &Path, message: &str) -> Result<Oid> {
+        git(repo_path, &["commit", "--allow-empty", "-m", message])?;
+        Ok(Oid::from_str(&git(repo_path, &["rev-parse", "HEAD"])?)?)
+    }
+
+    /// root - base - left ---- merge (main)
+    ///            \- right -/
+    ///                  \- topic
+    fn create_test_repo() -> Result<(TempDir, HashMap<&'static str, Oid>)> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        let mut ids = HashMap::new();
+        ids.insert("root", commit(path, "root")?);
+        ids.insert("base", commit(path, "base")?);
+        git(path, &["checkout", "-b", "side"])?;
+        ids.insert("right", commit(path, "right")?);
+        git(path, &["checkout", "-b", "topic"])?;
+        ids.insert("topic", commit(path, "topic")?);
+        git(path, &["checkout", "main"])?;
+        ids.insert("left", commit(path, "left")?);
+        git(path, &["merge", "--no-ff", "-m", "merge", "side"])?;
+        ids.insert("merge", Oid::from_str(&git(path, &["rev-parse", "HEAD"])?)?);
+
+        Ok((temp_dir, ids))
+    }
+
+    fn check_queries(index: &CommitGraphIndex, ids: &HashMap<&str, Oid>) {
+        assert_eq!(index.len(), 6);
+        assert_eq!(index.generation(ids["root"]), Some(1));
+        assert_eq!(index.generation(ids["merge"]), Some(4));
+        assert_eq!(index.parents(ids["merge"]).len(), 2);
+        assert_eq!(index.children(ids["base"]).len(), 2);
+
+        assert!(index.is_ancestor(ids["root"], ids["merge"]));
+        assert!(index.is_ancestor(ids["right"], ids["merge"]));
+        assert!(index.is_ancestor(ids["merge"], ids["merge"]));
+        assert!(!index.is_ancestor(ids["topic"], ids["merge"]));
+        assert!(!index.is_ancestor(ids["merge"], ids["root"]));
+
+        assert_eq!(
+            index.merge_base(ids["topic"], ids["merge"]),
+            Some(ids["right"])
+        );
+        assert_eq!(
+            index.merge_base(ids["topic"], ids["left"]),
+            Some(ids["base"])
+        );
+        assert_eq!(
+            index.merge_bases(ids["left"], ids["right"]),
+            vec![ids["base"]]
+        );
+
+        let tips = [("main", ids["merge"]), ("topic", ids["topic"])];
+        assert_eq!(
+            index.tips_containing(ids["right"], tips),
+            vec!["main", "topic"]
+        );
+        assert_eq!(index.tips_containing(ids["left"], tips), vec!["main"]);
+
+        let marked = |id: Oid| id == ids["base"] || id == ids["right"] || id == ids["merge"];
+        assert_eq!(
+            index.nearest_ancestors_matching(ids["topic"], marked),
+            vec![ids["right"]]
+        );
+        assert_eq!(
+            index.nearest_descendants_matching(ids["base"], marked),
+            vec![ids["right"]]
+        );
+        assert_eq!(
+            index.nearest_descendants_matching(ids["left"], marked),
+            vec![ids["merge"]]
+        );
+        assert!(
+            index
+                .nearest_ancestors_matching(ids["root"], marked)
+                .is_empty()
+        );
+
+        let ancestors = index.ancestors(ids["merge"], 10);
+        assert_eq!(ancestors.len(), 5);
+        assert_eq!(ancestors[0], ids["merge"]);
+    }
+
+    #[test]
+    fn test_index_from_objects() -> Result<()> {
+        let (temp_dir, ids) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let index = repo.build_commit_graph_index()?;
+        check_queries(&index, &ids);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_from_commit_graph_file() -> Result<()> {
+        let (temp_dir, ids) = create_test_repo()?;
+        git(temp_dir.path(), &["commit-graph", "write", "--reachable"])?;
+        let graph = read_commit_graph_file(&temp_dir.path().join(".git"));
+        assert_eq!(graph.map(|graph| graph.ids.len()), Some(6));
+
+        let repo = GitRepository::open(temp_dir.path())?;
+        let index = repo.build_commit_graph_index()?;
+        check_queries(&index, &ids);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_update() -> Result<()> {
+        let (temp_dir, ids) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+        let mut index = repo.build_commit_graph_index()?;
+
+        let next = commit(temp_dir.path(), "after merge")?;
+        assert_eq!(index.update(&repo)?, 1);
+        assert_eq!(index.generation(next), Some(5));
+        assert!(index.is_ancestor(ids["right"], next));
+        assert_eq!(index.update(&repo)?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_background_update() -> Result<()> {
+        let (temp_dir, ids) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let update = repo.start_commit_index_update(CommitGraphIndex::new())?;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let index = loop {
+            if let Some(result) = update.try_finish() {
+                break result?;
+            }
+            assert!(Instant::now() < deadline, "Index update did not finish");
+            thread::sleep(Duration::from_millis(5));
+        };
+        check_queries(&index, &ids);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_bases_of_criss_cross_merge() -> Result<()> {
+        let (temp_dir, ids) = create_test_repo()?;
+        let path = temp_dir.path();
+
+        // Two merges of left and topic in both directions: both are best bases
+        git(path, &["checkout", "-b", "criss", &ids["left"].to_string()])?;
+        git(path, &["merge", "--no-edit", "topic"])?;
+        let criss = Oid::from_str(&git(path, &["rev-parse", "HEAD"])?)?;
+        git(path, &["checkout", "-b", "cross", "topic"])?;
+        git(path, &["merge", "--no-edit", &ids["left"].to_string()])?;
+        let cross = Oid::from_str(&git(path, &["rev-parse", "HEAD"])?)?;
+
+        let repo = GitRepository::open(path)?;
+        let index = repo.build_commit_graph_index()?;
+        let mut bases = index.merge_bases(criss, cross);
+        bases.sort();
+        let mut expected = vec![ids["left"], ids["topic"]];
+        expected.sort();
+        assert_eq!(bases, expected);
+        assert!(!index.is_ancestor(criss, cross));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+use crate::models::GitCommit;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A line from a commit down to one of its parents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LaneParent {
+    pub id: String,
+    pub lane: usize,        // Lane the line continues in below the commit
+    pub row: Option<usize>, // None when the parent is not part of the layout
+}
+
+/// Placement of one commit and the lines around it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LaneRow {
+    pub commit_id: String,
+    pub lane: usize,
+    pub pass_through: Vec<usize>, // Lanes whose lines run past this commit
+    pub parents: Vec<LaneParent>,
+}
+
+/// Lane assignment for a commit list ordered children first.
+///
+/// Shared by the graph view, image export and the command line. Each lane
+/// waits for one commit; a commit takes the lane waiting for it, its first
+/// parent continues straight down and further parents open lanes to the right.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LaneLayout {
+    pub rows: Vec<LaneRow>,
+    pub lane_count: usize,
+}
+
+/// One character of the text graph and the lane it belongs to, for coloring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphCell {
+    pub ch: char,
+    pub lane: Option<usize>,
+}
+
+/// A line of the text graph: a commit's row or the connector below it
+#[derive(Debug, Clone)]
+pub struct GraphTextRow {
+    pub commit_row: Option<usize>, // Index into `LaneLayout::rows`; None for connectors
+    pub cells: Vec<GraphCell>,
+}
+
+/// The commit each lane waits for; `None` marks a free lane
+type WaitingLanes<'a> = Vec<Option<&'a str>>;
+
+// Directions a box-drawing character connects to
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+impl LaneLayout {
+    #[must_use]
+    pub fn compute(commits: &[GitCommit]) -> Self {
+        let mut waiting = WaitingLanes::new();
+        let mut rows = Vec::with_capacity(commits.len());
+
+        for commit in commits {
+            // A commit nothing waits for is a branch tip
+            let lane =
+                waiting_lane(&waiting, &commit.id).unwrap_or_else(|| free_lane(&mut waiting, 0));
+            let pass_through = waiting
+                .iter()
+                .enumerate()
+                .filter(|(other, id)| *other != lane && id.is_some())
+                .map(|(other, _)| other)
+                .collect();
+            waiting[lane] = None;
+
+            let mut parents = Vec::with_capacity(commit.parent_ids.len());
+            for (index, parent_id) in commit.parent_ids.iter().enumerate() {
+                // A parent already waited for joins that lane instead
+                let parent_lane = waiting_lane(&waiting, parent_id).unwrap_or_else(|| {
+                    let parent_lane = if index == 0 {
+                        lane
+                    } else {
+                        free_lane(&mut waiting, lane + 1)
+                    };
+                    waiting[parent_lane] = Some(parent_id.as_str());
+                    parent_lane
+                });
+                parents.push(LaneParent {
+                    id: parent_id.clone(),
+                    lane: parent_lane,
+                    row: None,
+                });
+            }
+
+            while waiting.last() == Some(&None) {
+                waiting.pop();
+            }
+            rows.push(LaneRow {
+                commit_id: commit.id.clone(),
+                lane,
+                pass_through,
+                parents,
+            });
+        }
+
+        let commit_rows: HashMap<String, usize> = rows
+            .iter()
+            .enumerate()
+            .map(|(row, lane_row)| (lane_row.commit_id.clone(), row))
+            .collect();
+        let mut lane_count = 0;
+        for row in &mut rows {
+            for parent in &mut row.parents {
+                parent.row = commit_rows.get(&parent.id).copied();
+                lane_count = lane_count.max(parent.lane + 1);
+            }
+            let widest = row
+                .pass_through
+                .iter()
+                .copied()
+                .max()
+                .unwrap_or(0)
+                .max(row.lane);
+            lane_count = lane_count.max(widest + 1);
+        }
+
+        Self { rows, lane_count }
+    }
+
+    /// Lane of every commit, by commit ID
+    #[must_use]
+    pub fn lanes(&self) -> HashMap<String, usize> {
+        self.rows
+            .iter()
+            .map(|row| (row.commit_id.clone(), row.lane))
+            .collect()
+    }
+
+    /// The graph as Unicode box-drawing text, two columns per lane. A
+    /// connector line follows a commit whenever one of its lines bends.
+    #[must_use]
+    pub fn text_rows(&self) -> Vec<GraphTextRow> {
+        let width = (self.lane_count * 2).saturating_sub(1);
+        let mut text_rows = Vec::new();
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let mut cells = vec![
+                GraphCell {
+                    ch: ' ',
+                    lane: None,
+                };
+                width
+            ];
+            for &lane in &row.pass_through {
+                cells[lane * 2] = GraphCell {
+                    ch: '│',
+                    lane: Some(lane),
+                };
+            }
+            cells[row.lane * 2] = GraphCell {
+                ch: if row.parents.len() > 1 { '◆' } else { '●' },
+                lane: Some(row.lane),
+            };
+            text_rows.push(GraphTextRow {
+                commit_row: Some(index),
+                cells: trim_cells(cells),
+            });
+
+            if row.parents.iter().all(|parent| parent.lane == row.lane) {
+                continue;
+            }
+
+            let mut directions = vec![0u8; width];
+            let mut lanes = vec![None; width];
+            for &lane in &row.pass_through {
+                directions[lane * 2] |= UP | DOWN;
+                lanes[lane * 2] = Some(lane);
+            }
+            for parent in &row.parents {
+                let (from, to) = (row.lane * 2, parent.lane * 2);
+                if from == to {
+                    directions[from] |= UP | DOWN;
+                    lanes[from] = Some(row.lane);
+                    continue;
+                }
+                let (toward, back) = if to > from {
+                    (RIGHT, LEFT)
+                } else {
+                    (LEFT, RIGHT)
+                };
+                directions[from] |= UP | toward;
+                for column in from.min(to) + 1..from.max(to) {
+                    directions[column] |= LEFT | RIGHT;
+                    lanes[column] = Some(parent.lane);
+                }
+                directions[to] |= DOWN | back;
+                lanes[to] = Some(parent.lane);
+                lanes[from].get_or_insert(row.lane);
+            }
+
+            let cells = directions
+                .iter()
+                .zip(lanes)
+                .map(|(&direction, lane)| GraphCell {
+                    ch: box_char(direction),
+                    lane,
+                })
+                .collect();
+            text_rows.push(GraphTextRow {
+                commit_row: None,
+                cells: trim_cells(cells),
+            });
+        }
+
+        text_rows
+    }
+}
+
+/// Lane already waiting for a commit
+fn waiting_lane(waiting: &WaitingLanes, id: &str) -> Option<usize> {
+    waiting.iter().position(|waited| *waited == Some(id))
+}
+
+/// First lane at or after `start` not waiting for a commit, added if needed
+fn free_lane(waiting: &mut WaitingLanes, start: usize) -> usize {
+    if let Some(lane) = (start..waiting.len()).find(|&lane| waiting[lane].is_none()) {
+        return lane;
+    }
+    waiting.resize(waiting.len().max(start), None);
+    waiting.push(None);
+    waiting.len() - 1
+}
+
+fn trim_cells(mut cells: Vec<GraphCell>) -> Vec<GraphCell> {
+    while cells.last().is_some_and(|cell| cell.ch == ' ') {
+        cells.pop();
+    }
+    cells
+}
+
+const fn box_char(directions: u8) -> char {
+    match directions {
+        0 => ' ',
+        d if d == UP | DOWN => '│',
+        d if d == LEFT | RIGHT => '─',
+        d if d == DOWN | RIGHT => '╭',
+        d if d == DOWN | LEFT => '╮',
+        d if d == UP | RIGHT => '╰',
+        d if d == UP | LEFT => '╯',
+        d if d == UP | DOWN | RIGHT => '├',
+        d if d == UP | DOWN | LEFT => '┤',
+        d if d == DOWN | LEFT | RIGHT => '┬',
+        d if d == UP | LEFT | RIGHT => '┴',
+        d if d == UP | DOWN | LEFT | RIGHT => '┼',
+        UP => '╵',
+        DOWN => '╷',
+        LEFT => '╴',
+        _ => '╶',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GitSignature;
+    use chrono::Utc;
+
+    fn commit(id: &str, parents: &[&str]) -> GitCommit {
+        let signature = GitSignature {
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+            when: Utc::now(),
+        };
+        GitCommit {
+            id: id.to_string(),
+            short_id: id.to_string(),
+            author: signature.clone(),
+            committer: signature,
+            message: format!("Commit {}", id),
+            summary: format!("Commit {}", id),
+            parent_ids: parents.iter().map(|parent| parent.to_string()).collect(),
+            tree_id: String::new(),
+            is_signed: false,
+            notes: Vec::new(),
+        }
+    }
+
+    fn graph_text(layout: &LaneLayout) -> Vec<String> {
+        layout
+            .text_rows()
+            .iter()
+            .map(|row| row.cells.iter().map(|cell| cell.ch).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_history_uses_one_lane() {
+        let commits = vec![commit("c", &["b"]), commit("b", &["a"]), commit("a", &[])];
+        let layout = LaneLayout::compute(&commits);
+
+        assert_eq!(layout.lane_count, 1);
+        assert!(layout.rows.iter().all(|row| row.lane == 0));
+        assert_eq!(layout.rows[0].parents[0].row, Some(1));
+        assert_eq!(graph_text(&layout), vec!["●", "●", "●"]);
+    }
+
+    #[test]
+    fn test_branch_and_merge() {
+        let commits = vec![
+            commit("merge", &["main", "topic"]),
+            commit("topic", &["base"]),
+            commit("main", &["base"]),
+            commit("base", &["older"]),
+        ];
+        let layout = LaneLayout::compute(&commits);
+
+        assert_eq!(layout.lane_count, 2);
+        let lanes: Vec<usize> = layout.rows.iter().map(|row| row.lane).collect();
+        assert_eq!(lanes, vec![0, 1, 0, 1]);
+        assert_eq!(layout.rows[1].pass_through, vec![0]);
+        assert_eq!(layout.rows[2].parents[0].lane, 1); // Joins the lane waiting for base
+        assert_eq!(layout.rows[3].parents[0].row, None); // Not loaded
+        assert_eq!(layout.lanes()["topic"], 1);
+
+        assert_eq!(
+            graph_text(&layout),
+            vec!["◆", "├─╮", "│ ●", "● │", "╰─┤", "  ●"]
+        );
+    }
+
+    #[test]
+    fn test_new_tips_reuse_free_lanes() {
+        let commits = vec![
+            commit("feature", &["base"]),
+            commit("main", &["base"]),
+            commit("base", &[]),
+            commit("orphan", &[]),
+        ];
+        let layout = LaneLayout::compute(&commits);
+
+        let lanes: Vec<usize> = layout.rows.iter().map(|row| row.lane).collect();
+        assert_eq!(lanes, vec![0, 1, 0, 0]);
+        assert_eq!(graph_text(&layout), vec!["●", "│ ●", "├─╯", "●", "●"]);
+    }
+}
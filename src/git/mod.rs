@@ -1,31 +1,75 @@
+pub mod activity;
+pub mod apply;
+pub mod approxidate;
 pub mod commands;
 pub mod commit;
+pub mod commit_cache;
+pub mod commit_export;
+pub mod commit_scan;
 pub mod commits;
+pub mod compare;
+pub mod describe;
 pub mod diff;
 pub mod error_handling;
+pub mod format_patch;
+pub mod graph_index;
+pub mod lane_layout;
+pub mod notes;
 pub mod operations;
+pub mod path_search;
+pub mod pickaxe;
 pub mod platform_security;
+pub mod query;
 pub mod references;
 pub mod remotes;
 pub mod repository;
 pub mod security;
+pub mod signatures;
 pub mod stash;
+pub mod statistics;
 pub mod stream;
+pub mod submodules;
 pub mod tags;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod unified_diff;
 pub mod views;
+pub mod watcher;
+pub mod worktrees;
 
+pub use activity::*;
+pub use apply::*;
+pub use approxidate::*;
 pub use commands::*;
 pub use commit::*;
+pub use commit_cache::*;
+pub use commit_export::*;
+pub use commit_scan::*;
 pub use commits::*;
+pub use compare::*;
+pub use describe::*;
 pub use diff::*;
 pub use error_handling::*;
+pub use format_patch::*;
+pub use graph_index::*;
+pub use lane_layout::*;
+pub use notes::*;
 pub use operations::*;
+pub use path_search::*;
+pub use pickaxe::*;
 pub use platform_security::*;
+pub use query::*;
 pub use references::*;
 pub use remotes::*;
 pub use repository::*;
 pub use security::*;
+pub use signatures::*;
 pub use stash::*;
+pub use statistics::*;
 pub use stream::*;
+pub use submodules::*;
 pub use tags::*;
+pub use unified_diff::*;
 pub use views::*;
+pub use watcher::*;
+pub use worktrees::*;
@@ -0,0 +1,506 @@
+use crate::git::operations::{OperationRecord, OperationType};
+use crate::git::{ErrorReporter, GitError, GitRepository, InputValidator};
+use crate::models::GitNote;
+use anyhow::Result;
+use git2::{Oid, Repository, Signature};
+use tracing::{info, warn};
+
+/// Notes ref used when `core.notesRef` is not configured
+pub const DEFAULT_NOTES_REF: &str = "refs/notes/commits";
+
+/// Git notes management (add/edit/remove notes on commits)
+pub struct NotesManager {
+    repo: Repository,
+    operation_history: Vec<OperationRecord>,
+}
+
+/// Note operation result with detailed information
+#[derive(Debug)]
+pub struct NoteOperationResult {
+    pub success: bool,
+    pub operation: OperationType,
+    pub commit_id: String,
+    pub notes_ref: String,
+    pub message: String,
+}
+
+/// Expand a notes ref shorthand the way git does (`commits` -> `refs/notes/commits`)
+#[must_use]
+pub fn expand_notes_ref(name: &str) -> String {
+    if name.starts_with("refs/notes/") {
+        name.to_string()
+    } else if let Some(rest) = name.strip_prefix("notes/") {
+        format!("refs/notes/{rest}")
+    } else {
+        format!("refs/notes/{name}")
+    }
+}
+
+impl GitRepository {
+    /// The notes ref written to by default (`core.notesRef`)
+    pub fn default_notes_ref(&self) -> String {
+        self.repo()
+            .note_default_ref()
+            .unwrap_or_else(|_| DEFAULT_NOTES_REF.to_string())
+    }
+
+    /// Notes refs whose notes are displayed: the default ref, every
+    /// `notes.displayRef` entry (globs allowed) and any extra configured refs.
+    /// Only refs that exist in the repository are returned.
+    pub fn get_notes_display_refs(&self, extra_refs: &[String]) -> Vec<String> {
+        let mut patterns = vec![self.default_notes_ref()];
+
+        if let Ok(config) = self.repo().config() {
+            if let Ok(entries) = config.multivar("notes.displayRef", None) {
+                let _ = entries.for_each(|entry| {
+                    if let Some(value) = entry.value() {
+                        patterns.push(value.to_string());
+                    }
+                });
+            }
+        }
+        patterns.extend(extra_refs.iter().cloned());
+
+        let mut refs: Vec<String> = Vec::new();
+        for pattern in patterns {
+            let pattern = expand_notes_ref(pattern.trim());
+            let matches: Vec<String> = if pattern.contains(['*', '?', '[']) {
+                match self.repo().references_glob(&pattern) {
+                    Ok(references) => references
+                        .flatten()
+                        .filter_map(|reference| reference.name().map(str::to_string))
+                        .collect(),
+                    Err(e) => {
+                        warn!("Invalid notes ref pattern '{}': {}", pattern, e);
+                        Vec::new()
+                    }
+                }
+            } else if self.repo().find_reference(&pattern).is_ok() {
+                vec![pattern]
+            } else {
+                Vec::new()
+            };
+
+            for name in matches {
+                if !refs.contains(&name) {
+                    refs.push(name);
+                }
+            }
+        }
+
+        refs
+    }
+
+    /// Read a commit's note from one notes ref (the default ref when `None`)
+    pub fn get_commit_note(
+        &self,
+        commit_id: &str,
+        notes_ref: Option<&str>,
+    ) -> Result<Option<String>> {
+        InputValidator::validate_commit_id(commit_id)?;
+        let oid = self
+            .repo()
+            .revparse_single(commit_id)?
+            .peel_to_commit()?
+            .id();
+        let notes_ref = notes_ref.map_or_else(|| self.default_notes_ref(), expand_notes_ref);
+
+        match self.repo().find_note(Some(&notes_ref), oid) {
+            Ok(note) => Ok(Some(note.message().unwrap_or("").to_string())),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Read the notes attached to a commit from every display notes ref
+    pub fn get_commit_notes(&self, commit_id: &str, extra_refs: &[String]) -> Result<Vec<GitNote>> {
+        InputValidator::validate_commit_id(commit_id)?;
+        let oid = self
+            .repo()
+            .revparse_single(commit_id)?
+            .peel_to_commit()?
+            .id();
+
+        let mut notes = Vec::new();
+        for notes_ref in self.get_notes_display_refs(extra_refs) {
+            match self.repo().find_note(Some(&notes_ref), oid) {
+                Ok(note) => notes.push(GitNote {
+                    notes_ref,
+                    message: note.message().unwrap_or("").to_string(),
+                }),
+                Err(e) if e.code() == git2::ErrorCode::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(notes)
+    }
+}
+
+impl NotesManager {
+    /// Create a new notes manager
+    pub fn new(git_repo: &GitRepository) -> Result<Self> {
+        let repo_path = git_repo.get_repository().path();
+        let repo = Repository::open(repo_path)?;
+
+        Ok(Self {
+            repo,
+            operation_history: Vec::new(),
+        })
+    }
+
+    /// Add a note to a commit, replacing any existing note in the same ref
+    pub fn set_note(
+        &mut self,
+        commit_id: &str,
+        message: &str,
+        notes_ref: Option<&str>,
+    ) -> Result<NoteOperationResult> {
+        let notes_ref = self.resolve_notes_ref(notes_ref);
+
+        if let Err(e) = InputValidator::validate_commit_id(commit_id)
+            .and_then(|()| InputValidator::validate_commit_message(message))
+            .and_then(|()| InputValidator::validate_ref_name(&notes_ref))
+        {
+            ErrorReporter::log_error(&e, "note validation");
+            return Ok(Self::failure(
+                OperationType::NoteAdd,
+                commit_id,
+                notes_ref,
+                format!("Invalid note: {e}"),
+            ));
+        }
+
+        let oid = match self.resolve_commit(commit_id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Ok(Self::failure(
+                    OperationType::NoteAdd,
+                    commit_id,
+                    notes_ref,
+                    format!("Commit not found: {e}"),
+                ));
+            }
+        };
+
+        let signature = self.signature()?;
+        let existing = self.repo.find_note(Some(&notes_ref), oid).ok();
+        let operation = if existing.is_some() {
+            OperationType::NoteEdit
+        } else {
+            OperationType::NoteAdd
+        };
+
+        // git stores notes with a trailing newline; mirror `git notes add -m`
+        let mut note_text = message.trim_end().to_string();
+        note_text.push('\n');
+
+        if let Err(e) = self.repo.note(
+            &signature,
+            &signature,
+            Some(&notes_ref),
+            oid,
+            &note_text,
+            true,
+        ) {
+            ErrorReporter::log_error(&GitError::from(e), "note write");
+            return Ok(Self::failure(
+                operation,
+                commit_id,
+                notes_ref,
+                "Failed to write note".to_string(),
+            ));
+        }
+
+        let original_state = existing.and_then(|note| note.message().map(str::to_string));
+        self.record_operation(OperationRecord {
+            operation_type: operation.clone(),
+            timestamp: chrono::Utc::now(),
+            description: format!(
+                "{} note on commit {} in {}",
+                if operation == OperationType::NoteEdit {
+                    "Edited"
+                } else {
+                    "Added"
+                },
+                &oid.to_string()[..8],
+                notes_ref
+            ),
+            original_state,
+            new_state: Some(note_text),
+            affected_refs: vec![notes_ref.clone()],
+        });
+
+        info!("Wrote note for commit {} to {}", oid, notes_ref);
+
+        Ok(NoteOperationResult {
+            success: true,
+            operation,
+            commit_id: oid.to_string(),
+            notes_ref,
+            message: "Successfully saved note".to_string(),
+        })
+    }
+
+    /// Remove the note attached to a commit
+    pub fn remove_note(
+        &mut self,
+        commit_id: &str,
+        notes_ref: Option<&str>,
+    ) -> Result<NoteOperationResult> {
+        let notes_ref = self.resolve_notes_ref(notes_ref);
+
+        if let Err(e) = InputValidator::validate_commit_id(commit_id)
+            .and_then(|()| InputValidator::validate_ref_name(&notes_ref))
+        {
+            ErrorReporter::log_error(&e, "note validation");
+            return Ok(Self::failure(
+                OperationType::NoteRemove,
+                commit_id,
+                notes_ref,
+                format!("Invalid note: {e}"),
+            ));
+        }
+
+        let oid = match self.resolve_commit(commit_id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Ok(Self::failure(
+                    OperationType::NoteRemove,
+                    commit_id,
+                    notes_ref,
+                    format!("Commit not found: {e}"),
+                ));
+            }
+        };
+
+        let original_state = match self.repo.find_note(Some(&notes_ref), oid) {
+            Ok(note) => note.message().map(str::to_string),
+            Err(_) => {
+                return Ok(Self::failure(
+                    OperationType::NoteRemove,
+                    commit_id,
+                    notes_ref.clone(),
+                    format!("Commit has no note in {notes_ref}"),
+                ));
+            }
+        };
+
+        let signature = self.signature()?;
+        if let Err(e) = self
+            .repo
+            .note_delete(oid, Some(&notes_ref), &signature, &signature)
+        {
+            ErrorReporter::log_error(&GitError::from(e), "note removal");
+            return Ok(Self::failure(
+                OperationType::NoteRemove,
+                commit_id,
+                notes_ref,
+                "Failed to remove note".to_string(),
+            ));
+        }
+
+        self.record_operation(OperationRecord {
+            operation_type: OperationType::NoteRemove,
+            timestamp: chrono::Utc::now(),
+            description: format!(
+                "Removed note on commit {} from {}",
+                &oid.to_string()[..8],
+                notes_ref
+            ),
+            original_state,
+            new_state: None,
+            affected_refs: vec![notes_ref.clone()],
+        });
+
+        info!("Removed note for commit {} from {}", oid, notes_ref);
+
+        Ok(NoteOperationResult {
+            success: true,
+            operation: OperationType::NoteRemove,
+            commit_id: oid.to_string(),
+            notes_ref,
+            message: "Successfully removed note".to_string(),
+        })
+    }
+
+    /// Get operation history
+    #[must_use]
+    pub fn get_operation_history(&self) -> &[OperationRecord] {
+        &self.operation_history
+    }
+
+    /// Use the requested ref, falling back to `core.notesRef`
+    fn resolve_notes_ref(&self, notes_ref: Option<&str>) -> String {
+        match notes_ref {
+            Some(name) if !name.trim().is_empty() => expand_notes_ref(name.trim()),
+            _ => self
+                .repo
+                .note_default_ref()
+                .unwrap_or_else(|_| DEFAULT_NOTES_REF.to_string()),
+        }
+    }
+
+    fn resolve_commit(&self, commit_id: &str) -> Result<Oid> {
+        Ok(self.repo.revparse_single(commit_id)?.peel_to_commit()?.id())
+    }
+
+    fn signature(&self) -> Result<Signature<'static>> {
+        match self.repo.signature() {
+            Ok(sig) => Ok(sig),
+            Err(_) => Ok(Signature::now("Git User", "user@example.com")?),
+        }
+    }
+
+    fn failure(
+        operation: OperationType,
+        commit_id: &str,
+        notes_ref: String,
+        message: String,
+    ) -> NoteOperationResult {
+        warn!("Note operation failed: {}", message);
+        NoteOperationResult {
+            success: false,
+            operation,
+            commit_id: commit_id.to_string(),
+            notes_ref,
+            message,
+        }
+    }
+
+    /// Record an operation in history
+    fn record_operation(&mut self, record: OperationRecord) {
+        let operation_type = record.operation_type.clone();
+        self.operation_history.push(record);
+
+        // Maintain history limit
+        if self.operation_history.len() > 100 {
+            self.operation_history.remove(0);
+        }
+
+        info!("Recorded note operation: {:?}", operation_type);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn create_test_repo() -> Result<(TempDir, PathBuf, String)> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().to_path_buf();
+
+        git(&repo_path, &["init"])?;
+        git(&repo_path, &["config", "user.name", "Test User"])?;
+        git(&repo_path, &["config", "user.email", "test@example.com"])?;
+
+        fs::write(repo_path.join("test.txt"), "content")?;
+        git(&repo_path, &["add", "test.txt"])?;
+        git(&repo_path, &["commit", "-m", "Initial commit"])?;
+        let commit_sha = git(&repo_path, &["rev-parse", "HEAD"])?;
+
+        Ok((temp_dir, repo_path, commit_sha))
+    }
+
+    #[test]
+    fn test_expand_notes_ref() {
+        assert_eq!(expand_notes_ref("commits"), "refs/notes/commits");
+        assert_eq!(expand_notes_ref("notes/review"), "refs/notes/review");
+        assert_eq!(expand_notes_ref("refs/notes/review"), "refs/notes/review");
+    }
+
+    #[test]
+    fn test_read_notes_from_display_refs() -> Result<()> {
+        let (_temp_dir, repo_path, commit_sha) = create_test_repo()?;
+        git(&repo_path, &["notes", "add", "-m", "default note"])?;
+        git(
+            &repo_path,
+            &["notes", "--ref=review", "add", "-m", "review note"],
+        )?;
+        git(&repo_path, &["notes", "--ref=ci", "add", "-m", "ci note"])?;
+
+        let git_repo = GitRepository::open(&repo_path)?;
+        let notes = git_repo.get_commit_notes(&commit_sha, &[])?;
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].notes_ref, "refs/notes/commits");
+        assert_eq!(notes[0].message.trim(), "default note");
+
+        // notes.displayRef accepts globs; extra refs accept shorthand
+        git(
+            &repo_path,
+            &["config", "notes.displayRef", "refs/notes/rev*"],
+        )?;
+        let git_repo = GitRepository::open(&repo_path)?;
+        let notes = git_repo.get_commit_notes(&commit_sha, &["ci".to_string()])?;
+        let refs: Vec<&str> = notes.iter().map(|n| n.notes_ref.as_str()).collect();
+        assert_eq!(
+            refs,
+            vec!["refs/notes/commits", "refs/notes/review", "refs/notes/ci"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_edit_and_remove_note() -> Result<()> {
+        let (_temp_dir, repo_path, commit_sha) = create_test_repo()?;
+        let git_repo = GitRepository::open(&repo_path)?;
+        let mut manager = NotesManager::new(&git_repo)?;
+
+        let result = manager.set_note(&commit_sha, "first note", None)?;
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.operation, OperationType::NoteAdd);
+        assert_eq!(result.notes_ref, "refs/notes/commits");
+        assert_eq!(
+            git_repo.get_commit_note(&commit_sha, None)?.as_deref(),
+            Some("first note\n")
+        );
+        assert_eq!(
+            git(&repo_path, &["notes", "show", &commit_sha])?,
+            "first note"
+        );
+
+        let result = manager.set_note(&commit_sha, "edited note", None)?;
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.operation, OperationType::NoteEdit);
+        assert_eq!(
+            git(&repo_path, &["notes", "show", &commit_sha])?,
+            "edited note"
+        );
+
+        let result = manager.remove_note(&commit_sha, None)?;
+        assert!(result.success, "{}", result.message);
+        assert!(git_repo.get_commit_notes(&commit_sha, &[])?.is_empty());
+
+        // Removing again reports failure without erroring
+        let result = manager.remove_note(&commit_sha, None)?;
+        assert!(!result.success);
+        assert_eq!(manager.get_operation_history().len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_note_in_custom_ref() -> Result<()> {
+        let (_temp_dir, repo_path, commit_sha) = create_test_repo()?;
+        let git_repo = GitRepository::open(&repo_path)?;
+        let mut manager = NotesManager::new(&git_repo)?;
+
+        let result = manager.set_note(&commit_sha, "review note", Some("review"))?;
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.notes_ref, "refs/notes/review");
+        assert_eq!(
+            git(&repo_path, &["notes", "--ref=review", "show", &commit_sha])?,
+            "review note"
+        );
+
+        let result = manager.set_note(&commit_sha, "", None)?;
+        assert!(!result.success);
+
+        Ok(())
+    }
+}
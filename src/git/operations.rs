@@ -11,6 +11,9 @@ use crate::git::stash::{
 use crate::git::tags::{
     TagCreateConfig, TagFilterOptions, TagInfo, TagManager, TagOperationResult,
 };
+use crate::git::worktrees::{
+    branch_checked_out_elsewhere, ensure_branch_not_checked_out_elsewhere,
+};
 use crate::git::{ErrorReporter, GitRepository, InputSanitizer, InputValidator};
 use anyhow::Result;
 use git2::{BranchType as Git2BranchType, Oid, Repository};
@@ -59,6 +62,17 @@ pub enum OperationType {
     TagDelete,
     TagMove,
 
+    // Note operations
+    NoteAdd,
+    NoteEdit,
+    NoteRemove,
+
+    // Worktree operations
+    WorktreeAdd,
+    WorktreeRemove,
+    WorktreeLock,
+    WorktreeUnlock,
+
     // Stash operations
     StashSave,
     StashApply,
@@ -358,6 +372,22 @@ impl GitOperations {
             }
         }
 
+        // Safety check: don't delete a branch checked out in another worktree
+        if let Ok(Some(holder)) = branch_checked_out_elsewhere(&self.repo, &sanitized_name) {
+            return Ok(BranchOperationResult {
+                success: false,
+                operation: OperationType::BranchDelete,
+                branch_name: sanitized_name,
+                commit_id: None,
+                message: format!(
+                    "Cannot delete branch checked out at '{}'",
+                    holder.path.display()
+                ),
+                conflicts: vec![],
+                modified_files: vec![],
+            });
+        }
+
         // Delete branch in separate scope
         let delete_result = {
             let mut branch = match self
@@ -435,8 +465,75 @@ impl GitOperations {
         })
     }
 
+    /// Check out an existing local branch
+    pub fn checkout_branch(&mut self, branch_name: &str) -> Result<BranchOperationResult> {
+        if let Err(e) = InputValidator::validate_ref_name(branch_name) {
+            ErrorReporter::log_error(&e, "branch checkout validation");
+            return Ok(BranchOperationResult {
+                success: false,
+                operation: OperationType::BranchCheckout,
+                branch_name: branch_name.to_string(),
+                commit_id: None,
+                message: format!("Invalid branch name: {e}"),
+                conflicts: vec![],
+                modified_files: vec![],
+            });
+        }
+
+        let original_head = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string());
+
+        if let Err(e) = self.checkout_branch_simple(branch_name) {
+            warn!("Checkout of '{}' failed: {}", branch_name, e);
+            return Ok(BranchOperationResult {
+                success: false,
+                operation: OperationType::BranchCheckout,
+                branch_name: branch_name.to_string(),
+                commit_id: None,
+                message: format!("Checkout failed: {e}"),
+                conflicts: vec![],
+                modified_files: vec![],
+            });
+        }
+
+        let new_head = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string());
+
+        self.record_operation(OperationRecord {
+            operation_type: OperationType::BranchCheckout,
+            timestamp: chrono::Utc::now(),
+            description: format!("Checked out branch '{branch_name}'"),
+            original_state: original_head,
+            new_state: new_head.clone(),
+            affected_refs: vec!["HEAD".to_string()],
+        });
+
+        info!("Checked out branch '{}'", branch_name);
+
+        Ok(BranchOperationResult {
+            success: true,
+            operation: OperationType::BranchCheckout,
+            branch_name: branch_name.to_string(),
+            commit_id: new_head,
+            message: "Successfully checked out branch".to_string(),
+            conflicts: vec![],
+            modified_files: vec![],
+        })
+    }
+
     /// Simple checkout implementation without complex merge checks
     fn checkout_branch_simple(&mut self, branch_name: &str) -> Result<()> {
+        // A branch can only be checked out in one worktree at a time
+        ensure_branch_not_checked_out_elsewhere(&self.repo, branch_name)?;
+
         let branch = self.repo.find_branch(branch_name, Git2BranchType::Local)?;
         let target_oid = branch
             .get()
@@ -0,0 +1,177 @@
+use crate::git::commit_scan::walk_history;
+use crate::git::{CommitScan, GitRepository, ScanContext, ScanEvent};
+use anyhow::{Result, anyhow};
+use git2::{DiffOptions, Oid, Repository};
+use std::path::Path;
+
+/// Split a "touching paths" query into pathspecs (directories, files or globs)
+pub fn parse_pathspecs(input: &str) -> Vec<String> {
+    input.split_whitespace().map(str::to_string).collect()
+}
+
+/// Whether a commit changes any path matching the pathspecs. Merges only
+/// count when they differ from every parent, as with `git log -- <paths>`.
+fn commit_touches_paths(
+    repo: &Repository,
+    commit: &git2::Commit,
+    pathspecs: &[String],
+) -> Result<bool> {
+    let mut options = DiffOptions::new();
+    for pathspec in pathspecs {
+        options.pathspec(pathspec);
+    }
+
+    let tree = commit.tree()?;
+    if commit.parent_count() == 0 {
+        let diff = repo.diff_tree_to_tree(None, Some(&tree), Some(&mut options))?;
+        return Ok(diff.deltas().len() > 0);
+    }
+
+    for parent in commit.parents() {
+        let diff =
+            repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&tree), Some(&mut options))?;
+        if diff.deltas().len() == 0 {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Incremental output of a background path search; items are commit ids
+pub type PathSearchEvent = ScanEvent<String>;
+
+/// A "touching paths" search running on a background thread
+pub type PathSearch = CommitScan<String, Vec<String>>;
+
+fn run_search(
+    repo_path: &Path,
+    start: Option<Oid>,
+    pathspecs: &[String],
+    limit: usize,
+    scan: &mut ScanContext<String>,
+) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    for oid in walk_history(&repo, start)?.take(limit) {
+        if !scan.next_commit() {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        // The receiver is gone when the search was dropped
+        if commit_touches_paths(&repo, &commit, pathspecs)? && !scan.report(commit.id().to_string())
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+impl GitRepository {
+    /// Whether a commit changes any path matching the pathspecs
+    pub fn commit_touches_paths(&self, commit_id: &str, pathspecs: &[String]) -> Result<bool> {
+        let commit = self.repo().find_commit(Oid::from_str(commit_id)?)?;
+        commit_touches_paths(self.repo(), &commit, pathspecs)
+    }
+
+    /// Start a background scan for commits touching the pathspecs
+    pub fn start_path_search(
+        &self,
+        pathspecs: Vec<String>,
+        start: Option<&str>,
+        limit: usize,
+    ) -> Result<PathSearch> {
+        let start = match start {
+            Some(start) => Some(self.repo().revparse_single(start)?.peel_to_commit()?.id()),
+            None => None,
+        };
+        if pathspecs.is_empty() {
+            return Err(anyhow!("No paths given"));
+        }
+
+        let repo_path = self.repo().path().to_path_buf();
+        let thread_pathspecs = pathspecs.clone();
+        CommitScan::start("path-search", pathspecs, None, move |scan| {
+            run_search(&repo_path, start, &thread_pathspecs, limit, scan)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn commit_file(repo_path: &Path, file: &str, content: &str) -> Result<String> {
+        let path = repo_path.join(file);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, content)?;
+        git(repo_path, &["add", file])?;
+        git(repo_path, &["commit", "-m", &format!("Change {}", file)])?;
+        git(repo_path, &["rev-parse", "HEAD"])
+    }
+
+    fn create_test_repo() -> Result<(TempDir, Vec<String>)> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        let commits = vec![
+            commit_file(path, "README.md", "readme")?,
+            commit_file(path, "src/main.rs", "fn main() {}")?,
+            commit_file(path, "docs/guide.md", "guide")?,
+            commit_file(path, "src/lib.rs", "pub fn lib() {}")?,
+        ];
+        Ok((temp_dir, commits))
+    }
+
+    #[test]
+    fn test_commit_touches_paths() -> Result<()> {
+        let (temp_dir, commits) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let src = parse_pathspecs("src");
+        let touching: Vec<bool> = commits
+            .iter()
+            .map(|id| repo.commit_touches_paths(id, &src))
+            .collect::<Result<_>>()?;
+        assert_eq!(touching, vec![false, true, false, true]);
+
+        let markdown = parse_pathspecs("*.md  src/lib.rs");
+        let touching: Vec<bool> = commits
+            .iter()
+            .map(|id| repo.commit_touches_paths(id, &markdown))
+            .collect::<Result<_>>()?;
+        assert_eq!(touching, vec![true, false, true, true]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_background_path_search() -> Result<()> {
+        let (temp_dir, commits) = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let mut search = repo.start_path_search(parse_pathspecs("docs"), None, 100)?;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut matches = Vec::new();
+        while !search.is_complete() && Instant::now() < deadline {
+            match search.try_next() {
+                Some(PathSearchEvent::Item(id)) => matches.push(id),
+                Some(_) => {}
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+
+        assert_eq!(matches, vec![commits[2].clone()]);
+        assert_eq!(search.scanned_count(), 4);
+        assert!(repo.start_path_search(Vec::new(), None, 100).is_err());
+
+        Ok(())
+    }
+}
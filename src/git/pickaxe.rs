@@ -0,0 +1,366 @@
+use crate::git::commit_scan::walk_history;
+use crate::git::{CommitScan, GitRepository, ScanContext, ScanEvent};
+use crate::models::GitCommit;
+use anyhow::{Result, anyhow};
+use git2::{Diff, Oid, Repository};
+use regex::bytes::{Regex, RegexBuilder};
+use std::path::Path;
+
+/// What a pickaxe search looks for in each commit's diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickaxeMode {
+    /// `git log -S`: the number of occurrences changes, i.e. the string is added or removed
+    AddsOrRemoves,
+    /// `git log -G`: an added or removed line matches the regex
+    ChangesMatching,
+}
+
+/// Pickaxe search parameters
+#[derive(Debug, Clone)]
+pub struct PickaxeQuery {
+    pub pattern: String,
+    pub mode: PickaxeMode,
+    pub regex: bool, // Treat the pattern as a regex in `AddsOrRemoves` mode (`--pickaxe-regex`)
+    pub ignore_case: bool,
+    pub max_matches: Option<usize>,
+}
+
+impl PickaxeQuery {
+    #[must_use]
+    pub fn new(pattern: &str, mode: PickaxeMode) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            mode,
+            regex: false,
+            ignore_case: false,
+            max_matches: None,
+        }
+    }
+
+    /// `-G` is always a regex, `-S` only with `regex`
+    #[must_use]
+    pub fn is_regex(&self) -> bool {
+        self.regex || self.mode == PickaxeMode::ChangesMatching
+    }
+
+    /// Compile the pattern, escaping it unless it is a regex
+    fn matcher(&self) -> Result<PickaxeMatcher> {
+        if self.pattern.is_empty() {
+            return Err(anyhow!("Search pattern cannot be empty"));
+        }
+
+        let source = if self.is_regex() {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(self.ignore_case)
+            .build()
+            .map_err(|e| anyhow!("Invalid search pattern: {e}"))?;
+
+        Ok(PickaxeMatcher {
+            regex,
+            mode: self.mode,
+        })
+    }
+}
+
+/// Compiled pickaxe query
+struct PickaxeMatcher {
+    regex: Regex,
+    mode: PickaxeMode,
+}
+
+impl PickaxeMatcher {
+    /// Whether the diff introducing `commit` is a pickaxe hit
+    fn matches_commit(&self, repo: &Repository, commit: &git2::Commit) -> Result<bool> {
+        // Like `git log` without -m, merges are not diffed
+        if commit.parent_count() > 1 {
+            return Ok(false);
+        }
+
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+        match self.mode {
+            PickaxeMode::AddsOrRemoves => self.occurrences_changed(repo, &diff),
+            PickaxeMode::ChangesMatching => self.changed_line_matches(&diff),
+        }
+    }
+
+    fn occurrences_changed(&self, repo: &Repository, diff: &Diff) -> Result<bool> {
+        let count = |id: Oid| -> Result<usize> {
+            if id.is_zero() {
+                return Ok(0);
+            }
+            // Gitlinks point at commits in another repository
+            repo.find_blob(id).map_or_else(
+                |_| Ok(0),
+                |blob| Ok(self.regex.find_iter(blob.content()).count()),
+            )
+        };
+
+        for delta in diff.deltas() {
+            if count(delta.old_file().id())? != count(delta.new_file().id())? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn changed_line_matches(&self, diff: &Diff) -> Result<bool> {
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(diff, index)? else {
+                continue; // Binary files have no lines to match
+            };
+
+            for hunk in 0..patch.num_hunks() {
+                for line in 0..patch.num_lines_in_hunk(hunk)? {
+                    let line = patch.line_in_hunk(hunk, line)?;
+                    if matches!(line.origin(), '+' | '-') && self.regex.is_match(line.content()) {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Incremental output of a background pickaxe search
+pub type PickaxeEvent = ScanEvent<GitCommit>;
+
+/// A pickaxe search running on a background thread
+pub type PickaxeSearch = CommitScan<GitCommit, PickaxeQuery>;
+
+fn run_search(
+    repo_path: &Path,
+    start: Option<Oid>,
+    matcher: &PickaxeMatcher,
+    max_matches: Option<usize>,
+    scan: &mut ScanContext<GitCommit>,
+) -> Result<()> {
+    let repo = Repository::open(repo_path)?;
+    let mut match_count = 0;
+    for oid in walk_history(&repo, start)? {
+        if !scan.next_commit() {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        if matcher.matches_commit(&repo, &commit)? {
+            match_count += 1;
+            // The receiver is gone when the search was dropped
+            if !scan.report(GitCommit::new(&commit)?) {
+                break;
+            }
+            if max_matches.is_some_and(|max| match_count >= max) {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl GitRepository {
+    /// Start a background pickaxe search from `start` (HEAD when `None`)
+    pub fn start_pickaxe_search(
+        &self,
+        query: PickaxeQuery,
+        start: Option<&str>,
+    ) -> Result<PickaxeSearch> {
+        let start = match start {
+            Some(start) => Some(self.repo().revparse_single(start)?.peel_to_commit()?.id()),
+            None => None,
+        };
+
+        // Fail fast on a bad pattern instead of inside the thread
+        let matcher = query.matcher()?;
+        let repo_path = self.repo().path().to_path_buf();
+        let max_matches = query.max_matches;
+        CommitScan::start("pickaxe-search", query, None, move |scan| {
+            run_search(&repo_path, start, &matcher, max_matches, scan)
+        })
+    }
+
+    /// Commits whose diff matches a pickaxe query, searched synchronously
+    pub fn pickaxe_search(
+        &self,
+        query: &PickaxeQuery,
+        max_count: Option<usize>,
+    ) -> Result<Vec<GitCommit>> {
+        let matcher = query.matcher()?;
+        let limit = max_count.unwrap_or(1000);
+        let mut commits = Vec::new();
+        for oid in walk_history(self.repo(), None)? {
+            if commits.len() >= limit {
+                break;
+            }
+            let commit = self.repo().find_commit(oid?)?;
+            if matcher.matches_commit(self.repo(), &commit)? {
+                commits.push(GitCommit::new(&commit)?);
+            }
+        }
+
+        Ok(commits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::{git, git_with_env};
+    use std::fs;
+    use std::path::Path;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    /// Commit on `date`, so the time-sorted walk has a fixed order
+    fn commit_file(repo_path: &Path, content: &str, message: &str, date: &str) -> Result<()> {
+        fs::write(repo_path.join("code.rs"), content)?;
+        git(repo_path, &["add", "code.rs"])?;
+        let dates = [("GIT_AUTHOR_DATE", date), ("GIT_COMMITTER_DATE", date)];
+        git_with_env(repo_path, &["commit", "-m", message], &dates)?;
+        Ok(())
+    }
+
+    fn create_test_repo() -> Result<TempDir> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        commit_file(path, "fn main() {}\n", "Initial", "2024-01-01T12:00:00")?;
+        commit_file(
+            path,
+            "fn main() {}\nfn frobnicate() {}\n",
+            "Add frobnicate",
+            "2024-01-02T12:00:00",
+        )?;
+        // Edits the line: -G sees it changed, -S sees the same count
+        commit_file(
+            path,
+            "fn main() {}\nfn frobnicate(x: u32) {}\n",
+            "Add argument",
+            "2024-01-03T12:00:00",
+        )?;
+        commit_file(
+            path,
+            "fn main() {}\n",
+            "Remove frobnicate",
+            "2024-01-04T12:00:00",
+        )?;
+
+        Ok(temp_dir)
+    }
+
+    fn summaries(commits: &[GitCommit]) -> Vec<&str> {
+        commits.iter().map(|c| c.summary.as_str()).collect()
+    }
+
+    #[test]
+    fn test_adds_or_removes() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let query = PickaxeQuery::new("frobnicate", PickaxeMode::AddsOrRemoves);
+        let commits = repo.pickaxe_search(&query, None)?;
+        assert_eq!(
+            summaries(&commits),
+            vec!["Remove frobnicate", "Add frobnicate"]
+        );
+
+        let mut query = PickaxeQuery::new("FROB", PickaxeMode::AddsOrRemoves);
+        assert!(repo.pickaxe_search(&query, None)?.is_empty());
+        query.ignore_case = true;
+        assert_eq!(repo.pickaxe_search(&query, None)?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_pattern_with_regex_characters() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        commit_file(
+            temp_dir.path(),
+            "fn main() { a || b; \"\\n\"; }\n",
+            "Add operators",
+            "2024-01-05T12:00:00",
+        )?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        for pattern in ["a || b", r#""\n""#] {
+            let query = PickaxeQuery::new(pattern, PickaxeMode::AddsOrRemoves);
+            assert!(!query.is_regex());
+            assert_eq!(
+                summaries(&repo.pickaxe_search(&query, None)?),
+                vec!["Add operators"]
+            );
+        }
+
+        let mut query = PickaxeQuery::new("main() {", PickaxeMode::AddsOrRemoves);
+        assert_eq!(
+            summaries(&repo.pickaxe_search(&query, None)?),
+            vec!["Initial"]
+        );
+        query.regex = true;
+        assert!(query.is_regex());
+        assert!(repo.pickaxe_search(&query, None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_changes_matching() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let query = PickaxeQuery::new(r"fn frob\w+", PickaxeMode::ChangesMatching);
+        let commits = repo.pickaxe_search(&query, None)?;
+        assert_eq!(
+            summaries(&commits),
+            vec!["Remove frobnicate", "Add argument", "Add frobnicate"]
+        );
+
+        let invalid = PickaxeQuery::new("(unclosed", PickaxeMode::ChangesMatching);
+        assert!(repo.pickaxe_search(&invalid, None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_background_search() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let query = PickaxeQuery::new("frobnicate", PickaxeMode::AddsOrRemoves);
+        let mut search = repo.start_pickaxe_search(query, None)?;
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut matches = Vec::new();
+        while !search.is_complete() && Instant::now() < deadline {
+            match search.try_next() {
+                Some(PickaxeEvent::Item(commit)) => matches.push(commit),
+                Some(_) => {}
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+
+        assert!(search.is_complete());
+        assert_eq!(
+            summaries(&matches),
+            vec!["Remove frobnicate", "Add frobnicate"]
+        );
+        assert_eq!(search.scanned_count(), 4);
+        assert_eq!(search.matched_count(), 2);
+
+        Ok(())
+    }
+}
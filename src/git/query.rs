@@ -0,0 +1,878 @@
+use crate::git::{CommitScan, GitRepository, ScanContext, ScanEvent, approxidate, walk_history};
+use crate::models::GitCommit;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use git2::Oid;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+/// Longest query accepted, matching `InputValidator::validate_search_query`
+const MAX_QUERY_LENGTH: usize = 1000;
+
+/// A syntax error in a commit query, with the 1-based column it was found at
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{message} (column {column})")]
+pub struct QueryParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl QueryParseError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            column: position + 1,
+            message: message.into(),
+        }
+    }
+}
+
+/// How a text field is compared: substrings ignore case, `/regex/` is used as written
+#[derive(Debug, Clone)]
+pub enum TextMatcher {
+    Contains(String), // Lowercased needle
+    Regex(Regex),
+}
+
+impl TextMatcher {
+    #[must_use]
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Contains(needle) => text.to_lowercase().contains(needle),
+            Self::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// A single predicate on a commit
+#[derive(Debug, Clone)]
+pub enum QueryTerm {
+    Text(TextMatcher), // Bare words: message, author or commit id
+    Author(TextMatcher),
+    Committer(TextMatcher),
+    Message(TextMatcher),
+    After(DateTime<Utc>), // Committer date at or after (approxidate, as `--since`)
+    Before(DateTime<Utc>), // Committer date strictly before
+    Path(String),         // Pathspec the commit's diff touches
+    Ref(String),          // Glob over branch and tag names pointing at the commit
+    Parents(usize),       // Exact number of parents
+    Merge,
+}
+
+/// Parsed commit query; whitespace-separated terms are `ANDed`
+#[derive(Debug, Clone)]
+pub enum QueryExpr {
+    Term(QueryTerm),
+    Not(Box<Self>),
+    And(Vec<Self>),
+    Or(Vec<Self>),
+}
+
+impl QueryExpr {
+    #[must_use]
+    pub fn matches(&self, commit: &GitCommit, context: &QueryContext) -> bool {
+        match self {
+            Self::Term(term) => term_matches(term, commit, context),
+            Self::Not(expr) => !expr.matches(commit, context),
+            Self::And(exprs) => exprs.iter().all(|expr| expr.matches(commit, context)),
+            Self::Or(exprs) => exprs.iter().any(|expr| expr.matches(commit, context)),
+        }
+    }
+
+    fn any_term(&self, predicate: &impl Fn(&QueryTerm) -> bool) -> bool {
+        match self {
+            Self::Term(term) => predicate(term),
+            Self::Not(expr) => expr.any_term(predicate),
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().any(|expr| expr.any_term(predicate)),
+        }
+    }
+}
+
+fn term_matches(term: &QueryTerm, commit: &GitCommit, context: &QueryContext) -> bool {
+    match term {
+        QueryTerm::Text(matcher) => {
+            matcher.is_match(&commit.message)
+                || matcher.is_match(&commit.author.name)
+                || matcher.is_match(&commit.author.email)
+                || matcher.is_match(&commit.id)
+        }
+        QueryTerm::Author(matcher) => {
+            matcher.is_match(&commit.author.name) || matcher.is_match(&commit.author.email)
+        }
+        QueryTerm::Committer(matcher) => {
+            matcher.is_match(&commit.committer.name) || matcher.is_match(&commit.committer.email)
+        }
+        QueryTerm::Message(matcher) => matcher.is_match(&commit.message),
+        QueryTerm::After(date) => commit.committer.when >= *date,
+        QueryTerm::Before(date) => commit.committer.when < *date,
+        QueryTerm::Path(pathspec) => context.touches_path(&commit.id, pathspec),
+        QueryTerm::Ref(pattern) => context
+            .refs_for_commit(&commit.id)
+            .iter()
+            .any(|name| glob_matches(pattern, name)),
+        QueryTerm::Parents(count) => commit.parent_ids.len() == *count,
+        QueryTerm::Merge => commit.parent_ids.len() > 1,
+    }
+}
+
+/// Commit ids mapped to the short and full names of refs pointing at them
+type RefNames = HashMap<String, Vec<String>>;
+
+/// Branch and tag names by commit, as `ref:` terms see them.
+///
+/// Walking the refs is the costly part of a `QueryContext`, so callers
+/// evaluating a query repeatedly build this once and reuse it until the
+/// refs change.
+#[derive(Debug, Clone, Default)]
+pub struct QueryRefs {
+    names: RefNames, // Commit id -> short and full ref names
+}
+
+impl QueryRefs {
+    pub fn from_repository(repo: &GitRepository) -> Result<Self> {
+        let mut names = RefNames::new();
+        for reference in repo.repo().references()? {
+            let reference = reference?;
+            // Tags may point at tag objects; queries are about the commits
+            let Ok(commit) = reference.peel_to_commit() else {
+                continue;
+            };
+            let commit_names = names.entry(commit.id().to_string()).or_default();
+            if let Some(shorthand) = reference.shorthand() {
+                commit_names.push(shorthand.to_string());
+            }
+            if let Some(name) = reference.name() {
+                commit_names.push(name.to_string());
+            }
+        }
+        Ok(Self { names })
+    }
+}
+
+/// Repository data that `path:` and `ref:` terms need beyond the commit itself
+#[derive(Default)]
+pub struct QueryContext<'a> {
+    repo: Option<&'a GitRepository>,
+    refs: Cow<'a, QueryRefs>,
+}
+
+impl<'a> QueryContext<'a> {
+    /// Context without a repository: `path:` and `ref:` terms never match
+    #[must_use]
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn from_repository(repo: &'a GitRepository) -> Result<Self> {
+        Ok(Self {
+            repo: Some(repo),
+            refs: Cow::Owned(QueryRefs::from_repository(repo)?),
+        })
+    }
+
+    /// Context reusing refs collected earlier with `QueryRefs::from_repository`
+    pub const fn with_refs(repo: &'a GitRepository, refs: &'a QueryRefs) -> Self {
+        Self {
+            repo: Some(repo),
+            refs: Cow::Borrowed(refs),
+        }
+    }
+
+    fn refs_for_commit(&self, commit_id: &str) -> &[String] {
+        self.refs
+            .names
+            .get(commit_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn touches_path(&self, commit_id: &str, pathspec: &str) -> bool {
+        let Some(repo) = self.repo else {
+            return false;
+        };
+        repo.commit_touches_paths(commit_id, &[pathspec.to_string()])
+            .unwrap_or(false)
+    }
+}
+
+/// `*` matches any run of characters (including `/`), `?` a single one
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// A commit-list query such as
+/// `author:alice after:2026-01-01 path:src/git msg:/fix(ed)?/ -merge parent:2 ref:release/*`.
+///
+/// Terms are `ANDed`; `OR` (or `|`) binds looser, `-term` and `NOT` negate, and
+/// parentheses group. Bare words search message, author and id, except that
+/// a bare `merge` or `merges` selects merge commits (like `parent:2` or more);
+/// quote it as `"merge"` to search for the word instead.
+#[derive(Debug, Clone)]
+pub struct CommitQuery {
+    source: String,
+    expr: Option<QueryExpr>, // None for an empty query
+}
+
+impl CommitQuery {
+    pub fn parse(input: &str) -> Result<Self, QueryParseError> {
+        if input.len() > MAX_QUERY_LENGTH {
+            return Err(QueryParseError::new(
+                MAX_QUERY_LENGTH,
+                format!("Query too long (maximum {MAX_QUERY_LENGTH} characters)"),
+            ));
+        }
+
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            end: input.chars().count(),
+        };
+        let expr = if parser.tokens.is_empty() {
+            None
+        } else {
+            Some(parser.parse_query()?)
+        };
+
+        Ok(Self {
+            source: input.trim().to_string(),
+            expr,
+        })
+    }
+
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    #[must_use]
+    pub const fn expr(&self) -> Option<&QueryExpr> {
+        self.expr.as_ref()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.expr.is_none()
+    }
+
+    /// Whether evaluation needs a repository-backed `QueryContext`
+    #[must_use]
+    pub fn needs_repository(&self) -> bool {
+        self.expr.as_ref().is_some_and(|expr| {
+            expr.any_term(&|term| matches!(term, QueryTerm::Path(_) | QueryTerm::Ref(_)))
+        })
+    }
+
+    /// Whether evaluation diffs commits, which is slow enough for a
+    /// background search
+    #[must_use]
+    pub fn needs_diffs(&self) -> bool {
+        self.expr
+            .as_ref()
+            .is_some_and(|expr| expr.any_term(&|term| matches!(term, QueryTerm::Path(_))))
+    }
+
+    /// Whether results depend on where branches and tags point
+    #[must_use]
+    pub fn needs_refs(&self) -> bool {
+        self.expr
+            .as_ref()
+            .is_some_and(|expr| expr.any_term(&|term| matches!(term, QueryTerm::Ref(_))))
+    }
+
+    /// An empty query matches every commit
+    #[must_use]
+    pub fn matches(&self, commit: &GitCommit, context: &QueryContext) -> bool {
+        self.expr
+            .as_ref()
+            .is_none_or(|expr| expr.matches(commit, context))
+    }
+}
+
+/// Result of a parsing step, with the offset of any error
+type ParseResult<T> = Result<T, QueryParseError>;
+
+/// A word's token and the offset just past the word
+type ScannedWord = (TokenKind, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+enum ValueKind {
+    Plain,
+    Quoted,
+    Regex,
+}
+
+#[derive(Debug, Clone)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word {
+        field: Option<String>,
+        value: String,
+        kind: ValueKind,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize, // Character offset in the query
+}
+
+fn tokenize(input: &str) -> ParseResult<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_control() {
+            return Err(QueryParseError::new(i, "Control character in query"));
+        }
+
+        let start = i;
+        let kind = match c {
+            '(' => {
+                i += 1;
+                TokenKind::LParen
+            }
+            ')' => {
+                i += 1;
+                TokenKind::RParen
+            }
+            '|' => {
+                i += 1;
+                TokenKind::Or
+            }
+            '-' if chars.get(i + 1).is_some_and(|next| !next.is_whitespace()) => {
+                i += 1;
+                TokenKind::Not
+            }
+            _ => {
+                let (word, next) = read_word(&chars, i)?;
+                i = next;
+                keyword(&word).unwrap_or(word)
+            }
+        };
+        tokens.push(Token { kind, start });
+    }
+
+    Ok(tokens)
+}
+
+/// Unquoted, field-less `AND`, `OR` and `NOT` are operators
+fn keyword(word: &TokenKind) -> Option<TokenKind> {
+    let TokenKind::Word {
+        field: None,
+        value,
+        kind: ValueKind::Plain,
+    } = word
+    else {
+        return None;
+    };
+    match value.as_str() {
+        "AND" => Some(TokenKind::And),
+        "OR" => Some(TokenKind::Or),
+        "NOT" => Some(TokenKind::Not),
+        _ => None,
+    }
+}
+
+/// Read `[field:]value` where value is bare, `"quoted"` or `/regex/`
+fn read_word(chars: &[char], start: usize) -> ParseResult<ScannedWord> {
+    let mut i = start;
+    while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+        i += 1;
+    }
+    let field = if i > start && chars.get(i) == Some(&':') {
+        let field: String = chars[start..i].iter().collect();
+        i += 1;
+        Some(field.to_lowercase())
+    } else {
+        i = start;
+        None
+    };
+
+    let value_start = i;
+    let (value, kind) = match chars.get(i) {
+        Some('"') => {
+            let close = chars[i + 1..]
+                .iter()
+                .position(|c| *c == '"')
+                .ok_or_else(|| QueryParseError::new(value_start, "Unterminated quote"))?;
+            let value = chars[i + 1..i + 1 + close].iter().collect();
+            i += close + 2;
+            (value, ValueKind::Quoted)
+        }
+        Some('/') => {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => {
+                        return Err(QueryParseError::new(
+                            value_start,
+                            "Unterminated regular expression",
+                        ));
+                    }
+                    Some('/') => {
+                        i += 1;
+                        break;
+                    }
+                    // `\/` is a literal slash; other escapes belong to the regex
+                    Some('\\') if chars.get(i + 1) == Some(&'/') => {
+                        value.push('/');
+                        i += 2;
+                    }
+                    Some('\\') if i + 1 < chars.len() => {
+                        value.push('\\');
+                        value.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(c) => {
+                        value.push(*c);
+                        i += 1;
+                    }
+                }
+            }
+            (value, ValueKind::Regex)
+        }
+        _ => {
+            while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')') {
+                i += 1;
+            }
+            (chars[value_start..i].iter().collect(), ValueKind::Plain)
+        }
+    };
+
+    Ok((TokenKind::Word { field, value, kind }, i))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    end: usize, // Query length, for errors at end of input
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn current_offset(&self) -> usize {
+        self.peek().map_or(self.end, |token| token.start)
+    }
+
+    fn parse_query(&mut self) -> ParseResult<QueryExpr> {
+        let expr = self.parse_or()?;
+        match self.peek() {
+            None => Ok(expr),
+            Some(token) if matches!(token.kind, TokenKind::RParen) => {
+                Err(QueryParseError::new(token.start, "Unmatched ')'"))
+            }
+            Some(token) => Err(QueryParseError::new(token.start, "Unexpected token")),
+        }
+    }
+
+    fn parse_or(&mut self) -> ParseResult<QueryExpr> {
+        let mut exprs = vec![self.parse_and()?];
+        while self
+            .peek()
+            .is_some_and(|token| matches!(token.kind, TokenKind::Or))
+        {
+            self.position += 1;
+            exprs.push(self.parse_and()?);
+        }
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            QueryExpr::Or(exprs)
+        })
+    }
+
+    fn parse_and(&mut self) -> ParseResult<QueryExpr> {
+        let mut exprs = vec![self.parse_unary()?];
+        loop {
+            match self.peek().map(|token| &token.kind) {
+                None | Some(TokenKind::Or | TokenKind::RParen) => break,
+                Some(TokenKind::And) => {
+                    self.position += 1;
+                    exprs.push(self.parse_unary()?);
+                }
+                Some(_) => exprs.push(self.parse_unary()?),
+            }
+        }
+
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            QueryExpr::And(exprs)
+        })
+    }
+
+    fn parse_unary(&mut self) -> ParseResult<QueryExpr> {
+        let offset = self.current_offset();
+        let Some(token) = self.tokens.get(self.position).cloned() else {
+            return Err(QueryParseError::new(offset, "Expected a search term"));
+        };
+        self.position += 1;
+
+        match token.kind {
+            TokenKind::Not => Ok(QueryExpr::Not(Box::new(self.parse_unary()?))),
+            TokenKind::LParen => {
+                let expr = self.parse_or()?;
+                match self.peek() {
+                    Some(next) if matches!(next.kind, TokenKind::RParen) => {
+                        self.position += 1;
+                        Ok(expr)
+                    }
+                    _ => Err(QueryParseError::new(token.start, "Unmatched '('")),
+                }
+            }
+            TokenKind::Word { field, value, kind } => {
+                parse_term(field.as_deref(), &value, &kind, token.start).map(QueryExpr::Term)
+            }
+            TokenKind::RParen => Err(QueryParseError::new(token.start, "Unmatched ')'")),
+            TokenKind::And | TokenKind::Or => Err(QueryParseError::new(
+                token.start,
+                "Operator without a term before it",
+            )),
+        }
+    }
+}
+
+fn parse_term(
+    field: Option<&str>,
+    value: &str,
+    kind: &ValueKind,
+    start: usize,
+) -> ParseResult<QueryTerm> {
+    if *kind == ValueKind::Plain && field.is_none() && matches!(value, "merge" | "merges") {
+        return Ok(QueryTerm::Merge);
+    }
+    if value.is_empty() && *kind != ValueKind::Regex {
+        return Err(QueryParseError::new(
+            start,
+            format!("Missing value for '{}:'", field.unwrap_or_default()),
+        ));
+    }
+
+    let text = || text_matcher(value, kind, start);
+    let plain = |name: &str| {
+        if *kind == ValueKind::Regex {
+            Err(QueryParseError::new(
+                start,
+                format!("'{name}:' does not take a regular expression"),
+            ))
+        } else {
+            Ok(value.to_string())
+        }
+    };
+
+    match field {
+        None => Ok(QueryTerm::Text(text()?)),
+        Some("author") => Ok(QueryTerm::Author(text()?)),
+        Some("committer") => Ok(QueryTerm::Committer(text()?)),
+        Some("msg" | "message") => Ok(QueryTerm::Message(text()?)),
+        Some("after" | "since") => Ok(QueryTerm::After(parse_date(&plain("after")?, start)?)),
+        Some("before" | "until") => Ok(QueryTerm::Before(parse_date(&plain("before")?, start)?)),
+        Some("path" | "file") => Ok(QueryTerm::Path(plain("path")?)),
+        Some("ref") => Ok(QueryTerm::Ref(plain("ref")?)),
+        Some("parent" | "parents") => plain("parent")?
+            .parse()
+            .map(QueryTerm::Parents)
+            .map_err(|_| QueryParseError::new(start, "'parent:' takes a number of parents")),
+        Some(other) => Err(QueryParseError::new(
+            start,
+            format!("Unknown field '{other}:'"),
+        )),
+    }
+}
+
+fn text_matcher(value: &str, kind: &ValueKind, start: usize) -> ParseResult<TextMatcher> {
+    match kind {
+        ValueKind::Regex => Regex::new(value)
+            .map(TextMatcher::Regex)
+            .map_err(|e| QueryParseError::new(start, format!("Invalid regular expression: {e}"))),
+        ValueKind::Plain | ValueKind::Quoted => Ok(TextMatcher::Contains(value.to_lowercase())),
+    }
+}
+
+/// Any date git's `--since` accepts, e.g. `2026-01-01` or `2.weeks.ago`
+fn parse_date(value: &str, start: usize) -> ParseResult<DateTime<Utc>> {
+    approxidate(value).map_err(|e| QueryParseError::new(start, e.to_string()))
+}
+
+/// Incremental output of a background commit query
+pub type QuerySearchEvent = ScanEvent<GitCommit>;
+
+/// A commit query running on a background thread
+pub type QuerySearch = CommitScan<GitCommit, CommitQuery>;
+
+fn run_query(
+    repo_path: &Path,
+    start: Option<Oid>,
+    query: &CommitQuery,
+    max_count: usize,
+    scan: &mut ScanContext<GitCommit>,
+) -> Result<()> {
+    let repo = GitRepository::open(repo_path)?;
+    let context = QueryContext::from_repository(&repo)?;
+    for oid in walk_history(repo.repo(), start)?.take(max_count) {
+        if !scan.next_commit() {
+            break;
+        }
+
+        let commit = GitCommit::new(&repo.repo().find_commit(oid?)?)?;
+        if query.matches(&commit, &context) && !scan.report(commit) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+impl GitRepository {
+    /// Commits among the first `max_count` from `start` (HEAD when `None`)
+    /// that match the query
+    pub fn query_commits(
+        &self,
+        query: &CommitQuery,
+        start: Option<&str>,
+        max_count: Option<usize>,
+    ) -> Result<Vec<GitCommit>> {
+        let start = self.resolve_query_start(start)?;
+        let context = if query.needs_repository() {
+            QueryContext::from_repository(self)?
+        } else {
+            QueryContext::empty()
+        };
+
+        let mut commits = Vec::new();
+        for oid in walk_history(self.repo(), start)?.take(max_count.unwrap_or(1000)) {
+            let commit = self.repo().find_commit(oid?)?;
+            let git_commit = GitCommit::new(&commit)?;
+            if query.matches(&git_commit, &context) {
+                commits.push(git_commit);
+            }
+        }
+
+        Ok(commits)
+    }
+
+    /// Run a query over the first `max_count` commits from `start` on a
+    /// background thread, for queries that diff commits
+    pub fn start_query_search(
+        &self,
+        query: CommitQuery,
+        start: Option<&str>,
+        max_count: usize,
+    ) -> Result<QuerySearch> {
+        let start = self.resolve_query_start(start)?;
+        let repo_path = self.repo().path().to_path_buf();
+        let thread_query = query.clone();
+        CommitScan::start("query-search", query, None, move |scan| {
+            run_query(&repo_path, start, &thread_query, max_count, scan)
+        })
+    }
+
+    fn resolve_query_start(&self, start: Option<&str>) -> Result<Option<Oid>> {
+        Ok(match start {
+            Some(start) => Some(self.repo().revparse_single(start)?.peel_to_commit()?.id()),
+            None => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use crate::models::GitSignature;
+    use chrono::TimeZone;
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn test_commit(message: &str, author: &str, year: i32, parents: usize) -> GitCommit {
+        let signature = GitSignature {
+            name: author.to_string(),
+            email: format!("{}@example.com", author),
+            when: Utc.with_ymd_and_hms(year, 6, 1, 12, 0, 0).unwrap(),
+        };
+        GitCommit {
+            id: format!("{:040x}", year),
+            short_id: "abc1234".to_string(),
+            author: signature.clone(),
+            committer: signature,
+            message: message.to_string(),
+            summary: message.to_string(),
+            parent_ids: (0..parents).map(|i| format!("{:040x}", i)).collect(),
+            tree_id: "tree".to_string(),
+            is_signed: false,
+            notes: vec![],
+        }
+    }
+
+    fn matches(query: &str, commit: &GitCommit) -> bool {
+        CommitQuery::parse(query)
+            .unwrap()
+            .matches(commit, &QueryContext::empty())
+    }
+
+    #[test]
+    fn test_field_terms() {
+        let fix = test_commit("Fixed the parser", "alice", 2026, 1);
+        let merge = test_commit("Merge branch 'topic'", "bob", 2025, 2);
+
+        assert!(matches("author:alice", &fix));
+        assert!(!matches("author:alice", &merge));
+        assert!(!matches("msg:/fix(ed)?/", &merge));
+        assert!(matches("msg:/Fix(ed)?/", &fix));
+        assert!(matches("after:2026-01-01", &fix));
+        assert!(!matches("after:2026-01-01", &merge));
+        assert!(matches("before:2026-01-01 parent:2", &merge));
+        assert!(matches("merge", &merge));
+        assert!(!matches("-merge", &merge));
+        assert!(matches("\"merge\"", &merge));
+        assert!(matches("", &fix));
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        let fix = test_commit("Fix crash", "alice", 2026, 1);
+        let feature = test_commit("Add feature", "bob", 2026, 1);
+
+        assert!(matches("author:carol OR fix", &fix));
+        assert!(matches("author:carol | fix", &fix));
+        assert!(!matches("author:carol OR fix", &feature));
+        assert!(matches("(fix OR feature) author:bob", &feature));
+        assert!(!matches("(fix OR feature) AND NOT author:bob", &feature));
+        assert!(matches("-(author:alice crash)", &feature));
+        assert!(!matches("-(author:alice crash)", &fix));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let error = |query: &str| CommitQuery::parse(query).unwrap_err();
+
+        assert_eq!(error("author:").column, 1);
+        assert_eq!(error("fix (author:bob").column, 5);
+        assert_eq!(error("fix)").column, 4);
+        assert_eq!(error("fix OR").column, 7);
+        assert_eq!(error("msg:/unclosed").column, 5);
+        assert_eq!(error("msg:/fix(/").column, 1);
+        assert!(error("colour:red").message.contains("Unknown field"));
+        assert!(error("after:yesterdayish").message.contains("Invalid date"));
+        assert!(error("parent:two").message.contains("number"));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("release/*", "release/1.0"));
+        assert!(glob_matches("*/release/*", "origin/release/1.0"));
+        assert!(glob_matches("v?.0", "v2.0"));
+        assert!(!glob_matches("release/*", "main"));
+        assert!(glob_matches("main", "main"));
+    }
+
+    #[test]
+    fn test_query_commits_with_paths_and_refs() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        fs::create_dir_all(path.join("src/git"))?;
+        fs::write(path.join("src/git/mod.rs"), "// git")?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Add git module"])?;
+        git(path, &["branch", "release/1.0"])?;
+        fs::write(path.join("README.md"), "readme")?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Add readme"])?;
+
+        let repo = GitRepository::open(path)?;
+        let summaries = |query: &str| -> Result<Vec<String>> {
+            let query = CommitQuery::parse(query)?;
+            Ok(repo
+                .query_commits(&query, None, None)?
+                .into_iter()
+                .map(|commit| commit.summary)
+                .collect())
+        };
+
+        assert_eq!(summaries("path:src/git")?, vec!["Add git module"]);
+        assert_eq!(summaries("ref:release/*")?, vec!["Add git module"]);
+        assert_eq!(summaries("ref:main OR path:src")?.len(), 2);
+        assert_eq!(summaries("add -path:src")?, vec!["Add readme"]);
+
+        let refs = QueryRefs::from_repository(&repo)?;
+        let context = QueryContext::with_refs(&repo, &refs);
+        let query = CommitQuery::parse("ref:release/*")?;
+        assert!(query.needs_refs());
+        assert!(!query.needs_diffs());
+        let listed = repo.get_commits(None)?;
+        let matched: Vec<&str> = listed
+            .iter()
+            .filter(|commit| query.matches(commit, &context))
+            .map(|commit| commit.summary.as_str())
+            .collect();
+        assert_eq!(matched, vec!["Add git module"]);
+
+        let first = git(path, &["rev-parse", "HEAD~1"])?;
+        let from_first = repo.query_commits(&CommitQuery::parse("add")?, Some(&first), None)?;
+        assert_eq!(from_first.len(), 1);
+        assert_eq!(from_first[0].id, first);
+
+        let query = CommitQuery::parse("path:src/git OR path:README.md")?;
+        assert!(query.needs_diffs());
+        assert!(!CommitQuery::parse("ref:main")?.needs_diffs());
+        let mut search = repo.start_query_search(query, Some("main"), 100)?;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut found = Vec::new();
+        while !search.is_complete() && Instant::now() < deadline {
+            match search.try_next() {
+                Some(QuerySearchEvent::Item(commit)) => found.push(commit.summary),
+                Some(_) => {}
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        assert!(search.is_complete());
+        found.sort();
+        assert_eq!(found, vec!["Add git module", "Add readme"]);
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::git::GitRepository;
+use crate::git::{CommitGraphIndex, GitRepository};
 use crate::models::GitCommit;
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
@@ -21,6 +21,9 @@ pub struct GitRef {
     pub is_head: bool,
 }
 
+/// Borrowed refs, in the order a query returns them
+pub type RefList<'a> = Vec<&'a GitRef>;
+
 #[derive(Debug, Clone)]
 pub struct RefManager {
     refs: HashMap<String, GitRef>,
@@ -241,7 +244,7 @@ impl RefManager {
     /// Check if a commit is on a specific branch
     pub fn is_commit_on_branch(
         &self,
-        repo: &GitRepository,
+        index: &CommitGraphIndex,
         commit_sha: &str,
         branch_name: &str,
     ) -> Result<bool> {
@@ -251,24 +254,29 @@ impl RefManager {
                 return Ok(true);
             }
 
-            // More complex check: walk the branch history
             let branch_oid = git2::Oid::from_str(&branch_ref.target)?;
             let commit_oid = git2::Oid::from_str(commit_sha)?;
-
-            let mut revwalk = repo.repo().revwalk()?;
-            revwalk.push(branch_oid)?;
-
-            for oid in revwalk {
-                let oid = oid?;
-                if oid == commit_oid {
-                    return Ok(true);
-                }
-            }
+            return Ok(index.is_ancestor(commit_oid, branch_oid));
         }
 
         Ok(false)
     }
 
+    /// Local and remote branches whose history contains a commit
+    pub fn get_branches_containing(
+        &self,
+        index: &CommitGraphIndex,
+        commit_sha: &str,
+    ) -> Result<RefList<'_>> {
+        let commit_oid = git2::Oid::from_str(commit_sha)?;
+        let tips = self.get_branches().into_iter().filter_map(|branch| {
+            git2::Oid::from_str(&branch.target)
+                .ok()
+                .map(|target| (branch, target))
+        });
+        Ok(index.tips_containing(commit_oid, tips))
+    }
+
     /// Get the current branch name
     pub fn get_current_branch(&self) -> Option<String> {
         self.head_ref
@@ -1,5 +1,5 @@
 use crate::git::GitCommands;
-use crate::models::{GitCommit, RepositoryInfo};
+use crate::models::{GitCommit, RepositoryInfo, WorkingTreeStatus};
 use anyhow::{anyhow, Result};
 use git2::{Repository, RepositoryOpenFlags};
 use std::path::Path;
@@ -60,6 +60,12 @@ impl GitRepository {
         &self.info
     }
 
+    /// Re-read branch, tag and remote names after refs changed on disk
+    pub fn refresh_info(&mut self) -> Result<()> {
+        self.info = RepositoryInfo::from_repo(&self.repo)?;
+        Ok(())
+    }
+
     pub fn get_commits(&self, max_count: Option<usize>) -> Result<Vec<GitCommit>> {
         let mut revwalk = self.repo.revwalk()?;
         revwalk.set_sorting(git2::Sort::TIME)?;
@@ -223,6 +229,45 @@ impl GitRepository {
         Ok(!output.trim().is_empty())
     }
 
+    /// Count staged, unstaged, untracked and conflicted paths
+    pub fn working_tree_status(&self) -> Result<WorkingTreeStatus> {
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(false)
+            .exclude_submodules(true);
+
+        let mut status = WorkingTreeStatus::default();
+        for entry in self.repo.statuses(Some(&mut options))?.iter() {
+            let flags = entry.status();
+            if flags.is_conflicted() {
+                status.conflicted += 1;
+                continue;
+            }
+            if flags.intersects(
+                git2::Status::INDEX_NEW
+                    | git2::Status::INDEX_MODIFIED
+                    | git2::Status::INDEX_DELETED
+                    | git2::Status::INDEX_RENAMED
+                    | git2::Status::INDEX_TYPECHANGE,
+            ) {
+                status.staged += 1;
+            }
+            if flags.intersects(
+                git2::Status::WT_MODIFIED
+                    | git2::Status::WT_DELETED
+                    | git2::Status::WT_RENAMED
+                    | git2::Status::WT_TYPECHANGE,
+            ) {
+                status.unstaged += 1;
+            }
+            if flags.is_wt_new() {
+                status.untracked += 1;
+            }
+        }
+        Ok(status)
+    }
+
     /// Get the current branch name
     pub fn get_current_branch_safe(&self) -> Result<Option<String>> {
         match self.commands.rev_parse(&["--abbrev-ref", "HEAD"]) {
@@ -533,6 +578,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_working_tree_status() -> anyhow::Result<()> {
+        let (_temp_dir, repo_path) = create_test_repo()?;
+        create_test_commit(
+            &repo_path,
+            "tracked.txt",
+            "Initial content",
+            "Initial commit",
+        )?;
+
+        let repository = GitRepository::discover(&repo_path)?;
+        assert!(repository.working_tree_status()?.is_clean());
+
+        std::fs::write(repo_path.join("tracked.txt"), "Modified content")?;
+        std::fs::write(repo_path.join("staged.txt"), "Staged content")?;
+        std::fs::write(repo_path.join("untracked.txt"), "Untracked content")?;
+        let mut index = repository.repo().index()?;
+        index.add_path(Path::new("staged.txt"))?;
+        index.write()?;
+
+        let status = repository.working_tree_status()?;
+        assert_eq!(status.staged, 1);
+        assert_eq!(status.unstaged, 1);
+        assert_eq!(status.untracked, 1);
+        assert_eq!(status.conflicted, 0);
+        Ok(())
+    }
+
     #[test]
     fn test_get_current_branch() -> anyhow::Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
@@ -1,3 +1,4 @@
+use crate::git::platform_security::PlatformSecurity;
 use anyhow::{anyhow, Result};
 use regex::Regex;
 use std::collections::HashSet;
@@ -260,7 +261,10 @@ impl SecurityValidator {
         ];
 
         for var in &dangerous_env_vars {
-            if env_vars.contains_key(*var) {
+            if let Some(value) = env_vars.get(*var) {
+                if Self::is_safe_override(var, value) {
+                    continue;
+                }
                 warn!("Potentially dangerous environment variable: {}", var);
                 return Err(anyhow!("Environment variable '{}' not allowed", var));
             }
@@ -268,6 +272,16 @@ impl SecurityValidator {
 
         Ok(())
     }
+
+    /// Values the command runner sets itself: commands that disable the
+    /// feature and the verified PATH. Anything else could run other programs.
+    fn is_safe_override(var: &str, value: &str) -> bool {
+        match var {
+            "GIT_SSH_COMMAND" | "GIT_PROXY_COMMAND" => value == "false",
+            "PATH" => PlatformSecurity::secure_path().is_ok_and(|path| path == value),
+            _ => false,
+        }
+    }
 }
 
 /// Input sanitization utilities
@@ -468,6 +482,40 @@ mod tests {
         assert!(validator.validate_arguments(&["|rm -rf /"]).is_err());
     }
 
+    #[test]
+    fn test_validate_environment() {
+        let validator = SecurityValidator::new().unwrap();
+        let env = |pairs: &[(&str, &str)]| -> std::collections::HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+
+        // The runner's own disabling values and verified PATH pass
+        let path = PlatformSecurity::secure_path().unwrap();
+        let safe = env(&[
+            ("GIT_SSH_COMMAND", "false"),
+            ("GIT_PROXY_COMMAND", "false"),
+            ("PATH", &path),
+            ("GIT_PAGER", "cat"),
+        ]);
+        assert!(validator.validate_environment(&safe).is_ok());
+
+        assert!(validator
+            .validate_environment(&env(&[("GIT_SSH_COMMAND", "ssh -o ProxyCommand=x")]))
+            .is_err());
+        assert!(validator
+            .validate_environment(&env(&[("PATH", "/tmp/evil:/usr/bin")]))
+            .is_err());
+        assert!(validator
+            .validate_environment(&env(&[("LD_PRELOAD", "/tmp/evil.so")]))
+            .is_err());
+        assert!(validator
+            .validate_environment(&env(&[("GIT_SSH", "false")]))
+            .is_err());
+    }
+
     #[test]
     fn test_input_sanitizer() {
         // Test commit ID sanitization
@@ -0,0 +1,1051 @@
+use crate::git::{GitError, GitRepository};
+use anyhow::Result;
+use git2::{Config, ErrorCode, Oid, Repository};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use tempfile::NamedTempFile;
+use tracing::{debug, warn};
+
+/// Armor line that starts an `OpenPGP` signature
+const PGP_SIGNATURE_HEADER: &str = "-----BEGIN PGP SIGNATURE-----";
+/// Armor line that starts an X.509 (CMS) signature
+const X509_SIGNATURE_HEADER: &str = "-----BEGIN SIGNED MESSAGE-----";
+/// Armor line that starts an SSH signature
+const SSH_SIGNATURE_HEADER: &str = "-----BEGIN SSH SIGNATURE-----";
+
+/// Namespace git uses for SSH signatures
+const SSH_SIGNATURE_NAMESPACE: &str = "git";
+
+/// Signature formats supported by git (`gpg.format`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureFormat {
+    OpenPgp, // gpg.format = openpgp (default)
+    X509,    // gpg.format = x509, verified with gpgsm
+    Ssh,     // gpg.format = ssh, verified with ssh-keygen
+}
+
+impl SignatureFormat {
+    /// Parse a `gpg.format` config value
+    #[must_use]
+    pub fn from_config_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "openpgp" => Some(Self::OpenPgp),
+            "x509" => Some(Self::X509),
+            "ssh" => Some(Self::Ssh),
+            _ => None,
+        }
+    }
+
+    /// Detect the format of a signature from its armor header
+    #[must_use]
+    pub fn detect(signature: &[u8]) -> Option<Self> {
+        let text = String::from_utf8_lossy(signature);
+        let first_line = text.trim_start().lines().next().unwrap_or("");
+
+        [Self::OpenPgp, Self::X509, Self::Ssh]
+            .into_iter()
+            .find(|format| first_line.starts_with(format.armor_header()))
+    }
+
+    /// Armor line that starts a signature of this format
+    #[must_use]
+    pub const fn armor_header(&self) -> &'static str {
+        match self {
+            Self::OpenPgp => PGP_SIGNATURE_HEADER,
+            Self::X509 => X509_SIGNATURE_HEADER,
+            Self::Ssh => SSH_SIGNATURE_HEADER,
+        }
+    }
+}
+
+/// Outcome of verifying a commit or tag signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    Unsigned,   // Object carries no signature
+    Good,       // Valid signature from a known key
+    Bad,        // Signature does not match the signed data
+    UnknownKey, // Signature cannot be checked against a known key
+    Expired,    // Valid signature made by an expired key
+    Revoked,    // Signature made by a key its owner has revoked
+    Error,      // Verification program could not be run
+    Pending,    // Verification is still running in the background
+}
+
+impl SignatureStatus {
+    /// Short label for badges
+    #[must_use]
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Unsigned => "unsigned",
+            Self::Good => "good signature",
+            Self::Bad => "bad signature",
+            Self::UnknownKey => "unknown key",
+            Self::Expired => "expired key",
+            Self::Revoked => "revoked key",
+            Self::Error => "unverified",
+            Self::Pending => "pending",
+        }
+    }
+
+    /// Whether the object is known to carry a signature of any kind
+    #[must_use]
+    pub const fn is_signed(&self) -> bool {
+        !matches!(self, Self::Unsigned | Self::Pending)
+    }
+}
+
+/// Result of verifying a signed object
+#[derive(Debug, Clone)]
+pub struct SignatureVerification {
+    pub status: SignatureStatus,
+    pub format: Option<SignatureFormat>,
+    pub signer: Option<String>, // User ID or SSH principal
+    pub key: Option<String>,    // Key ID or fingerprint
+    pub output: String,         // Verifier output for the details view
+}
+
+impl SignatureVerification {
+    /// Verification result for an object without a signature
+    #[must_use]
+    pub const fn unsigned() -> Self {
+        Self {
+            status: SignatureStatus::Unsigned,
+            format: None,
+            signer: None,
+            key: None,
+            output: String::new(),
+        }
+    }
+
+    /// Placeholder shown until a background verification finishes
+    #[must_use]
+    pub fn pending() -> Self {
+        Self {
+            status: SignatureStatus::Pending,
+            ..Self::unsigned()
+        }
+    }
+
+    const fn new(status: SignatureStatus, format: SignatureFormat, output: String) -> Self {
+        Self {
+            status,
+            format: Some(format),
+            signer: None,
+            key: None,
+            output,
+        }
+    }
+}
+
+/// Verifies signatures with the programs configured for a repository
+#[derive(Debug, Clone)]
+pub struct SignatureVerifier {
+    default_format: SignatureFormat,
+    openpgp_program: String,
+    x509_program: String,
+    ssh_program: String,
+    allowed_signers_file: Option<PathBuf>,
+}
+
+impl Default for SignatureVerifier {
+    fn default() -> Self {
+        Self {
+            default_format: SignatureFormat::OpenPgp,
+            openpgp_program: "gpg".to_string(),
+            x509_program: "gpgsm".to_string(),
+            ssh_program: "ssh-keygen".to_string(),
+            allowed_signers_file: None,
+        }
+    }
+}
+
+impl SignatureVerifier {
+    /// Read `gpg.*` settings from a git config
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = Self::default();
+        let get = |key: &str| config.get_string(key).ok().filter(|v| !v.trim().is_empty());
+
+        Self {
+            default_format: get("gpg.format")
+                .and_then(|v| SignatureFormat::from_config_value(&v))
+                .unwrap_or(defaults.default_format),
+            // gpg.program is the historical name of gpg.openpgp.program
+            openpgp_program: get("gpg.openpgp.program")
+                .or_else(|| get("gpg.program"))
+                .unwrap_or(defaults.openpgp_program),
+            x509_program: get("gpg.x509.program").unwrap_or(defaults.x509_program),
+            ssh_program: get("gpg.ssh.program").unwrap_or(defaults.ssh_program),
+            allowed_signers_file: get("gpg.ssh.allowedSignersFile").map(|v| expand_home(&v)),
+        }
+    }
+
+    /// Create a verifier from the repository's effective configuration
+    pub fn from_repository(repo: &Repository) -> Result<Self> {
+        let config = repo.config()?.snapshot()?;
+        Ok(Self::from_config(&config))
+    }
+
+    /// Format assumed for signatures without a recognizable armor header
+    #[must_use]
+    pub const fn default_format(&self) -> SignatureFormat {
+        self.default_format
+    }
+
+    /// Program configured for a signature format
+    fn program(&self, format: SignatureFormat) -> &str {
+        match format {
+            SignatureFormat::OpenPgp => &self.openpgp_program,
+            SignatureFormat::X509 => &self.x509_program,
+            SignatureFormat::Ssh => &self.ssh_program,
+        }
+    }
+
+    /// Verify `signature` over `signed_data`
+    pub fn verify(&self, signature: &[u8], signed_data: &[u8]) -> Result<SignatureVerification> {
+        let format = SignatureFormat::detect(signature).unwrap_or(self.default_format);
+        debug!("Verifying {:?} signature", format);
+
+        match format {
+            SignatureFormat::OpenPgp => {
+                Self::verify_gpg(&self.openpgp_program, format, signature, signed_data)
+            }
+            SignatureFormat::X509 => {
+                Self::verify_gpg(&self.x509_program, format, signature, signed_data)
+            }
+            SignatureFormat::Ssh => self.verify_ssh(signature, signed_data),
+        }
+    }
+
+    /// Verify an `OpenPGP` or X.509 signature using gpg's machine-readable status output
+    fn verify_gpg(
+        program: &str,
+        format: SignatureFormat,
+        signature: &[u8],
+        signed_data: &[u8],
+    ) -> Result<SignatureVerification> {
+        let sig_file = SigningTempFile::create(signature)?;
+        let sig_path = sig_file.path().to_string_lossy().to_string();
+
+        let output = run_signing_program(
+            program,
+            &[
+                "--status-fd=1",
+                "--keyid-format=long",
+                "--verify",
+                &sig_path,
+                "-",
+            ],
+            signed_data,
+        )?;
+
+        let status_output = String::from_utf8_lossy(&output.stdout).to_string();
+        let (status, signer, key) = parse_gpg_status(&status_output);
+
+        let mut verification = SignatureVerification::new(
+            status,
+            format,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        );
+        verification.signer = signer;
+        verification.key = key;
+        Ok(verification)
+    }
+
+    /// Verify an SSH signature against `gpg.ssh.allowedSignersFile`, mirroring git
+    fn verify_ssh(&self, signature: &[u8], signed_data: &[u8]) -> Result<SignatureVerification> {
+        let sig_file = SigningTempFile::create(signature)?;
+        let sig_path = sig_file.path().to_string_lossy().to_string();
+
+        let allowed_signers = self
+            .allowed_signers_file
+            .as_ref()
+            .filter(|path| path.is_file())
+            .map(|path| path.to_string_lossy().to_string());
+
+        if let Some(ref allowed_signers) = allowed_signers {
+            let principals = run_signing_program(
+                &self.ssh_program,
+                &[
+                    "-Y",
+                    "find-principals",
+                    "-f",
+                    allowed_signers,
+                    "-s",
+                    &sig_path,
+                ],
+                &[],
+            )?;
+
+            if principals.status.success() {
+                let mut last_output = String::new();
+
+                for principal in String::from_utf8_lossy(&principals.stdout)
+                    .lines()
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                {
+                    let output = run_signing_program(
+                        &self.ssh_program,
+                        &[
+                            "-Y",
+                            "verify",
+                            "-n",
+                            SSH_SIGNATURE_NAMESPACE,
+                            "-f",
+                            allowed_signers,
+                            "-I",
+                            principal,
+                            "-s",
+                            &sig_path,
+                        ],
+                        signed_data,
+                    )?;
+                    last_output = combined_output(&output);
+
+                    if output.status.success() {
+                        let mut verification = SignatureVerification::new(
+                            SignatureStatus::Good,
+                            SignatureFormat::Ssh,
+                            last_output.clone(),
+                        );
+                        verification.signer = Some(principal.to_string());
+                        verification.key = parse_ssh_key(&last_output);
+                        return Ok(verification);
+                    }
+                }
+
+                // The key is trusted, so a failed verification means tampered data
+                return Ok(SignatureVerification::new(
+                    SignatureStatus::Bad,
+                    SignatureFormat::Ssh,
+                    last_output,
+                ));
+            }
+        } else {
+            warn!("gpg.ssh.allowedSignersFile is not configured; SSH signatures cannot be trusted");
+        }
+
+        // No trusted principal: check the signature itself to tell unknown keys from bad data
+        let output = run_signing_program(
+            &self.ssh_program,
+            &[
+                "-Y",
+                "check-novalidate",
+                "-n",
+                SSH_SIGNATURE_NAMESPACE,
+                "-s",
+                &sig_path,
+            ],
+            signed_data,
+        )?;
+        let text = combined_output(&output);
+        let status = if output.status.success() {
+            SignatureStatus::UnknownKey
+        } else {
+            SignatureStatus::Bad
+        };
+
+        let mut verification = SignatureVerification::new(status, SignatureFormat::Ssh, text);
+        verification.key = parse_ssh_key(&verification.output);
+        Ok(verification)
+    }
+}
+
+/// Signs objects with the key and program configured for a repository
+#[derive(Debug, Clone)]
+pub struct ObjectSigner {
+    format: SignatureFormat,
+    program: String,
+    signing_key: Option<String>,   // user.signingkey
+    default_ident: Option<String>, // "Name <email>", gpg's fallback key
+}
+
+/// An armored signature, or why it could not be made
+pub type SigningResult = Result<Vec<u8>, GitError>;
+
+impl ObjectSigner {
+    /// Read `gpg.*` and `user.signingkey` settings from a git config
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        let programs = SignatureVerifier::from_config(config);
+        let format = programs.default_format();
+        let get = |key: &str| config.get_string(key).ok().filter(|v| !v.trim().is_empty());
+
+        let default_ident = match (get("user.name"), get("user.email")) {
+            (Some(name), Some(email)) => Some(format!("{name} <{email}>")),
+            _ => None,
+        };
+
+        Self {
+            format,
+            program: programs.program(format).to_string(),
+            signing_key: get("user.signingkey"),
+            default_ident,
+        }
+    }
+
+    /// Create a signer from the repository's effective configuration
+    pub fn from_repository(repo: &Repository) -> Result<Self> {
+        let config = repo.config()?.snapshot()?;
+        Ok(Self::from_config(&config))
+    }
+
+    /// Format of the signatures this signer produces
+    #[must_use]
+    pub const fn format(&self) -> SignatureFormat {
+        self.format
+    }
+
+    /// Produce an armored detached signature over `payload`
+    pub fn sign(&self, payload: &[u8]) -> SigningResult {
+        match self.format {
+            SignatureFormat::OpenPgp | SignatureFormat::X509 => self.sign_gpg(payload),
+            SignatureFormat::Ssh => self.sign_ssh(payload),
+        }
+    }
+
+    fn sign_gpg(&self, payload: &[u8]) -> SigningResult {
+        let key = self
+            .signing_key
+            .clone()
+            .or_else(|| self.default_ident.clone())
+            .ok_or_else(|| GitError::Configuration {
+                setting: "user.signingkey".to_string(),
+                reason: "no signing key or user identity configured".to_string(),
+            })?;
+
+        let output = run_signing_program(&self.program, &["--status-fd=2", "-bsau", &key], payload)
+            .map_err(|e| GitError::command_failed(&self.program, e.to_string()))?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let created = stderr
+            .lines()
+            .any(|line| line.starts_with("[GNUPG:] SIG_CREATED "));
+
+        if !output.status.success() || !created || output.stdout.is_empty() {
+            return Err(GitError::command_failed(
+                &self.program,
+                format!(
+                    "failed to sign the data with key '{}': {}",
+                    key,
+                    stderr.trim()
+                ),
+            ));
+        }
+
+        Ok(output.stdout)
+    }
+
+    fn sign_ssh(&self, payload: &[u8]) -> SigningResult {
+        let key = self
+            .signing_key
+            .as_deref()
+            .ok_or_else(|| GitError::Configuration {
+                setting: "user.signingkey".to_string(),
+                reason: "gpg.format is ssh but no SSH signing key is configured".to_string(),
+            })?;
+
+        // Literal public keys are signed through ssh-agent, paths are used directly
+        let literal_key = key
+            .strip_prefix("key::")
+            .or_else(|| key.starts_with("ssh-").then_some(key));
+        let key_file = match literal_key {
+            Some(public_key) => Some(
+                SigningTempFile::create(public_key.as_bytes())
+                    .map_err(|e| GitError::command_failed(&self.program, e.to_string()))?,
+            ),
+            None => None,
+        };
+        let key_path = key_file.as_ref().map_or_else(
+            || expand_home(key).to_string_lossy().to_string(),
+            |file| file.path().to_string_lossy().to_string(),
+        );
+
+        let payload_file = SigningTempFile::create(payload)
+            .map_err(|e| GitError::command_failed(&self.program, e.to_string()))?;
+        let payload_path = payload_file.path().to_string_lossy().to_string();
+        let signature_path = PathBuf::from(format!("{payload_path}.sig"));
+
+        let mut args = vec![
+            "-Y",
+            "sign",
+            "-n",
+            SSH_SIGNATURE_NAMESPACE,
+            "-f",
+            key_path.as_str(),
+        ];
+        if key_file.is_some() {
+            args.push("-U");
+        }
+        args.push(payload_path.as_str());
+
+        let output = run_signing_program(&self.program, &args, &[])
+            .map_err(|e| GitError::command_failed(&self.program, e.to_string()))?;
+        let signature = std::fs::read(&signature_path);
+        let _ = std::fs::remove_file(&signature_path);
+
+        match signature {
+            Ok(signature) if output.status.success() => Ok(signature),
+            _ => Err(GitError::command_failed(
+                &self.program,
+                format!(
+                    "failed to sign the data with key '{}': {}",
+                    key,
+                    combined_output(&output)
+                ),
+            )),
+        }
+    }
+}
+
+/// A signed payload and the signature over it
+pub type SignedParts<'a> = (&'a [u8], &'a [u8]);
+
+/// Split a raw tag object (or tag message) into its signed payload and trailing signature
+#[must_use]
+pub fn split_tag_signature(raw: &[u8]) -> Option<SignedParts<'_>> {
+    let mut start = None;
+    let mut offset = 0;
+
+    for line in raw.split_inclusive(|b| *b == b'\n') {
+        if [
+            PGP_SIGNATURE_HEADER,
+            X509_SIGNATURE_HEADER,
+            SSH_SIGNATURE_HEADER,
+        ]
+        .iter()
+        .any(|header| line.starts_with(header.as_bytes()))
+        {
+            // The last armor block is the signature; earlier ones belong to the message
+            start = Some(offset);
+        }
+        offset += line.len();
+    }
+
+    start.map(|pos| raw.split_at(pos))
+}
+
+/// Status, signer and key of a gpg verification
+pub type GpgStatus = (SignatureStatus, Option<String>, Option<String>);
+
+/// Interpret `gpg --status-fd` output as (status, signer, key)
+pub fn parse_gpg_status(status_output: &str) -> GpgStatus {
+    let mut status = SignatureStatus::Error;
+    let mut signer = None;
+    let mut key = None;
+    let mut fingerprint = None;
+
+    for line in status_output.lines() {
+        let Some(rest) = line.strip_prefix("[GNUPG:] ") else {
+            continue;
+        };
+        let mut parts = rest.splitn(3, ' ');
+        let keyword = parts.next().unwrap_or("");
+        let key_id = parts.next().map(str::to_string);
+        let user_id = parts.next().map(str::to_string);
+
+        match keyword {
+            "GOODSIG" => {
+                status = SignatureStatus::Good;
+                key = key_id;
+                signer = user_id;
+            }
+            "BADSIG" => {
+                status = SignatureStatus::Bad;
+                key = key_id;
+                signer = user_id;
+            }
+            "EXPSIG" | "EXPKEYSIG" => {
+                status = SignatureStatus::Expired;
+                key = key_id;
+                signer = user_id;
+            }
+            "REVKEYSIG" => {
+                status = SignatureStatus::Revoked;
+                key = key_id;
+                signer = user_id;
+            }
+            "ERRSIG" => {
+                // Missing public key is reported separately with NO_PUBKEY
+                if status != SignatureStatus::UnknownKey {
+                    status = SignatureStatus::Error;
+                }
+                key = key_id;
+            }
+            "NO_PUBKEY" => {
+                status = SignatureStatus::UnknownKey;
+                key = key_id;
+            }
+            "VALIDSIG" => {
+                fingerprint = key_id;
+            }
+            _ => {}
+        }
+    }
+
+    (status, signer, fingerprint.or(key))
+}
+
+/// Extract the key fingerprint from ssh-keygen's verification message
+fn parse_ssh_key(output: &str) -> Option<String> {
+    output
+        .rsplit_once(" key ")
+        .map(|(_, key)| key.lines().next().unwrap_or("").trim().to_string())
+        .filter(|key| !key.is_empty())
+}
+
+/// Run gpg, gpgsm or ssh-keygen, feeding `input` on stdin
+fn run_signing_program(program: &str, args: &[&str], input: &[u8]) -> Result<Output> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitError::command_failed(program, e.to_string()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A program that exits early closes the pipe; its exit status reports the reason
+        if let Err(e) = stdin.write_all(input) {
+            debug!("{} closed stdin early: {}", program, e);
+        }
+    }
+
+    child
+        .wait_with_output()
+        .map_err(|e| GitError::command_failed(program, e.to_string()).into())
+}
+
+fn combined_output(output: &Output) -> String {
+    let mut text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(stderr.trim());
+    }
+    text
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    path.strip_prefix("~/")
+        .and_then(|rest| dirs::home_dir().map(|home| home.join(rest)))
+        .unwrap_or_else(|| PathBuf::from(path))
+}
+
+/// Data written to a temporary file for gpg or ssh-keygen, removed on drop.
+/// The file is created exclusively with a random name, so other users
+/// cannot substitute its contents.
+struct SigningTempFile {
+    file: NamedTempFile,
+}
+
+impl SigningTempFile {
+    fn create(contents: &[u8]) -> Result<Self> {
+        let file_error = |path: &Path, e: std::io::Error| GitError::FileSystem {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        };
+
+        let mut file = tempfile::Builder::new()
+            .prefix("gitk_rust_sig_")
+            .tempfile()
+            .map_err(|e| file_error(&std::env::temp_dir(), e))?;
+        file.write_all(contents)
+            .and_then(|()| file.flush())
+            .map_err(|e| file_error(file.path(), e))?;
+
+        Ok(Self { file })
+    }
+
+    fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+/// An object whose signature is verified in the background
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SignatureTarget {
+    Commit(String), // Commit id
+    Tag(String),    // Tag name
+}
+
+/// A finished verification from `SignatureVerificationQueue`
+pub type SignatureResult = (SignatureTarget, Result<SignatureVerification>);
+
+/// Verifies signatures on a background thread so gpg and ssh-keygen never
+/// block the UI. Requests are handled in order; the thread exits when the
+/// queue is dropped.
+pub struct SignatureVerificationQueue {
+    requests: Sender<SignatureTarget>,
+    results: Receiver<SignatureResult>,
+}
+
+impl SignatureVerificationQueue {
+    /// Verify objects of the repository at `repo_path`
+    pub fn start(repo_path: PathBuf) -> Result<Self> {
+        let (requests, request_receiver) = mpsc::channel::<SignatureTarget>();
+        let (result_sender, results) = mpsc::channel();
+        thread::Builder::new()
+            .name("signature-verification".to_string())
+            .spawn(move || {
+                let repo = match GitRepository::open(&repo_path) {
+                    Ok(repo) => repo,
+                    Err(e) => {
+                        warn!("Not verifying signatures: {}", e);
+                        return;
+                    }
+                };
+                for target in request_receiver {
+                    let verification = match target {
+                        SignatureTarget::Commit(ref commit_id) => {
+                            repo.verify_commit_signature(commit_id)
+                        }
+                        SignatureTarget::Tag(ref tag_name) => repo.verify_tag_signature(tag_name),
+                    };
+                    // The receiver is gone when the queue was dropped
+                    if result_sender.send((target, verification)).is_err() {
+                        break;
+                    }
+                }
+            })?;
+
+        Ok(Self { requests, results })
+    }
+
+    /// Queue an object for verification; `false` if the thread has stopped
+    #[must_use]
+    pub fn request(&self, target: SignatureTarget) -> bool {
+        self.requests.send(target).is_ok()
+    }
+
+    /// Next finished verification, without blocking
+    #[must_use]
+    pub fn try_next(&self) -> Option<SignatureResult> {
+        self.results.try_recv().ok()
+    }
+}
+
+impl std::fmt::Debug for SignatureVerificationQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignatureVerificationQueue")
+            .finish_non_exhaustive()
+    }
+}
+
+impl GitRepository {
+    /// Get a signature verifier configured from this repository
+    pub fn signature_verifier(&self) -> Result<SignatureVerifier> {
+        SignatureVerifier::from_repository(self.repo())
+    }
+
+    /// Verify signatures of this repository on a background thread
+    pub fn start_signature_verification(&self) -> Result<SignatureVerificationQueue> {
+        SignatureVerificationQueue::start(self.repo().path().to_path_buf())
+    }
+
+    /// Verify the signature of a commit
+    pub fn verify_commit_signature(&self, commit_id: &str) -> Result<SignatureVerification> {
+        let oid = Oid::from_str(commit_id)?;
+
+        let (signature, signed_data) = match self.repo().extract_signature(&oid, None) {
+            Ok(extracted) => extracted,
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                return Ok(SignatureVerification::unsigned());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        self.signature_verifier()?.verify(&signature, &signed_data)
+    }
+
+    /// Verify the signature of an annotated tag
+    pub fn verify_tag_signature(&self, tag_name: &str) -> Result<SignatureVerification> {
+        let tag_ref = self
+            .repo()
+            .find_reference(&format!("refs/tags/{tag_name}"))?;
+        let Some(target) = tag_ref.target() else {
+            return Ok(SignatureVerification::unsigned());
+        };
+
+        // Lightweight tags have no tag object and cannot be signed
+        if self.repo().find_tag(target).is_err() {
+            return Ok(SignatureVerification::unsigned());
+        }
+
+        let odb = self.repo().odb()?;
+        let object = odb.read(target)?;
+
+        match split_tag_signature(object.data()) {
+            Some((signed_data, signature)) => {
+                self.signature_verifier()?.verify(signature, signed_data)
+            }
+            None => Ok(SignatureVerification::unsigned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use std::fs;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    /// Create a repository whose commits and tags are signed with a fresh SSH key
+    fn create_ssh_signed_repo() -> Result<Option<(TempDir, PathBuf)>> {
+        if Command::new("ssh-keygen").arg("-?").output().is_err() {
+            return Ok(None);
+        }
+
+        let temp_dir = TempDir::new()?;
+        let key_path = temp_dir.path().join("signing_key");
+        let repo_path = temp_dir.path().join("repo");
+        fs::create_dir(&repo_path)?;
+
+        let keygen = Command::new("ssh-keygen")
+            .args([
+                "-q",
+                "-t",
+                "ed25519",
+                "-N",
+                "",
+                "-C",
+                "test@example.com",
+                "-f",
+            ])
+            .arg(&key_path)
+            .output()?;
+        if !keygen.status.success() {
+            return Ok(None);
+        }
+
+        let public_key = fs::read_to_string(key_path.with_extension("pub"))?;
+        let allowed_signers = temp_dir.path().join("allowed_signers");
+        fs::write(
+            &allowed_signers,
+            format!("test@example.com {}", public_key.trim()),
+        )?;
+
+        git(&repo_path, &["init"])?;
+        git(&repo_path, &["config", "user.name", "Test User"])?;
+        git(&repo_path, &["config", "user.email", "test@example.com"])?;
+        git(&repo_path, &["config", "gpg.format", "ssh"])?;
+        git(
+            &repo_path,
+            &[
+                "config",
+                "user.signingkey",
+                &key_path.with_extension("pub").to_string_lossy(),
+            ],
+        )?;
+        git(
+            &repo_path,
+            &[
+                "config",
+                "gpg.ssh.allowedSignersFile",
+                &allowed_signers.to_string_lossy(),
+            ],
+        )?;
+
+        Ok(Some((temp_dir, repo_path)))
+    }
+
+    #[test]
+    fn test_ssh_signed_commit_and_tag() -> Result<()> {
+        let Some((_temp_dir, repo_path)) = create_ssh_signed_repo()? else {
+            return Ok(());
+        };
+
+        fs::write(repo_path.join("file.txt"), "signed")?;
+        git(&repo_path, &["add", "file.txt"])?;
+        git(&repo_path, &["commit", "-S", "-m", "Signed commit"])?;
+        git(&repo_path, &["tag", "-s", "-m", "Signed tag", "v1.0.0"])?;
+        let head = git(&repo_path, &["rev-parse", "HEAD"])?;
+
+        let repo = GitRepository::open(&repo_path)?;
+
+        let commit_sig = repo.verify_commit_signature(&head)?;
+        assert_eq!(commit_sig.status, SignatureStatus::Good);
+        assert_eq!(commit_sig.format, Some(SignatureFormat::Ssh));
+        assert_eq!(commit_sig.signer.as_deref(), Some("test@example.com"));
+        assert!(commit_sig.key.is_some());
+
+        let tag_sig = repo.verify_tag_signature("v1.0.0")?;
+        assert_eq!(tag_sig.status, SignatureStatus::Good);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ssh_signature_unknown_key_and_unsigned() -> Result<()> {
+        let Some((temp_dir, repo_path)) = create_ssh_signed_repo()? else {
+            return Ok(());
+        };
+
+        fs::write(repo_path.join("file.txt"), "unsigned")?;
+        git(&repo_path, &["add", "file.txt"])?;
+        git(&repo_path, &["commit", "-m", "Unsigned commit"])?;
+        git(
+            &repo_path,
+            &["commit", "--allow-empty", "-S", "-m", "Signed"],
+        )?;
+        let unsigned = git(&repo_path, &["rev-parse", "HEAD~1"])?;
+        let signed = git(&repo_path, &["rev-parse", "HEAD"])?;
+
+        // Nobody is trusted anymore
+        fs::write(temp_dir.path().join("allowed_signers"), "")?;
+
+        let repo = GitRepository::open(&repo_path)?;
+        assert_eq!(
+            repo.verify_commit_signature(&unsigned)?.status,
+            SignatureStatus::Unsigned
+        );
+        assert_eq!(
+            repo.verify_commit_signature(&signed)?.status,
+            SignatureStatus::UnknownKey
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ssh_signature_bad_data() -> Result<()> {
+        let verifier = SignatureVerifier::default();
+        let Some((_temp_dir, repo_path)) = create_ssh_signed_repo()? else {
+            return Ok(());
+        };
+
+        git(
+            &repo_path,
+            &["commit", "--allow-empty", "-S", "-m", "Signed"],
+        )?;
+        let head = git(&repo_path, &["rev-parse", "HEAD"])?;
+        let repo = GitRepository::open(&repo_path)?;
+        let (signature, signed_data) = repo
+            .repo()
+            .extract_signature(&Oid::from_str(&head)?, None)?;
+
+        let mut tampered = signed_data.to_vec();
+        tampered.extend_from_slice(b"tampered\n");
+
+        let verification = verifier.verify(&signature, &tampered)?;
+        assert_eq!(verification.status, SignatureStatus::Bad);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_background_verification() -> Result<()> {
+        let Some((_temp_dir, repo_path)) = create_ssh_signed_repo()? else {
+            return Ok(());
+        };
+
+        git(
+            &repo_path,
+            &["commit", "--allow-empty", "-S", "-m", "Signed"],
+        )?;
+        git(&repo_path, &["tag", "-s", "-m", "Signed tag", "v1.0.0"])?;
+        let head = git(&repo_path, &["rev-parse", "HEAD"])?;
+
+        let repo = GitRepository::open(&repo_path)?;
+        let queue = repo.start_signature_verification()?;
+        assert!(queue.request(SignatureTarget::Commit(head.clone())));
+        assert!(queue.request(SignatureTarget::Tag("v1.0.0".to_string())));
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let mut results = Vec::new();
+        while results.len() < 2 && Instant::now() < deadline {
+            match queue.try_next() {
+                Some((target, verification)) => results.push((target, verification?.status)),
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        assert_eq!(
+            results,
+            vec![
+                (SignatureTarget::Commit(head), SignatureStatus::Good),
+                (
+                    SignatureTarget::Tag("v1.0.0".to_string()),
+                    SignatureStatus::Good
+                ),
+            ]
+        );
+        assert!(!SignatureVerification::pending().status.is_signed());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signing_temp_file_is_unique_and_removed() -> Result<()> {
+        let first = SigningTempFile::create(b"payload")?;
+        let second = SigningTempFile::create(b"payload")?;
+        assert_ne!(first.path(), second.path());
+        assert_eq!(fs::read(first.path())?, b"payload");
+
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_tag_signature() {
+        let raw = b"object abc\ntype commit\ntag v1\n\nmessage\n-----BEGIN SSH SIGNATURE-----\ndata\n-----END SSH SIGNATURE-----\n";
+        let (payload, signature) = split_tag_signature(raw).unwrap();
+
+        assert!(payload.ends_with(b"message\n"));
+        assert!(signature.starts_with(SSH_SIGNATURE_HEADER.as_bytes()));
+        assert!(split_tag_signature(b"object abc\n\nplain message\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_gpg_status() {
+        let good = "[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 0123456789ABCDEF Test User <test@example.com>\n[GNUPG:] VALIDSIG FINGERPRINT 2024-01-01\n";
+        let (status, signer, key) = parse_gpg_status(good);
+        assert_eq!(status, SignatureStatus::Good);
+        assert_eq!(signer.as_deref(), Some("Test User <test@example.com>"));
+        assert_eq!(key.as_deref(), Some("FINGERPRINT"));
+
+        let unknown = "[GNUPG:] ERRSIG 0123456789ABCDEF 1 8 00 1700000000 9 -\n[GNUPG:] NO_PUBKEY 0123456789ABCDEF\n";
+        assert_eq!(parse_gpg_status(unknown).0, SignatureStatus::UnknownKey);
+
+        let bad = "[GNUPG:] BADSIG 0123456789ABCDEF Test User <test@example.com>\n";
+        assert_eq!(parse_gpg_status(bad).0, SignatureStatus::Bad);
+
+        let expired = "[GNUPG:] EXPKEYSIG 0123456789ABCDEF Test User <test@example.com>\n";
+        assert_eq!(parse_gpg_status(expired).0, SignatureStatus::Expired);
+
+        let revoked = "[GNUPG:] REVKEYSIG 0123456789ABCDEF Test User <test@example.com>\n[GNUPG:] VALIDSIG FINGERPRINT 2024-01-01\n";
+        let (status, signer, key) = parse_gpg_status(revoked);
+        assert_eq!(status, SignatureStatus::Revoked);
+        assert_eq!(signer.as_deref(), Some("Test User <test@example.com>"));
+        assert_eq!(key.as_deref(), Some("FINGERPRINT"));
+        assert!(status.is_signed());
+
+        assert_eq!(parse_gpg_status("").0, SignatureStatus::Error);
+    }
+
+    #[test]
+    fn test_signature_format_detection() {
+        assert_eq!(
+            SignatureFormat::detect(b"-----BEGIN PGP SIGNATURE-----\n"),
+            Some(SignatureFormat::OpenPgp)
+        );
+        assert_eq!(
+            SignatureFormat::detect(b"-----BEGIN SSH SIGNATURE-----\n"),
+            Some(SignatureFormat::Ssh)
+        );
+        assert_eq!(SignatureFormat::detect(b"garbage"), None);
+        assert_eq!(
+            SignatureFormat::from_config_value("SSH"),
+            Some(SignatureFormat::Ssh)
+        );
+    }
+}
@@ -0,0 +1,414 @@
+use crate::git::{CommitScan, FileStat, GitRepository, ScanContext, ScanEvent};
+use crate::models::{GitCommit, GitDiffStats};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Offset, Timelike, Utc};
+use git2::Oid;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+/// Percentage of a directory's changed lines its top authors must reach for
+/// the bus factor, i.e. how many people would have to leave before half the
+/// work there was done by someone gone
+const BUS_FACTOR_PERCENT: usize = 50;
+
+/// Commit counts by author email
+pub type CommitsByAuthor = HashMap<String, usize>;
+
+/// Commits and changed lines of one author, keyed by email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorStatistics {
+    pub name: String, // As written on the newest commit seen
+    pub email: String,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// How often a file or directory changed, and by whom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathChurn {
+    pub path: String,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub lines_by_author: HashMap<String, usize>, // Changed lines by author email
+}
+
+impl PathChurn {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            commits: 0,
+            insertions: 0,
+            deletions: 0,
+            lines_by_author: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn churn(&self) -> usize {
+        self.insertions + self.deletions
+    }
+
+    /// Fewest authors who together changed at least half of the lines
+    #[must_use]
+    pub fn bus_factor(&self) -> usize {
+        let mut lines: Vec<usize> = self.lines_by_author.values().copied().collect();
+        lines.sort_unstable_by(|a, b| b.cmp(a));
+        let needed = self.churn() * BUS_FACTOR_PERCENT;
+        let mut covered = 0;
+        for (count, author_lines) in lines.iter().enumerate() {
+            covered += author_lines;
+            if covered * 100 >= needed {
+                return count + 1;
+            }
+        }
+        lines.len()
+    }
+}
+
+/// Aggregates for a list of commits, e.g. the current view
+#[derive(Debug, Clone, Default)]
+pub struct RepositoryStatistics {
+    pub commit_count: usize,
+    pub merge_count: usize, // Counted for authors and activity, not for lines
+    pub authors: Vec<AuthorStatistics>, // Most commits first
+    pub commits_by_month: BTreeMap<NaiveDate, CommitsByAuthor>,
+    pub files: Vec<PathChurn>,          // Most changed lines first
+    pub directories: Vec<PathChurn>,    // Every ancestor directory of a changed file
+    pub commits_by_hour: [usize; 24],   // In the author's own timezone
+    pub commits_by_weekday: [usize; 7], // Monday first
+}
+
+impl RepositoryStatistics {
+    #[must_use]
+    pub fn author_name<'a>(&'a self, email: &'a str) -> &'a str {
+        self.authors
+            .iter()
+            .find(|author| author.email == email)
+            .map_or(email, |author| author.name.as_str())
+    }
+}
+
+/// Collects statistics one commit at a time, newest first
+#[derive(Debug, Default)]
+pub struct StatisticsBuilder {
+    statistics: RepositoryStatistics,
+    authors: HashMap<String, AuthorStatistics>,
+    files: HashMap<String, PathChurn>,
+    directories: HashMap<String, PathChurn>,
+}
+
+impl StatisticsBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a commit authored at `authored` (local time) that changed `files`;
+    /// merges should pass no files
+    pub fn add_commit(
+        &mut self,
+        commit: &GitCommit,
+        authored: DateTime<FixedOffset>,
+        files: &[FileStat],
+    ) {
+        let statistics = &mut self.statistics;
+        let email = commit.author.email.to_lowercase();
+        statistics.commit_count += 1;
+        if commit.parent_ids.len() > 1 {
+            statistics.merge_count += 1;
+        }
+        statistics.commits_by_hour[authored.hour() as usize] += 1;
+        statistics.commits_by_weekday[authored.weekday().num_days_from_monday() as usize] += 1;
+        if let Some(month) = NaiveDate::from_ymd_opt(authored.year(), authored.month(), 1) {
+            *statistics
+                .commits_by_month
+                .entry(month)
+                .or_default()
+                .entry(email.clone())
+                .or_default() += 1;
+        }
+
+        let author = self
+            .authors
+            .entry(email.clone())
+            .or_insert_with(|| AuthorStatistics {
+                name: commit.author.name.clone(),
+                email: email.clone(),
+                commits: 0,
+                insertions: 0,
+                deletions: 0,
+            });
+        author.commits += 1;
+
+        let mut touched_directories: Vec<&str> = Vec::new();
+        for (path, stats) in files {
+            author.insertions += stats.insertions;
+            author.deletions += stats.deletions;
+            add_churn(&mut self.files, path, &email, stats);
+            for (index, _) in path.match_indices('/') {
+                let directory = &path[..index];
+                let churn = self
+                    .directories
+                    .entry(directory.to_string())
+                    .or_insert_with(|| PathChurn::new(directory));
+                if !touched_directories.contains(&directory) {
+                    touched_directories.push(directory);
+                    churn.commits += 1;
+                }
+                churn.insertions += stats.insertions;
+                churn.deletions += stats.deletions;
+                *churn.lines_by_author.entry(email.clone()).or_default() +=
+                    stats.insertions + stats.deletions;
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn finish(self) -> RepositoryStatistics {
+        let mut statistics = self.statistics;
+
+        statistics.authors = self.authors.into_values().collect();
+        statistics
+            .authors
+            .sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+        statistics.files = sorted_by_churn(self.files);
+        statistics.directories = sorted_by_churn(self.directories);
+        statistics
+    }
+}
+
+fn add_churn(
+    paths: &mut HashMap<String, PathChurn>,
+    path: &str,
+    email: &str,
+    stats: &GitDiffStats,
+) {
+    let churn = paths
+        .entry(path.to_string())
+        .or_insert_with(|| PathChurn::new(path));
+    churn.commits += 1;
+    churn.insertions += stats.insertions;
+    churn.deletions += stats.deletions;
+    *churn.lines_by_author.entry(email.to_string()).or_default() +=
+        stats.insertions + stats.deletions;
+}
+
+fn sorted_by_churn(paths: HashMap<String, PathChurn>) -> Vec<PathChurn> {
+    let mut paths: Vec<PathChurn> = paths.into_values().collect();
+    paths.sort_by(|a, b| b.churn().cmp(&a.churn()).then_with(|| a.path.cmp(&b.path)));
+    paths
+}
+
+/// Author date in the timezone it was recorded in
+fn authored_at(commit: &git2::Commit) -> DateTime<FixedOffset> {
+    let when = commit.author().when();
+    let offset = FixedOffset::east_opt(when.offset_minutes() * 60).unwrap_or_else(|| Utc.fix());
+    DateTime::from_timestamp(when.seconds(), 0)
+        .unwrap_or_default()
+        .with_timezone(&offset)
+}
+
+impl GitRepository {
+    /// Statistics for `commits`, computed on the calling thread
+    pub fn compute_statistics(&self, commits: &[GitCommit]) -> Result<RepositoryStatistics> {
+        let mut builder = StatisticsBuilder::new();
+        for commit in commits {
+            self.add_commit_statistics(&mut builder, commit)?;
+        }
+        Ok(builder.finish())
+    }
+
+    /// Compute statistics for `commits` on a background thread
+    pub fn start_statistics(&self, commits: Vec<GitCommit>) -> Result<StatisticsJob> {
+        let repo_path = self.repo().path().to_path_buf();
+        let total = commits.len();
+        CommitScan::start("statistics", (), Some(total), move |scan| {
+            run_statistics(&repo_path, &commits, scan)
+        })
+    }
+
+    fn add_commit_statistics(
+        &self,
+        builder: &mut StatisticsBuilder,
+        commit: &GitCommit,
+    ) -> Result<()> {
+        let raw = self.repo().find_commit(Oid::from_str(&commit.id)?)?;
+        let files = if commit.parent_ids.len() > 1 {
+            Vec::new() // A merge's diff repeats the work of the merged branch
+        } else {
+            self.get_commit_file_stats(&commit.id)?
+        };
+        builder.add_commit(commit, authored_at(&raw), &files);
+        Ok(())
+    }
+}
+
+/// Incremental output of a background statistics job; the one item is
+/// the finished statistics
+pub type StatisticsEvent = ScanEvent<Box<RepositoryStatistics>>;
+
+/// Statistics computed on a background thread
+pub type StatisticsJob = CommitScan<Box<RepositoryStatistics>>;
+
+/// Scan-thread side of a [`StatisticsJob`]
+type StatisticsScan = ScanContext<Box<RepositoryStatistics>>;
+
+fn run_statistics(
+    repo_path: &Path,
+    commits: &[GitCommit],
+    scan: &mut StatisticsScan,
+) -> Result<()> {
+    let repo = GitRepository::open(repo_path)?;
+    let mut builder = StatisticsBuilder::new();
+
+    for commit in commits {
+        if !scan.next_commit() {
+            return Ok(());
+        }
+        repo.add_commit_statistics(&mut builder, commit)?;
+    }
+
+    scan.report(Box::new(builder.finish()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git_with_env;
+    use anyhow::anyhow;
+    use std::fs;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn git(repo_path: &Path, args: &[&str], author: (&str, &str), date: &str) -> Result<String> {
+        let env = [
+            ("GIT_AUTHOR_NAME", author.0),
+            ("GIT_AUTHOR_EMAIL", author.1),
+            ("GIT_COMMITTER_NAME", author.0),
+            ("GIT_COMMITTER_EMAIL", author.1),
+            ("GIT_AUTHOR_DATE", date),
+            ("GIT_COMMITTER_DATE", date),
+        ];
+        git_with_env(repo_path, args, &env)
+    }
+
+    const ALICE: (&str, &str) = ("Alice", "alice@example.com");
+    const BOB: (&str, &str) = ("Bob", "bob@example.com");
+
+    /// Alice writes src/lib.rs and docs; Bob edits src/lib.rs once
+    fn create_test_repo() -> Result<(TempDir, GitRepository)> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"], ALICE, "2024-01-01T09:00:00+0100")?;
+        fs::create_dir_all(path.join("src"))?;
+        fs::create_dir_all(path.join("docs"))?;
+
+        fs::write(path.join("src/lib.rs"), "a\nb\nc\nd\n")?;
+        fs::write(path.join("docs/guide.md"), "guide\n")?;
+        git(path, &["add", "."], ALICE, "2024-01-01T09:00:00+0100")?;
+        git(
+            path,
+            &["commit", "-m", "Initial"],
+            ALICE,
+            "2024-01-01T09:00:00+0100",
+        )?;
+
+        fs::write(path.join("src/lib.rs"), "a\nB\nc\nd\n")?;
+        git(
+            path,
+            &["commit", "-am", "Fix b"],
+            BOB,
+            "2024-02-03T22:30:00-0500",
+        )?;
+
+        fs::write(path.join("docs/guide.md"), "guide\nmore\n")?;
+        git(
+            path,
+            &["commit", "-am", "Docs"],
+            ALICE,
+            "2024-02-05T09:15:00+0100",
+        )?;
+
+        let repo = GitRepository::open(path)?;
+        Ok((temp_dir, repo))
+    }
+
+    #[test]
+    fn test_compute_statistics() -> Result<()> {
+        let (_temp_dir, repo) = create_test_repo()?;
+        let commits = repo.get_commits(None)?;
+        let statistics = repo.compute_statistics(&commits)?;
+
+        assert_eq!(statistics.commit_count, 3);
+        assert_eq!(statistics.authors[0].name, "Alice");
+        assert_eq!(statistics.authors[0].commits, 2);
+        assert_eq!(statistics.authors[0].insertions, 6);
+        assert_eq!(statistics.authors[1].insertions, 1);
+        assert_eq!(statistics.authors[1].deletions, 1);
+
+        // Local hours: 9 twice (Alice, +0100) and 22 (Bob, -0500)
+        assert_eq!(statistics.commits_by_hour[9], 2);
+        assert_eq!(statistics.commits_by_hour[22], 1);
+        assert_eq!(statistics.commits_by_weekday[0], 2); // Two Mondays
+        assert_eq!(statistics.commits_by_weekday[5], 1); // Bob's Saturday
+
+        let february = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        assert_eq!(statistics.commits_by_month[&february].len(), 2);
+
+        assert_eq!(statistics.files[0].path, "src/lib.rs");
+        assert_eq!(statistics.files[0].churn(), 6);
+        let src = statistics
+            .directories
+            .iter()
+            .find(|directory| directory.path == "src")
+            .ok_or_else(|| anyhow!("no src directory"))?;
+        assert_eq!(src.commits, 2);
+        assert_eq!(src.bus_factor(), 1); // Alice wrote 4 of its 6 changed lines
+        Ok(())
+    }
+
+    #[test]
+    fn test_statistics_job_matches_synchronous_result() -> Result<()> {
+        let (_temp_dir, repo) = create_test_repo()?;
+        let commits = repo.get_commits(None)?;
+        let expected = repo.compute_statistics(&commits)?;
+
+        let mut job = repo.start_statistics(commits)?;
+        let statistics = loop {
+            match job.try_next() {
+                Some(StatisticsEvent::Item(statistics)) => break statistics,
+                Some(StatisticsEvent::Failed(e)) => return Err(anyhow!(e)),
+                Some(_) => {}
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        };
+
+        while !job.is_complete() {
+            if job.try_next().is_none() {
+                thread::sleep(Duration::from_millis(5));
+            }
+        }
+        assert_eq!(job.scanned_count(), job.total_count().unwrap_or(0));
+        assert_eq!(statistics.authors, expected.authors);
+        assert_eq!(statistics.commits_by_hour, expected.commits_by_hour);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bus_factor() {
+        let mut churn = PathChurn::new("src");
+        churn.insertions = 100;
+        churn.lines_by_author = HashMap::from([
+            ("a".to_string(), 30),
+            ("b".to_string(), 30),
+            ("c".to_string(), 40),
+        ]);
+        assert_eq!(churn.bus_factor(), 2);
+
+        churn.lines_by_author = HashMap::from([("a".to_string(), 100)]);
+        assert_eq!(churn.bus_factor(), 1);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::git::GitRepository;
+use crate::git::commit_scan::walk_history;
+use crate::git::{DateCutoff, DateRange, GitRepository, commit_time};
 use crate::models::GitCommit;
 use anyhow::Result;
 use std::collections::VecDeque;
@@ -11,6 +12,8 @@ pub struct CommitStream {
     batch_size: usize,
     is_complete: bool,
     next_skip: usize,
+    start: Option<git2::Oid>, // Walk from this commit instead of HEAD
+    cutoff: DateCutoff,       // Committer-date window; ends the walk past `since`
 }
 
 impl CommitStream {
@@ -25,9 +28,25 @@ impl CommitStream {
             batch_size: 50,
             is_complete: false,
             next_skip: 0,
+            start: None,
+            cutoff: DateCutoff::default(),
         })
     }
 
+    /// Walk history from `start` instead of HEAD
+    #[must_use]
+    pub const fn with_start(mut self, start: git2::Oid) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Only yield commits committed inside `range`
+    #[must_use]
+    pub const fn with_date_range(mut self, range: DateRange) -> Self {
+        self.cutoff = DateCutoff::new(range);
+        self
+    }
+
     pub fn try_next(&mut self) -> Option<Result<GitCommit>> {
         // If we have commits in the queue, return one
         if let Some(commit) = self.commits.pop_front() {
@@ -55,7 +74,10 @@ impl CommitStream {
         // Create a fresh revwalk each time to avoid lifetime issues
         let mut revwalk = self.repo.repo().revwalk()?;
         revwalk.set_sorting(git2::Sort::TIME)?;
-        revwalk.push_head()?;
+        match self.start {
+            Some(start) => revwalk.push(start)?,
+            None => revwalk.push_head()?,
+        }
 
         // Skip commits we've already processed
         let mut skipped = 0;
@@ -76,17 +98,25 @@ impl CommitStream {
 
             match oid {
                 Ok(oid) => match self.repo.repo().find_commit(oid) {
-                    Ok(commit) => match GitCommit::new(&commit) {
-                        Ok(git_commit) => {
-                            self.commits.push_back(git_commit);
-                            batch_loaded += 1;
-                            self.loaded += 1;
-                            self.next_skip += 1;
-                        }
-                        Err(e) => {
-                            tracing::warn!("Error creating GitCommit: {}", e);
-                            self.next_skip += 1;
+                    Ok(commit) => match self.cutoff.check(commit_time(&commit)) {
+                        // Everything further is older than the date range
+                        None => {
+                            self.is_complete = true;
+                            return Ok(());
                         }
+                        Some(false) => self.next_skip += 1,
+                        Some(true) => match GitCommit::new(&commit) {
+                            Ok(git_commit) => {
+                                self.commits.push_back(git_commit);
+                                batch_loaded += 1;
+                                self.loaded += 1;
+                                self.next_skip += 1;
+                            }
+                            Err(e) => {
+                                tracing::warn!("Error creating GitCommit: {}", e);
+                                self.next_skip += 1;
+                            }
+                        },
                     },
                     Err(e) => {
                         tracing::warn!("Error finding commit: {}", e);
@@ -126,6 +156,8 @@ impl std::fmt::Debug for CommitStream {
             .field("batch_size", &self.batch_size)
             .field("is_complete", &self.is_complete)
             .field("next_skip", &self.next_skip)
+            .field("start", &self.start)
+            .field("cutoff", &self.cutoff)
             .finish()
     }
 }
@@ -144,4 +176,93 @@ impl GitRepository {
 
         CommitStream::new(repo, limit)
     }
+
+    /// Stream history starting at a specific commit rather than HEAD
+    pub fn get_commits_streaming_from(
+        &self,
+        start: &str,
+        limit: Option<usize>,
+    ) -> Result<CommitStream> {
+        let start = self.repo().revparse_single(start)?.peel_to_commit()?.id();
+        Ok(self.get_commits_streaming(limit)?.with_start(start))
+    }
+
+    /// History from `revision` (HEAD when `None`) in a single walk, like
+    /// `git log`: every commit unless `max_count` is given
+    pub fn get_history(
+        &self,
+        revision: Option<&str>,
+        max_count: Option<usize>,
+    ) -> Result<Vec<GitCommit>> {
+        let start = match revision {
+            Some(revision) => Some(
+                self.repo()
+                    .revparse_single(revision)?
+                    .peel_to_commit()?
+                    .id(),
+            ),
+            None => None,
+        };
+        walk_history(self.repo(), start)?
+            .take(max_count.unwrap_or(usize::MAX))
+            .map(|oid| GitCommit::new(&self.repo().find_commit(oid?)?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write as _;
+    use std::io::Write as _;
+    use std::process::{Command, Stdio};
+    use tempfile::TempDir;
+
+    /// A repository with `count` linear commits, built in one fast-import
+    fn create_long_history(count: usize) -> Result<TempDir> {
+        let temp_dir = TempDir::new()?;
+        crate::git::test_support::git(temp_dir.path(), &["init", "-b", "main"])?;
+
+        let mut script = String::new();
+        for index in 0..count {
+            let message = format!("Commit {}", index);
+            let _ = writeln!(script, "commit refs/heads/main");
+            let _ = writeln!(
+                script,
+                "committer Test User <test@example.com> {} +0000",
+                1_700_000_000 + index
+            );
+            let _ = writeln!(script, "data {}\n{}", message.len(), message);
+        }
+
+        let mut child = Command::new("git")
+            .args(["fast-import", "--quiet"])
+            .current_dir(temp_dir.path())
+            .stdin(Stdio::piped())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(script.as_bytes())?;
+        assert!(child.wait()?.success());
+        Ok(temp_dir)
+    }
+
+    #[test]
+    fn test_history_without_max_count_reads_every_commit() -> Result<()> {
+        let count = 10_050; // Past the stream's 10000-commit default
+        let temp_dir = create_long_history(count)?;
+        let repo = GitRepository::open(temp_dir.path())?;
+
+        let history = repo.get_history(None, None)?;
+        assert_eq!(history.len(), count);
+        assert_eq!(history[0].summary, format!("Commit {}", count - 1));
+        assert_eq!(history[count - 1].summary, "Commit 0");
+
+        assert_eq!(repo.get_history(None, Some(120))?.len(), 120);
+        let from_parent = repo.get_history(Some("main~10000"), None)?;
+        assert_eq!(from_parent.len(), count - 10_000);
+        Ok(())
+    }
 }
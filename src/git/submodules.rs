@@ -0,0 +1,247 @@
+use crate::git::GitRepository;
+use crate::models::{GitCommit, SubmoduleChange};
+use anyhow::{Result, anyhow};
+use git2::Repository;
+
+/// Maximum commits listed on each side of a submodule range
+const SUBMODULE_LOG_LIMIT: usize = 50;
+
+/// Commits between the old and new gitlink of a submodule change,
+/// as shown by `git diff --submodule=log`
+#[derive(Debug, Clone)]
+pub struct SubmoduleLogSummary {
+    pub change: SubmoduleChange,
+    pub added: Vec<GitCommit>,   // Reachable from the new commit only
+    pub removed: Vec<GitCommit>, // Reachable from the old commit only (rewinds)
+    pub truncated: bool,
+}
+
+impl SubmoduleLogSummary {
+    /// The old commit is an ancestor of the new one
+    #[must_use]
+    pub const fn is_fast_forward(&self) -> bool {
+        self.removed.is_empty()
+    }
+}
+
+impl GitRepository {
+    /// Open the repository of the submodule checked out at `path`.
+    /// Falls back to the gitdir under `.git/modules` when the submodule
+    /// is not checked out in the working tree.
+    pub fn open_submodule(&self, path: &str) -> Result<Self> {
+        let name = self
+            .repo()
+            .find_submodule(path)
+            .ok()
+            .and_then(|submodule| submodule.name().map(str::to_string))
+            .unwrap_or_else(|| path.to_string());
+
+        let checked_out = self
+            .repo()
+            .workdir()
+            .and_then(|workdir| Repository::open(workdir.join(path)).ok());
+        let sub_repo = match checked_out {
+            Some(repo) => repo,
+            None => Repository::open(self.repo().path().join("modules").join(&name))
+                .map_err(|_| anyhow!("Submodule '{path}' is not initialized"))?,
+        };
+
+        // An unpopulated worktree would make discovery climb into the superproject
+        match sub_repo.workdir() {
+            Some(workdir) if workdir.join(".git").exists() => Self::open(workdir),
+            _ => Self::open(sub_repo.path()),
+        }
+    }
+
+    /// Summarize the commits a submodule pointer change brings in or drops
+    pub fn get_submodule_log_summary(
+        &self,
+        change: &SubmoduleChange,
+    ) -> Result<SubmoduleLogSummary> {
+        let submodule = self.open_submodule(&change.path)?;
+        let repo = submodule.repo();
+
+        let resolve = |id: &Option<String>| -> Result<Option<git2::Oid>> {
+            match id {
+                Some(id) => {
+                    let oid = git2::Oid::from_str(id)?;
+                    repo.find_commit(oid).map_err(|_| {
+                        anyhow!(
+                            "Commit {} is not available in submodule '{}'; fetch it first",
+                            &id[..id.len().min(7)],
+                            change.path
+                        )
+                    })?;
+                    Ok(Some(oid))
+                }
+                None => Ok(None),
+            }
+        };
+        let old = resolve(&change.old_commit)?;
+        let new = resolve(&change.new_commit)?;
+
+        let mut truncated = false;
+        let mut walk = |include: Option<git2::Oid>, exclude: Option<git2::Oid>| {
+            let mut commits = Vec::new();
+            let Some(include) = include else {
+                return Ok::<_, anyhow::Error>(commits);
+            };
+
+            let mut revwalk = repo.revwalk()?;
+            revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+            revwalk.push(include)?;
+            if let Some(exclude) = exclude {
+                revwalk.hide(exclude)?;
+            }
+
+            for oid in revwalk {
+                if commits.len() >= SUBMODULE_LOG_LIMIT {
+                    truncated = true;
+                    break;
+                }
+                commits.push(GitCommit::new(&repo.find_commit(oid?)?)?);
+            }
+            Ok(commits)
+        };
+
+        let added = walk(new, old)?;
+        let removed = walk(old, new)?;
+
+        Ok(SubmoduleLogSummary {
+            change: change.clone(),
+            added,
+            removed,
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+        // Submodules are cloned from local paths
+        let args: Vec<&str> = ["-c", "protocol.file.allow=always"]
+            .iter()
+            .chain(args)
+            .copied()
+            .collect();
+        test_support::git(repo_path, &args)
+    }
+
+    fn init_repo(path: &Path) -> Result<()> {
+        fs::create_dir_all(path)?;
+        git(path, &["init"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+        Ok(())
+    }
+
+    /// Superproject with a `lib` submodule bumped by two commits in HEAD
+    fn create_superproject() -> Result<(TempDir, std::path::PathBuf, String, String)> {
+        let temp_dir = TempDir::new()?;
+        let lib_path = temp_dir.path().join("lib");
+        let super_path = temp_dir.path().join("super");
+
+        init_repo(&lib_path)?;
+        git(
+            &lib_path,
+            &["commit", "--allow-empty", "-m", "lib: initial"],
+        )?;
+
+        init_repo(&super_path)?;
+        git(
+            &super_path,
+            &["submodule", "add", lib_path.to_str().unwrap(), "lib"],
+        )?;
+        git(&super_path, &["commit", "-m", "Add lib submodule"])?;
+
+        let checkout = super_path.join("lib");
+        git(&checkout, &["config", "user.name", "Test User"])?;
+        git(&checkout, &["config", "user.email", "test@example.com"])?;
+        let old_commit = git(&checkout, &["rev-parse", "HEAD"])?;
+        git(
+            &checkout,
+            &["commit", "--allow-empty", "-m", "lib: first change"],
+        )?;
+        git(
+            &checkout,
+            &["commit", "--allow-empty", "-m", "lib: second change"],
+        )?;
+        let new_commit = git(&checkout, &["rev-parse", "HEAD"])?;
+
+        git(&super_path, &["add", "lib"])?;
+        git(&super_path, &["commit", "-m", "Bump lib"])?;
+
+        Ok((temp_dir, super_path, old_commit, new_commit))
+    }
+
+    #[test]
+    fn test_gitlink_detected_in_diff_and_tree() -> Result<()> {
+        let (_temp_dir, super_path, old_commit, new_commit) = create_superproject()?;
+        let repo = GitRepository::open(&super_path)?;
+        let head = git(&super_path, &["rev-parse", "HEAD"])?;
+
+        let diffs = repo.get_commit_diff_enhanced(&head)?;
+        assert_eq!(diffs.len(), 1);
+        let change = diffs[0].submodule.as_ref().expect("gitlink change");
+        assert_eq!(change.path, "lib");
+        assert_eq!(change.old_commit.as_deref(), Some(old_commit.as_str()));
+        assert_eq!(change.new_commit.as_deref(), Some(new_commit.as_str()));
+        assert!(diffs[0].hunks.is_empty());
+
+        let entries = repo.get_commit_tree_entries(&head)?;
+        let lib = entries.iter().find(|e| e.path == "lib").unwrap();
+        assert!(lib.is_submodule());
+        let gitmodules = entries.iter().find(|e| e.path == ".gitmodules").unwrap();
+        assert!(!gitmodules.is_submodule());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_submodule_log_summary() -> Result<()> {
+        let (_temp_dir, super_path, old_commit, new_commit) = create_superproject()?;
+        let repo = GitRepository::open(&super_path)?;
+
+        let change = SubmoduleChange {
+            path: "lib".to_string(),
+            old_commit: Some(old_commit.clone()),
+            new_commit: Some(new_commit.clone()),
+        };
+        let summary = repo.get_submodule_log_summary(&change)?;
+        let subjects: Vec<&str> = summary.added.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(subjects, vec!["lib: second change", "lib: first change"]);
+        assert!(summary.is_fast_forward());
+        assert!(!summary.truncated);
+
+        // Rewinding the pointer lists the dropped commits instead
+        let rewind = SubmoduleChange {
+            path: "lib".to_string(),
+            old_commit: Some(new_commit),
+            new_commit: Some(old_commit),
+        };
+        let summary = repo.get_submodule_log_summary(&rewind)?;
+        assert!(summary.added.is_empty());
+        assert_eq!(summary.removed.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_submodule() -> Result<()> {
+        let (_temp_dir, super_path, _old_commit, new_commit) = create_superproject()?;
+        let repo = GitRepository::open(&super_path)?;
+
+        let submodule = repo.open_submodule("lib")?;
+        assert_eq!(submodule.get_head_commit()?.id, new_commit);
+        assert!(repo.open_submodule("missing").is_err());
+
+        Ok(())
+    }
+}
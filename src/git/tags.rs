@@ -1,6 +1,7 @@
 use crate::git::operations::{OperationRecord, OperationType};
-use crate::git::{ErrorReporter, GitRepository, InputSanitizer, InputValidator};
-use anyhow::Result;
+use crate::git::signatures::{ObjectSigner, split_tag_signature};
+use crate::git::{ErrorReporter, GitError, GitRepository, InputSanitizer, InputValidator};
+use anyhow::{Result, anyhow};
 use git2::{ObjectType, Oid, Repository, Signature};
 use tracing::{error, info, warn};
 
@@ -32,6 +33,7 @@ pub struct TagInfo {
     pub message: Option<String>,
     pub tagger: Option<TagSignature>,
     pub created_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_signed: bool, // Annotated tag carries a GPG/SSH signature
 }
 
 /// Tag type classification
@@ -101,65 +103,36 @@ impl TagManager {
         &mut self,
         tag_name: &str,
         target_commit: &str,
-        config: TagCreateConfig,
+        mut config: TagCreateConfig,
     ) -> Result<TagOperationResult> {
         // Validate inputs
         if let Err(e) = InputValidator::validate_ref_name(tag_name) {
             ErrorReporter::log_error(&e, "tag creation validation");
-            return Ok(TagOperationResult {
-                success: false,
-                operation: OperationType::TagCreate,
-                tag_name: tag_name.to_string(),
-                target_commit: None,
-                message: format!("Invalid tag name: {}", e),
-                tag_type: config.tag_type,
-                signature: None,
-            });
+            return Err(anyhow!("Invalid tag name: {e}"));
         }
 
         if let Err(e) = InputValidator::validate_commit_id(target_commit) {
             ErrorReporter::log_error(&e, "tag creation validation");
-            return Ok(TagOperationResult {
-                success: false,
-                operation: OperationType::TagCreate,
-                tag_name: tag_name.to_string(),
-                target_commit: None,
-                message: format!("Invalid commit ID: {}", e),
-                tag_type: config.tag_type,
-                signature: None,
-            });
+            return Err(anyhow!("Invalid commit ID: {e}"));
         }
 
         // Sanitize inputs
-        let sanitized_name = match InputSanitizer::sanitize_ref_name(tag_name) {
-            Ok(name) => name,
-            Err(e) => {
-                return Ok(TagOperationResult {
-                    success: false,
-                    operation: OperationType::TagCreate,
-                    tag_name: tag_name.to_string(),
-                    target_commit: None,
-                    message: format!("Failed to sanitize tag name: {}", e),
-                    tag_type: config.tag_type,
-                    signature: None,
-                });
-            }
-        };
+        let sanitized_name = InputSanitizer::sanitize_ref_name(tag_name)
+            .map_err(|e| anyhow!("Failed to sanitize tag name: {e}"))?;
+        // git's own rules, e.g. no component starting with a dot
+        if !git2::Reference::is_valid_name(&format!("refs/tags/{sanitized_name}")) {
+            return Err(anyhow!("Invalid tag name: {sanitized_name}"));
+        }
 
-        let sanitized_commit = match InputSanitizer::sanitize_commit_id(target_commit) {
-            Ok(commit) => commit,
-            Err(e) => {
-                return Ok(TagOperationResult {
-                    success: false,
-                    operation: OperationType::TagCreate,
-                    tag_name: tag_name.to_string(),
-                    target_commit: None,
-                    message: format!("Failed to sanitize commit ID: {}", e),
-                    tag_type: config.tag_type,
-                    signature: None,
-                });
-            }
-        };
+        let sanitized_commit = InputSanitizer::sanitize_commit_id(target_commit)
+            .map_err(|e| anyhow!("Failed to sanitize commit ID: {e}"))?;
+
+        // Signed tags are always annotated; tag.gpgSign signs every annotated tag
+        let sign_tag = config.sign_tag
+            || (config.tag_type == TagType::Annotated && self.is_tag_signing_enabled());
+        if sign_tag {
+            config.tag_type = TagType::Annotated;
+        }
 
         // Check if tag already exists
         if !config.force_overwrite {
@@ -167,53 +140,22 @@ impl TagManager {
                 .repo
                 .find_reference(&format!("refs/tags/{}", sanitized_name))
             {
-                return Ok(TagOperationResult {
-                    success: false,
-                    operation: OperationType::TagCreate,
-                    tag_name: sanitized_name.clone(),
-                    target_commit: Some(sanitized_commit),
-                    message: format!(
-                        "Tag '{}' already exists. Use force to overwrite.",
-                        sanitized_name
-                    ),
-                    tag_type: config.tag_type,
-                    signature: None,
-                });
+                return Err(anyhow!(
+                    "Tag '{sanitized_name}' already exists. Use force to overwrite."
+                ));
             }
         }
 
         // Find target commit
-        let target_oid = match Oid::from_str(&sanitized_commit) {
-            Ok(oid) => oid,
-            Err(e) => {
-                return Ok(TagOperationResult {
-                    success: false,
-                    operation: OperationType::TagCreate,
-                    tag_name: sanitized_name,
-                    target_commit: Some(sanitized_commit),
-                    message: format!("Invalid commit OID: {}", e),
-                    tag_type: config.tag_type,
-                    signature: None,
-                });
-            }
-        };
+        let target_oid =
+            Oid::from_str(&sanitized_commit).map_err(|e| anyhow!("Invalid commit OID: {e}"))?;
 
         // Create the tag in separate scope to avoid borrowing issues
         let tag_result = {
-            let target_object = match self.repo.find_object(target_oid, None) {
-                Ok(obj) => obj,
-                Err(e) => {
-                    return Ok(TagOperationResult {
-                        success: false,
-                        operation: OperationType::TagCreate,
-                        tag_name: sanitized_name,
-                        target_commit: Some(sanitized_commit),
-                        message: format!("Target object not found: {}", e),
-                        tag_type: config.tag_type,
-                        signature: None,
-                    });
-                }
-            };
+            let target_object = self
+                .repo
+                .find_object(target_oid, None)
+                .map_err(|e| anyhow!("Target object not found: {e}"))?;
 
             // Create signature
             let signature = match self.create_signature(&config) {
@@ -252,13 +194,27 @@ impl TagManager {
                 TagType::Annotated => {
                     // Create annotated tag object
                     let tag_message = config.message.as_deref().unwrap_or("Tag created");
-                    match self.repo.tag(
-                        &sanitized_name,
-                        &target_object,
-                        &signature,
-                        tag_message,
-                        config.force_overwrite,
-                    ) {
+                    let created = if sign_tag {
+                        self.create_signed_tag(
+                            &sanitized_name,
+                            &target_object,
+                            &signature,
+                            tag_message,
+                            config.force_overwrite,
+                        )
+                    } else {
+                        self.repo
+                            .tag(
+                                &sanitized_name,
+                                &target_object,
+                                &signature,
+                                tag_message,
+                                config.force_overwrite,
+                            )
+                            .map_err(GitError::from)
+                    };
+
+                    match created {
                         Ok(_tag_oid) => {
                             let tag_sig = TagSignature {
                                 name: signature.name().unwrap_or("Unknown").to_string(),
@@ -274,11 +230,18 @@ impl TagManager {
                             };
                             (true, Some(tag_sig), String::new())
                         }
-                        Err(e) => (
-                            false,
-                            None,
-                            format!("Failed to create annotated tag: {}", e),
-                        ),
+                        Err(e) => {
+                            ErrorReporter::log_error(&e, "tag creation");
+                            (
+                                false,
+                                None,
+                                format!(
+                                    "Failed to create {} tag: {}",
+                                    if sign_tag { "signed" } else { "annotated" },
+                                    e
+                                ),
+                            )
+                        }
                     }
                 }
             }
@@ -302,7 +265,9 @@ impl TagManager {
             timestamp: chrono::Utc::now(),
             description: format!(
                 "Created {} tag '{}' at commit {}",
-                if config.tag_type == TagType::Annotated {
+                if sign_tag {
+                    "signed"
+                } else if config.tag_type == TagType::Annotated {
                     "annotated"
                 } else {
                     "lightweight"
@@ -317,7 +282,9 @@ impl TagManager {
 
         info!(
             "Successfully created {} tag '{}' at commit {}",
-            if config.tag_type == TagType::Annotated {
+            if sign_tag {
+                "signed"
+            } else if config.tag_type == TagType::Annotated {
                 "annotated"
             } else {
                 "lightweight"
@@ -342,63 +309,23 @@ impl TagManager {
         // Validate input
         if let Err(e) = InputValidator::validate_ref_name(tag_name) {
             ErrorReporter::log_error(&e, "tag deletion validation");
-            return Ok(TagOperationResult {
-                success: false,
-                operation: OperationType::TagDelete,
-                tag_name: tag_name.to_string(),
-                target_commit: None,
-                message: format!("Invalid tag name: {}", e),
-                tag_type: TagType::Lightweight, // Default, will be determined
-                signature: None,
-            });
+            return Err(anyhow!("Invalid tag name: {e}"));
         }
 
         // Sanitize input
-        let sanitized_name = match InputSanitizer::sanitize_ref_name(tag_name) {
-            Ok(name) => name,
-            Err(e) => {
-                return Ok(TagOperationResult {
-                    success: false,
-                    operation: OperationType::TagDelete,
-                    tag_name: tag_name.to_string(),
-                    target_commit: None,
-                    message: format!("Failed to sanitize tag name: {}", e),
-                    tag_type: TagType::Lightweight,
-                    signature: None,
-                });
-            }
-        };
+        let sanitized_name = InputSanitizer::sanitize_ref_name(tag_name)
+            .map_err(|e| anyhow!("Failed to sanitize tag name: {e}"))?;
 
         // Get tag information before deletion
-        let tag_info = match self.get_tag_info(&sanitized_name) {
-            Ok(info) => info,
-            Err(_) => {
-                return Ok(TagOperationResult {
-                    success: false,
-                    operation: OperationType::TagDelete,
-                    tag_name: sanitized_name.clone(),
-                    target_commit: None,
-                    message: format!("Tag '{}' not found", sanitized_name),
-                    tag_type: TagType::Lightweight,
-                    signature: None,
-                });
-            }
-        };
+        let tag_info = self
+            .get_tag_info(&sanitized_name)
+            .map_err(|_| anyhow!("Tag '{sanitized_name}' not found"))?;
 
         // Safety check for protected tags (unless force)
         if !force && self.is_protected_tag(&sanitized_name) {
-            return Ok(TagOperationResult {
-                success: false,
-                operation: OperationType::TagDelete,
-                tag_name: sanitized_name.clone(),
-                target_commit: Some(tag_info.target_oid.clone()),
-                message: format!(
-                    "Tag '{}' is protected. Use force to delete.",
-                    sanitized_name
-                ),
-                tag_type: tag_info.tag_type,
-                signature: tag_info.tagger,
-            });
+            return Err(anyhow!(
+                "Tag '{sanitized_name}' is protected. Use force to delete."
+            ));
         }
 
         // Delete the tag reference in separate scope
@@ -505,6 +432,9 @@ impl TagManager {
                 message: Some(tag_obj.message().unwrap_or("").to_string()),
                 tagger: tagger.clone(),
                 created_date: tagger.as_ref().map(|t| t.when),
+                is_signed: tag_obj
+                    .message_bytes()
+                    .is_some_and(|message| split_tag_signature(message).is_some()),
             })
         } else {
             // Lightweight tag - direct reference to object
@@ -518,6 +448,7 @@ impl TagManager {
                 message: None,
                 tagger: None,
                 created_date: None,
+                is_signed: false,
             })
         }
     }
@@ -688,6 +619,64 @@ impl TagManager {
         }
     }
 
+    /// Whether `tag.gpgSign` asks for annotated tags to be signed
+    fn is_tag_signing_enabled(&self) -> bool {
+        self.repo
+            .config()
+            .and_then(|config| config.get_bool("tag.gpgSign"))
+            .unwrap_or(false)
+    }
+
+    /// Build, sign and write an annotated tag object, then point the tag ref at it
+    fn create_signed_tag(
+        &self,
+        tag_name: &str,
+        target: &git2::Object,
+        tagger: &Signature,
+        message: &str,
+        force: bool,
+    ) -> Result<Oid, GitError> {
+        let signer =
+            ObjectSigner::from_repository(&self.repo).map_err(|e| GitError::Configuration {
+                setting: "gpg.format".to_string(),
+                reason: e.to_string(),
+            })?;
+
+        let when = tagger.when();
+        let offset = when.offset_minutes();
+        let mut buffer = format!(
+            "object {}\ntype {}\ntag {}\ntagger {} <{}> {} {}{:02}{:02}\n\n{}",
+            target.id(),
+            target.kind().unwrap_or(ObjectType::Commit).str(),
+            tag_name,
+            tagger.name().unwrap_or(""),
+            tagger.email().unwrap_or(""),
+            when.seconds(),
+            if offset < 0 { '-' } else { '+' },
+            offset.abs() / 60,
+            offset.abs() % 60,
+            message
+        );
+        // The signature armor has to start on its own line
+        if !buffer.ends_with('\n') {
+            buffer.push('\n');
+        }
+
+        let mut tag_object = buffer.into_bytes();
+        let tag_signature = signer.sign(&tag_object)?;
+        tag_object.extend_from_slice(&tag_signature);
+
+        let tag_oid = self.repo.odb()?.write(ObjectType::Tag, &tag_object)?;
+        self.repo.reference(
+            &format!("refs/tags/{tag_name}"),
+            tag_oid,
+            force,
+            "Create signed tag",
+        )?;
+
+        Ok(tag_oid)
+    }
+
     /// Record an operation in the history
     fn record_operation(&mut self, record: OperationRecord) {
         let operation_type = record.operation_type.clone();
@@ -840,8 +829,8 @@ mod tests {
         let (_temp_dir, repo_path) = create_test_repo()?;
         create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let manager = TagManager::new(&repo)?;
 
         assert_eq!(manager.operation_history.len(), 0);
 
@@ -851,17 +840,18 @@ mod tests {
     #[test]
     fn test_tag_create_config() -> Result<()> {
         let config = TagCreateConfig {
-            name: "v1.0.0".to_string(),
+            tag_type: TagType::Annotated,
             message: Some("Version 1.0.0".to_string()),
-            target: None,
-            annotated: true,
-            force: false,
+            force_overwrite: false,
+            sign_tag: false,
+            tagger: None,
         };
 
-        assert_eq!(config.name, "v1.0.0");
+        assert_eq!(config.tag_type, TagType::Annotated);
         assert_eq!(config.message, Some("Version 1.0.0".to_string()));
-        assert!(config.annotated);
-        assert!(!config.force);
+        assert!(!config.force_overwrite);
+        assert!(!config.sign_tag);
+        assert!(config.tagger.is_none());
 
         Ok(())
     }
@@ -873,7 +863,7 @@ mod tests {
             include_lightweight: true,
             include_annotated: true,
             limit: Some(10),
-            sort_by: TagSortBy::Date,
+            sort_by: TagSortBy::CreationDate,
             sort_order: SortOrder::Descending,
         };
 
@@ -881,7 +871,7 @@ mod tests {
         assert!(options.include_lightweight);
         assert!(options.include_annotated);
         assert_eq!(options.limit, Some(10));
-        assert_eq!(options.sort_by, TagSortBy::Date);
+        assert_eq!(options.sort_by, TagSortBy::CreationDate);
         assert_eq!(options.sort_order, SortOrder::Descending);
 
         Ok(())
@@ -895,12 +885,13 @@ mod tests {
             target_type: ObjectType::Commit,
             tag_type: TagType::Annotated,
             message: Some("Release v1.0.0".to_string()),
-            signature: Some(TagSignature {
+            tagger: Some(TagSignature {
                 name: "Tagger".to_string(),
                 email: "tagger@example.com".to_string(),
                 when: chrono::Utc::now(),
             }),
-            refs: vec!["refs/tags/v1.0.0".to_string()],
+            created_date: Some(chrono::Utc::now()),
+            is_signed: false,
         };
 
         assert_eq!(info.name, "v1.0.0");
@@ -908,8 +899,9 @@ mod tests {
         assert_eq!(info.target_type, ObjectType::Commit);
         assert_eq!(info.tag_type, TagType::Annotated);
         assert_eq!(info.message, Some("Release v1.0.0".to_string()));
-        assert!(info.signature.is_some());
-        assert_eq!(info.refs.len(), 1);
+        assert!(info.tagger.is_some());
+        assert!(info.created_date.is_some());
+        assert!(!info.is_signed);
 
         Ok(())
     }
@@ -944,11 +936,11 @@ mod tests {
     #[test]
     fn test_tag_sort_options() -> Result<()> {
         let sort_by_name = TagSortBy::Name;
-        let sort_by_date = TagSortBy::Date;
+        let sort_by_date = TagSortBy::CreationDate;
         let sort_by_version = TagSortBy::Version;
 
         assert_eq!(sort_by_name, TagSortBy::Name);
-        assert_eq!(sort_by_date, TagSortBy::Date);
+        assert_eq!(sort_by_date, TagSortBy::CreationDate);
         assert_eq!(sort_by_version, TagSortBy::Version);
 
         let ascending = SortOrder::Ascending;
@@ -961,23 +953,23 @@ mod tests {
         Ok(())
     }
 
+    fn annotated(message: &str) -> TagCreateConfig {
+        TagCreateConfig {
+            tag_type: TagType::Annotated,
+            message: Some(message.to_string()),
+            ..TagCreateConfig::default()
+        }
+    }
+
     #[test]
     fn test_tag_create_operation() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
         let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
-        let config = TagCreateConfig {
-            name: "v1.0.0".to_string(),
-            message: Some("Version 1.0.0".to_string()),
-            target: Some(commit_sha),
-            annotated: true,
-            force: false,
-        };
-
-        let result = manager.create_tag(config)?;
+        let result = manager.create_tag("v1.0.0", &commit_sha, annotated("Version 1.0.0"))?;
 
         assert!(result.success);
         assert_eq!(result.operation, OperationType::TagCreate);
@@ -990,21 +982,13 @@ mod tests {
     #[test]
     fn test_tag_list_operation() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
         // Create a tag first
-        let config = TagCreateConfig {
-            name: "v1.0.0".to_string(),
-            message: Some("Version 1.0.0".to_string()),
-            target: None, // Use HEAD
-            annotated: true,
-            force: false,
-        };
-
-        manager.create_tag(config)?;
+        manager.create_tag("v1.0.0", &commit_sha, annotated("Version 1.0.0"))?;
 
         // List tags
         let filter_options = TagFilterOptions {
@@ -1016,9 +1000,9 @@ mod tests {
             sort_order: SortOrder::Ascending,
         };
 
-        let tags = manager.list_tags(filter_options)?;
+        let tags = manager.list_tags(Some(filter_options))?;
 
-        assert!(tags.len() > 0);
+        assert!(!tags.is_empty());
         assert!(tags.iter().any(|t| t.name == "v1.0.0"));
 
         Ok(())
@@ -1027,24 +1011,16 @@ mod tests {
     #[test]
     fn test_tag_delete_operation() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
         // Create a tag first
-        let config = TagCreateConfig {
-            name: "to-delete".to_string(),
-            message: None,
-            target: None,
-            annotated: false,
-            force: false,
-        };
-
-        manager.create_tag(config)?;
+        manager.create_tag("to-delete", &commit_sha, TagCreateConfig::default())?;
 
         // Delete the tag
-        let result = manager.delete_tag("to-delete")?;
+        let result = manager.delete_tag("to-delete", false)?;
 
         assert!(result.success);
         assert_eq!(result.operation, OperationType::TagDelete);
@@ -1056,10 +1032,10 @@ mod tests {
     #[test]
     fn test_tag_validation() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
         // Test invalid tag names
         let invalid_names = vec![
@@ -1071,15 +1047,7 @@ mod tests {
         ];
 
         for invalid_name in invalid_names {
-            let config = TagCreateConfig {
-                name: invalid_name.to_string(),
-                message: None,
-                target: None,
-                annotated: false,
-                force: false,
-            };
-
-            let result = manager.create_tag(config);
+            let result = manager.create_tag(invalid_name, &commit_sha, TagCreateConfig::default());
             assert!(
                 result.is_err(),
                 "Should reject invalid tag name: {}",
@@ -1093,39 +1061,25 @@ mod tests {
     #[test]
     fn test_tag_operation_history() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
         // Perform tag operations
-        let config1 = TagCreateConfig {
-            name: "v1.0.0".to_string(),
-            message: Some("Version 1.0.0".to_string()),
-            target: None,
-            annotated: true,
-            force: false,
-        };
-
-        let config2 = TagCreateConfig {
-            name: "v1.1.0".to_string(),
-            message: Some("Version 1.1.0".to_string()),
-            target: None,
-            annotated: true,
-            force: false,
-        };
-
-        manager.create_tag(config1)?;
-        manager.create_tag(config2)?;
+        manager.create_tag("v1.0.0", &commit_sha, annotated("Version 1.0.0"))?;
+        manager.create_tag("v1.1.0", &commit_sha, annotated("Version 1.1.0"))?;
 
         // Check operation history
         let history = manager.get_operation_history();
         assert!(history.len() >= 2);
 
         // Verify operations are recorded
-        assert!(history
-            .iter()
-            .any(|op| op.operation_type == OperationType::TagCreate));
+        assert!(
+            history
+                .iter()
+                .any(|op| op.operation_type == OperationType::TagCreate)
+        );
 
         Ok(())
     }
@@ -1133,23 +1087,16 @@ mod tests {
     #[test]
     fn test_tag_filter_by_pattern() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
         // Create multiple tags
         let tags_to_create = vec!["v1.0.0", "v1.1.0", "release-1.0", "beta-1"];
 
         for tag_name in tags_to_create {
-            let config = TagCreateConfig {
-                name: tag_name.to_string(),
-                message: None,
-                target: None,
-                annotated: false,
-                force: false,
-            };
-            manager.create_tag(config)?;
+            manager.create_tag(tag_name, &commit_sha, TagCreateConfig::default())?;
         }
 
         // Filter tags with pattern
@@ -1162,7 +1109,7 @@ mod tests {
             sort_order: SortOrder::Ascending,
         };
 
-        let filtered_tags = manager.list_tags(filter_options)?;
+        let filtered_tags = manager.list_tags(Some(filter_options))?;
 
         // Should only include tags starting with 'v'
         for tag in &filtered_tags {
@@ -1179,44 +1126,25 @@ mod tests {
     #[test]
     fn test_tag_force_create() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
         // Create initial tag
-        let config1 = TagCreateConfig {
-            name: "v1.0.0".to_string(),
-            message: Some("Initial version".to_string()),
-            target: None,
-            annotated: true,
-            force: false,
-        };
-
-        manager.create_tag(config1)?;
+        manager.create_tag("v1.0.0", &commit_sha, annotated("Initial version"))?;
 
         // Try to create same tag without force (should fail)
-        let config2 = TagCreateConfig {
-            name: "v1.0.0".to_string(),
-            message: Some("Updated version".to_string()),
-            target: None,
-            annotated: true,
-            force: false,
-        };
-
-        let result = manager.create_tag(config2);
+        let result = manager.create_tag("v1.0.0", &commit_sha, annotated("Updated version"));
         assert!(result.is_err());
 
         // Create same tag with force (should succeed)
-        let config3 = TagCreateConfig {
-            name: "v1.0.0".to_string(),
-            message: Some("Force updated version".to_string()),
-            target: None,
-            annotated: true,
-            force: true,
+        let config = TagCreateConfig {
+            force_overwrite: true,
+            ..annotated("Force updated version")
         };
 
-        let result = manager.create_tag(config3)?;
+        let result = manager.create_tag("v1.0.0", &commit_sha, config)?;
         assert!(result.success);
 
         Ok(())
@@ -1239,20 +1167,12 @@ mod tests {
     #[test]
     fn test_tag_operation_result_structure() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
-
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let config = TagCreateConfig {
-            name: "v1.0.0".to_string(),
-            message: Some("Version 1.0.0".to_string()),
-            target: None,
-            annotated: true,
-            force: false,
-        };
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
-        let result = manager.create_tag(config)?;
+        let result = manager.create_tag("v1.0.0", &commit_sha, annotated("Version 1.0.0"))?;
 
         // Verify result structure
         assert!(result.success);
@@ -1270,23 +1190,16 @@ mod tests {
         let (_temp_dir, repo_path) = create_test_repo()?;
         create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&repo)?;
 
         // Test delete non-existent tag
-        let result = manager.delete_tag("non-existent-tag");
+        let result = manager.delete_tag("non-existent-tag", false);
         assert!(result.is_err());
 
         // Test create tag with invalid target
-        let config = TagCreateConfig {
-            name: "invalid-target".to_string(),
-            message: None,
-            target: Some("invalid-sha".to_string()),
-            annotated: false,
-            force: false,
-        };
-
-        let result = manager.create_tag(config);
+        let result =
+            manager.create_tag("invalid-target", "invalid-sha", TagCreateConfig::default());
         assert!(result.is_err());
 
         Ok(())
@@ -1295,29 +1208,136 @@ mod tests {
     #[test]
     fn test_tag_manager_isolation() -> Result<()> {
         let (_temp_dir, repo_path) = create_test_repo()?;
-        create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
 
-        let repo = Repository::open(&repo_path)?;
-        let mut manager1 = TagManager::new(repo.clone())?;
-        let mut manager2 = TagManager::new(repo)?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager1 = TagManager::new(&repo)?;
+        let manager2 = TagManager::new(&repo)?;
 
         // Test that managers are isolated
         assert_eq!(manager1.operation_history.len(), 0);
         assert_eq!(manager2.operation_history.len(), 0);
 
         // Operations on one manager shouldn't affect the other's history
+        manager1.create_tag("test-tag", &commit_sha, TagCreateConfig::default())?;
+
+        assert!(!manager1.operation_history.is_empty());
+        assert_eq!(manager2.operation_history.len(), 0);
+
+        Ok(())
+    }
+
+    /// Configure SSH signing with a freshly generated key, if ssh-keygen is available
+    fn configure_ssh_signing(repo_path: &Path) -> Result<bool> {
+        let key_path = repo_path.join(".git").join("signing_key");
+        let keygen = Command::new("ssh-keygen")
+            .args([
+                "-q",
+                "-t",
+                "ed25519",
+                "-N",
+                "",
+                "-C",
+                "test@example.com",
+                "-f",
+            ])
+            .arg(&key_path)
+            .output();
+        if !matches!(keygen, Ok(ref output) if output.status.success()) {
+            return Ok(false);
+        }
+
+        let public_key = fs::read_to_string(key_path.with_extension("pub"))?;
+        let allowed_signers = repo_path.join(".git").join("allowed_signers");
+        fs::write(
+            &allowed_signers,
+            format!("test@example.com {}", public_key.trim()),
+        )?;
+
+        for (key, value) in [
+            ("gpg.format", "ssh".to_string()),
+            ("user.signingkey", key_path.to_string_lossy().to_string()),
+            (
+                "gpg.ssh.allowedSignersFile",
+                allowed_signers.to_string_lossy().to_string(),
+            ),
+        ] {
+            Command::new("git")
+                .args(["config", key, &value])
+                .current_dir(repo_path)
+                .output()?;
+        }
+
+        Ok(true)
+    }
+
+    #[test]
+    fn test_signed_tag_creation() -> Result<()> {
+        let (_temp_dir, repo_path) = create_test_repo()?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        if !configure_ssh_signing(&repo_path)? {
+            return Ok(());
+        }
+
+        let git_repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&git_repo)?;
+
         let config = TagCreateConfig {
-            name: "test-tag".to_string(),
-            message: None,
-            target: None,
-            annotated: false,
-            force: false,
+            tag_type: TagType::Lightweight,
+            message: Some("Signed release".to_string()),
+            sign_tag: true,
+            ..TagCreateConfig::default()
         };
+        let result = manager.create_tag("v1.0.0", &commit_sha, config)?;
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.tag_type, TagType::Annotated);
 
-        manager1.create_tag(config)?;
+        let info = manager.get_tag_info("v1.0.0")?;
+        assert!(info.is_signed);
+        assert_eq!(info.target_oid, commit_sha);
 
-        assert!(manager1.operation_history.len() > 0);
-        assert_eq!(manager2.operation_history.len(), 0);
+        let verification = git_repo.verify_tag_signature("v1.0.0")?;
+        assert_eq!(verification.status, crate::git::SignatureStatus::Good);
+
+        let verify = Command::new("git")
+            .args(["verify-tag", "v1.0.0"])
+            .current_dir(&repo_path)
+            .output()?;
+        assert!(verify.status.success());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signed_tag_creation_without_key() -> Result<()> {
+        let (_temp_dir, repo_path) = create_test_repo()?;
+        let commit_sha = create_test_commit(&repo_path, "test.txt", "content", "Initial commit")?;
+        Command::new("git")
+            .args(["config", "gpg.format", "ssh"])
+            .current_dir(&repo_path)
+            .output()?;
+        Command::new("git")
+            .args(["config", "tag.gpgSign", "true"])
+            .current_dir(&repo_path)
+            .output()?;
+
+        let git_repo = GitRepository::open(&repo_path)?;
+        let mut manager = TagManager::new(&git_repo)?;
+
+        let config = TagCreateConfig {
+            tag_type: TagType::Annotated,
+            message: Some("Release".to_string()),
+            ..TagCreateConfig::default()
+        };
+        let result = manager.create_tag("v1.0.0", &commit_sha, config)?;
+        assert!(!result.success);
+        assert!(result.message.contains("user.signingkey"));
+        assert!(
+            git_repo
+                .get_repository()
+                .find_reference("refs/tags/v1.0.0")
+                .is_err()
+        );
 
         Ok(())
     }
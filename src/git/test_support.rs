@@ -0,0 +1,31 @@
+use anyhow::{Result, anyhow};
+use std::path::Path;
+use std::process::Command;
+
+/// Run git in `repo_path` and return its trimmed standard output
+pub(crate) fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    git_with_env(repo_path, args, &[])
+}
+
+/// Run git with extra environment variables, e.g. fixed identities or dates
+pub(crate) fn git_with_env(
+    repo_path: &Path,
+    args: &[&str],
+    env: &[(&str, &str)],
+) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .envs(env.iter().copied())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
@@ -0,0 +1,587 @@
+use crate::models::{DiffStatus, GitDiff, GitDiffLine, GitDiffStats, GitHunk};
+
+/// Hunk ranges from an `@@ -old_start,old_lines +new_start,new_lines @@` line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HunkRange {
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+}
+
+/// Old and new path named by a diff header; `None` for /dev/null
+type PathPair = (Option<String>, Option<String>);
+
+/// Start line and line count of one side of a hunk
+type LineSpan = (u32, u32);
+
+/// Parse unified diff text, such as a `.patch` file, `git diff` output or a
+/// mailed patch, into the same types a repository diff produces.
+///
+/// No repository is needed. Text around the diffs (mail headers, commit
+/// messages, signatures) is skipped; hunks end where their header's line
+/// counts say, so trailing text is never taken for context.
+#[must_use]
+pub fn parse_unified_diff(text: &str) -> Vec<GitDiff> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut diffs: Vec<GitDiff> = Vec::new();
+    let mut in_git_header = false; // Between "diff --git" and the first hunk
+
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let (old_file, new_file) = split_git_header_paths(rest);
+            diffs.push(new_file_diff(old_file, new_file));
+            in_git_header = true;
+        } else if line.starts_with("--- ")
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.starts_with("+++ "))
+        {
+            // Plain `diff -u` output has no "diff --git" line to start a file
+            if !in_git_header {
+                diffs.push(new_file_diff(None, None));
+            }
+            if let Some(diff) = diffs.last_mut() {
+                let old_path = header_path(&line[4..]);
+                let new_path = header_path(&lines[index + 1][4..]);
+                apply_header_paths(diff, old_path, new_path);
+            }
+            in_git_header = false;
+            index += 2;
+            continue;
+        } else if line.starts_with("@@ ") {
+            if let (Some(diff), Some(range)) = (diffs.last_mut(), parse_hunk_header(line)) {
+                index = parse_hunk(&lines, index + 1, range, diff);
+                in_git_header = false;
+                continue;
+            }
+        } else if in_git_header {
+            if let Some(diff) = diffs.last_mut() {
+                parse_extended_header(line, diff);
+            }
+        }
+
+        index += 1;
+    }
+
+    diffs
+}
+
+const fn new_file_diff(old_file: Option<String>, new_file: Option<String>) -> GitDiff {
+    GitDiff {
+        old_file,
+        new_file,
+        hunks: Vec::new(),
+        stats: GitDiffStats {
+            files_changed: 1,
+            insertions: 0,
+            deletions: 0,
+        },
+        is_binary: false,
+        status: DiffStatus::Modified,
+        similarity: None,
+        submodule: None,
+    }
+}
+
+/// Lines between "diff --git" and the first hunk: modes, renames, binaries
+fn parse_extended_header(line: &str, diff: &mut GitDiff) {
+    if line.starts_with("new file mode ") {
+        diff.status = DiffStatus::Added;
+    } else if line.starts_with("deleted file mode ") {
+        diff.status = DiffStatus::Deleted;
+    } else if let Some(path) = line.strip_prefix("rename from ") {
+        diff.status = DiffStatus::Renamed;
+        diff.old_file = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("rename to ") {
+        diff.status = DiffStatus::Renamed;
+        diff.new_file = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("copy from ") {
+        diff.status = DiffStatus::Copied;
+        diff.old_file = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("copy to ") {
+        diff.status = DiffStatus::Copied;
+        diff.new_file = Some(unquote(path));
+    } else if let Some(percent) = line
+        .strip_prefix("similarity index ")
+        .and_then(|rest| rest.strip_suffix('%'))
+    {
+        diff.similarity = percent.parse().ok();
+    } else if line == "GIT binary patch"
+        || (line.starts_with("Binary files ") && line.ends_with(" differ"))
+    {
+        diff.is_binary = true;
+    }
+}
+
+/// Take the paths from the "--- " and "+++ " lines; /dev/null marks an
+/// added or deleted file, which then uses the other path for both sides
+fn apply_header_paths(diff: &mut GitDiff, old_path: Option<String>, new_path: Option<String>) {
+    // git's a/ and b/ prefixes, unless the patch was made without them
+    let prefixed = old_path.as_ref().is_none_or(|path| path.starts_with("a/"))
+        && new_path.as_ref().is_none_or(|path| path.starts_with("b/"))
+        && (old_path.is_some() || new_path.is_some());
+    let strip = |path: Option<String>| {
+        if prefixed {
+            path.map(|path| path[2..].to_string())
+        } else {
+            path
+        }
+    };
+
+    match (strip(old_path), strip(new_path)) {
+        (None, Some(new_path)) => {
+            diff.status = DiffStatus::Added;
+            diff.old_file = Some(new_path.clone());
+            diff.new_file = Some(new_path);
+        }
+        (Some(old_path), None) => {
+            diff.status = DiffStatus::Deleted;
+            diff.old_file = Some(old_path.clone());
+            diff.new_file = Some(old_path);
+        }
+        (Some(old_path), Some(new_path)) => {
+            diff.old_file = Some(old_path);
+            diff.new_file = Some(new_path);
+        }
+        (None, None) => {}
+    }
+}
+
+/// The path on a "--- " or "+++ " line, without the timestamp `diff -u`
+/// appends after a tab; None for /dev/null
+fn header_path(text: &str) -> Option<String> {
+    let path = text.split('\t').next().unwrap_or(text).trim_end();
+    if path == "/dev/null" {
+        None
+    } else {
+        Some(unquote(path))
+    }
+}
+
+/// Old and new paths from the rest of a "diff --git a/old b/new" line
+fn split_git_header_paths(rest: &str) -> PathPair {
+    let (old_path, new_path) = if rest.starts_with('"') || rest.ends_with('"') {
+        let (old_path, remainder) = take_path_token(rest);
+        let (new_path, _) = take_path_token(remainder.trim_start());
+        (old_path, new_path)
+    } else {
+        // Both sides are usually the same path, which may contain " b/"
+        let half = rest.len() / 2;
+        match (rest.get(..half), rest.get(half + 1..)) {
+            (Some(old_path), Some(new_path))
+                if rest.as_bytes()[half] == b' ' && old_path.get(2..) == new_path.get(2..) =>
+            {
+                (old_path.to_string(), new_path.to_string())
+            }
+            _ => match rest.split_once(" b/") {
+                Some((old_path, new_path)) => (old_path.to_string(), format!("b/{new_path}")),
+                None => return (None, None),
+            },
+        }
+    };
+
+    (
+        old_path.strip_prefix("a/").map(str::to_string),
+        new_path.strip_prefix("b/").map(str::to_string),
+    )
+}
+
+/// One path from the start of `text`, quoted or up to the next space
+fn take_path_token(text: &str) -> (String, &str) {
+    if text.starts_with('"') {
+        let mut escaped = false;
+        for (i, ch) in text.char_indices().skip(1) {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                return (unquote(&text[..=i]), &text[i + 1..]);
+            }
+        }
+        (unquote(text), "")
+    } else {
+        match text.split_once(' ') {
+            Some((token, remainder)) => (token.to_string(), remainder),
+            None => (text.to_string(), ""),
+        }
+    }
+}
+
+/// Undo git's C-style quoting of paths with special or non-ASCII characters
+fn unquote(token: &str) -> String {
+    let Some(inner) = token
+        .strip_prefix('"')
+        .and_then(|token| token.strip_suffix('"'))
+    else {
+        return token.to_string();
+    };
+
+    let input = inner.as_bytes();
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] != b'\\' || i + 1 == input.len() {
+            bytes.push(input[i]);
+            i += 1;
+            continue;
+        }
+
+        // Octal escapes carry the raw bytes of UTF-8 names
+        let octal = input
+            .get(i + 1..i + 4)
+            .filter(|digits| digits.iter().all(|digit| (b'0'..=b'7').contains(digit)))
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+        if let Some(byte) = octal {
+            bytes.push(byte);
+            i += 4;
+            continue;
+        }
+
+        bytes.push(match input[i + 1] {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 0x0b,
+            other => other,
+        });
+        i += 2;
+    }
+
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+fn parse_hunk_header(line: &str) -> Option<HunkRange> {
+    let ranges = line.strip_prefix("@@ -")?;
+    let end = ranges.find(" @@")?;
+    let (old_range, new_range) = ranges[..end].split_once(" +")?;
+    let (old_start, old_lines) = parse_range(old_range)?;
+    let (new_start, new_lines) = parse_range(new_range)?;
+
+    Some(HunkRange {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+    })
+}
+
+/// "start,count", where a missing count means one line
+fn parse_range(range: &str) -> Option<LineSpan> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Read a hunk's lines and add it to `diff`; returns the index of the first
+/// line after the hunk
+fn parse_hunk(lines: &[&str], mut index: usize, range: HunkRange, diff: &mut GitDiff) -> usize {
+    let mut old_remaining = range.old_lines;
+    let mut new_remaining = range.new_lines;
+    let mut old_lineno = range.old_start;
+    let mut new_lineno = range.new_start;
+    let mut hunk_lines: Vec<GitDiffLine> = Vec::new();
+
+    while let Some(&line) = lines.get(index) {
+        let origin = line.chars().next().unwrap_or(' ');
+        let content = format!("{}\n", line.get(1..).unwrap_or(""));
+
+        match origin {
+            // Blank context lines sometimes lose their leading space in mail
+            ' ' if old_remaining > 0 && new_remaining > 0 => {
+                hunk_lines.push(GitDiffLine {
+                    origin,
+                    content,
+                    old_lineno: Some(old_lineno),
+                    new_lineno: Some(new_lineno),
+                });
+                old_lineno += 1;
+                new_lineno += 1;
+                old_remaining -= 1;
+                new_remaining -= 1;
+            }
+            '-' if old_remaining > 0 => {
+                hunk_lines.push(GitDiffLine {
+                    origin,
+                    content,
+                    old_lineno: Some(old_lineno),
+                    new_lineno: None,
+                });
+                diff.stats.deletions += 1;
+                old_lineno += 1;
+                old_remaining -= 1;
+            }
+            '+' if new_remaining > 0 => {
+                hunk_lines.push(GitDiffLine {
+                    origin,
+                    content,
+                    old_lineno: None,
+                    new_lineno: Some(new_lineno),
+                });
+                diff.stats.insertions += 1;
+                new_lineno += 1;
+                new_remaining -= 1;
+            }
+            // "\ No newline at end of file" belongs to the line before it
+            '\\' if !hunk_lines.is_empty() => {
+                let previous = hunk_lines.last_mut().expect("checked above");
+                if previous.content.ends_with('\n') {
+                    previous.content.pop();
+                }
+                let origin = match previous.origin {
+                    '+' => '>',
+                    '-' => '<',
+                    _ => '=',
+                };
+                hunk_lines.push(GitDiffLine {
+                    origin,
+                    content: format!("{line}\n"),
+                    old_lineno: None,
+                    new_lineno: None,
+                });
+            }
+            _ => break,
+        }
+        index += 1;
+
+        // Past the counted lines only a no-newline marker can still follow
+        if old_remaining == 0
+            && new_remaining == 0
+            && !lines.get(index).is_some_and(|next| next.starts_with('\\'))
+        {
+            break;
+        }
+    }
+
+    diff.hunks.push(GitHunk {
+        old_start: range.old_start,
+        old_lines: range.old_lines,
+        new_start: range.new_start,
+        new_lines: range.new_lines,
+        header: format!(
+            "@@ -{},{} +{},{} @@",
+            range.old_start, range.old_lines, range.new_start, range.new_lines
+        ),
+        lines: hunk_lines,
+    });
+
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::collect_git_diffs;
+    use crate::git::test_support::git;
+    use anyhow::Result;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count)
+            .map(|number| format!("line {}\n", number))
+            .collect()
+    }
+
+    /// Content lines only; libgit2 and we may differ on the no-newline marker
+    fn content_lines(hunk: &GitHunk) -> Vec<(char, String, Option<u32>, Option<u32>)> {
+        hunk.lines
+            .iter()
+            .filter(|line| matches!(line.origin, '+' | '-' | ' '))
+            .map(|line| {
+                (
+                    line.origin,
+                    line.content.trim_end_matches('\n').to_string(),
+                    line.old_lineno,
+                    line.new_lineno,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_libgit2_on_git_diff_output() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        fs::write(path.join("lines.txt"), numbered_lines(20))?;
+        fs::write(path.join("removed.txt"), "going away\n")?;
+        fs::write(path.join("noeol.txt"), "first\nlast")?;
+        fs::write(path.join("ñame.txt"), "before\n")?;
+        fs::write(path.join("old.txt"), numbered_lines(10))?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Initial commit"])?;
+
+        let lines = numbered_lines(20)
+            .replace("line 2\n", "line two\n")
+            .replace("line 17\n", "line 17\nextra\n");
+        fs::write(path.join("lines.txt"), lines)?;
+        fs::remove_file(path.join("removed.txt"))?;
+        fs::write(path.join("added.txt"), "new\nfile\n")?;
+        fs::write(path.join("noeol.txt"), "first\nchanged")?;
+        fs::write(path.join("ñame.txt"), "after\n")?;
+        fs::remove_file(path.join("old.txt"))?;
+        fs::write(
+            path.join("moved.txt"),
+            numbered_lines(10).replace("line 5\n", "line five\n"),
+        )?;
+        git(path, &["add", "-A"])?;
+        git(path, &["commit", "-m", "Change everything"])?;
+
+        let text = git(path, &["diff", "-M", "HEAD~1", "HEAD"])? + "\n";
+        let ours = parse_unified_diff(&text);
+        let theirs = collect_git_diffs(&git2::Diff::from_buffer(text.as_bytes())?)?;
+
+        assert_eq!(ours.len(), 6);
+        assert_eq!(ours.len(), theirs.len());
+        for (ours, theirs) in ours.iter().zip(&theirs) {
+            assert_eq!(ours.old_file, theirs.old_file);
+            assert_eq!(ours.new_file, theirs.new_file);
+            assert_eq!(ours.status, theirs.status, "{:?}", ours.new_file);
+            assert_eq!(ours.stats.insertions, theirs.stats.insertions);
+            assert_eq!(ours.stats.deletions, theirs.stats.deletions);
+            assert_eq!(ours.hunks.len(), theirs.hunks.len());
+            for (our_hunk, their_hunk) in ours.hunks.iter().zip(&theirs.hunks) {
+                assert_eq!(our_hunk.header, their_hunk.header);
+                assert_eq!(content_lines(our_hunk), content_lines(their_hunk));
+            }
+        }
+
+        let lines = ours
+            .iter()
+            .find(|diff| diff.new_file.as_deref() == Some("lines.txt"))
+            .expect("lines.txt in diff");
+        assert_eq!(lines.hunks.len(), 2);
+        assert!(
+            ours.iter()
+                .any(|diff| diff.new_file.as_deref() == Some("ñame.txt"))
+        );
+        let moved = ours
+            .iter()
+            .find(|diff| diff.status == DiffStatus::Renamed)
+            .expect("rename in diff");
+        assert_eq!(moved.old_file.as_deref(), Some("old.txt"));
+        assert_eq!(moved.new_file.as_deref(), Some("moved.txt"));
+        assert!(moved.similarity.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_plain_diff_without_prefixes() {
+        let text = "\
+--- src/main.c.orig\t2024-05-01 10:00:00.000000000 +0200
++++ src/main.c\t2024-05-01 10:05:00.000000000 +0200
+@@ -1,4 +1,4 @@
+ #include <stdio.h>
+
+-int main() {
++int main(void) {
+     return 0;
+";
+        let diffs = parse_unified_diff(text);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].old_file.as_deref(), Some("src/main.c.orig"));
+        assert_eq!(diffs[0].new_file.as_deref(), Some("src/main.c"));
+        assert_eq!(diffs[0].status, DiffStatus::Modified);
+
+        let hunk = &diffs[0].hunks[0];
+        assert_eq!(hunk.lines.len(), 5);
+        assert_eq!(hunk.lines[1].content, "\n"); // Blank context line without its space
+        assert_eq!(hunk.lines[3].origin, '+');
+        assert_eq!(hunk.lines[3].new_lineno, Some(3));
+        assert_eq!(hunk.lines[4].old_lineno, Some(4));
+        assert_eq!(diffs[0].stats.insertions, 1);
+        assert_eq!(diffs[0].stats.deletions, 1);
+    }
+
+    #[test]
+    fn test_mail_text_around_patch_is_skipped() {
+        let text = "\
+From 1234567890abcdef Mon Sep 17 00:00:00 2001
+From: Test User <test@example.com>
+Subject: [PATCH] Shorten greeting
+
+--- a/list of changes
+---
+ greeting.txt | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/greeting.txt b/greeting.txt
+index 3b18e51..cd08755 100644
+--- a/greeting.txt
++++ b/greeting.txt
+@@ -1 +1 @@
+-hello world
++hello
+-- 
+2.43.0
+
+";
+        let diffs = parse_unified_diff(text);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].new_file.as_deref(), Some("greeting.txt"));
+        assert_eq!(diffs[0].hunks.len(), 1);
+        assert_eq!(diffs[0].hunks[0].header, "@@ -1,1 +1,1 @@");
+        assert_eq!(diffs[0].hunks[0].lines.len(), 2);
+        assert_eq!(diffs[0].stats.deletions, 1);
+    }
+
+    #[test]
+    fn test_no_newline_marker() {
+        let text = "\
+diff --git a/end.txt b/end.txt
+--- a/end.txt
++++ b/end.txt
+@@ -1,2 +1,2 @@
+ first
+-last
+\\ No newline at end of file
++last
+";
+        let hunk = &parse_unified_diff(text)[0].hunks[0];
+        let origins: Vec<char> = hunk.lines.iter().map(|line| line.origin).collect();
+        assert_eq!(origins, vec![' ', '-', '<', '+']);
+        assert_eq!(hunk.lines[1].content, "last");
+        assert_eq!(hunk.lines[3].content, "last\n");
+    }
+
+    #[test]
+    fn test_git_headers_without_hunks() {
+        let text = "\
+diff --git a/logo.png b/logo.png
+new file mode 100644
+index 0000000..3f2a1b4
+Binary files /dev/null and b/logo.png differ
+diff --git \"a/\\303\\261o space.txt\" \"b/\\303\\261o space.txt\"
+deleted file mode 100644
+index e69de29..0000000
+diff --git a/with b/inside.txt b/with b/inside.txt
+old mode 100644
+new mode 100755
+";
+        let diffs = parse_unified_diff(text);
+        assert_eq!(diffs.len(), 3);
+
+        assert_eq!(diffs[0].status, DiffStatus::Added);
+        assert!(diffs[0].is_binary);
+        assert_eq!(diffs[0].new_file.as_deref(), Some("logo.png"));
+
+        assert_eq!(diffs[1].status, DiffStatus::Deleted);
+        assert_eq!(diffs[1].old_file.as_deref(), Some("ño space.txt"));
+
+        assert_eq!(diffs[2].status, DiffStatus::Modified);
+        assert_eq!(diffs[2].new_file.as_deref(), Some("with b/inside.txt"));
+        assert!(diffs[2].hunks.is_empty());
+    }
+}
@@ -1,9 +1,16 @@
-use crate::git::GitRepository;
+use crate::git::{CommitQuery, CommitScan, DateRange, GitRepository, QueryContext, ScanEvent};
 use crate::models::GitCommit;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Incremental output of a background view refresh
+pub type ViewUpdateEvent = ScanEvent<GitCommit>;
+
+/// A view refresh running on a background thread, for queries that diff
+/// commits or look up refs
+pub type ViewUpdate = CommitScan<GitCommit>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ViewFilter {
     pub name: String,
@@ -19,6 +26,8 @@ pub struct ViewFilter {
     pub include_merges: bool,
     pub case_sensitive: bool,
     pub use_regex: bool,
+    #[serde(default)]
+    pub query: Option<String>, // Commit query applied after the fields above
 }
 
 impl Default for ViewFilter {
@@ -37,6 +46,7 @@ impl Default for ViewFilter {
             include_merges: true,
             case_sensitive: false,
             use_regex: false,
+            query: None,
         }
     }
 }
@@ -50,8 +60,38 @@ impl ViewFilter {
         }
     }
 
-    /// Check if a commit matches this filter
-    pub fn matches_commit(&self, commit: &GitCommit) -> bool {
+    /// A view showing the commits matching a search query
+    #[must_use]
+    pub fn from_query(name: &str, query: &CommitQuery) -> Self {
+        Self {
+            name: name.to_string(),
+            description: format!("Query: {}", query.source()),
+            query: Some(query.source().to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// The parsed `query`, if the filter has one
+    pub fn parse_query(&self) -> Result<Option<CommitQuery>> {
+        match self.query {
+            Some(ref query) => Ok(Some(CommitQuery::parse(query)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `date_from`/`date_to` parsed as git approxidates relative to now
+    pub fn date_range(&self) -> Result<DateRange> {
+        DateRange::parse(
+            self.date_from.as_deref(),
+            self.date_to.as_deref(),
+            chrono::Utc::now(),
+        )
+    }
+
+    /// Check if a commit matches this filter, with `range` the resolved
+    /// `date_range()` so it's parsed once per refresh rather than per commit
+    #[must_use]
+    pub fn matches_commit(&self, commit: &GitCommit, range: &DateRange) -> bool {
         // Author filter
         if let Some(ref author_filter) = self.author_filter {
             if !self.text_matches(&commit.author.name, author_filter)
@@ -82,7 +122,11 @@ impl ViewFilter {
             return false;
         }
 
-        // Date filters would be implemented here
+        // Date filters
+        if !range.contains(commit.committer.when) {
+            return false;
+        }
+
         // File filters would require additional commit analysis
 
         true
@@ -178,7 +222,21 @@ impl GitView {
         }
     }
 
+    /// Whether the view's query needs the repository per commit, in which
+    /// case `ViewManager` refreshes it with `start_update` instead
+    #[must_use]
+    pub fn needs_background_update(&self) -> bool {
+        matches!(self.filter.parse_query(), Ok(Some(ref query)) if query.needs_repository())
+    }
+
     pub fn update_commits(&mut self, repo: &GitRepository) -> Result<()> {
+        let range = self.filter.date_range()?;
+        let query = self.filter.parse_query()?;
+        let context = match query {
+            Some(ref query) if query.needs_repository() => QueryContext::from_repository(repo)?,
+            _ => QueryContext::empty(),
+        };
+
         self.is_loading = true;
 
         // Use git rev-list with filter arguments for efficient filtering
@@ -190,7 +248,12 @@ impl GitView {
                 // Apply additional filters that git can't handle
                 self.commits = commits
                     .into_iter()
-                    .filter(|commit| self.filter.matches_commit(commit))
+                    .filter(|commit| self.filter.matches_commit(commit, &range))
+                    .filter(|commit| {
+                        query
+                            .as_ref()
+                            .is_none_or(|query| query.matches(commit, &context))
+                    })
                     .collect();
 
                 self.last_updated = Some(std::time::SystemTime::now());
@@ -208,6 +271,56 @@ impl GitView {
         self.update_commits(repo)
     }
 
+    /// Clear the view and refill it on a background thread; the caller
+    /// feeds the job's events back through `apply_update_event`
+    pub fn start_update(&mut self, repo: &GitRepository) -> Result<ViewUpdate> {
+        let range = self.filter.date_range()?;
+        let query = self.filter.parse_query()?;
+        let filter = self.filter.clone();
+        let repo_path = repo.repo().path().to_path_buf();
+
+        self.commits.clear();
+        self.is_loading = true;
+        CommitScan::start("view-update", (), None, move |scan| {
+            let repo = GitRepository::open(&repo_path)?;
+            let context = QueryContext::from_repository(&repo)?;
+            let args = filter.to_git_args();
+            let git_args: Vec<&str> = args.iter().map(std::string::String::as_str).collect();
+
+            for commit in repo.get_commits_from_git_args(&git_args)? {
+                if !scan.next_commit() {
+                    break;
+                }
+
+                let matches = filter.matches_commit(&commit, &range)
+                    && query
+                        .as_ref()
+                        .is_none_or(|query| query.matches(&commit, &context));
+                if matches && !scan.report(commit) {
+                    break;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Apply one event of a job from `start_update`
+    pub fn apply_update_event(&mut self, event: ViewUpdateEvent) -> Result<()> {
+        match event {
+            ViewUpdateEvent::Item(commit) => self.commits.push(commit),
+            ViewUpdateEvent::Progress { .. } => {}
+            ViewUpdateEvent::Finished { .. } => {
+                self.last_updated = Some(std::time::SystemTime::now());
+                self.is_loading = false;
+            }
+            ViewUpdateEvent::Failed(e) => {
+                self.is_loading = false;
+                return Err(anyhow::anyhow!(e));
+            }
+        }
+        Ok(())
+    }
+
     pub fn is_stale(&self, max_age: std::time::Duration) -> bool {
         match self.last_updated {
             Some(last_updated) => {
@@ -224,6 +337,7 @@ impl GitView {
 #[derive(Debug)]
 pub struct ViewManager {
     views: HashMap<String, GitView>,
+    updates: HashMap<String, ViewUpdate>, // Background refreshes by view name
     current_view: String,
     default_view: String,
 }
@@ -239,6 +353,7 @@ impl ViewManager {
 
         Self {
             views,
+            updates: HashMap::new(),
             current_view: default_name.clone(),
             default_view: default_name,
         }
@@ -259,6 +374,7 @@ impl ViewManager {
         }
 
         self.views.remove(name);
+        self.updates.remove(name);
         Ok(())
     }
 
@@ -296,19 +412,53 @@ impl ViewManager {
     }
 
     pub fn update_current_view(&mut self, repo: &GitRepository) -> Result<()> {
-        if let Some(view) = self.get_current_view_mut() {
-            view.update_commits(repo)
+        if self.views.contains_key(&self.current_view) {
+            let name = self.current_view.clone();
+            self.refresh_view(&name, repo)
         } else {
             Err(anyhow::anyhow!("No current view"))
         }
     }
 
+    /// Refresh a view, on a background thread when its query needs the
+    /// repository; `poll_updates` collects the results
     pub fn refresh_view(&mut self, name: &str, repo: &GitRepository) -> Result<()> {
-        if let Some(view) = self.get_view_mut(name) {
-            view.refresh(repo)
+        let Some(view) = self.views.get_mut(name) else {
+            return Err(anyhow::anyhow!("View '{name}' not found"));
+        };
+
+        self.updates.remove(name);
+        if view.needs_background_update() {
+            let update = view.start_update(repo)?;
+            self.updates.insert(name.to_string(), update);
+            Ok(())
         } else {
-            Err(anyhow::anyhow!("View '{}' not found", name))
+            view.refresh(repo)
+        }
+    }
+
+    /// Move results of background refreshes into their views, returning
+    /// whether anything changed
+    pub fn poll_updates(&mut self) -> bool {
+        let mut progress_made = false;
+        for (name, update) in &mut self.updates {
+            let Some(view) = self.views.get_mut(name) else {
+                continue;
+            };
+            while let Some(event) = update.try_next() {
+                progress_made = true;
+                if let Err(e) = view.apply_update_event(event) {
+                    tracing::warn!("Failed to refresh view '{}': {}", name, e);
+                }
+            }
         }
+        self.updates.retain(|_, update| !update.is_complete());
+        progress_made
+    }
+
+    #[must_use]
+    pub fn is_updating(&self) -> bool {
+        !self.updates.is_empty()
     }
 
     pub fn refresh_all_views(&mut self, repo: &GitRepository) -> Result<()> {
@@ -425,3 +575,64 @@ impl ViewPreset {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_path_query_views_refresh_in_background() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init", "-b", "main"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+
+        fs::create_dir_all(path.join("src"))?;
+        fs::write(path.join("src/lib.rs"), "// lib")?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Add lib"])?;
+        fs::write(path.join("README.md"), "readme")?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Add readme"])?;
+
+        let repo = GitRepository::open(path)?;
+        let mut manager = ViewManager::new();
+        let query = CommitQuery::parse("path:src")?;
+        manager.add_view(
+            "Sources".to_string(),
+            ViewFilter::from_query("Sources", &query),
+        );
+        manager.switch_view("Sources")?;
+        manager.update_current_view(&repo)?;
+        assert!(manager.is_updating());
+        assert!(manager.get_current_view().unwrap().is_loading);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while manager.is_updating() && Instant::now() < deadline {
+            if !manager.poll_updates() {
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+        assert!(!manager.is_updating());
+
+        let view = manager.get_current_view().unwrap();
+        assert!(!view.is_loading);
+        assert!(view.last_updated.is_some());
+        let summaries: Vec<&str> = view.commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["Add lib"]);
+
+        // Views without repository queries still refresh in place
+        manager.switch_view("Default")?;
+        manager.update_current_view(&repo)?;
+        assert!(!manager.is_updating());
+        assert_eq!(manager.get_current_view().unwrap().commits.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+use crate::git::GitRepository;
+use anyhow::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Quiet period after the last change before a refresh is reported
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(400);
+/// Report anyway once changes have been pending this long, e.g. during a build
+const WATCH_MAX_DELAY: Duration = Duration::from_secs(3);
+
+/// What changed on disk since the last refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatchChanges {
+    pub refs: bool,     // HEAD, refs/ or packed-refs
+    pub index: bool,    // The staging area
+    pub worktree: bool, // Tracked or untracked, non-ignored files
+}
+
+impl WatchChanges {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub const fn merge(&mut self, other: Self) {
+        self.refs |= other.refs;
+        self.index |= other.index;
+        self.worktree |= other.worktree;
+    }
+}
+
+/// Watches a repository's refs, index and worktree through the platform's
+/// file notification API (inotify on Linux) and reports debounced changes.
+pub struct RepositoryWatcher {
+    watcher: RecommendedWatcher, // Dropping it stops the notifications
+    events: Receiver<notify::Result<Event>>,
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+    workdir: Option<PathBuf>,
+    pending: WatchChanges,
+    first_change: Option<Instant>,
+    last_change: Option<Instant>,
+    debounce: Duration,
+}
+
+impl RepositoryWatcher {
+    pub fn new(repo: &GitRepository) -> Result<Self> {
+        Self::with_debounce(repo, WATCH_DEBOUNCE)
+    }
+
+    pub fn with_debounce(repo: &GitRepository, debounce: Duration) -> Result<Self> {
+        let git_dir = repo.repo().path().to_path_buf();
+        let common_dir = repo.repo().commondir().to_path_buf();
+        let workdir = repo.repo().workdir().map(Path::to_path_buf);
+
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+
+        // The git dirs are watched non-recursively for HEAD, index and
+        // packed-refs, which git replaces by renaming a lock file over them,
+        // and refs/ recursively; objects/ and its many writes are left out
+        watcher.watch(&git_dir, RecursiveMode::NonRecursive)?;
+        if common_dir != git_dir {
+            watcher.watch(&common_dir, RecursiveMode::NonRecursive)?;
+        }
+        watcher.watch(&common_dir.join("refs"), RecursiveMode::Recursive)?;
+        if let Some(ref workdir) = workdir {
+            // Large trees can exhaust the watch limit; refs still refresh then
+            if let Err(e) = watch_worktree(&mut watcher, workdir, repo) {
+                warn!("Not watching worktree {}: {}", workdir.display(), e);
+            }
+        }
+
+        debug!("Watching repository at {}", git_dir.display());
+        Ok(Self {
+            watcher,
+            events,
+            git_dir,
+            common_dir,
+            workdir,
+            pending: WatchChanges::default(),
+            first_change: None,
+            last_change: None,
+            debounce,
+        })
+    }
+
+    /// Drain pending notifications and return the accumulated changes once
+    /// the repository has been quiet for the debounce period
+    pub fn poll(&mut self, repo: &GitRepository) -> Option<WatchChanges> {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                // Reads, including our own, never change anything
+                Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+                Ok(event) => {
+                    if matches!(event.kind, EventKind::Create(_)) {
+                        for path in &event.paths {
+                            self.watch_new_directory(path, repo);
+                        }
+                    }
+                    for path in &event.paths {
+                        let change = self.classify(path, repo);
+                        if !change.is_empty() {
+                            self.pending.merge(change);
+                            self.first_change.get_or_insert_with(Instant::now);
+                            self.last_change = Some(Instant::now());
+                        }
+                    }
+                }
+                Err(e) => warn!("File watcher error: {}", e),
+            }
+        }
+
+        let (Some(first), Some(last)) = (self.first_change, self.last_change) else {
+            return None;
+        };
+        if last.elapsed() < self.debounce && first.elapsed() < WATCH_MAX_DELAY {
+            return None;
+        }
+
+        self.first_change = None;
+        self.last_change = None;
+        Some(std::mem::take(&mut self.pending))
+    }
+
+    /// Whether changes are waiting out the debounce period
+    #[must_use]
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Start watching a directory created at the top of the worktree, which
+    /// the non-recursive watch on the worktree root only reports
+    fn watch_new_directory(&mut self, path: &Path, repo: &GitRepository) {
+        let Some(ref workdir) = self.workdir else {
+            return;
+        };
+        if path.parent() != Some(workdir.as_path()) || !is_watched_worktree_dir(path, workdir, repo)
+        {
+            return;
+        }
+        if let Err(e) = self.watcher.watch(path, RecursiveMode::Recursive) {
+            warn!("Not watching {}: {}", path.display(), e);
+        }
+    }
+
+    fn classify(&self, path: &Path, repo: &GitRepository) -> WatchChanges {
+        if let Ok(relative) = path.strip_prefix(&self.git_dir) {
+            return classify_git_path(relative);
+        }
+        if let Ok(relative) = path.strip_prefix(&self.common_dir) {
+            return classify_git_path(relative);
+        }
+
+        let Some(relative) = self
+            .workdir
+            .as_ref()
+            .and_then(|workdir| path.strip_prefix(workdir).ok())
+        else {
+            return WatchChanges::default();
+        };
+        if relative.as_os_str().is_empty() || repo.repo().is_path_ignored(relative).unwrap_or(false)
+        {
+            return WatchChanges::default();
+        }
+        WatchChanges {
+            worktree: true,
+            ..WatchChanges::default()
+        }
+    }
+}
+
+impl std::fmt::Debug for RepositoryWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RepositoryWatcher")
+            .field("git_dir", &self.git_dir)
+            .field("workdir", &self.workdir)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Watch the worktree without its .git directory: the root itself, then
+/// each top-level directory that isn't ignored
+fn watch_worktree(
+    watcher: &mut RecommendedWatcher,
+    workdir: &Path,
+    repo: &GitRepository,
+) -> Result<()> {
+    watcher.watch(workdir, RecursiveMode::NonRecursive)?;
+    for entry in std::fs::read_dir(workdir)? {
+        let path = entry?.path();
+        if is_watched_worktree_dir(&path, workdir, repo) {
+            watcher.watch(&path, RecursiveMode::Recursive)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_watched_worktree_dir(path: &Path, workdir: &Path, repo: &GitRepository) -> bool {
+    let Ok(relative) = path.strip_prefix(workdir) else {
+        return false;
+    };
+    path.is_dir()
+        && relative != Path::new(".git")
+        && !repo.repo().path().starts_with(path)
+        && !repo.repo().is_path_ignored(relative).unwrap_or(false)
+}
+
+/// Classify a path relative to the git directory. Lock files are skipped:
+/// git renames them over the real file, which is reported separately.
+#[must_use]
+pub fn classify_git_path(relative: &Path) -> WatchChanges {
+    if relative
+        .extension()
+        .is_some_and(|extension| extension == "lock")
+    {
+        return WatchChanges::default();
+    }
+
+    let components: Vec<Component> = relative.components().collect();
+    let first = components
+        .first()
+        .and_then(|component| component.as_os_str().to_str());
+    match (first, components.len()) {
+        (Some("HEAD" | "packed-refs"), 1) | (Some("refs"), _) => WatchChanges {
+            refs: true,
+            ..WatchChanges::default()
+        },
+        (Some("index"), 1) => WatchChanges {
+            index: true,
+            ..WatchChanges::default()
+        },
+        _ => WatchChanges::default(),
+    }
+}
+
+impl GitRepository {
+    /// Start watching this repository for changes made by other programs
+    pub fn watch(&self) -> Result<RepositoryWatcher> {
+        RepositoryWatcher::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_repo() -> Result<TempDir> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path();
+        git(path, &["init"])?;
+        git(path, &["config", "user.name", "Test User"])?;
+        git(path, &["config", "user.email", "test@example.com"])?;
+        fs::write(path.join("README.md"), "readme")?;
+        fs::write(path.join(".gitignore"), "target/\n")?;
+        git(path, &["add", "."])?;
+        git(path, &["commit", "-m", "Initial commit"])?;
+        Ok(temp_dir)
+    }
+
+    /// Poll until a change is reported or the timeout expires
+    fn wait_for_changes(
+        watcher: &mut RepositoryWatcher,
+        repo: &GitRepository,
+    ) -> Option<WatchChanges> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Some(changes) = watcher.poll(repo) {
+                return Some(changes);
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        None
+    }
+
+    #[test]
+    fn test_classify_git_path() {
+        assert!(classify_git_path(Path::new("HEAD")).refs);
+        assert!(classify_git_path(Path::new("packed-refs")).refs);
+        assert!(classify_git_path(Path::new("refs/heads/main")).refs);
+        assert!(classify_git_path(Path::new("index")).index);
+
+        assert!(classify_git_path(Path::new("refs/heads/main.lock")).is_empty());
+        assert!(classify_git_path(Path::new("index.lock")).is_empty());
+        assert!(classify_git_path(Path::new("objects/ab/cdef")).is_empty());
+        assert!(classify_git_path(Path::new("logs/HEAD")).is_empty());
+        assert!(classify_git_path(Path::new("worktrees/other/HEAD")).is_empty());
+    }
+
+    #[test]
+    fn test_watcher_reports_ref_and_worktree_changes() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let path = temp_dir.path();
+        let repo = GitRepository::open(path)?;
+        let mut watcher = RepositoryWatcher::with_debounce(&repo, Duration::from_millis(50))?;
+
+        git(path, &["branch", "feature"])?;
+        let changes = wait_for_changes(&mut watcher, &repo).expect("branch creation reported");
+        assert!(changes.refs);
+
+        fs::write(path.join("README.md"), "changed")?;
+        let changes = wait_for_changes(&mut watcher, &repo).expect("file edit reported");
+        assert!(changes.worktree);
+        assert!(!changes.refs);
+        Ok(())
+    }
+
+    #[test]
+    fn test_watcher_follows_new_directories_but_not_objects() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let path = temp_dir.path();
+        let repo = GitRepository::open(path)?;
+        let mut watcher = RepositoryWatcher::with_debounce(&repo, Duration::from_millis(50))?;
+
+        fs::write(path.join(".git/objects/scratch"), "not a ref")?;
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(watcher.poll(&repo), None);
+        fs::remove_file(path.join(".git/objects/scratch"))?;
+
+        fs::create_dir(path.join("src"))?;
+        let changes = wait_for_changes(&mut watcher, &repo).expect("new directory reported");
+        assert!(changes.worktree);
+
+        fs::write(path.join("src/lib.rs"), "// lib")?;
+        let changes = wait_for_changes(&mut watcher, &repo).expect("file in it reported");
+        assert!(changes.worktree);
+        Ok(())
+    }
+
+    #[test]
+    fn test_watcher_ignores_ignored_files() -> Result<()> {
+        let temp_dir = create_test_repo()?;
+        let path = temp_dir.path();
+        let repo = GitRepository::open(path)?;
+        let mut watcher = RepositoryWatcher::with_debounce(&repo, Duration::from_millis(50))?;
+
+        fs::create_dir(path.join("target"))?;
+        fs::write(path.join("target/output"), "build output")?;
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(watcher.poll(&repo), None);
+        assert!(!watcher.has_pending());
+        Ok(())
+    }
+}
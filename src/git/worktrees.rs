@@ -0,0 +1,662 @@
+use crate::git::operations::{OperationRecord, OperationType};
+use crate::git::{ErrorReporter, GitError, GitRepository, InputValidator};
+use anyhow::{Result, anyhow};
+use git2::{
+    BranchType, Repository, StatusOptions, WorktreeAddOptions, WorktreeLockStatus,
+    WorktreePruneOptions,
+};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Worktree management (add/remove/lock linked worktrees)
+pub struct WorktreeManager {
+    repo: Repository, // The main repository, even when opened from a linked worktree
+    current_workdir: Option<PathBuf>,
+    operation_history: Vec<OperationRecord>,
+}
+
+/// A working tree of the repository: the main one or a linked `git worktree`
+#[derive(Debug, Clone)]
+pub struct WorktreeInfo {
+    pub name: String, // Linked worktree name; directory name for the main worktree
+    pub path: PathBuf,
+    pub head_branch: Option<String>, // None when HEAD is detached
+    pub head_commit: Option<String>,
+    pub is_main: bool,
+    pub is_current: bool, // The worktree gitk-rs is browsing
+    pub lock: WorktreeLock,
+    pub is_prunable: bool, // Working directory is gone
+}
+
+/// Whether a worktree is protected from pruning and removal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorktreeLock {
+    Unlocked,
+    Locked(Option<String>), // With the reason given to `git worktree lock`
+}
+
+impl WorktreeInfo {
+    #[must_use]
+    pub const fn is_locked(&self) -> bool {
+        matches!(self.lock, WorktreeLock::Locked(_))
+    }
+
+    #[must_use]
+    pub fn lock_reason(&self) -> Option<&str> {
+        match self.lock {
+            WorktreeLock::Locked(ref reason) => reason.as_deref(),
+            WorktreeLock::Unlocked => None,
+        }
+    }
+}
+
+/// Worktree creation configuration
+#[derive(Debug, Clone, Default)]
+pub struct WorktreeCreateConfig {
+    pub branch: Option<String>, // Defaults to a new branch named after the worktree
+    pub create_branch: bool,    // Create `branch` at HEAD instead of checking out an existing one
+    pub lock: bool,
+}
+
+/// Worktree operation result with detailed information
+#[derive(Debug)]
+pub struct WorktreeOperationResult {
+    pub success: bool,
+    pub operation: OperationType,
+    pub worktree_name: String,
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+impl WorktreeInfo {
+    /// Short "name (path)" label for menus and tooltips
+    #[must_use]
+    pub fn describe(&self) -> String {
+        format!("{} ({})", self.name, self.path.display())
+    }
+}
+
+/// Open the main repository that owns `repo`'s worktrees
+fn open_main_repository(repo: &Repository) -> Result<Repository> {
+    if repo.is_worktree() {
+        Ok(Repository::open(repo.commondir())?)
+    } else {
+        Ok(Repository::open(repo.path())?)
+    }
+}
+
+/// A worktree's branch (None when detached) and commit
+type WorktreeHead = (Option<String>, Option<String>);
+
+/// Read a worktree's HEAD from its admin directory; works even if the
+/// working directory has been deleted
+fn read_worktree_head(main: &Repository, gitdir: &Path) -> WorktreeHead {
+    let Ok(head) = std::fs::read_to_string(gitdir.join("HEAD")) else {
+        return (None, None);
+    };
+    let head = head.trim();
+
+    head.strip_prefix("ref: ").map_or_else(
+        || (None, Some(head.to_string())),
+        |refname| {
+            (
+                refname.strip_prefix("refs/heads/").map(str::to_string),
+                main.refname_to_id(refname).ok().map(|oid| oid.to_string()),
+            )
+        },
+    )
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// List the main worktree (unless bare) followed by every linked worktree
+fn collect_worktrees(main: &Repository, current: Option<&Path>) -> Result<Vec<WorktreeInfo>> {
+    let is_current = |path: &Path| current.is_some_and(|current| same_path(current, path));
+    let mut worktrees = Vec::new();
+
+    if let Some(workdir) = main.workdir() {
+        let (head_branch, head_commit) = read_worktree_head(main, main.path());
+        worktrees.push(WorktreeInfo {
+            name: workdir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: workdir.to_path_buf(),
+            head_branch,
+            head_commit,
+            is_main: true,
+            is_current: is_current(workdir),
+            lock: WorktreeLock::Unlocked,
+            is_prunable: false,
+        });
+    }
+
+    for name in main.worktrees()?.iter().flatten() {
+        let worktree = main.find_worktree(name)?;
+        let (head_branch, head_commit) =
+            read_worktree_head(main, &main.path().join("worktrees").join(name));
+        let lock = match worktree.is_locked()? {
+            WorktreeLockStatus::Unlocked => WorktreeLock::Unlocked,
+            // `git worktree lock` ends the reason with a newline
+            WorktreeLockStatus::Locked(reason) => WorktreeLock::Locked(
+                reason
+                    .map(|reason| reason.trim_end().to_string())
+                    .filter(|reason| !reason.is_empty()),
+            ),
+        };
+
+        worktrees.push(WorktreeInfo {
+            name: name.to_string(),
+            path: worktree.path().to_path_buf(),
+            head_branch,
+            head_commit,
+            is_main: false,
+            is_current: is_current(worktree.path()),
+            lock,
+            is_prunable: worktree.validate().is_err(),
+        });
+    }
+
+    Ok(worktrees)
+}
+
+/// The worktree other than the current one that has `branch` checked out
+pub fn branch_checked_out_elsewhere(
+    repo: &Repository,
+    branch: &str,
+) -> Result<Option<WorktreeInfo>> {
+    let main = open_main_repository(repo)?;
+    Ok(collect_worktrees(&main, repo.workdir())?
+        .into_iter()
+        .find(|worktree| !worktree.is_current && worktree.head_branch.as_deref() == Some(branch)))
+}
+
+/// Refuse to check out `branch` in `repo` when another worktree holds it
+pub fn ensure_branch_not_checked_out_elsewhere(repo: &Repository, branch: &str) -> Result<()> {
+    match branch_checked_out_elsewhere(repo, branch)? {
+        Some(holder) => Err(anyhow!(
+            "Branch '{}' is already checked out at '{}'",
+            branch,
+            holder.path.display()
+        )),
+        None => Ok(()),
+    }
+}
+
+impl GitRepository {
+    /// List all worktrees of this repository
+    pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        let main = open_main_repository(self.repo())?;
+        collect_worktrees(&main, self.repo().workdir())
+    }
+
+    /// The worktree (current or not) that has `branch` checked out
+    pub fn get_branch_worktree(&self, branch: &str) -> Result<Option<WorktreeInfo>> {
+        Ok(self
+            .list_worktrees()?
+            .into_iter()
+            .find(|worktree| worktree.head_branch.as_deref() == Some(branch)))
+    }
+}
+
+impl WorktreeManager {
+    /// Create a new worktree manager
+    pub fn new(git_repo: &GitRepository) -> Result<Self> {
+        let repo = open_main_repository(git_repo.get_repository())?;
+
+        Ok(Self {
+            repo,
+            current_workdir: git_repo.get_repository().workdir().map(Path::to_path_buf),
+            operation_history: Vec::new(),
+        })
+    }
+
+    /// List all worktrees
+    pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        collect_worktrees(&self.repo, self.current_workdir.as_deref())
+    }
+
+    /// Add a linked worktree at `path`
+    pub fn create_worktree(
+        &mut self,
+        name: &str,
+        path: &Path,
+        config: WorktreeCreateConfig,
+    ) -> Result<WorktreeOperationResult> {
+        let failure = |message: String| -> Result<WorktreeOperationResult> {
+            Ok(WorktreeOperationResult {
+                success: false,
+                operation: OperationType::WorktreeAdd,
+                worktree_name: name.to_string(),
+                path: Some(path.to_path_buf()),
+                message,
+            })
+        };
+
+        if let Err(e) = InputValidator::validate_ref_name(name) {
+            ErrorReporter::log_error(&e, "worktree creation validation");
+            return failure(format!("Invalid worktree name: {e}"));
+        }
+        if name.contains('/') {
+            return failure("Worktree name cannot contain '/'".to_string());
+        }
+        if path.exists() && path.read_dir().map_or(true, |mut d| d.next().is_some()) {
+            return failure(format!("'{}' already exists", path.display()));
+        }
+
+        // Resolve the branch to check out, creating it first if asked to
+        let reference = match config.branch.as_deref() {
+            Some(branch) => {
+                if let Err(e) = InputValidator::validate_ref_name(branch) {
+                    ErrorReporter::log_error(&e, "worktree creation validation");
+                    return failure(format!("Invalid branch name: {e}"));
+                }
+
+                if config.create_branch {
+                    let head = match self.repo.head().and_then(|head| head.peel_to_commit()) {
+                        Ok(commit) => commit,
+                        Err(e) => return failure(format!("Cannot resolve HEAD: {e}")),
+                    };
+                    if let Err(e) = self.repo.branch(branch, &head, false) {
+                        return failure(format!("Failed to create branch '{branch}': {e}"));
+                    }
+                } else if let Some(holder) = self
+                    .list_worktrees()?
+                    .into_iter()
+                    .find(|worktree| worktree.head_branch.as_deref() == Some(branch))
+                {
+                    return failure(format!(
+                        "Branch '{}' is already checked out at '{}'",
+                        branch,
+                        holder.path.display()
+                    ));
+                }
+
+                match self.repo.find_branch(branch, BranchType::Local) {
+                    Ok(branch) => Some(branch.into_reference()),
+                    Err(e) => return failure(format!("Branch not found: {e}")),
+                }
+            }
+            None => None,
+        };
+
+        let added = {
+            let mut options = WorktreeAddOptions::new();
+            options.lock(config.lock);
+            options.reference(reference.as_ref());
+            self.repo.worktree(name, path, Some(&options))
+        };
+        // The branch still borrows the repository, which recording needs mutably
+        drop(reference);
+        if let Err(e) = added {
+            ErrorReporter::log_error(&GitError::from(e), "worktree creation");
+            return failure(format!("Failed to add worktree '{name}'"));
+        }
+
+        let branch = config.branch.unwrap_or_else(|| name.to_string());
+        self.record_operation(OperationRecord {
+            operation_type: OperationType::WorktreeAdd,
+            timestamp: chrono::Utc::now(),
+            description: format!(
+                "Added worktree '{}' at {} on branch '{}'",
+                name,
+                path.display(),
+                branch
+            ),
+            original_state: None,
+            new_state: Some(path.display().to_string()),
+            affected_refs: vec![format!("refs/heads/{}", branch)],
+        });
+
+        info!("Added worktree '{}' at {}", name, path.display());
+
+        Ok(WorktreeOperationResult {
+            success: true,
+            operation: OperationType::WorktreeAdd,
+            worktree_name: name.to_string(),
+            path: Some(path.to_path_buf()),
+            message: "Successfully added worktree".to_string(),
+        })
+    }
+
+    /// Remove a linked worktree and its working directory
+    pub fn remove_worktree(&mut self, name: &str, force: bool) -> Result<WorktreeOperationResult> {
+        let failure = |message: String| -> Result<WorktreeOperationResult> {
+            Ok(WorktreeOperationResult {
+                success: false,
+                operation: OperationType::WorktreeRemove,
+                worktree_name: name.to_string(),
+                path: None,
+                message,
+            })
+        };
+
+        let Some(info) = self.find_info(name)? else {
+            return failure(format!("Worktree '{name}' not found"));
+        };
+        if info.is_main {
+            return failure("The main worktree cannot be removed".to_string());
+        }
+        if info.is_current {
+            return failure("Cannot remove the worktree being browsed".to_string());
+        }
+        if info.is_locked() && !force {
+            return failure(format!(
+                "Worktree '{}' is locked{}",
+                name,
+                info.lock_reason()
+                    .map(|reason| format!(": {reason}"))
+                    .unwrap_or_default()
+            ));
+        }
+
+        let worktree = self.repo.find_worktree(name)?;
+        if !force && !info.is_prunable {
+            let worktree_repo = Repository::open_from_worktree(&worktree)?;
+            let mut status_options = StatusOptions::new();
+            status_options
+                .include_untracked(true)
+                .include_ignored(false);
+            if !worktree_repo
+                .statuses(Some(&mut status_options))?
+                .is_empty()
+            {
+                return failure(format!(
+                    "Worktree '{name}' has uncommitted changes; use force to remove it"
+                ));
+            }
+        }
+
+        let mut prune_options = WorktreePruneOptions::new();
+        prune_options.valid(true).locked(true).working_tree(true);
+        if let Err(e) = worktree.prune(Some(&mut prune_options)) {
+            ErrorReporter::log_error(&GitError::from(e), "worktree removal");
+            return failure(format!("Failed to remove worktree '{name}'"));
+        }
+
+        self.record_operation(OperationRecord {
+            operation_type: OperationType::WorktreeRemove,
+            timestamp: chrono::Utc::now(),
+            description: format!("Removed worktree '{}' at {}", name, info.path.display()),
+            original_state: Some(info.path.display().to_string()),
+            new_state: None,
+            affected_refs: vec![],
+        });
+
+        info!("Removed worktree '{}'", name);
+
+        Ok(WorktreeOperationResult {
+            success: true,
+            operation: OperationType::WorktreeRemove,
+            worktree_name: name.to_string(),
+            path: Some(info.path),
+            message: "Successfully removed worktree".to_string(),
+        })
+    }
+
+    /// Lock a worktree so it is not pruned or removed
+    pub fn lock_worktree(
+        &mut self,
+        name: &str,
+        reason: Option<&str>,
+    ) -> Result<WorktreeOperationResult> {
+        Ok(self.set_locked(name, true, reason))
+    }
+
+    /// Unlock a previously locked worktree
+    pub fn unlock_worktree(&mut self, name: &str) -> Result<WorktreeOperationResult> {
+        Ok(self.set_locked(name, false, None))
+    }
+
+    /// Get operation history
+    #[must_use]
+    pub fn get_operation_history(&self) -> &[OperationRecord] {
+        &self.operation_history
+    }
+
+    fn set_locked(
+        &mut self,
+        name: &str,
+        lock: bool,
+        reason: Option<&str>,
+    ) -> WorktreeOperationResult {
+        let operation = if lock {
+            OperationType::WorktreeLock
+        } else {
+            OperationType::WorktreeUnlock
+        };
+
+        let result = self.repo.find_worktree(name).and_then(|worktree| {
+            if lock {
+                worktree.lock(reason)
+            } else {
+                worktree.unlock()
+            }
+        });
+        if let Err(e) = result {
+            let message = format!(
+                "Failed to {} worktree '{}': {}",
+                if lock { "lock" } else { "unlock" },
+                name,
+                e.message()
+            );
+            ErrorReporter::log_error(&GitError::from(e), "worktree locking");
+            return WorktreeOperationResult {
+                success: false,
+                operation,
+                worktree_name: name.to_string(),
+                path: None,
+                message,
+            };
+        }
+
+        self.record_operation(OperationRecord {
+            operation_type: operation.clone(),
+            timestamp: chrono::Utc::now(),
+            description: format!(
+                "{} worktree '{}'",
+                if lock { "Locked" } else { "Unlocked" },
+                name
+            ),
+            original_state: None,
+            new_state: reason.map(str::to_string),
+            affected_refs: vec![],
+        });
+
+        WorktreeOperationResult {
+            success: true,
+            operation,
+            worktree_name: name.to_string(),
+            path: None,
+            message: format!(
+                "Successfully {} worktree",
+                if lock { "locked" } else { "unlocked" }
+            ),
+        }
+    }
+
+    fn find_info(&self, name: &str) -> Result<Option<WorktreeInfo>> {
+        Ok(self
+            .list_worktrees()?
+            .into_iter()
+            .find(|worktree| !worktree.is_main && worktree.name == name))
+    }
+
+    /// Record an operation in history
+    fn record_operation(&mut self, record: OperationRecord) {
+        let operation_type = record.operation_type.clone();
+        self.operation_history.push(record);
+
+        // Maintain history limit
+        if self.operation_history.len() > 100 {
+            self.operation_history.remove(0);
+        }
+
+        info!("Recorded worktree operation: {:?}", operation_type);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_repo() -> Result<(TempDir, PathBuf)> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join("main");
+        fs::create_dir(&repo_path)?;
+
+        git(&repo_path, &["init", "-b", "main"])?;
+        git(&repo_path, &["config", "user.name", "Test User"])?;
+        git(&repo_path, &["config", "user.email", "test@example.com"])?;
+        fs::write(repo_path.join("test.txt"), "content")?;
+        git(&repo_path, &["add", "test.txt"])?;
+        git(&repo_path, &["commit", "-m", "Initial commit"])?;
+        git(&repo_path, &["branch", "feature"])?;
+
+        Ok((temp_dir, repo_path))
+    }
+
+    #[test]
+    fn test_list_worktrees() -> Result<()> {
+        let (temp_dir, repo_path) = create_test_repo()?;
+        let linked_path = temp_dir.path().join("feature-wt");
+        git(
+            &repo_path,
+            &["worktree", "add", linked_path.to_str().unwrap(), "feature"],
+        )?;
+        git(
+            &repo_path,
+            &["worktree", "lock", "--reason", "on usb", "feature-wt"],
+        )?;
+
+        let repo = GitRepository::open(&repo_path)?;
+        let worktrees = repo.list_worktrees()?;
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main && worktrees[0].is_current);
+        assert_eq!(worktrees[0].head_branch.as_deref(), Some("main"));
+        assert_eq!(worktrees[1].name, "feature-wt");
+        assert_eq!(worktrees[1].head_branch.as_deref(), Some("feature"));
+        assert!(worktrees[1].is_locked());
+        assert_eq!(worktrees[1].lock_reason(), Some("on usb"));
+
+        let holder = repo.get_branch_worktree("feature")?.unwrap();
+        assert!(same_path(&holder.path, &linked_path));
+
+        // Browsing from the linked worktree flips which one is current
+        let linked = GitRepository::open(&linked_path)?;
+        let worktrees = linked.list_worktrees()?;
+        assert!(!worktrees[0].is_current);
+        assert!(worktrees[1].is_current);
+        assert!(branch_checked_out_elsewhere(linked.repo(), "main")?.is_some());
+        assert!(branch_checked_out_elsewhere(linked.repo(), "feature")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_lock_and_remove_worktree() -> Result<()> {
+        let (temp_dir, repo_path) = create_test_repo()?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = WorktreeManager::new(&repo)?;
+        let linked_path = temp_dir.path().join("linked");
+
+        let config = WorktreeCreateConfig {
+            branch: Some("feature".to_string()),
+            ..WorktreeCreateConfig::default()
+        };
+        let result = manager.create_worktree("linked", &linked_path, config)?;
+        assert!(result.success, "{}", result.message);
+        assert!(linked_path.join("test.txt").exists());
+        assert_eq!(
+            git(&linked_path, &["rev-parse", "--abbrev-ref", "HEAD"])?,
+            "feature"
+        );
+
+        // The branch is now held by the linked worktree
+        let config = WorktreeCreateConfig {
+            branch: Some("feature".to_string()),
+            ..WorktreeCreateConfig::default()
+        };
+        let result = manager.create_worktree("other", &temp_dir.path().join("other"), config)?;
+        assert!(!result.success);
+        assert!(result.message.contains("already checked out"));
+
+        let result = manager.lock_worktree("linked", Some("keep"))?;
+        assert!(result.success, "{}", result.message);
+        let result = manager.remove_worktree("linked", false)?;
+        assert!(!result.success);
+        assert!(result.message.contains("locked"));
+
+        let result = manager.unlock_worktree("linked")?;
+        assert!(result.success, "{}", result.message);
+
+        fs::write(linked_path.join("dirty.txt"), "dirty")?;
+        let result = manager.remove_worktree("linked", false)?;
+        assert!(!result.success);
+        assert!(result.message.contains("uncommitted"));
+
+        let result = manager.remove_worktree("linked", true)?;
+        assert!(result.success, "{}", result.message);
+        assert!(!linked_path.exists());
+        assert_eq!(manager.list_worktrees()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_worktree_with_new_branch() -> Result<()> {
+        let (temp_dir, repo_path) = create_test_repo()?;
+        let repo = GitRepository::open(&repo_path)?;
+        let mut manager = WorktreeManager::new(&repo)?;
+
+        let config = WorktreeCreateConfig {
+            branch: Some("topic".to_string()),
+            create_branch: true,
+            lock: false,
+        };
+        let result = manager.create_worktree("topic", &temp_dir.path().join("topic"), config)?;
+        assert!(result.success, "{}", result.message);
+        assert!(repo.get_branch_worktree("topic")?.is_some());
+
+        // The main worktree's own branch cannot be checked out again
+        let config = WorktreeCreateConfig {
+            branch: Some("main".to_string()),
+            ..WorktreeCreateConfig::default()
+        };
+        let result = manager.create_worktree("dup", &temp_dir.path().join("dup"), config)?;
+        assert!(!result.success);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_checkout_refuses_branch_held_by_other_worktree() -> Result<()> {
+        let (temp_dir, repo_path) = create_test_repo()?;
+        let linked_path = temp_dir.path().join("feature-wt");
+        git(
+            &repo_path,
+            &["worktree", "add", linked_path.to_str().unwrap(), "feature"],
+        )?;
+
+        let repo = GitRepository::open(&repo_path)?;
+        let mut operations = crate::git::GitOperations::new(&repo)?;
+        let result = operations.checkout_branch("feature")?;
+        assert!(!result.success);
+        assert!(result.message.contains("already checked out"));
+
+        // From the linked worktree the branch is its own and may be checked out
+        let linked = GitRepository::open(&linked_path)?;
+        assert!(branch_checked_out_elsewhere(linked.get_repository(), "feature")?.is_none());
+        assert!(branch_checked_out_elsewhere(linked.get_repository(), "main")?.is_some());
+
+        Ok(())
+    }
+}
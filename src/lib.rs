@@ -10,6 +10,7 @@
 //!
 //! The library is organized into several main modules:
 //! - [`git`] - Git operations and repository handling
+//! - [`cli`] - Headless subcommands such as `log --graph`
 //! - [`ui`] - User interface components and layouts
 //! - [`models`] - Data structures and models
 //! - [`state`] - Application state management
@@ -49,6 +50,7 @@
 #![allow(missing_docs)] // Application-focused: internal implementation details don't require extensive documentation
 
 pub mod app;
+pub mod cli;
 pub mod git;
 pub mod models;
 pub mod state;
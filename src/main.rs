@@ -2,6 +2,7 @@ use eframe::egui;
 use tracing_subscriber;
 
 mod app;
+mod cli;
 mod git;
 mod models;
 mod state;
@@ -10,6 +11,12 @@ mod ui;
 use app::GitkApp;
 
 fn main() -> Result<(), eframe::Error> {
+    // Subcommands run headless; logging would mix into their output
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     tracing_subscriber::fmt::init();
 
     let options = eframe::NativeOptions {
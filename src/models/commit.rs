@@ -11,6 +11,16 @@ pub struct GitCommit {
     pub summary: String,
     pub parent_ids: Vec<String>,
     pub tree_id: String,
+    #[serde(default)]
+    pub is_signed: bool, // Carries a gpgsig header; verify via GitRepository
+    #[serde(default)]
+    pub notes: Vec<GitNote>, // Loaded on demand from the display notes refs
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitNote {
+    pub notes_ref: String,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,7 +38,16 @@ pub struct GitDiff {
     pub stats: GitDiffStats,
     pub is_binary: bool,
     pub status: DiffStatus,
-    pub similarity: Option<u32>, // For renames and copies
+    pub similarity: Option<u32>,            // For renames and copies
+    pub submodule: Option<SubmoduleChange>, // Set for gitlink (160000) entries
+}
+
+/// A submodule pointer change: the gitlink commit before and after
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubmoduleChange {
+    pub path: String,
+    pub old_commit: Option<String>,
+    pub new_commit: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -97,6 +116,8 @@ impl GitCommit {
 
         let parent_ids = commit.parent_ids().map(|id| id.to_string()).collect();
         let tree_id = commit.tree_id().to_string();
+        let is_signed = commit.header_field_bytes("gpgsig").is_ok()
+            || commit.header_field_bytes("gpgsig-sha256").is_ok();
 
         Ok(GitCommit {
             id,
@@ -107,6 +128,8 @@ impl GitCommit {
             summary,
             parent_ids,
             tree_id,
+            is_signed,
+            notes: Vec::new(),
         })
     }
 }
@@ -139,6 +162,8 @@ mod tests {
             summary: "Initial commit".to_string(),
             parent_ids: vec![],
             tree_id: "tree123".to_string(),
+            is_signed: false,
+            notes: vec![],
         };
 
         assert_eq!(commit.id, "abc123def456");
@@ -200,6 +225,7 @@ mod tests {
             is_binary: false,
             status: DiffStatus::Modified,
             similarity: None,
+            submodule: None,
         };
 
         assert_eq!(diff.old_file, Some("file.txt".to_string()));
@@ -271,6 +297,8 @@ mod tests {
             summary: "Test commit".to_string(),
             parent_ids: vec!["parent1".to_string()],
             tree_id: "tree1".to_string(),
+            is_signed: false,
+            notes: vec![],
         };
 
         // Test serialization
@@ -305,6 +333,8 @@ mod tests {
             summary: "Merge branch 'feature'".to_string(),
             parent_ids: vec!["parent1".to_string(), "parent2".to_string()],
             tree_id: "tree123".to_string(),
+            is_signed: false,
+            notes: vec![],
         };
 
         assert_eq!(commit.parent_ids.len(), 2);
@@ -339,6 +369,7 @@ mod tests {
             is_binary: true,
             status: DiffStatus::Modified,
             similarity: None,
+            submodule: None,
         };
 
         assert!(diff.is_binary);
@@ -363,6 +394,8 @@ mod tests {
             summary: "Test".to_string(),
             parent_ids: vec![],
             tree_id: "tree".to_string(),
+            is_signed: false,
+            notes: vec![],
         };
 
         // Test cloning
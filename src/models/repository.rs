@@ -12,6 +12,22 @@ pub struct RepositoryInfo {
     pub remotes: Vec<String>,
 }
 
+/// Local changes on top of HEAD, counted per path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkingTreeStatus {
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+}
+
+impl WorkingTreeStatus {
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FileEntry {
     pub name: String,
@@ -30,6 +46,14 @@ pub struct TreeEntry {
     pub is_tree: bool,
 }
 
+impl TreeEntry {
+    /// Gitlink entries (mode 160000) point at a commit in a submodule
+    #[must_use]
+    pub fn is_submodule(&self) -> bool {
+        self.filemode == git2::FileMode::Commit
+    }
+}
+
 impl RepositoryInfo {
     pub fn from_repo(repo: &git2::Repository) -> anyhow::Result<Self> {
         let path = repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf();
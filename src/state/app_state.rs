@@ -1,8 +1,26 @@
 use crate::git::{
-    CommitStream, ErrorRecovery, ErrorReporter, GitError, GitRepository, InputSanitizer,
-    InputValidator, RefManager, ViewManager,
+    ActivityCalendar, ActivityScope, CommitCacheEvent, CommitCacheLoad, CommitExportConfig,
+    CommitExportEvent, CommitExportJob, CommitGraphIndex, CommitIndexUpdate, CommitQuery,
+    CommitRefContext, CommitStream, DateRange, DiffEndpoint, ErrorRecovery, ErrorReporter,
+    FormatPatchOptions, GitError, GitOperations, GitRepository, InputSanitizer, InputValidator,
+    MailPatch, NoteOperationResult, NotesManager, PatchApplyConfig, PatchApplyResult, PathSearch,
+    PathSearchEvent, PickaxeEvent, PickaxeQuery, PickaxeSearch, QueryContext, QueryParseError,
+    QueryRefs, QuerySearch, QuerySearchEvent, RefManager, RepositoryStatistics, RepositoryWatcher,
+    SignatureStatus, SignatureTarget, SignatureVerification, SignatureVerificationQueue,
+    StatisticsEvent, StatisticsJob, ViewFilter, ViewManager, WatchChanges, WorktreeCreateConfig,
+    WorktreeInfo, WorktreeManager, WorktreeOperationResult, parse_mailbox, parse_pathspecs,
+    parse_unified_diff, patches_to_mbox,
 };
-use crate::models::{GitCommit, GitDiff, RepositoryInfo};
+use crate::models::{GitCommit, GitDiff, RepositoryInfo, SubmoduleChange, WorkingTreeStatus};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Position of a commit in the listed commits, with its id
+type ListedCommit = (usize, String);
+
+/// One way of applying or resuming a loaded patch series
+type PatchStep =
+    fn(&GitRepository, &[MailPatch], &PatchApplyConfig) -> anyhow::Result<PatchApplyResult>;
 
 #[derive(Debug)]
 pub struct AppState {
@@ -12,13 +30,16 @@ pub struct AppState {
     pub selected_files: Vec<String>,
     pub current_diff: Option<GitDiff>,
     pub search_query: String,
+    pub query_error: Option<QueryParseError>, // Shown inline next to the search box
     pub filter_author: String,
     pub filter_branch: String,
     pub show_all_branches: bool,
     pub commit_limit: usize,
     pub loading: bool,
     pub error_message: Option<String>,
+    pub status_message: Option<String>, // Outcome of the last export, shown in the status bar
     pub commit_stream: Option<CommitStream>,
+    commit_cache_load: Option<CommitCacheLoad>, // Runs before the stream when HEAD's history is cacheable
     pub stream_complete: bool,
     pub ref_manager: Option<RefManager>,
     pub selected_branch: Option<String>,
@@ -29,6 +50,80 @@ pub struct AppState {
     pub show_shortcuts_dialog: bool,
     pub show_about_dialog: bool,
     pub show_settings_dialog: bool,
+    pub commit_signatures: HashMap<String, SignatureVerification>,
+    pub tag_signatures: HashMap<String, SignatureVerification>,
+    signature_queue: Option<SignatureVerificationQueue>, // Started on the first signed object shown
+    pub notes_refs: Vec<String>, // Extra notes refs shown alongside notes.displayRef
+    pub start_commit: Option<String>, // Walk history from here instead of HEAD
+    pub parent_sessions: Vec<ParentSession>, // Superprojects left via "open submodule"
+    pub worktrees: Vec<WorktreeInfo>,
+    pub commit_index: CommitGraphIndex, // Ancestry queries over all refs
+    pub commit_index_update: Option<CommitIndexUpdate>, // Grows `commit_index` after ref changes
+    pub commit_ref_contexts: HashMap<String, CommitRefContext>,
+    pub pickaxe_search: Option<PickaxeSearch>, // Background -S/-G search feeding `commits`
+    pub query_search: Option<QuerySearch>,     // Background `path:` query feeding `commits`
+    pub path_search: Option<PathSearch>,
+    pub path_matches: HashSet<String>, // Commits touching the searched paths, highlighted in place
+    pub scroll_to_selected: bool,      // Bring the selected commit into view on the next frame
+    pub highlight_query: Option<CommitQuery>, // Emphasizes matches instead of filtering
+    pub highlight_error: Option<QueryParseError>,
+    pub highlight_matches: HashSet<String>,
+    highlight_checked: HashSet<String>, // Commits the highlight query was evaluated on
+    highlight_cursor: Option<ListedCommit>, // Listed commits evaluated so far and the last one
+    highlight_refs: Option<QueryRefs>,  // Collected once per highlight query and ref change
+    cache_stream: bool, // The running stream walks HEAD's full history; cache it when done
+    pub watcher: Option<RepositoryWatcher>, // Refreshes after other programs change the repo
+    pub working_tree_status: Option<WorkingTreeStatus>,
+    pub incoming_patches: Vec<MailPatch>, // Loaded from a mailbox to preview and apply
+    pub patches_applied: usize,           // Leading incoming patches already applied or skipped
+    pub previewed_patch: Option<usize>,   // Shown in the diff viewer instead of a commit
+    pub previewed_patch_diffs: Vec<GitDiff>,
+    pub patch_apply_result: Option<PatchApplyResult>,
+    pub opened_patch: Option<OpenedPatch>, // A patch file shown on its own, repository or not
+    pub compared_diff: Option<ComparedDiff>, // Two endpoints diffed in place of the selected commit
+    pub tree_file: Option<TreeFile>,       // Opened from the tree browser, shown instead of diffs
+    pub commit_export_job: Option<CommitExportJob>, // Writing the view to a CSV or JSON file
+    pub statistics_job: Option<StatisticsJob>,
+    pub statistics: Option<RepositoryStatistics>, // Of the view as it was when computed
+    pub activity_calendar: Option<ActivityCalendar>,
+    pub activity_day: Option<chrono::NaiveDate>, // Day the commit list is narrowed to
+    activity_return_view: Option<String>,        // View to go back to when the day is cleared
+}
+
+/// View the activity heatmap switches to when a day is clicked
+pub const ACTIVITY_DAY_VIEW: &str = "Activity day";
+
+/// A .patch or .diff file opened for reading
+#[derive(Debug)]
+pub struct OpenedPatch {
+    pub path: PathBuf,
+    pub data: Vec<u8>,
+    pub diffs: Vec<GitDiff>,
+}
+
+/// Changes between two arbitrary endpoints, shown until another commit is selected
+#[derive(Debug)]
+pub struct ComparedDiff {
+    pub from: DiffEndpoint,
+    pub to: DiffEndpoint,
+    pub diffs: Vec<GitDiff>,
+}
+
+/// A file as it is at a commit, opened from the tree browser
+#[derive(Debug)]
+pub struct TreeFile {
+    pub commit_id: String,
+    pub path: String,
+    pub content: String,
+    pub is_binary: bool,
+}
+
+/// A browsing session suspended while a submodule is open
+#[derive(Debug)]
+pub struct ParentSession {
+    pub repository: GitRepository,
+    pub selected_commit: Option<String>,
+    pub start_commit: Option<String>,
 }
 
 impl Default for AppState {
@@ -40,13 +135,16 @@ impl Default for AppState {
             selected_files: Vec::new(),
             current_diff: None,
             search_query: String::new(),
+            query_error: None,
             filter_author: String::new(),
             filter_branch: String::new(),
             show_all_branches: false,
             commit_limit: 1000,
             loading: false,
             error_message: None,
+            status_message: None,
             commit_stream: None,
+            commit_cache_load: None,
             stream_complete: false,
             ref_manager: None,
             selected_branch: None,
@@ -57,122 +155,1174 @@ impl Default for AppState {
             show_shortcuts_dialog: false,
             show_about_dialog: false,
             show_settings_dialog: false,
+            commit_signatures: HashMap::new(),
+            tag_signatures: HashMap::new(),
+            signature_queue: None,
+            notes_refs: Vec::new(),
+            start_commit: None,
+            parent_sessions: Vec::new(),
+            worktrees: Vec::new(),
+            commit_index: CommitGraphIndex::new(),
+            commit_index_update: None,
+            commit_ref_contexts: HashMap::new(),
+            pickaxe_search: None,
+            query_search: None,
+            path_search: None,
+            path_matches: HashSet::new(),
+            scroll_to_selected: false,
+            highlight_query: None,
+            highlight_error: None,
+            highlight_matches: HashSet::new(),
+            highlight_checked: HashSet::new(),
+            highlight_cursor: None,
+            highlight_refs: None,
+            cache_stream: false,
+            watcher: None,
+            working_tree_status: None,
+            incoming_patches: Vec::new(),
+            patches_applied: 0,
+            previewed_patch: None,
+            previewed_patch_diffs: Vec::new(),
+            patch_apply_result: None,
+            opened_patch: None,
+            compared_diff: None,
+            tree_file: None,
+            commit_export_job: None,
+            statistics_job: None,
+            statistics: None,
+            activity_calendar: None,
+            activity_day: None,
+            activity_return_view: None,
+        }
+    }
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_repository(&mut self, repo: GitRepository) {
+        self.watcher = match repo.watch() {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                tracing::warn!("Not watching repository for changes: {}", e);
+                None
+            }
+        };
+        self.repository = Some(repo);
+        self.refresh_working_tree_status();
+        self.pickaxe_search = None;
+        self.query_search = None;
+        self.clear_path_search();
+        self.compared_diff = None;
+        self.tree_file = None;
+        self.commit_export_job = None;
+        self.statistics_job = None;
+        self.statistics = None;
+        self.activity_calendar = None;
+        self.activity_day = None;
+        self.activity_return_view = None;
+        self.highlight_matches.clear();
+        self.highlight_checked.clear();
+        self.highlight_cursor = None;
+        self.highlight_refs = None;
+        self.commit_index = CommitGraphIndex::new();
+        self.commit_index_update = None;
+        self.commit_ref_contexts.clear();
+        self.commit_signatures.clear();
+        self.tag_signatures.clear();
+        self.signature_queue = None;
+        self.load_references();
+        self.initialize_views();
+        self.start_streaming_commits();
+    }
+
+    /// Browse a submodule at the commit a gitlink change points to,
+    /// keeping the current repository to return to
+    pub fn open_submodule(&mut self, change: &SubmoduleChange) {
+        let Some(commit_id) = change
+            .new_commit
+            .clone()
+            .or_else(|| change.old_commit.clone())
+        else {
+            return;
+        };
+
+        let submodule = match self.repository {
+            Some(ref repo) => match repo.open_submodule(&change.path) {
+                Ok(submodule) => submodule,
+                Err(e) => {
+                    self.error_message =
+                        Some(format!("Failed to open submodule '{}': {}", change.path, e));
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        if submodule.get_commit(&commit_id).is_err() {
+            self.error_message = Some(format!(
+                "Commit {} is not available in submodule '{}'; fetch it first",
+                &commit_id[..commit_id.len().min(7)],
+                change.path
+            ));
+            return;
+        }
+
+        if let Some(parent) = self.repository.take() {
+            self.parent_sessions.push(ParentSession {
+                repository: parent,
+                selected_commit: self.selected_commit.take(),
+                start_commit: self.start_commit.take(),
+            });
+        }
+
+        self.start_commit = Some(commit_id.clone());
+        self.set_repository(submodule);
+        self.select_commit(commit_id);
+    }
+
+    /// Go back to the superproject a submodule was opened from
+    pub fn return_to_parent_repository(&mut self) {
+        if let Some(session) = self.parent_sessions.pop() {
+            self.start_commit = session.start_commit;
+            self.set_repository(session.repository);
+            if let Some(commit_id) = session.selected_commit {
+                self.select_commit(commit_id);
+            }
+        }
+    }
+
+    /// Apply changes reported by the repository watcher once they settle
+    pub fn poll_repository_changes(&mut self) -> bool {
+        let (Some(watcher), Some(repo)) = (self.watcher.as_mut(), self.repository.as_ref()) else {
+            return false;
+        };
+        let Some(changes) = watcher.poll(repo) else {
+            return false;
+        };
+
+        self.apply_repository_changes(changes);
+        true
+    }
+
+    pub const fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    /// Pick up changes other programs made to refs, the index or the
+    /// worktree. The selected commit stays selected across the reload.
+    pub fn apply_repository_changes(&mut self, changes: WatchChanges) {
+        if changes.refs {
+            if let Some(ref mut repo) = self.repository {
+                if let Err(e) = repo.refresh_info() {
+                    tracing::warn!("Failed to refresh repository info: {}", e);
+                }
+            }
+            self.refresh_references();
+
+            if let (Some(repo), Some(view_manager)) = (&self.repository, &mut self.view_manager) {
+                if let Err(e) = view_manager.update_current_view(repo) {
+                    tracing::warn!("Failed to refresh view: {}", e);
+                }
+            }
+
+            // Searches and walks from a fixed commit own the list until cleared
+            if self.search_query.is_empty()
+                && self.pickaxe_search.is_none()
+                && self.query_search.is_none()
+                && self.start_commit.is_none()
+            {
+                self.start_streaming_commits();
+            }
+        }
+
+        self.refresh_working_tree_status();
+
+        // Index and working tree endpoints follow the files on disk
+        let compares_worktree = self.compared_diff.as_ref().is_some_and(|compared| {
+            compared.from.commit_id().is_none() || compared.to.commit_id().is_none()
+        });
+        if compares_worktree {
+            self.refresh_compared_diff();
+        }
+    }
+
+    pub fn refresh_working_tree_status(&mut self) {
+        self.working_tree_status = self.repository.as_ref().and_then(|repo| {
+            // Bare repositories have no worktree to report on
+            repo.working_tree_status()
+                .map_err(|e| tracing::debug!("No working tree status: {}", e))
+                .ok()
+        });
+    }
+
+    pub fn refresh_commits(&mut self) {
+        if let Some(ref repo) = self.repository {
+            self.loading = true;
+            match repo.get_commits(Some(self.commit_limit)) {
+                Ok(commits) => {
+                    self.commits = commits;
+                    self.error_message = None;
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to load commits: {}", e));
+                }
+            }
+            self.loading = false;
+        }
+    }
+
+    pub fn select_commit(&mut self, commit_id: String) {
+        // Enhanced validation and error handling
+        if let Err(e) = InputValidator::validate_commit_id(&commit_id) {
+            ErrorReporter::log_error(&e, "commit selection");
+            self.error_message = Some(ErrorRecovery::user_friendly_message(&e));
+            return;
+        }
+
+        // Sanitize commit ID for security
+        match InputSanitizer::sanitize_commit_id(&commit_id) {
+            Ok(sanitized_id) => {
+                self.selected_commit = Some(sanitized_id.clone());
+                self.compared_diff = None;
+                // Like gitk, keep showing the viewed file as of the new commit
+                self.tree_file = self
+                    .tree_file
+                    .take()
+                    .and_then(|file| self.load_tree_file(&sanitized_id, &file.path).ok());
+                self.load_commit_diff(&sanitized_id);
+                self.load_commit_notes(&sanitized_id);
+                self.ensure_commit_ref_context(&sanitized_id);
+            }
+            Err(e) => {
+                let git_error = GitError::invalid_input(commit_id, e.to_string());
+                ErrorReporter::log_error(&git_error, "commit ID sanitization");
+                self.error_message = Some(ErrorRecovery::user_friendly_message(&git_error));
+            }
+        }
+    }
+
+    pub fn load_commit_diff(&mut self, commit_id: &str) {
+        if let Some(ref repo) = self.repository {
+            // Sanitize commit ID again for safety
+            match InputSanitizer::sanitize_commit_id(commit_id) {
+                Ok(sanitized_id) => match repo.get_commit_diff_enhanced(&sanitized_id) {
+                    Ok(diffs) => {
+                        if let Some(first_diff) = diffs.into_iter().next() {
+                            self.current_diff = Some(first_diff);
+                        }
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to load diff: {}", e));
+                    }
+                },
+                Err(e) => {
+                    self.error_message = Some(format!("Invalid commit ID: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Read the notes for a commit from the display notes refs
+    pub fn load_commit_notes(&mut self, commit_id: &str) {
+        if let Some(ref repo) = self.repository {
+            match repo.get_commit_notes(commit_id, &self.notes_refs) {
+                Ok(notes) => {
+                    if let Some(commit) = self.commits.iter_mut().find(|c| c.id == commit_id) {
+                        commit.notes = notes;
+                    }
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to load notes: {e}"));
+                }
+            }
+        }
+    }
+
+    /// The commit's note in the default notes ref, i.e. the one the editor writes
+    pub fn get_commit_note(&self, commit_id: &str) -> Option<String> {
+        self.repository
+            .as_ref()
+            .and_then(|repo| repo.get_commit_note(commit_id, None).ok().flatten())
+    }
+
+    /// Add or replace the note on a commit in the default notes ref
+    pub fn set_commit_note(&mut self, commit_id: &str, message: &str) {
+        let result = match self.repository {
+            Some(ref repo) => NotesManager::new(repo)
+                .and_then(|mut manager| manager.set_note(commit_id, message, None)),
+            None => return,
+        };
+        self.apply_note_result(commit_id, result);
+    }
+
+    /// Remove the note on a commit from the default notes ref
+    pub fn remove_commit_note(&mut self, commit_id: &str) {
+        let result = match self.repository {
+            Some(ref repo) => {
+                NotesManager::new(repo).and_then(|mut manager| manager.remove_note(commit_id, None))
+            }
+            None => return,
+        };
+        self.apply_note_result(commit_id, result);
+    }
+
+    fn apply_note_result(&mut self, commit_id: &str, result: anyhow::Result<NoteOperationResult>) {
+        match result {
+            Ok(result) if result.success => {
+                self.error_message = None;
+                self.load_commit_notes(commit_id);
+            }
+            Ok(result) => self.error_message = Some(result.message),
+            Err(e) => self.error_message = Some(format!("Note operation failed: {e}")),
+        }
+    }
+
+    pub fn search_commits(&mut self, query: &str) {
+        self.pickaxe_search = None;
+        self.query_search = None;
+
+        if let Err(e) = InputValidator::validate_search_query(query) {
+            ErrorReporter::log_error(&e, "search query validation");
+            self.error_message = Some(ErrorRecovery::user_friendly_message(&e));
+            return;
+        }
+
+        // Sanitize search query for security
+        let query = match InputSanitizer::sanitize_search_query(query) {
+            Ok(sanitized_query) => sanitized_query,
+            Err(e) => {
+                let git_error = GitError::invalid_input(query, e.to_string());
+                ErrorReporter::log_error(&git_error, "search query sanitization");
+                self.error_message = Some(ErrorRecovery::user_friendly_message(&git_error));
+                return;
+            }
+        };
+
+        let parsed = match CommitQuery::parse(&query) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                // Keep the current list while the query is being typed
+                self.query_error = Some(e);
+                return;
+            }
+        };
+        self.query_error = None;
+
+        if let Some(ref repo) = self.repository {
+            self.search_query = parsed.source().to_string();
+            let start = self.start_commit.as_deref();
+            if parsed.is_empty() {
+                self.refresh_commits();
+            } else if parsed.needs_diffs() {
+                // Diffing every commit for `path:` would stall the UI
+                match repo.start_query_search(parsed, start, self.commit_limit) {
+                    Ok(search) => {
+                        self.commit_stream = None;
+                        self.commit_cache_load = None;
+                        self.commits.clear();
+                        self.loading = true;
+                        self.error_message = None;
+                        self.query_search = Some(search);
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to start search: {e}"));
+                    }
+                }
+            } else {
+                self.loading = true;
+                match repo.query_commits(&parsed, start, Some(self.commit_limit)) {
+                    Ok(commits) => {
+                        self.commits = commits;
+                        self.error_message = None;
+                    }
+                    Err(e) => {
+                        let git_error = GitError::command_failed("search", e.to_string());
+                        ErrorReporter::log_error(&git_error, "commit search");
+                        self.error_message = Some(ErrorRecovery::user_friendly_message(&git_error));
+                    }
+                }
+                self.loading = false;
+            }
+        }
+    }
+
+    /// Write commits as numbered format-patch files into `dir`
+    pub fn write_patch_series(
+        &mut self,
+        commit_ids: &[String],
+        options: &FormatPatchOptions,
+        dir: &Path,
+    ) {
+        if let Some(ref repo) = self.repository {
+            if let Err(e) = repo.write_patch_series(commit_ids, options, dir) {
+                self.error_message = Some(format!("Failed to export patches: {e}"));
+            }
+        }
+    }
+
+    /// Write commits as a single mbox file for `git am`
+    pub fn write_patch_mbox(
+        &mut self,
+        commit_ids: &[String],
+        options: &FormatPatchOptions,
+        path: &Path,
+    ) {
+        if let Some(ref repo) = self.repository {
+            if let Err(e) = repo.write_patch_mbox(commit_ids, options, path) {
+                self.error_message = Some(format!("Failed to export patches: {e}"));
+            }
+        }
+    }
+
+    /// The patch series as mbox text, e.g. for the clipboard
+    pub fn format_patch_text(
+        &mut self,
+        commit_ids: &[String],
+        options: &FormatPatchOptions,
+    ) -> Option<String> {
+        let repo = self.repository.as_ref()?;
+        match repo.format_patches(commit_ids, options) {
+            Ok(patches) => Some(String::from_utf8_lossy(&patches_to_mbox(&patches)).into_owned()),
+            Err(e) => {
+                self.error_message = Some(format!("Failed to export patches: {e}"));
+                None
+            }
+        }
+    }
+
+    /// Write the commits of the current view to a CSV or JSON file in the
+    /// background
+    pub fn start_commit_export(&mut self, path: &Path, config: &CommitExportConfig) {
+        if let Some(ref repo) = self.repository {
+            let commits = self.get_filtered_commits().to_vec();
+            match repo.start_commit_export(commits, path, config.clone()) {
+                Ok(job) => {
+                    self.status_message = None;
+                    self.commit_export_job = Some(job);
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to export commits: {e}"));
+                }
+            }
+        }
+    }
+
+    pub fn poll_commit_export(&mut self) -> bool {
+        let Some(ref mut job) = self.commit_export_job else {
+            return false;
+        };
+
+        let mut progress_made = false;
+        while let Some(event) = job.try_next() {
+            progress_made = true;
+            match event {
+                CommitExportEvent::Item(count) => {
+                    let message =
+                        format!("Exported {} commits to {}", count, job.query().display());
+                    tracing::info!("{}", message);
+                    self.status_message = Some(message);
+                }
+                CommitExportEvent::Finished {
+                    cancelled: true, ..
+                } => {
+                    self.status_message = Some("Export cancelled".to_string());
+                }
+                CommitExportEvent::Progress { .. } | CommitExportEvent::Finished { .. } => {}
+                CommitExportEvent::Failed(e) => {
+                    self.error_message = Some(format!("Failed to export commits: {e}"));
+                }
+            }
+        }
+        progress_made
+    }
+
+    pub fn is_exporting_commits(&self) -> bool {
+        self.commit_export_job
+            .as_ref()
+            .is_some_and(|job| !job.is_complete())
+    }
+
+    /// Load an mbox or patch file and preview its first patch
+    pub fn load_patches(&mut self, path: &Path) {
+        let loaded = std::fs::read(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| self.queue_patches(&data));
+        if let Err(e) = loaded {
+            self.error_message = Some(format!("Failed to read patches: {e}"));
+        }
+    }
+
+    fn queue_patches(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        self.incoming_patches = parse_mailbox(data)?;
+        self.patches_applied = 0;
+        self.patch_apply_result = None;
+        self.preview_patch(0);
+        Ok(())
+    }
+
+    /// Show a .patch or .diff file in the diff viewer; no repository needed
+    pub fn open_patch_file(&mut self, path: &Path) {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to read patch: {e}"));
+                return;
+            }
+        };
+        let diffs = parse_unified_diff(&String::from_utf8_lossy(&data));
+        if diffs.is_empty() {
+            self.error_message = Some(format!("No diff found in {}", path.display()));
+            return;
+        }
+
+        self.opened_patch = Some(OpenedPatch {
+            path: path.to_path_buf(),
+            data,
+            diffs,
+        });
+    }
+
+    pub fn close_patch_file(&mut self) {
+        self.opened_patch = None;
+    }
+
+    /// Show the changes from `from` to `to` in the diff viewer, like gitk's
+    /// "Diff this -> selected"
+    pub fn compare_endpoints(&mut self, from: DiffEndpoint, to: DiffEndpoint) {
+        if let Some(ref repo) = self.repository {
+            match repo.diff_endpoints(&from, &to) {
+                Ok(diffs) => {
+                    self.compared_diff = Some(ComparedDiff { from, to, diffs });
+                    self.tree_file = None;
+                }
+                Err(e) => {
+                    self.error_message = Some(format!(
+                        "Failed to diff {} against {}: {}",
+                        from.label(),
+                        to.label(),
+                        e
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Diff the compared endpoints again, e.g. after the working tree changed
+    pub fn refresh_compared_diff(&mut self) {
+        if let Some(compared) = self.compared_diff.take() {
+            self.compare_endpoints(compared.from, compared.to);
+        }
+    }
+
+    pub fn close_compared_diff(&mut self) {
+        self.compared_diff = None;
+    }
+
+    /// Show a file's content at a commit in the diff viewer
+    pub fn view_tree_file(&mut self, commit_id: &str, path: &str) {
+        match self.load_tree_file(commit_id, path) {
+            Ok(file) => {
+                self.tree_file = Some(file);
+                self.compared_diff = None;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to read {path}: {e}"));
+            }
+        }
+    }
+
+    fn load_tree_file(&self, commit_id: &str, path: &str) -> anyhow::Result<TreeFile> {
+        let repo = self
+            .repository
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No repository open"))?;
+        let content = repo.get_file_content(commit_id, path)?;
+
+        Ok(TreeFile {
+            commit_id: commit_id.to_string(),
+            path: path.to_string(),
+            is_binary: content.contains('\0'),
+            content,
+        })
+    }
+
+    pub fn close_tree_file(&mut self) {
+        self.tree_file = None;
+    }
+
+    /// Hand the opened patch file over to the patch preview, where it can
+    /// be applied to the open repository
+    pub fn apply_opened_patch(&mut self) {
+        if self.repository.is_none() {
+            return;
+        }
+        let Some(patch) = self.opened_patch.take() else {
+            return;
+        };
+        if let Err(e) = self.queue_patches(&patch.data) {
+            self.error_message = Some(format!(
+                "Cannot apply {}: {} (only git diffs can be applied)",
+                patch.path.display(),
+                e
+            ));
+            self.opened_patch = Some(patch);
+        }
+    }
+
+    pub fn preview_patch(&mut self, index: usize) {
+        let Some(patch) = self.incoming_patches.get(index) else {
+            return;
+        };
+        self.previewed_patch_diffs = match patch.diffs() {
+            Ok(diffs) => diffs,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to parse patch: {e}"));
+                Vec::new()
+            }
+        };
+        self.previewed_patch = Some(index);
+    }
+
+    pub fn get_previewed_patch(&self) -> Option<&MailPatch> {
+        self.previewed_patch
+            .and_then(|index| self.incoming_patches.get(index))
+    }
+
+    pub fn close_patch_preview(&mut self) {
+        self.incoming_patches.clear();
+        self.patches_applied = 0;
+        self.previewed_patch = None;
+        self.previewed_patch_diffs.clear();
+        self.patch_apply_result = None;
+    }
+
+    /// Apply the incoming patches not applied yet, stopping at the first
+    /// that fails; that one is previewed with its conflicts
+    pub fn apply_incoming_patches(&mut self, config: &PatchApplyConfig) {
+        self.run_patch_step(config, "apply patches", 0, GitRepository::apply_patches);
+    }
+
+    /// Commit the resolved index for the patch that stopped, then apply the rest
+    pub fn continue_incoming_patches(&mut self, config: &PatchApplyConfig) {
+        self.run_patch_step(config, "continue", 0, GitRepository::continue_patches);
+    }
+
+    /// Drop the patch that stopped and apply the rest
+    pub fn skip_incoming_patch(&mut self, config: &PatchApplyConfig) {
+        self.run_patch_step(config, "skip patch", 1, GitRepository::skip_patch);
+    }
+
+    fn run_patch_step(
+        &mut self,
+        config: &PatchApplyConfig,
+        action: &str,
+        skipped: usize,
+        step: PatchStep,
+    ) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let pending = &self.incoming_patches[self.patches_applied..];
+        match step(repo, pending, config) {
+            Ok(mut result) => {
+                if let Some(ref mut stopped_at) = result.stopped_at {
+                    *stopped_at += self.patches_applied;
+                }
+                self.patches_applied += result.applied + skipped;
+                if let Some(stopped_at) = result.stopped_at {
+                    self.preview_patch(stopped_at);
+                }
+
+                let changes = WatchChanges {
+                    refs: !result.new_commits.is_empty(),
+                    index: true,
+                    worktree: true,
+                };
+                self.patch_apply_result = Some(result);
+                self.apply_repository_changes(changes);
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to {action}: {e}"));
+            }
+        }
+    }
+
+    /// Save the current search query as a view and switch to it
+    pub fn save_query_as_view(&mut self, name: &str) {
+        let query = match CommitQuery::parse(&self.search_query) {
+            Ok(query) if !query.is_empty() => query,
+            Ok(_) => return,
+            Err(e) => {
+                self.query_error = Some(e);
+                return;
+            }
+        };
+
+        if let Some(ref mut view_manager) = self.view_manager {
+            view_manager.add_view(name.to_string(), ViewFilter::from_query(name, &query));
+            if let Err(e) = view_manager.switch_view(name) {
+                self.error_message = Some(format!("Failed to switch view: {e}"));
+                return;
+            }
+        }
+        self.update_current_view();
+    }
+
+    /// Count the last year's commits per day for the heatmap
+    pub fn load_activity_calendar(&mut self, scope: &ActivityScope) {
+        if let Some(ref repo) = self.repository {
+            let today = chrono::Utc::now().date_naive();
+            match repo.activity_calendar(scope, today) {
+                Ok(calendar) => self.activity_calendar = Some(calendar),
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to load activity: {e}"));
+                }
+            }
+        }
+    }
+
+    /// Narrow the commit list to one day of the heatmap through a view of
+    /// its own, so the user's views keep their filters
+    pub fn show_activity_day(&mut self, day: chrono::NaiveDate, scope: &ActivityScope) {
+        let Some(ref mut view_manager) = self.view_manager else {
+            return;
+        };
+        let current = view_manager.get_current_view_name().to_string();
+        if current != ACTIVITY_DAY_VIEW {
+            self.activity_return_view = Some(current);
+        }
+
+        view_manager.add_view(
+            ACTIVITY_DAY_VIEW.to_string(),
+            scope.to_filter(ACTIVITY_DAY_VIEW, day, day),
+        );
+        if let Err(e) = view_manager.switch_view(ACTIVITY_DAY_VIEW) {
+            self.error_message = Some(format!("Failed to switch view: {e}"));
+            return;
+        }
+        self.activity_day = Some(day);
+        self.update_current_view();
+    }
+
+    /// Leave the heatmap's day view for the view shown before it
+    pub fn clear_activity_day(&mut self) {
+        self.activity_day = None;
+        let Some(ref mut view_manager) = self.view_manager else {
+            return;
+        };
+        let previous = self.activity_return_view.take();
+        let _ = view_manager.remove_view(ACTIVITY_DAY_VIEW); // Falls back to the default view
+        if let Some(previous) = previous {
+            let _ = view_manager.switch_view(&previous);
+        }
+        self.update_current_view();
+    }
+
+    /// Search history for commits adding/removing a string (`-S`) or
+    /// changing lines matching a regex (`-G`); matches arrive incrementally
+    pub fn start_pickaxe_search(&mut self, query: PickaxeQuery) {
+        // A literal -S pattern may contain `\` or `|`; the matcher escapes it
+        if query.is_regex() {
+            if let Err(e) = InputValidator::validate_search_query(&query.pattern) {
+                ErrorReporter::log_error(&e, "pickaxe query validation");
+                self.error_message = Some(ErrorRecovery::user_friendly_message(&e));
+                return;
+            }
+        }
+
+        if let Some(ref repo) = self.repository {
+            let pattern = query.pattern.clone();
+            match repo.start_pickaxe_search(query, self.start_commit.as_deref()) {
+                Ok(search) => {
+                    // Results replace the streamed history like a regular search
+                    self.commit_stream = None;
+                    self.commit_cache_load = None;
+                    self.commits.clear();
+                    self.search_query = pattern;
+                    self.loading = true;
+                    self.error_message = None;
+                    self.pickaxe_search = Some(search);
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to start search: {e}"));
+                }
+            }
+        }
+    }
+
+    pub fn poll_pickaxe_search(&mut self) -> bool {
+        let Some(ref mut search) = self.pickaxe_search else {
+            return false;
+        };
+
+        let mut progress_made = false;
+        while let Some(event) = search.try_next() {
+            progress_made = true;
+            match event {
+                PickaxeEvent::Item(commit) => self.commits.push(commit),
+                PickaxeEvent::Progress { .. } => {}
+                PickaxeEvent::Finished { .. } => self.loading = false,
+                PickaxeEvent::Failed(e) => {
+                    self.loading = false;
+                    self.error_message = Some(format!("Search failed: {e}"));
+                }
+            }
+        }
+        progress_made
+    }
+
+    /// Stop a running pickaxe search, keeping the matches found so far
+    pub fn cancel_pickaxe_search(&mut self) {
+        if let Some(ref search) = self.pickaxe_search {
+            search.cancel();
+        }
+    }
+
+    pub fn is_pickaxe_searching(&self) -> bool {
+        self.pickaxe_search
+            .as_ref()
+            .is_some_and(|search| !search.is_complete())
+    }
+
+    pub fn poll_query_search(&mut self) -> bool {
+        let Some(ref mut search) = self.query_search else {
+            return false;
+        };
+
+        let mut progress_made = false;
+        while let Some(event) = search.try_next() {
+            progress_made = true;
+            match event {
+                QuerySearchEvent::Item(commit) => self.commits.push(commit),
+                QuerySearchEvent::Progress { .. } => {}
+                QuerySearchEvent::Finished { .. } => self.loading = false,
+                QuerySearchEvent::Failed(e) => {
+                    self.loading = false;
+                    self.error_message = Some(format!("Search failed: {e}"));
+                }
+            }
+        }
+        progress_made
+    }
+
+    /// Drain every background job of this session, returning whether any
+    /// made progress
+    pub fn poll_background_jobs(&mut self) -> bool {
+        // Poll commit stream for new commits
+        let mut progress_made = self.poll_commit_stream();
+
+        // Poll background pickaxe search for matches
+        progress_made |= self.poll_pickaxe_search();
+
+        // Poll the background commit export
+        progress_made |= self.poll_commit_export();
+
+        // Poll background commit query for `path:` matches
+        progress_made |= self.poll_query_search();
+
+        // Poll background refreshes of views with `path:` or `ref:` queries
+        progress_made |= self.poll_view_updates();
+
+        // Poll background signature verification for the badges
+        progress_made |= self.poll_signatures();
+
+        // Poll background statistics for the dashboard
+        progress_made |= self.poll_statistics();
+
+        // Poll the background commit index update for ancestry queries
+        progress_made |= self.poll_commit_index();
+
+        // Poll background path search for commits to highlight
+        progress_made |= self.poll_path_search();
+
+        // Evaluate the highlight query on newly listed commits
+        progress_made |= self.update_highlights();
+
+        progress_made
+    }
+
+    /// Whether a background job is still running and needs polling
+    pub fn has_background_jobs(&self) -> bool {
+        self.is_streaming()
+            || self.is_pickaxe_searching()
+            || self.is_query_searching()
+            || self.is_exporting_commits()
+            || self.is_updating_views()
+            || self.is_verifying_signatures()
+            || self.is_path_searching()
+            || self.is_indexing_commits()
+    }
+
+    /// Fill views whose queries are refreshed on a background thread
+    pub fn poll_view_updates(&mut self) -> bool {
+        self.view_manager
+            .as_mut()
+            .is_some_and(super::super::git::views::ViewManager::poll_updates)
+    }
+
+    pub fn is_updating_views(&self) -> bool {
+        self.view_manager
+            .as_ref()
+            .is_some_and(super::super::git::views::ViewManager::is_updating)
+    }
+
+    /// Stop a running query search, keeping the matches found so far
+    pub fn cancel_query_search(&mut self) {
+        if let Some(ref search) = self.query_search {
+            search.cancel();
+        }
+    }
+
+    pub fn is_query_searching(&self) -> bool {
+        self.query_search
+            .as_ref()
+            .is_some_and(|search| !search.is_complete())
+    }
+
+    /// Compute statistics for the current view in the background
+    pub fn start_statistics(&mut self) {
+        if let Some(ref repo) = self.repository {
+            match repo.start_statistics(self.get_filtered_commits().to_vec()) {
+                Ok(job) => self.statistics_job = Some(job),
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to compute statistics: {e}"));
+                }
+            }
         }
     }
-}
 
-impl AppState {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn poll_statistics(&mut self) -> bool {
+        let Some(ref mut job) = self.statistics_job else {
+            return false;
+        };
+
+        let mut progress_made = false;
+        while let Some(event) = job.try_next() {
+            progress_made = true;
+            match event {
+                StatisticsEvent::Progress { .. } | StatisticsEvent::Finished { .. } => {}
+                StatisticsEvent::Item(statistics) => self.statistics = Some(*statistics),
+                StatisticsEvent::Failed(e) => {
+                    self.error_message = Some(format!("Failed to compute statistics: {e}"));
+                }
+            }
+        }
+        progress_made
     }
 
-    pub fn set_repository(&mut self, repo: GitRepository) {
-        self.repository = Some(repo);
-        self.load_references();
-        self.initialize_views();
-        self.start_streaming_commits();
+    pub fn is_computing_statistics(&self) -> bool {
+        self.statistics_job
+            .as_ref()
+            .is_some_and(|job| !job.is_complete())
     }
 
-    pub fn refresh_commits(&mut self) {
+    /// Highlight commits touching any of the whitespace-separated pathspecs
+    pub fn start_path_search(&mut self, input: &str) {
+        let pathspecs = parse_pathspecs(input);
+        for pathspec in &pathspecs {
+            if let Err(e) = InputValidator::validate_file_path(pathspec) {
+                ErrorReporter::log_error(&e, "path search validation");
+                self.error_message = Some(ErrorRecovery::user_friendly_message(&e));
+                return;
+            }
+        }
+
+        self.clear_path_search();
+        if pathspecs.is_empty() {
+            return;
+        }
+
         if let Some(ref repo) = self.repository {
-            self.loading = true;
-            match repo.get_commits(Some(self.commit_limit)) {
-                Ok(commits) => {
-                    self.commits = commits;
+            match repo.start_path_search(pathspecs, self.start_commit.as_deref(), self.commit_limit)
+            {
+                Ok(search) => {
                     self.error_message = None;
+                    self.path_search = Some(search);
                 }
                 Err(e) => {
-                    self.error_message = Some(format!("Failed to load commits: {}", e));
+                    self.error_message = Some(format!("Failed to start path search: {e}"));
                 }
             }
-            self.loading = false;
         }
     }
 
-    pub fn select_commit(&mut self, commit_id: String) {
-        // Enhanced validation and error handling
-        if let Err(e) = InputValidator::validate_commit_id(&commit_id) {
-            ErrorReporter::log_error(&e, "commit selection");
-            self.error_message = Some(ErrorRecovery::user_friendly_message(&e));
-            return;
-        }
+    pub fn poll_path_search(&mut self) -> bool {
+        let Some(ref mut search) = self.path_search else {
+            return false;
+        };
 
-        // Sanitize commit ID for security
-        match InputSanitizer::sanitize_commit_id(&commit_id) {
-            Ok(sanitized_id) => {
-                self.selected_commit = Some(sanitized_id.clone());
-                self.load_commit_diff(&sanitized_id);
-            }
-            Err(e) => {
-                let git_error = GitError::invalid_input(commit_id, e.to_string());
-                ErrorReporter::log_error(&git_error, "commit ID sanitization");
-                self.error_message = Some(ErrorRecovery::user_friendly_message(&git_error));
+        let mut progress_made = false;
+        while let Some(event) = search.try_next() {
+            progress_made = true;
+            match event {
+                PathSearchEvent::Item(commit_id) => {
+                    self.path_matches.insert(commit_id);
+                }
+                PathSearchEvent::Progress { .. } | PathSearchEvent::Finished { .. } => {}
+                PathSearchEvent::Failed(e) => {
+                    self.error_message = Some(format!("Path search failed: {e}"));
+                }
             }
         }
+        progress_made
     }
 
-    pub fn load_commit_diff(&mut self, commit_id: &str) {
-        if let Some(ref repo) = self.repository {
-            // Sanitize commit ID again for safety
-            match InputSanitizer::sanitize_commit_id(commit_id) {
-                Ok(sanitized_id) => match repo.get_commit_diff_enhanced(&sanitized_id) {
-                    Ok(diffs) => {
-                        if let Some(first_diff) = diffs.into_iter().next() {
-                            self.current_diff = Some(first_diff);
-                        }
-                    }
-                    Err(e) => {
-                        self.error_message = Some(format!("Failed to load diff: {}", e));
-                    }
-                },
-                Err(e) => {
-                    self.error_message = Some(format!("Invalid commit ID: {}", e));
-                }
-            }
+    /// Stop a running path search, keeping the highlights found so far
+    pub fn cancel_path_search(&mut self) {
+        if let Some(ref search) = self.path_search {
+            search.cancel();
         }
     }
 
-    pub fn search_commits(&mut self, query: &str) {
-        if let Some(ref repo) = self.repository {
-            // Enhanced validation
-            if let Err(e) = InputValidator::validate_search_query(query) {
-                ErrorReporter::log_error(&e, "search query validation");
-                self.error_message = Some(ErrorRecovery::user_friendly_message(&e));
+    pub fn clear_path_search(&mut self) {
+        self.path_search = None;
+        self.path_matches.clear();
+    }
+
+    pub fn is_path_searching(&self) -> bool {
+        self.path_search
+            .as_ref()
+            .is_some_and(|search| !search.is_complete())
+    }
+
+    pub fn is_path_match(&self, commit_id: &str) -> bool {
+        self.path_matches.contains(commit_id)
+    }
+
+    /// Select the next (or previous) commit touching the searched paths
+    pub fn select_path_match(&mut self, forward: bool) {
+        if let Some((index, commit_id)) = self.find_marked_commit(&self.path_matches, forward) {
+            self.select_listed_commit(index, commit_id);
+        }
+    }
+
+    /// Highlight commits matching a query without hiding the others
+    pub fn set_highlight_query(&mut self, input: &str) {
+        let query = match CommitQuery::parse(input) {
+            Ok(query) => query,
+            Err(e) => {
+                self.highlight_error = Some(e);
                 return;
             }
+        };
+
+        self.clear_highlight();
+        if !query.is_empty() {
+            self.highlight_query = Some(query);
+            self.update_highlights();
+        }
+    }
+
+    pub fn clear_highlight(&mut self) {
+        self.highlight_query = None;
+        self.highlight_error = None;
+        self.highlight_matches.clear();
+        self.highlight_checked.clear();
+        self.highlight_cursor = None;
+        self.highlight_refs = None;
+    }
+
+    /// Evaluate the highlight query on commits listed since the last call
+    pub fn update_highlights(&mut self) -> bool {
+        let (Some(query), Some(repo)) = (&self.highlight_query, &self.repository) else {
+            return false;
+        };
 
-            // Sanitize search query for security
-            match InputSanitizer::sanitize_search_query(query) {
-                Ok(sanitized_query) => {
-                    self.search_query = sanitized_query.clone();
-                    if sanitized_query.is_empty() {
-                        self.refresh_commits();
+        if query.needs_repository() && self.highlight_refs.is_none() {
+            self.highlight_refs = Some(QueryRefs::from_repository(repo).unwrap_or_else(|e| {
+                tracing::warn!("Highlighting without ref data: {}", e);
+                QueryRefs::default()
+            }));
+        }
+
+        let (checked, matched, cursor) = {
+            let commits = self.get_filtered_commits();
+            // Streaming appends to the list; when it was replaced, walk it again
+            let start = match self.highlight_cursor {
+                Some((count, ref last_id)) => {
+                    let listed = commits.get(count - 1);
+                    if listed.is_some_and(|commit| commit.id == *last_id) {
+                        count
                     } else {
-                        self.loading = true;
-                        match repo.search_commits(&sanitized_query, Some(self.commit_limit)) {
-                            Ok(commits) => {
-                                self.commits = commits;
-                                self.error_message = None;
-                            }
-                            Err(e) => {
-                                let git_error = GitError::command_failed("search", e.to_string());
-                                ErrorReporter::log_error(&git_error, "commit search");
-                                self.error_message =
-                                    Some(ErrorRecovery::user_friendly_message(&git_error));
-                            }
-                        }
-                        self.loading = false;
+                        0
                     }
                 }
-                Err(e) => {
-                    let git_error = GitError::invalid_input(query, e.to_string());
-                    ErrorReporter::log_error(&git_error, "search query sanitization");
-                    self.error_message = Some(ErrorRecovery::user_friendly_message(&git_error));
+                None => 0,
+            };
+            let Some(last) = commits.last().filter(|_| start < commits.len()) else {
+                return false;
+            };
+
+            let context = self
+                .highlight_refs
+                .as_ref()
+                .map_or_else(QueryContext::empty, |refs| {
+                    QueryContext::with_refs(repo, refs)
+                });
+
+            let mut checked = Vec::new();
+            let mut matched = Vec::new();
+            for commit in &commits[start..] {
+                if self.highlight_checked.contains(&commit.id) {
+                    continue;
                 }
+                if query.matches(commit, &context) {
+                    matched.push(commit.id.clone());
+                }
+                checked.push(commit.id.clone());
             }
+            (checked, matched, (commits.len(), last.id.clone()))
+        };
+
+        self.highlight_cursor = Some(cursor);
+        self.highlight_checked.extend(checked);
+        self.highlight_matches.extend(matched);
+        true
+    }
+
+    /// `None` when no highlight is active, otherwise whether the commit matches
+    pub fn highlight_match(&self, commit_id: &str) -> Option<bool> {
+        self.highlight_query
+            .as_ref()
+            .map(|_| self.highlight_matches.contains(commit_id))
+    }
+
+    /// Select the next (or previous) commit matching the highlight query
+    pub fn select_highlight_match(&mut self, forward: bool) {
+        if let Some((index, commit_id)) = self.find_marked_commit(&self.highlight_matches, forward)
+        {
+            self.select_listed_commit(index, commit_id);
         }
     }
 
+    /// Next (or previous) listed commit in `marked`, starting from the selection
+    fn find_marked_commit(&self, marked: &HashSet<String>, forward: bool) -> Option<ListedCommit> {
+        let commits = self.get_filtered_commits();
+        let current = self
+            .selected_commit
+            .as_ref()
+            .and_then(|id| commits.iter().position(|c| &c.id == id));
+
+        let is_match = |index: &usize| marked.contains(&commits[*index].id);
+        let found = match (forward, current) {
+            (true, Some(current)) => (current + 1..commits.len()).find(is_match),
+            (true, None) => (0..commits.len()).find(is_match),
+            (false, Some(current)) => (0..current).rev().find(is_match),
+            (false, None) => (0..commits.len()).rev().find(is_match),
+        };
+        found.map(|index| (index, commits[index].id.clone()))
+    }
+
+    /// Select a commit by list position and scroll it into view
+    fn select_listed_commit(&mut self, index: usize, commit_id: String) {
+        self.selected_commit_index = Some(index);
+        self.scroll_to_selected = true;
+        self.select_commit(commit_id);
+    }
+
     pub fn clear_error(&mut self) {
         self.error_message = None;
     }
@@ -199,19 +1349,93 @@ impl AppState {
             self.commits.clear();
             self.stream_complete = false;
 
-            match repo.get_commits_streaming(Some(self.commit_limit)) {
-                Ok(stream) => {
-                    self.commit_stream = Some(stream);
+            // The current view's date range bounds the walk itself
+            let range = self
+                .view_manager
+                .as_ref()
+                .and_then(|view_manager| view_manager.get_current_view())
+                .map(|view| view.filter.date_range())
+                .transpose();
+            let range = range.map(Option::unwrap_or_default);
+
+            // HEAD's unfiltered history comes from the on-disk cache when it's current
+            self.cache_stream =
+                self.start_commit.is_none() && range.as_ref().is_ok_and(DateRange::is_unbounded);
+            self.commit_cache_load = None;
+            if self.cache_stream {
+                match repo.start_cached_commit_load(self.commit_limit) {
+                    Ok(load) => {
+                        self.commit_stream = None;
+                        self.commit_cache_load = Some(load);
+                        return;
+                    }
+                    Err(e) => tracing::warn!("Failed to read commit cache: {}", e),
                 }
-                Err(e) => {
-                    self.error_message = Some(format!("Failed to start streaming commits: {}", e));
+            }
+            self.start_commit_walk(range);
+        }
+    }
+
+    /// Stream the history from the start commit or HEAD, bounded by `range`
+    fn start_commit_walk(&mut self, range: anyhow::Result<DateRange>) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let stream = match self.start_commit {
+            Some(ref start) => repo.get_commits_streaming_from(start, Some(self.commit_limit)),
+            None => repo.get_commits_streaming(Some(self.commit_limit)),
+        };
+        let stream = range.and_then(|range| stream.map(|stream| stream.with_date_range(range)));
+
+        match stream {
+            Ok(stream) => {
+                self.commit_stream = Some(stream);
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to start streaming commits: {e}"));
+                self.loading = false;
+            }
+        }
+    }
+
+    /// Take the cached history once the background load has it, or fall
+    /// back to walking HEAD when there is no usable cache
+    fn poll_commit_cache_load(&mut self) -> bool {
+        let Some(ref mut load) = self.commit_cache_load else {
+            return false;
+        };
+        let mut progress_made = false;
+        while let Some(event) = load.try_next() {
+            match event {
+                CommitCacheEvent::Item(commits) => {
+                    tracing::debug!("Loaded {} commits from the commit cache", commits.len());
+                    self.commits = commits;
+                    self.stream_complete = true;
                     self.loading = false;
+                    self.cache_stream = false;
+                    progress_made = true;
+                }
+                CommitCacheEvent::Failed(e) => {
+                    tracing::warn!("Failed to read commit cache: {}", e);
                 }
+                CommitCacheEvent::Progress { .. } | CommitCacheEvent::Finished { .. } => {}
+            }
+        }
+
+        if load.is_complete() {
+            self.commit_cache_load = None;
+            if !self.stream_complete {
+                self.start_commit_walk(Ok(DateRange::default()));
             }
+            progress_made = true;
         }
+        progress_made
     }
 
     pub fn poll_commit_stream(&mut self) -> bool {
+        if self.commit_cache_load.is_some() {
+            return self.poll_commit_cache_load();
+        }
         if let Some(ref mut stream) = self.commit_stream {
             let mut progress_made = false;
             // Poll for new commits (non-blocking)
@@ -237,6 +1461,15 @@ impl AppState {
                 self.stream_complete = true;
                 self.loading = false;
                 self.commit_stream = None;
+
+                if std::mem::take(&mut self.cache_stream) {
+                    if let Some(ref repo) = self.repository {
+                        let commits = self.commits.clone();
+                        if let Err(e) = repo.start_commit_cache_save(commits, self.commit_limit) {
+                            tracing::warn!("Failed to write commit cache: {}", e);
+                        }
+                    }
+                }
             }
 
             progress_made
@@ -246,7 +1479,7 @@ impl AppState {
     }
 
     pub fn is_streaming(&self) -> bool {
-        self.commit_stream.is_some()
+        self.commit_stream.is_some() || self.commit_cache_load.is_some()
     }
 
     pub fn load_references(&mut self) {
@@ -261,7 +1494,48 @@ impl AppState {
                     self.error_message = Some(format!("Failed to load references: {}", e));
                 }
             }
+
+            // Bare repositories and odd layouts simply have no worktrees to show
+            self.worktrees = repo.list_worktrees().unwrap_or_default();
+
+            // New ref tips only add the commits the index has not seen yet;
+            // a first index of a large history takes a while, so off the UI thread
+            self.commit_index_update =
+                match repo.start_commit_index_update(self.commit_index.clone()) {
+                    Ok(update) => Some(update),
+                    Err(e) => {
+                        tracing::warn!("Failed to update commit index: {}", e);
+                        None
+                    }
+                };
+        }
+    }
+
+    /// Take the commit index once its background update is done, then
+    /// fill in the selected commit's branches and tags
+    pub fn poll_commit_index(&mut self) -> bool {
+        let Some(result) = self
+            .commit_index_update
+            .as_ref()
+            .and_then(CommitIndexUpdate::try_finish)
+        else {
+            return false;
+        };
+        self.commit_index_update = None;
+
+        match result {
+            Ok(index) => self.commit_index = index,
+            Err(e) => tracing::warn!("Failed to update commit index: {}", e),
         }
+        self.commit_ref_contexts.clear();
+        if let Some(commit_id) = self.selected_commit.clone() {
+            self.ensure_commit_ref_context(&commit_id);
+        }
+        true
+    }
+
+    pub const fn is_indexing_commits(&self) -> bool {
+        self.commit_index_update.is_some()
     }
 
     pub fn get_branches(&self) -> Vec<String> {
@@ -330,6 +1604,121 @@ impl AppState {
         }
     }
 
+    /// Whether a commit is reachable from a local branch
+    pub fn is_commit_on_branch(&self, commit_id: &str, branch_name: &str) -> bool {
+        self.ref_manager
+            .as_ref()
+            .and_then(|rm| {
+                rm.is_commit_on_branch(&self.commit_index, commit_id, branch_name)
+                    .ok()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Names of the local and remote branches that contain a commit
+    pub fn get_branches_containing(&self, commit_id: &str) -> Vec<String> {
+        self.ref_manager
+            .as_ref()
+            .and_then(|rm| {
+                rm.get_branches_containing(&self.commit_index, commit_id)
+                    .ok()
+            })
+            .map(|branches| branches.into_iter().map(|b| b.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// The worktree that has `branch` checked out, if any
+    pub fn get_branch_worktree(&self, branch: &str) -> Option<&WorktreeInfo> {
+        self.worktrees
+            .iter()
+            .find(|worktree| worktree.head_branch.as_deref() == Some(branch))
+    }
+
+    /// Check out a local branch in the current worktree
+    pub fn checkout_branch(&mut self, branch_name: &str) {
+        let result = match self.repository {
+            Some(ref repo) => GitOperations::new(repo)
+                .and_then(|mut operations| operations.checkout_branch(branch_name)),
+            None => return,
+        };
+
+        match result {
+            Ok(result) if result.success => {
+                self.error_message = None;
+                self.refresh_references();
+                self.start_streaming_commits();
+            }
+            Ok(result) => self.error_message = Some(result.message),
+            Err(e) => self.error_message = Some(format!("Checkout failed: {e}")),
+        }
+    }
+
+    /// Browse another worktree of the same repository
+    pub fn switch_worktree(&mut self, path: &Path) {
+        match GitRepository::open(path) {
+            Ok(repo) => {
+                self.start_commit = None;
+                self.parent_sessions.clear();
+                self.selected_commit = None;
+                self.error_message = None;
+                self.set_repository(repo);
+            }
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Failed to open worktree '{}': {}",
+                    path.display(),
+                    e
+                ));
+            }
+        }
+    }
+
+    /// Add a linked worktree
+    pub fn create_worktree(&mut self, name: &str, path: &Path, config: WorktreeCreateConfig) {
+        let result = match self.repository {
+            Some(ref repo) => WorktreeManager::new(repo)
+                .and_then(|mut manager| manager.create_worktree(name, path, config)),
+            None => return,
+        };
+        self.apply_worktree_result(result);
+    }
+
+    /// Remove a linked worktree; `force` also discards local changes and locks
+    pub fn remove_worktree(&mut self, name: &str, force: bool) {
+        let result = match self.repository {
+            Some(ref repo) => WorktreeManager::new(repo)
+                .and_then(|mut manager| manager.remove_worktree(name, force)),
+            None => return,
+        };
+        self.apply_worktree_result(result);
+    }
+
+    /// Lock or unlock a linked worktree
+    pub fn set_worktree_locked(&mut self, name: &str, locked: bool) {
+        let result = match self.repository {
+            Some(ref repo) => WorktreeManager::new(repo).and_then(|mut manager| {
+                if locked {
+                    manager.lock_worktree(name, None)
+                } else {
+                    manager.unlock_worktree(name)
+                }
+            }),
+            None => return,
+        };
+        self.apply_worktree_result(result);
+    }
+
+    fn apply_worktree_result(&mut self, result: anyhow::Result<WorktreeOperationResult>) {
+        match result {
+            Ok(result) if result.success => {
+                self.error_message = None;
+                self.refresh_references();
+            }
+            Ok(result) => self.error_message = Some(result.message),
+            Err(e) => self.error_message = Some(format!("Worktree operation failed: {e}")),
+        }
+    }
+
     pub fn get_refs_for_commit(&self, commit_sha: &str) -> Vec<String> {
         self.ref_manager
             .as_ref()
@@ -343,7 +1732,144 @@ impl AppState {
     }
 
     pub fn refresh_references(&mut self) {
+        self.tag_signatures.clear();
+        self.commit_ref_contexts.clear();
+        self.highlight_refs = None;
+        if self
+            .highlight_query
+            .as_ref()
+            .is_some_and(CommitQuery::needs_refs)
+        {
+            // `ref:` matches may have moved; evaluate the list again
+            self.highlight_matches.clear();
+            self.highlight_checked.clear();
+            self.highlight_cursor = None;
+        }
         self.load_references();
+
+        if let Some(commit_id) = self.selected_commit.clone() {
+            self.ensure_commit_ref_context(&commit_id);
+        }
+    }
+
+    /// Compute containing branches and nearest tags for a commit once
+    pub fn ensure_commit_ref_context(&mut self, commit_id: &str) {
+        // Computed when the index update arrives, so new commits are covered
+        if self.commit_ref_contexts.contains_key(commit_id) || self.is_indexing_commits() {
+            return;
+        }
+
+        if let (Some(repo), Some(rm)) = (&self.repository, &self.ref_manager) {
+            match rm.get_commit_ref_context(repo, &self.commit_index, commit_id) {
+                Ok(context) => {
+                    self.commit_ref_contexts
+                        .insert(commit_id.to_string(), context);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to compute refs around {}: {}", commit_id, e);
+                }
+            }
+        }
+    }
+
+    pub fn get_commit_ref_context(&self, commit_id: &str) -> Option<&CommitRefContext> {
+        self.commit_ref_contexts.get(commit_id)
+    }
+
+    /// Verify a commit's signature once in the background; it shows as
+    /// pending until `poll_signatures` stores the result
+    pub fn ensure_commit_signature(&mut self, commit: &GitCommit) {
+        if !commit.is_signed || self.commit_signatures.contains_key(&commit.id) {
+            return;
+        }
+
+        let verification = self.request_signature(SignatureTarget::Commit(commit.id.clone()));
+        self.commit_signatures
+            .insert(commit.id.clone(), verification);
+    }
+
+    /// Verify a tag's signature once in the background
+    pub fn ensure_tag_signature(&mut self, tag_name: &str) {
+        if self.tag_signatures.contains_key(tag_name) {
+            return;
+        }
+
+        let verification = self.request_signature(SignatureTarget::Tag(tag_name.to_string()));
+        self.tag_signatures
+            .insert(tag_name.to_string(), verification);
+    }
+
+    /// Queue a verification, starting the verifier thread on first use
+    fn request_signature(&mut self, target: SignatureTarget) -> SignatureVerification {
+        if self.signature_queue.is_none() {
+            if let Some(ref repo) = self.repository {
+                match repo.start_signature_verification() {
+                    Ok(queue) => self.signature_queue = Some(queue),
+                    Err(e) => return Self::failed_verification(&e, "signature verification"),
+                }
+            }
+        }
+
+        match self.signature_queue {
+            Some(ref queue) if queue.request(target) => SignatureVerification::pending(),
+            _ => Self::failed_verification(
+                &anyhow::anyhow!("Signature verification stopped"),
+                "signature verification",
+            ),
+        }
+    }
+
+    /// Store verifications finished since the last call
+    pub fn poll_signatures(&mut self) -> bool {
+        let Some(ref queue) = self.signature_queue else {
+            return false;
+        };
+
+        let mut progress_made = false;
+        while let Some((target, result)) = queue.try_next() {
+            progress_made = true;
+            match target {
+                SignatureTarget::Commit(commit_id) => {
+                    let verification = result.unwrap_or_else(|e| {
+                        Self::failed_verification(&e, "commit signature verification")
+                    });
+                    self.commit_signatures.insert(commit_id, verification);
+                }
+                SignatureTarget::Tag(tag_name) => {
+                    let verification = result.unwrap_or_else(|e| {
+                        Self::failed_verification(&e, "tag signature verification")
+                    });
+                    self.tag_signatures.insert(tag_name, verification);
+                }
+            }
+        }
+        progress_made
+    }
+
+    pub fn is_verifying_signatures(&self) -> bool {
+        self.commit_signatures
+            .values()
+            .chain(self.tag_signatures.values())
+            .any(|verification| verification.status == SignatureStatus::Pending)
+    }
+
+    pub fn get_commit_signature(&self, commit_id: &str) -> Option<&SignatureVerification> {
+        self.commit_signatures.get(commit_id)
+    }
+
+    pub fn get_tag_signature(&self, tag_name: &str) -> Option<&SignatureVerification> {
+        self.tag_signatures.get(tag_name)
+    }
+
+    fn failed_verification(error: &anyhow::Error, context: &str) -> SignatureVerification {
+        let git_error = GitError::command_failed("verify signature", error.to_string());
+        ErrorReporter::log_error(&git_error, context);
+
+        SignatureVerification {
+            status: SignatureStatus::Error,
+            output: ErrorRecovery::user_friendly_message(&git_error),
+            ..SignatureVerification::unsigned()
+        }
     }
 
     pub fn initialize_views(&mut self) {
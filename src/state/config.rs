@@ -22,6 +22,8 @@ pub struct AppConfig {
     pub diff_settings: DiffSettings,
     pub layout_settings: LayoutSettings,
     pub performance_settings: PerformanceSettings,
+    #[serde(default)]
+    pub notes_refs: Vec<String>, // Extra notes refs to display, e.g. "refs/notes/review"
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -97,6 +99,7 @@ impl Default for AppConfig {
             diff_settings: DiffSettings::default(),
             layout_settings: LayoutSettings::default(),
             performance_settings: PerformanceSettings::default(),
+            notes_refs: Vec::new(),
         }
     }
 }
@@ -0,0 +1,207 @@
+use crate::git::{ACTIVITY_WEEKS, ActivityCalendar, ActivityScope};
+use crate::state::AppState;
+use chrono::{Datelike, Duration, NaiveDate};
+use eframe::egui;
+
+const CELL_SIZE: f32 = 11.0;
+const CELL_GAP: f32 = 2.0;
+const LABEL_WIDTH: f32 = 28.0; // Weekday names left of the grid
+const HEADER_HEIGHT: f32 = 14.0; // Month names above the grid
+const WEEKDAY_LABELS: [WeekdayLabel; 3] = [(0, "Mon"), (2, "Wed"), (4, "Fri")];
+
+/// Grid row and name of a labelled weekday
+type WeekdayLabel = (u16, &'static str);
+
+// Shades for levels 1 to 4, in GitHub's greens
+const LIGHT_LEVELS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(155, 233, 168),
+    egui::Color32::from_rgb(64, 196, 99),
+    egui::Color32::from_rgb(48, 161, 78),
+    egui::Color32::from_rgb(33, 110, 57),
+];
+const DARK_LEVELS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(14, 68, 41),
+    egui::Color32::from_rgb(0, 109, 50),
+    egui::Color32::from_rgb(38, 166, 65),
+    egui::Color32::from_rgb(57, 211, 83),
+];
+
+/// Calendar heatmap of commits per day; clicking a day lists its commits
+pub struct ActivityPanel {
+    scope: ActivityScope,                // As typed
+    loaded_scope: Option<ActivityScope>, // What the calendar in the state counts
+}
+
+impl Default for ActivityPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActivityPanel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            scope: ActivityScope::default(),
+            loaded_scope: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
+        if state.activity_calendar.is_none() && state.has_repository() {
+            self.load(state);
+        }
+
+        ui.horizontal(|ui| {
+            ui.strong("🗓 Activity");
+            ui.separator();
+
+            ui.label("Author:");
+            let author = ui.add(
+                egui::TextEdit::singleline(&mut self.scope.author)
+                    .hint_text("anyone")
+                    .desired_width(120.0),
+            );
+            ui.label("Path:");
+            let path = ui.add(
+                egui::TextEdit::singleline(&mut self.scope.path)
+                    .hint_text("whole repository")
+                    .desired_width(160.0),
+            );
+            let submitted = (author.lost_focus() || path.lost_focus())
+                && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Apply").clicked() || submitted {
+                self.load(state);
+            }
+
+            if let Some(day) = state.activity_day {
+                ui.separator();
+                ui.label(format!("Showing {}", day.format("%a %-d %b %Y")));
+                if ui.button("✖ Clear").clicked() {
+                    state.clear_activity_day();
+                }
+            }
+        });
+
+        let Some(ref calendar) = state.activity_calendar else {
+            return;
+        };
+        let clicked = show_calendar(ui, calendar, state.activity_day);
+        ui.weak(format!(
+            "{} commits in the last year{}",
+            calendar.total(),
+            match self.loaded_scope {
+                Some(ref scope) if !scope.is_empty() => " matching the filter",
+                _ => "",
+            }
+        ));
+
+        if let Some(day) = clicked {
+            let scope = self.loaded_scope.clone().unwrap_or_default();
+            state.show_activity_day(day, &scope);
+        }
+    }
+
+    fn load(&mut self, state: &mut AppState) {
+        state.load_activity_calendar(&self.scope);
+        self.loaded_scope = Some(self.scope.clone());
+    }
+}
+
+/// Paint the weeks as columns, Monday on top; returns a clicked day
+fn show_calendar(
+    ui: &mut egui::Ui,
+    calendar: &ActivityCalendar,
+    selected: Option<NaiveDate>,
+) -> Option<NaiveDate> {
+    let weeks = calendar.week_starts(ACTIVITY_WEEKS);
+    let last_day = calendar.last_day()?;
+    let columns = u16::try_from(weeks.len()).unwrap_or(u16::MAX);
+    let size = egui::vec2(
+        grid_offset(columns, LABEL_WIDTH),
+        grid_offset(7, HEADER_HEIGHT),
+    );
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    let levels = if visuals.dark_mode {
+        DARK_LEVELS
+    } else {
+        LIGHT_LEVELS
+    };
+    let font = egui::FontId::proportional(10.0);
+    let text_color = visuals.weak_text_color();
+
+    for (row, label) in WEEKDAY_LABELS {
+        painter.text(
+            rect.min + egui::vec2(0.0, grid_offset(row, HEADER_HEIGHT) + CELL_SIZE / 2.0),
+            egui::Align2::LEFT_CENTER,
+            label,
+            font.clone(),
+            text_color,
+        );
+    }
+
+    let cell_rect = |column: u16, row: u16| {
+        let min = rect.min
+            + egui::vec2(
+                grid_offset(column, LABEL_WIDTH),
+                grid_offset(row, HEADER_HEIGHT),
+            );
+        egui::Rect::from_min_size(min, egui::vec2(CELL_SIZE, CELL_SIZE))
+    };
+
+    let mut hovered = None;
+    let hover_pos = response.hover_pos();
+    for (column, week_start) in (0..).zip(&weeks) {
+        // Name a month above the first week starting in it
+        if week_start.day() <= 7 {
+            painter.text(
+                cell_rect(column, 0).left_top() - egui::vec2(0.0, 2.0),
+                egui::Align2::LEFT_BOTTOM,
+                week_start.format("%b").to_string(),
+                font.clone(),
+                text_color,
+            );
+        }
+
+        for row in 0..7 {
+            let day = *week_start + Duration::days(i64::from(row));
+            if day > last_day {
+                break;
+            }
+            let cell = cell_rect(column, row);
+            let fill = match calendar.level(day) {
+                0 => visuals.faint_bg_color,
+                level => levels[level - 1],
+            };
+            painter.rect_filled(cell, 2.0, fill);
+            if selected == Some(day) {
+                painter.rect_stroke(
+                    cell.expand(1.0),
+                    2.0,
+                    egui::Stroke::new(1.5, visuals.selection.stroke.color),
+                    egui::StrokeKind::Outside,
+                );
+            }
+            if hover_pos.is_some_and(|pointer| cell.contains(pointer)) {
+                hovered = Some(day);
+            }
+        }
+    }
+
+    let day = hovered?;
+    let count = calendar.count(day);
+    let response = response.on_hover_text_at_pointer(format!(
+        "{} commit{} on {}",
+        count,
+        if count == 1 { "" } else { "s" },
+        day.format("%a %-d %b %Y")
+    ));
+    response.clicked().then_some(day)
+}
+
+/// Position of the `cells`-th row or column past `start`
+fn grid_offset(cells: u16, start: f32) -> f32 {
+    f32::from(cells).mul_add(CELL_SIZE + CELL_GAP, start)
+}
@@ -1,13 +1,21 @@
+use crate::git::{DiffEndpoint, FormatPatchOptions, SignatureStatus};
 use crate::models::GitCommit;
 use crate::state::{AppConfig, AppState};
-use crate::ui::graph::CommitGraphRenderer;
+use crate::ui::graph::{
+    CommitGraphRenderer, GraphExportColumn, GraphExportConfig, GraphExportFormat, GraphExportTheme,
+};
 use eframe::egui;
+use std::ops::Range;
+use std::path::PathBuf;
 
 pub struct CommitGraph {
     selected_index: Option<usize>,
     graph_renderer: CommitGraphRenderer,
     view_mode: GraphViewMode,
     show_advanced_graph: bool,
+    note_dialog: NoteEditorDialog,
+    patch_dialog: PatchExportDialog,
+    graph_export_dialog: GraphExportDialog,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -24,10 +32,15 @@ impl CommitGraph {
             graph_renderer: CommitGraphRenderer::new(),
             view_mode: GraphViewMode::Advanced,
             show_advanced_graph: true,
+            note_dialog: NoteEditorDialog::new(),
+            patch_dialog: PatchExportDialog::new(),
+            graph_export_dialog: GraphExportDialog::new(),
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, state: &mut AppState, config: &AppConfig) {
+        let mut open_export = false;
+
         // Header with view mode controls
         ui.horizontal(|ui| {
             ui.heading("Commits");
@@ -54,6 +67,14 @@ impl CommitGraph {
                         );
                     });
 
+                if ui
+                    .button("🖼 Export")
+                    .on_hover_text("Save the graph as an SVG or PNG image")
+                    .clicked()
+                {
+                    open_export = true;
+                }
+
                 // Graph controls
                 if self.view_mode != GraphViewMode::Simple {
                     ui.separator();
@@ -100,6 +121,12 @@ impl CommitGraph {
             return;
         }
 
+        if open_export {
+            let rows = self.export_rows(&filtered_commits, state);
+            self.graph_export_dialog
+                .show_dialog(rows, filtered_commits.len());
+        }
+
         // Show commits based on view mode
         match self.view_mode {
             GraphViewMode::Simple => {
@@ -112,6 +139,63 @@ impl CommitGraph {
                 self.show_hybrid_view(ui, &filtered_commits, state, config);
             }
         }
+
+        if let Some((commit_id, message)) = self.note_dialog.show(ui.ctx()) {
+            state.set_commit_note(&commit_id, &message);
+        }
+
+        if let Some(request) = self.patch_dialog.show(ui.ctx()) {
+            let PatchExportRequest {
+                commit_ids,
+                options,
+                target,
+            } = request;
+            match target {
+                PatchExportTarget::Directory(dir) => {
+                    state.write_patch_series(&commit_ids, &options, &dir);
+                }
+                PatchExportTarget::Mbox(path) => {
+                    state.write_patch_mbox(&commit_ids, &options, &path);
+                }
+                PatchExportTarget::Clipboard => {
+                    if let Some(text) = state.format_patch_text(&commit_ids, &options) {
+                        ui.ctx().copy_text(text);
+                    }
+                }
+            }
+        }
+
+        if let Some(request) = self.graph_export_dialog.show(ui.ctx()) {
+            let written = self
+                .graph_renderer
+                .export_graph(&filtered_commits, state, &request.config, request.format)
+                .and_then(|data| Ok(std::fs::write(&request.path, data)?));
+            if let Err(e) = written {
+                state.error_message = Some(format!("Failed to export graph: {e}"));
+            }
+        }
+    }
+
+    /// Rows offered for export: the graph selection, or a page from the
+    /// selected commit
+    fn export_rows(&self, commits: &[GitCommit], state: &AppState) -> Range<usize> {
+        let selected: Vec<usize> = self
+            .graph_renderer
+            .get_selected_commits()
+            .iter()
+            .filter_map(|id| commits.iter().position(|commit| &commit.id == id))
+            .collect();
+        match (selected.iter().min(), selected.iter().max()) {
+            (Some(&first), Some(&last)) if first < last => first..last + 1,
+            _ => {
+                let start = state
+                    .selected_commit
+                    .as_ref()
+                    .and_then(|id| commits.iter().position(|commit| &commit.id == id))
+                    .unwrap_or(0);
+                start..(start + 50).min(commits.len())
+            }
+        }
     }
 
     /// Show the original simple list view
@@ -124,6 +208,27 @@ impl CommitGraph {
     ) {
         let mut clicked_commit = None;
 
+        // Selection moved outside the list, e.g. jumping between path matches
+        let scroll_to_selected = state.scroll_to_selected;
+        if scroll_to_selected {
+            self.selected_index = state.selected_commit_index;
+            state.scroll_to_selected = false;
+        }
+
+        // Keep the selected commit highlighted when the list reloads beneath it
+        let relisted = state.selected_commit.as_ref().and_then(|selected| {
+            let listed = self.selected_index.and_then(|index| commits.get(index));
+            if listed.is_some_and(|commit| &commit.id == selected) {
+                None
+            } else {
+                commits.iter().position(|commit| &commit.id == selected)
+            }
+        });
+        if let Some(index) = relisted {
+            self.selected_index = Some(index);
+            state.selected_commit_index = Some(index);
+        }
+
         egui::ScrollArea::vertical().show(ui, |ui| {
             for (index, commit) in commits.iter().enumerate() {
                 let is_selected = self.selected_index == Some(index);
@@ -131,9 +236,22 @@ impl CommitGraph {
                 ui.push_id(index, |ui| {
                     let response = self.show_commit_row(ui, commit, is_selected, config, state);
 
+                    if is_selected && scroll_to_selected {
+                        response.scroll_to_me(Some(egui::Align::Center));
+                    }
+
+                    // Verify lazily so only rows that are actually shown spawn a verifier
+                    if commit.is_signed && ui.is_rect_visible(response.rect) {
+                        state.ensure_commit_signature(commit);
+                    }
+
                     if response.clicked() {
                         clicked_commit = Some((index, commit.id.clone()));
                     }
+
+                    response.context_menu(|ui| {
+                        self.show_commit_actions(ui, &commit.id, state);
+                    });
                 });
             }
         });
@@ -254,19 +372,37 @@ impl CommitGraph {
                 ui.visuals().selection.bg_fill
             } else if response.hovered() {
                 ui.visuals().widgets.hovered.bg_fill
+            } else if state.is_path_match(&commit.id) {
+                egui::Color32::from_rgba_unmultiplied(255, 220, 0, 40)
             } else {
                 ui.visuals().panel_fill
             };
 
             painter.rect_filled(rect, 2.0, bg_color);
 
+            // Highlight layer: matches stand out, everything else fades back
+            let emphasis = state.highlight_match(&commit.id);
+            let fade = |color: egui::Color32| match emphasis {
+                Some(false) => color.gamma_multiply(0.35),
+                _ => color,
+            };
+            if emphasis == Some(true) {
+                let bar = egui::Rect::from_min_size(rect.min, egui::vec2(3.0, rect.height()));
+                painter.rect_filled(bar, 0.0, ui.visuals().selection.stroke.color);
+            }
+
             // Graph visualization (simplified)
             let graph_width = 20.0;
             let graph_rect =
                 egui::Rect::from_min_size(rect.min, egui::vec2(graph_width, rect.height()));
             // Draw a simple dot for each commit
             let center = graph_rect.center();
-            painter.circle_filled(center, 6.0, egui::Color32::from_rgb(100, 150, 255));
+            let dot_radius = if emphasis == Some(true) { 7.5 } else { 6.0 };
+            painter.circle_filled(
+                center,
+                dot_radius,
+                fade(egui::Color32::from_rgb(100, 150, 255)),
+            );
 
             // Text area
             let text_rect = egui::Rect::from_min_size(
@@ -277,8 +413,10 @@ impl CommitGraph {
             // Commit info
             let text_color = if is_selected {
                 ui.visuals().selection.stroke.color
+            } else if emphasis == Some(true) {
+                ui.visuals().strong_text_color()
             } else {
-                ui.visuals().text_color()
+                fade(ui.visuals().text_color())
             };
 
             // Short ID and message
@@ -304,6 +442,16 @@ impl CommitGraph {
                 egui::FontId::proportional(12.0),
                 text_color,
             );
+            if emphasis == Some(true) {
+                // Overstrike for a bold summary; the default fonts have no bold face
+                painter.text(
+                    text_rect.min + egui::vec2(80.6, 0.0),
+                    egui::Align2::LEFT_TOP,
+                    &message,
+                    egui::FontId::proportional(12.0),
+                    text_color,
+                );
+            }
 
             // Author and date
             let author_text = format!("{}", commit.author.name);
@@ -314,7 +462,7 @@ impl CommitGraph {
                 egui::Align2::LEFT_TOP,
                 &author_text,
                 egui::FontId::proportional(11.0),
-                ui.visuals().weak_text_color(),
+                fade(ui.visuals().weak_text_color()),
             );
 
             painter.text(
@@ -322,9 +470,32 @@ impl CommitGraph {
                 egui::Align2::LEFT_TOP,
                 &date_text,
                 egui::FontId::proportional(11.0),
-                ui.visuals().weak_text_color(),
+                fade(ui.visuals().weak_text_color()),
             );
 
+            // Signature badge
+            if commit.is_signed {
+                let (badge_text, badge_color) = state.get_commit_signature(&commit.id).map_or_else(
+                    || ("🔏 signed".to_string(), egui::Color32::GRAY),
+                    |verification| {
+                        let label = verification.status.label();
+                        let text = verification.signer.as_ref().map_or_else(
+                            || format!("🔏 {label}"),
+                            |signer| format!("🔏 {label} ({signer})"),
+                        );
+                        (text, signature_color(verification.status))
+                    },
+                );
+
+                painter.text(
+                    text_rect.min + egui::vec2(120.0, 35.0),
+                    egui::Align2::LEFT_TOP,
+                    &badge_text,
+                    egui::FontId::proportional(10.0),
+                    badge_color,
+                );
+            }
+
             // Parent count indicator
             if commit.parent_ids.len() > 1 {
                 let merge_text = format!("Merge ({})", commit.parent_ids.len());
@@ -384,59 +555,520 @@ impl CommitGraph {
     /// Show context menu for commit operations
     fn show_context_menu(&mut self, ui: &mut egui::Ui, commit_id: &str, state: &mut AppState) {
         ui.menu_button("⋮", |ui| {
-            ui.set_min_width(150.0);
+            self.show_commit_actions(ui, commit_id, state);
+        });
+    }
 
-            if ui.button("📋 Copy commit ID").clicked() {
-                ui.output_mut(|o| o.copied_text = commit_id.to_string());
-                ui.close_menu();
-            }
+    /// Commit actions shared by the "⋮" menu and the row context menu
+    fn show_commit_actions(&mut self, ui: &mut egui::Ui, commit_id: &str, state: &mut AppState) {
+        ui.set_min_width(150.0);
 
-            if ui.button("📋 Copy short ID").clicked() {
-                let short_id = if commit_id.len() >= 7 {
-                    &commit_id[..7]
-                } else {
-                    commit_id
-                };
-                ui.output_mut(|o| o.copied_text = short_id.to_string());
-                ui.close_menu();
-            }
+        if ui.button("📋 Copy commit ID").clicked() {
+            ui.ctx().copy_text(commit_id.to_string());
+            ui.close();
+        }
 
-            ui.separator();
+        if ui.button("📋 Copy short ID").clicked() {
+            let short_id = if commit_id.len() >= 7 {
+                &commit_id[..7]
+            } else {
+                commit_id
+            };
+            ui.ctx().copy_text(short_id.to_string());
+            ui.close();
+        }
 
-            if ui.button("🔍 Show in diff view").clicked() {
-                state.select_commit(commit_id.to_string());
-                ui.close_menu();
-            }
+        ui.separator();
 
-            if ui.button("📊 Show commit details").clicked() {
-                // Could open detailed commit view
-                state.select_commit(commit_id.to_string());
-                ui.close_menu();
-            }
+        if ui.button("🔍 Show in diff view").clicked() {
+            state.select_commit(commit_id.to_string());
+            ui.close();
+        }
 
-            ui.separator();
+        if ui.button("📊 Show commit details").clicked() {
+            // Could open detailed commit view
+            state.select_commit(commit_id.to_string());
+            ui.close();
+        }
+
+        ui.separator();
 
-            if ui.button("🌿 Create branch here").clicked() {
-                // Could open branch creation dialog
+        // gitk's "selected" is the other commit of a two-commit graph
+        // selection, else the commit selected in the list
+        let selected = self.graph_renderer.get_selected_commits();
+        let other = if selected.len() == 2 && selected.iter().any(|id| id == commit_id) {
+            selected.iter().find(|id| *id != commit_id).cloned()
+        } else {
+            state.selected_commit.clone().filter(|id| id != commit_id)
+        };
+        let this = DiffEndpoint::Commit(commit_id.to_string());
+        if let Some(other) = other {
+            let other = DiffEndpoint::Commit(other);
+            if ui.button("↔ Diff this → selected").clicked() {
+                state.compare_endpoints(this.clone(), other.clone());
                 ui.close_menu();
             }
-
-            if ui.button("🏷️ Create tag here").clicked() {
-                // Could open tag creation dialog
+            if ui.button("↔ Diff selected → this").clicked() {
+                state.compare_endpoints(other, this.clone());
                 ui.close_menu();
             }
+        }
+        if ui.button("↔ Diff this → working tree").clicked() {
+            state.compare_endpoints(this.clone(), DiffEndpoint::WorkingTree);
+            ui.close();
+        }
+        if ui.button("↔ Diff this → index").clicked() {
+            state.compare_endpoints(this, DiffEndpoint::Index);
+            ui.close();
+        }
 
-            ui.separator();
+        ui.separator();
 
-            if ui.button("🔄 Reset to this commit").clicked() {
-                // Could show reset confirmation dialog
-                ui.close_menu();
-            }
+        // Export the whole graph selection when the clicked commit is part of it
+        let selected = self.graph_renderer.get_selected_commits();
+        let commit_ids = if selected.len() > 1 && selected.iter().any(|id| id == commit_id) {
+            selected.to_vec()
+        } else {
+            vec![commit_id.to_string()]
+        };
+        let label = if commit_ids.len() > 1 {
+            format!("📤 Export {} commits as patches...", commit_ids.len())
+        } else {
+            "📤 Export as patch...".to_string()
+        };
+        if ui.button(label).clicked() {
+            self.patch_dialog.show_dialog(commit_ids);
+            ui.close();
+        }
 
-            if ui.button("🍒 Cherry-pick").clicked() {
-                // Could initiate cherry-pick operation
-                ui.close_menu();
-            }
-        });
+        ui.separator();
+
+        let note = state.get_commit_note(commit_id);
+        let label = if note.is_some() {
+            "📝 Edit note..."
+        } else {
+            "📝 Add note..."
+        };
+        if ui.button(label).clicked() {
+            self.note_dialog.show_dialog(commit_id, note.as_deref());
+            ui.close();
+        }
+        if note.is_some() && ui.button("🗑 Remove note").clicked() {
+            state.remove_commit_note(commit_id);
+            ui.close();
+        }
+
+        ui.separator();
+
+        if ui.button("🌿 Create branch here").clicked() {
+            // Could open branch creation dialog
+            ui.close();
+        }
+
+        if ui.button("🏷️ Create tag here").clicked() {
+            // Could open tag creation dialog
+            ui.close();
+        }
+
+        ui.separator();
+
+        if ui.button("🔄 Reset to this commit").clicked() {
+            // Could show reset confirmation dialog
+            ui.close();
+        }
+
+        if ui.button("🍒 Cherry-pick").clicked() {
+            // Could initiate cherry-pick operation
+            ui.close();
+        }
+    }
+}
+
+/// A commit ID and the note text saved for it
+pub type SavedNote = (String, String);
+
+/// Dialog for adding or editing a commit's note
+pub struct NoteEditorDialog {
+    commit_id: String,
+    note_text: String,
+    is_edit: bool,
+    show: bool,
+}
+
+impl Default for NoteEditorDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoteEditorDialog {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            commit_id: String::new(),
+            note_text: String::new(),
+            is_edit: false,
+            show: false,
+        }
+    }
+
+    pub fn show_dialog(&mut self, commit_id: &str, existing_note: Option<&str>) {
+        self.show = true;
+        self.commit_id = commit_id.to_string();
+        self.note_text = existing_note.unwrap_or("").trim_end().to_string();
+        self.is_edit = existing_note.is_some();
+    }
+
+    /// Returns the commit ID and note text once the user saves
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<SavedNote> {
+        if !self.show {
+            return None;
+        }
+
+        let mut result = None;
+        let mut keep_open = true;
+        let title = if self.is_edit {
+            "Edit Note"
+        } else {
+            "Add Note"
+        };
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Commit:");
+                        ui.monospace(&self.commit_id[..self.commit_id.len().min(7)]);
+                    });
+
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.note_text)
+                            .desired_rows(6)
+                            .desired_width(400.0),
+                    );
+
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        let can_save = !self.note_text.trim().is_empty();
+
+                        if ui
+                            .add_enabled(can_save, egui::Button::new("Save"))
+                            .clicked()
+                        {
+                            result = Some((self.commit_id.clone(), self.note_text.clone()));
+                            keep_open = false;
+                        }
+
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            });
+
+        if !keep_open {
+            self.show = false;
+        }
+
+        result
+    }
+}
+
+/// Where an exported patch series goes
+pub enum PatchExportTarget {
+    Directory(PathBuf), // One numbered file per commit
+    Mbox(PathBuf),      // A single mbox file
+    Clipboard,
+}
+
+pub struct PatchExportRequest {
+    pub commit_ids: Vec<String>,
+    pub options: FormatPatchOptions,
+    pub target: PatchExportTarget,
+}
+
+/// Dialog for exporting commits as a `git format-patch` series
+pub struct PatchExportDialog {
+    commit_ids: Vec<String>,
+    options: FormatPatchOptions,
+    show: bool,
+}
+
+impl Default for PatchExportDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PatchExportDialog {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            commit_ids: Vec::new(),
+            options: FormatPatchOptions::default(),
+            show: false,
+        }
+    }
+
+    pub fn show_dialog(&mut self, commit_ids: Vec<String>) {
+        self.show = true;
+        self.options.cover_letter = commit_ids.len() > 1 && self.options.cover_letter;
+        self.commit_ids = commit_ids;
+    }
+
+    /// Returns the export to perform once the user picks a destination
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<PatchExportRequest> {
+        if !self.show {
+            return None;
+        }
+
+        let mut target = None;
+        let mut keep_open = true;
+        let count = self.commit_ids.len();
+
+        egui::Window::new("Export Patches")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(if count == 1 {
+                    "1 commit".to_string()
+                } else {
+                    format!("{count} commits, exported oldest first")
+                });
+                ui.horizontal_wrapped(|ui| {
+                    for commit_id in self.commit_ids.iter().take(8) {
+                        ui.monospace(&commit_id[..commit_id.len().min(7)]);
+                    }
+                    if count > 8 {
+                        ui.weak(format!("and {} more", count - 8));
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Subject prefix:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.options.subject_prefix)
+                            .desired_width(120.0),
+                    );
+                });
+                ui.checkbox(&mut self.options.numbered, "Number patches")
+                    .on_hover_text("A series of more than one patch is always numbered");
+                ui.add_enabled(
+                    count > 1,
+                    egui::Checkbox::new(&mut self.options.cover_letter, "Cover letter"),
+                );
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Save files...").clicked() {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            target = Some(PatchExportTarget::Directory(dir));
+                        }
+                    }
+                    if ui.button("📦 Save mbox...").clicked() {
+                        let file_name = match self.commit_ids.as_slice() {
+                            [commit_id] => {
+                                format!("{}.patch", &commit_id[..commit_id.len().min(7)])
+                            }
+                            _ => "series.mbox".to_string(),
+                        };
+                        if let Some(path) =
+                            rfd::FileDialog::new().set_file_name(file_name).save_file()
+                        {
+                            target = Some(PatchExportTarget::Mbox(path));
+                        }
+                    }
+                    if ui.button("📋 Copy").clicked() {
+                        target = Some(PatchExportTarget::Clipboard);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        keep_open = false;
+                    }
+                });
+            });
+
+        if !keep_open {
+            self.show = false;
+            return None;
+        }
+
+        let target = target?;
+        self.show = false;
+        Some(PatchExportRequest {
+            commit_ids: std::mem::take(&mut self.commit_ids),
+            options: self.options.clone(),
+            target,
+        })
+    }
+}
+
+pub struct GraphExportRequest {
+    pub path: PathBuf,
+    pub format: GraphExportFormat,
+    pub config: GraphExportConfig,
+}
+
+/// Dialog for saving rows of the commit graph as an SVG or PNG image
+pub struct GraphExportDialog {
+    config: GraphExportConfig,
+    first_row: usize, // One-based and inclusive, as shown in the dialog
+    last_row: usize,
+    row_count: usize,
+    fit_width: bool,
+    width: u32,
+    show: bool,
+}
+
+impl Default for GraphExportDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphExportDialog {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            config: GraphExportConfig::default(),
+            first_row: 1,
+            last_row: 1,
+            row_count: 0,
+            fit_width: true,
+            width: 1200,
+            show: false,
+        }
+    }
+
+    pub fn show_dialog(&mut self, rows: Range<usize>, row_count: usize) {
+        self.show = true;
+        self.row_count = row_count;
+        self.first_row = rows.start + 1;
+        self.last_row = rows.end.max(rows.start + 1);
+    }
+
+    /// Returns the export to perform once the user picks a file
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<GraphExportRequest> {
+        if !self.show {
+            return None;
+        }
+
+        let mut target = None;
+        let mut keep_open = true;
+
+        egui::Window::new("Export Graph")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Rows:");
+                    ui.add(egui::DragValue::new(&mut self.first_row).range(1..=self.row_count));
+                    ui.label("to");
+                    ui.add(
+                        egui::DragValue::new(&mut self.last_row)
+                            .range(self.first_row..=self.row_count),
+                    );
+                    ui.weak(format!("of {}", self.row_count));
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Width:");
+                    ui.checkbox(&mut self.fit_width, "Fit text");
+                    ui.add_enabled(
+                        !self.fit_width,
+                        egui::DragValue::new(&mut self.width)
+                            .range(200..=10000)
+                            .suffix(" px"),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Theme:");
+                    ui.radio_value(&mut self.config.theme, GraphExportTheme::Light, "Light");
+                    ui.radio_value(&mut self.config.theme, GraphExportTheme::Dark, "Dark");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Columns:");
+                    let columns = &mut self.config.columns;
+                    for column in GraphExportColumn::ALL {
+                        let mut shown = columns.contains(&column);
+                        if ui.checkbox(&mut shown, column.label()).changed() {
+                            if shown {
+                                columns.push(column);
+                            } else {
+                                columns.retain(|c| *c != column);
+                            }
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("PNG scale:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.config.scale)
+                            .range(1.0..=4.0)
+                            .speed(0.1)
+                            .suffix("×"),
+                    );
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    for (label, format, extension) in [
+                        ("💾 Save SVG...", GraphExportFormat::Svg, "svg"),
+                        ("💾 Save PNG...", GraphExportFormat::Png, "png"),
+                    ] {
+                        if ui.button(label).clicked() {
+                            if let Some(path) = rfd::FileDialog::new()
+                                .add_filter(extension.to_uppercase(), &[extension])
+                                .set_file_name(format!("graph.{extension}"))
+                                .save_file()
+                            {
+                                target = Some((path, format));
+                            }
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        keep_open = false;
+                    }
+                });
+            });
+
+        if !keep_open {
+            self.show = false;
+            return None;
+        }
+
+        let (path, format) = target?;
+        self.show = false;
+        self.config.rows = self.first_row - 1..self.last_row;
+        self.config.width = (!self.fit_width).then_some(self.width);
+        Some(GraphExportRequest {
+            path,
+            format,
+            config: self.config.clone(),
+        })
+    }
+}
+
+/// Badge color for a signature verification status
+#[must_use]
+pub const fn signature_color(status: SignatureStatus) -> egui::Color32 {
+    match status {
+        SignatureStatus::Good => egui::Color32::from_rgb(100, 200, 100), // Green
+        SignatureStatus::Bad | SignatureStatus::Revoked => egui::Color32::from_rgb(255, 80, 80), // Red
+        SignatureStatus::UnknownKey | SignatureStatus::Expired => {
+            egui::Color32::from_rgb(255, 180, 60) // Orange
+        }
+        SignatureStatus::Unsigned | SignatureStatus::Error | SignatureStatus::Pending => {
+            egui::Color32::GRAY
+        }
     }
 }
@@ -1,9 +1,16 @@
-use crate::models::{DiffStatus, GitDiff, GitDiffLine};
+use crate::git::{
+    CommitRefContext, DiffEndpoint, MailPatch, PatchApplyConfig, PatchApplyResult,
+    PatchApplyTarget, SubmoduleLogSummary,
+};
+use crate::models::{DiffStatus, GitCommit, GitDiff, GitDiffLine, SubmoduleChange};
 use crate::state::{AppConfig, AppState};
 use eframe::egui;
 use regex::Regex;
 use std::collections::HashMap;
 
+/// A submodule's log summary, or why it could not be read
+type SubmoduleSummary = Result<SubmoduleLogSummary, String>;
+
 pub struct DiffViewer {
     show_line_numbers: bool,
     font_size: f32,
@@ -26,6 +33,18 @@ pub struct DiffViewer {
     current_match: usize,
     syntax_highlighter: SyntaxHighlighter,
     word_diff_engine: WordDiffEngine,
+    submodule_summaries: HashMap<SubmoduleChange, SubmoduleSummary>,
+    pending_submodule: Option<SubmoduleChange>, // "Open submodule" clicked this frame
+    patch_apply_config: PatchApplyConfig,
+    followed_file: Option<String>, // Last file picked in the file list
+    scroll_to_file: Option<usize>, // File the unified view scrolls to once
+}
+
+/// How to go on after a patch stopped with conflicts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatchResume {
+    Continue, // Commit the resolved patch
+    Skip,     // Drop the patch
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -280,6 +299,11 @@ impl DiffViewer {
             current_match: 0,
             syntax_highlighter: SyntaxHighlighter::new(),
             word_diff_engine: WordDiffEngine::new(),
+            submodule_summaries: HashMap::new(),
+            pending_submodule: None,
+            patch_apply_config: PatchApplyConfig::default(),
+            followed_file: None,
+            scroll_to_file: None,
         }
     }
 
@@ -349,8 +373,38 @@ impl DiffViewer {
 
         ui.separator();
 
+        let previewing_patch = state.get_previewed_patch().is_some();
+        let showing_patch_file = !previewing_patch && state.opened_patch.is_some();
+        let comparing = !previewing_patch && !showing_patch_file && state.compared_diff.is_some();
+        let viewing_file =
+            !previewing_patch && !showing_patch_file && !comparing && state.tree_file.is_some();
+        if previewing_patch {
+            self.show_patch_preview(ui, state);
+            ui.separator();
+        } else if showing_patch_file {
+            self.show_patch_file_header(ui, state);
+            ui.separator();
+        } else if comparing {
+            self.show_compared_header(ui, state);
+            ui.separator();
+        } else if let Some(commit) = state.get_selected_commit() {
+            Self::show_commit_details(ui, commit, state.get_commit_ref_context(&commit.id));
+            ui.separator();
+        }
+
+        if viewing_file {
+            self.show_tree_file(ui, state);
+            return;
+        }
+
         // Get available diffs
-        let diffs = if let Some(ref selected_commit) = state.get_selected_commit() {
+        let diffs = if previewing_patch {
+            state.previewed_patch_diffs.clone()
+        } else if let Some(ref patch) = state.opened_patch {
+            patch.diffs.clone()
+        } else if let Some(ref compared) = state.compared_diff {
+            compared.diffs.clone()
+        } else if let Some(selected_commit) = state.get_selected_commit() {
             if let Some(ref repo) = state.repository {
                 match repo.get_commit_diff_enhanced(&selected_commit.id) {
                     Ok(diffs) => diffs,
@@ -378,6 +432,21 @@ impl DiffViewer {
             return;
         }
 
+        // Jump to a file picked in the file list
+        let picked = state.selected_files.first();
+        if picked != self.followed_file.as_ref() {
+            self.followed_file = picked.cloned();
+            let index = picked.and_then(|path| {
+                diffs.iter().position(|diff| {
+                    diff.new_file.as_ref() == Some(path) || diff.old_file.as_ref() == Some(path)
+                })
+            });
+            if let Some(index) = index {
+                self.current_file_index = index;
+                self.scroll_to_file = Some(index);
+            }
+        }
+
         // Show file navigation bar (except for split view which has its own tree)
         if self.view_mode != DiffViewMode::Split && diffs.len() > 1 {
             self.show_file_navigation_bar(ui, &diffs);
@@ -391,14 +460,28 @@ impl DiffViewer {
             DiffViewMode::Split => self.show_split_view(ui, &diffs, state),
             DiffViewMode::InlineChanges => self.show_inline_changes_view(ui, &diffs, state),
         }
+
+        if let Some(change) = self.pending_submodule.take() {
+            self.submodule_summaries.clear();
+            state.open_submodule(&change);
+        }
     }
 
     /// Show unified diff view (traditional single-column diff)
-    fn show_unified_view(&mut self, ui: &mut egui::Ui, diffs: &[GitDiff], _state: &AppState) {
+    fn show_unified_view(&mut self, ui: &mut egui::Ui, diffs: &[GitDiff], state: &AppState) {
         egui::ScrollArea::vertical().show(ui, |ui| {
             for (file_idx, diff) in diffs.iter().enumerate() {
+                if self.scroll_to_file == Some(file_idx) {
+                    self.scroll_to_file = None;
+                    ui.scroll_to_cursor(Some(egui::Align::TOP));
+                }
                 self.show_file_header(ui, diff, file_idx);
 
+                if let Some(ref change) = diff.submodule {
+                    self.show_submodule_summary(ui, change, state);
+                    continue;
+                }
+
                 if diff.is_binary {
                     self.show_binary_file_info(ui, diff);
                     continue;
@@ -423,7 +506,7 @@ impl DiffViewer {
     }
 
     /// Show side-by-side diff view
-    fn show_side_by_side_view(&mut self, ui: &mut egui::Ui, diffs: &[GitDiff], _state: &AppState) {
+    fn show_side_by_side_view(&mut self, ui: &mut egui::Ui, diffs: &[GitDiff], state: &AppState) {
         if diffs.is_empty() {
             return;
         }
@@ -448,6 +531,11 @@ impl DiffViewer {
             ui.separator();
         }
 
+        if let Some(ref change) = diff.submodule {
+            self.show_submodule_summary(ui, change, state);
+            return;
+        }
+
         if diff.is_binary {
             self.show_binary_file_info(ui, diff);
             return;
@@ -485,7 +573,7 @@ impl DiffViewer {
     }
 
     /// Show split view with file tree
-    fn show_split_view(&mut self, ui: &mut egui::Ui, diffs: &[GitDiff], _state: &AppState) {
+    fn show_split_view(&mut self, ui: &mut egui::Ui, diffs: &[GitDiff], state: &AppState) {
         ui.horizontal(|ui| {
             // File tree panel (only show if enabled)
             if self.show_file_tree {
@@ -502,22 +590,22 @@ impl DiffViewer {
 
             // Main diff content
             if self.current_file_index < diffs.len() {
-                self.show_side_by_side_view(ui, &[diffs[self.current_file_index].clone()], _state);
+                self.show_side_by_side_view(ui, &[diffs[self.current_file_index].clone()], state);
             }
         });
     }
 
     /// Show inline changes view with word-level highlighting
-    fn show_inline_changes_view(
-        &mut self,
-        ui: &mut egui::Ui,
-        diffs: &[GitDiff],
-        _state: &AppState,
-    ) {
+    fn show_inline_changes_view(&mut self, ui: &mut egui::Ui, diffs: &[GitDiff], state: &AppState) {
         egui::ScrollArea::vertical().show(ui, |ui| {
             for (file_idx, diff) in diffs.iter().enumerate() {
                 self.show_file_header(ui, diff, file_idx);
 
+                if let Some(ref change) = diff.submodule {
+                    self.show_submodule_summary(ui, change, state);
+                    continue;
+                }
+
                 if diff.is_binary {
                     self.show_binary_file_info(ui, diff);
                     continue;
@@ -583,6 +671,525 @@ impl DiffViewer {
         ui.separator();
     }
 
+    /// Show author, committer, message and notes of the selected commit
+    fn show_commit_details(
+        ui: &mut egui::Ui,
+        commit: &GitCommit,
+        context: Option<&CommitRefContext>,
+    ) {
+        egui::CollapsingHeader::new(format!("Commit {}", commit.short_id))
+            .id_salt("commit_details")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("commit_details_grid")
+                    .num_columns(2)
+                    .spacing([8.0, 2.0])
+                    .show(ui, |ui| {
+                        ui.label("Author:");
+                        ui.label(format!(
+                            "{} <{}>  {}",
+                            commit.author.name,
+                            commit.author.email,
+                            commit.author.when.format("%Y-%m-%d %H:%M")
+                        ));
+                        ui.end_row();
+
+                        ui.label("Committer:");
+                        ui.label(format!(
+                            "{} <{}>  {}",
+                            commit.committer.name,
+                            commit.committer.email,
+                            commit.committer.when.format("%Y-%m-%d %H:%M")
+                        ));
+                        ui.end_row();
+
+                        ui.label("Commit:");
+                        ui.monospace(&commit.id);
+                        ui.end_row();
+
+                        if let Some(context) = context {
+                            let list = |names: &[String]| {
+                                if names.is_empty() {
+                                    "none".to_string()
+                                } else {
+                                    names.join(", ")
+                                }
+                            };
+
+                            ui.label("Branches:");
+                            ui.label(list(&context.branches));
+                            ui.end_row();
+
+                            ui.label("Follows:");
+                            ui.label(list(&context.follows));
+                            ui.end_row();
+
+                            ui.label("Precedes:");
+                            ui.label(list(&context.precedes));
+                            ui.end_row();
+
+                            if let Some(ref describe) = context.describe {
+                                ui.label("Describe:");
+                                ui.monospace(describe);
+                                ui.end_row();
+                            }
+                        }
+                    });
+
+                ui.add_space(4.0);
+                ui.label(commit.message.trim_end());
+
+                for note in &commit.notes {
+                    ui.add_space(4.0);
+                    let label = match note.notes_ref.strip_prefix("refs/notes/") {
+                        Some("commits") => "Notes:".to_string(),
+                        Some(name) => format!("Notes ({name}):"),
+                        None => format!("Notes ({}):", note.notes_ref),
+                    };
+                    ui.colored_label(egui::Color32::from_rgb(220, 180, 80), label);
+                    ui.indent(&note.notes_ref, |ui| {
+                        ui.label(note.message.trim_end());
+                    });
+                }
+            });
+    }
+
+    /// Metadata of a patch loaded from a mailbox, with controls to step
+    /// through the series and apply what has not been applied yet
+    fn show_patch_preview(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
+        let count = state.incoming_patches.len();
+        let current = state.previewed_patch.unwrap_or(0);
+        let mut selected = current;
+        let mut close = false;
+        let mut apply = false;
+        let mut resume = None;
+
+        ui.horizontal(|ui| {
+            ui.strong("📨 Patch");
+            egui::ComboBox::from_id_salt("patch_preview_select")
+                .selected_text(format!("{}/{}", current + 1, count))
+                .show_ui(ui, |ui| {
+                    for (index, patch) in state.incoming_patches.iter().enumerate() {
+                        let mark = if index < state.patches_applied {
+                            "✔ "
+                        } else {
+                            ""
+                        };
+                        ui.selectable_value(
+                            &mut selected,
+                            index,
+                            format!("{}{}/{} {}", mark, index + 1, count, patch.subject),
+                        );
+                    }
+                });
+            if ui.button("✖ Close").clicked() {
+                close = true;
+            }
+        });
+
+        if let Some(patch) = state.get_previewed_patch() {
+            Self::show_mail_patch_details(ui, patch);
+        }
+
+        ui.add_space(4.0);
+        let pending = count - state.patches_applied;
+        ui.horizontal(|ui| {
+            ui.label("Apply to:");
+            let target = &mut self.patch_apply_config.target;
+            ui.radio_value(target, PatchApplyTarget::WorkingTree, "Working tree");
+            ui.radio_value(target, PatchApplyTarget::Index, "Index");
+            ui.radio_value(target, PatchApplyTarget::Commit, "New commits");
+            ui.checkbox(&mut self.patch_apply_config.three_way, "3-way fallback")
+                .on_hover_text("Merge using the blobs the patch was made against");
+
+            let label = match pending {
+                1 => "✅ Apply patch".to_string(),
+                _ => format!("✅ Apply {pending} patches"),
+            };
+            if ui
+                .add_enabled(
+                    pending > 0 && state.has_repository(),
+                    egui::Button::new(label),
+                )
+                .clicked()
+            {
+                apply = true;
+            }
+        });
+
+        if let Some(ref result) = state.patch_apply_result {
+            resume = Self::show_patch_apply_result(ui, result);
+        }
+
+        if close {
+            state.close_patch_preview();
+        } else if apply {
+            state.apply_incoming_patches(&self.patch_apply_config);
+        } else if resume == Some(PatchResume::Continue) {
+            state.continue_incoming_patches(&self.patch_apply_config);
+        } else if resume == Some(PatchResume::Skip) {
+            state.skip_incoming_patch(&self.patch_apply_config);
+        } else if selected != current {
+            state.preview_patch(selected);
+            self.current_file_index = 0;
+        }
+    }
+
+    /// Author, date and message of a mailbox patch
+    fn show_mail_patch_details(ui: &mut egui::Ui, patch: &MailPatch) {
+        egui::Grid::new("patch_preview_grid")
+            .num_columns(2)
+            .spacing([8.0, 2.0])
+            .show(ui, |ui| {
+                if let Some(ref author) = patch.author {
+                    ui.label("Author:");
+                    ui.label(format!("{} <{}>", author.name, author.email));
+                    ui.end_row();
+                }
+                if let Some(date) = patch.date {
+                    ui.label("Date:");
+                    ui.label(date.format("%Y-%m-%d %H:%M %z").to_string());
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(4.0);
+        if patch.message.is_empty() {
+            ui.weak("No commit message");
+        } else {
+            ui.label(patch.message.trim_end());
+        }
+    }
+
+    /// Outcome of the last apply; offers continue and skip when it stopped
+    fn show_patch_apply_result(
+        ui: &mut egui::Ui,
+        result: &PatchApplyResult,
+    ) -> Option<PatchResume> {
+        let color = if result.is_success() {
+            egui::Color32::from_rgb(100, 200, 100)
+        } else {
+            egui::Color32::from_rgb(220, 100, 100)
+        };
+        ui.colored_label(color, &result.message);
+        for conflict in &result.conflicts {
+            ui.horizontal(|ui| {
+                ui.label("⚠");
+                ui.monospace(&conflict.path);
+                ui.weak(format!("{:?}", conflict.conflict_type));
+            });
+        }
+        // Only a stopped series can be continued or skipped
+        result.stopped_at?;
+
+        if result.merged {
+            ui.weak("Resolve the conflicts and stage the files, then continue");
+        }
+        let mut resume = None;
+        ui.horizontal(|ui| {
+            if ui
+                .button("▶ Continue")
+                .on_hover_text("Commit the resolved patch and apply the rest")
+                .clicked()
+            {
+                resume = Some(PatchResume::Continue);
+            }
+            if ui
+                .button("⏭ Skip")
+                .on_hover_text("Drop this patch and apply the rest")
+                .clicked()
+            {
+                resume = Some(PatchResume::Skip);
+            }
+        });
+        resume
+    }
+
+    /// File name and totals of an opened patch file, with close and apply
+    fn show_patch_file_header(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
+        let Some(ref patch) = state.opened_patch else {
+            return;
+        };
+        let insertions: usize = patch.diffs.iter().map(|diff| diff.stats.insertions).sum();
+        let deletions: usize = patch.diffs.iter().map(|diff| diff.stats.deletions).sum();
+        let mut close = false;
+        let mut apply = false;
+
+        ui.horizontal(|ui| {
+            let name = patch.path.file_name().map_or_else(
+                || patch.path.display().to_string(),
+                |name| name.to_string_lossy().to_string(),
+            );
+            ui.strong(format!("📄 {name}"))
+                .on_hover_text(patch.path.display().to_string());
+            ui.label(match patch.diffs.len() {
+                1 => "1 file".to_string(),
+                count => format!("{count} files"),
+            });
+            ui.colored_label(
+                egui::Color32::from_rgb(100, 200, 100),
+                format!("+{insertions}"),
+            );
+            ui.colored_label(
+                egui::Color32::from_rgb(220, 100, 100),
+                format!("-{deletions}"),
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("✖ Close").clicked() {
+                    close = true;
+                }
+                if ui
+                    .add_enabled(
+                        state.has_repository(),
+                        egui::Button::new("📥 Apply to repository..."),
+                    )
+                    .on_disabled_hover_text("Open a repository to apply the patch")
+                    .clicked()
+                {
+                    apply = true;
+                }
+            });
+        });
+
+        if close {
+            state.close_patch_file();
+            self.current_file_index = 0;
+        } else if apply {
+            state.apply_opened_patch();
+            self.current_file_index = 0;
+        }
+    }
+
+    /// Show a file opened from the tree browser instead of the diff
+    fn show_tree_file(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
+        let Some(ref file) = state.tree_file else {
+            return;
+        };
+        let mut close = false;
+
+        ui.horizontal(|ui| {
+            ui.strong(format!("📄 {}", file.path));
+            ui.label(format!(
+                "at {}",
+                file.commit_id.chars().take(7).collect::<String>()
+            ));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .button("✖ Close")
+                    .on_hover_text("Back to the diff")
+                    .clicked()
+                {
+                    close = true;
+                }
+            });
+        });
+        ui.separator();
+
+        if file.is_binary {
+            ui.vertical_centered(|ui| {
+                ui.label("Binary file not shown");
+            });
+        } else {
+            let lines: Vec<&str> = file.content.lines().collect();
+            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+            let text_color = ui.visuals().text_color();
+            egui::ScrollArea::both().id_salt("tree_file").show_rows(
+                ui,
+                row_height,
+                lines.len(),
+                |ui, rows| {
+                    for row in rows {
+                        ui.horizontal(|ui| {
+                            if self.show_line_numbers {
+                                ui.monospace(format!("{:>5}", row + 1));
+                                ui.separator();
+                            }
+                            if self.syntax_highlight {
+                                ui.label(self.highlighted_line(lines[row], &file.path, text_color));
+                            } else {
+                                ui.monospace(lines[row]);
+                            }
+                        });
+                    }
+                },
+            );
+        }
+
+        if close {
+            state.close_tree_file();
+        }
+    }
+
+    /// Color a line of a file by the highlighter for its extension
+    fn highlighted_line(
+        &mut self,
+        line: &str,
+        path: &str,
+        text_color: egui::Color32,
+    ) -> egui::text::LayoutJob {
+        let font = egui::FontId::monospace(self.font_size);
+        let mut job = egui::text::LayoutJob::default();
+        let mut position = 0;
+
+        for token in self.syntax_highlighter.highlight_text(line, path) {
+            if token.start < position {
+                continue; // Overlaps the previous token
+            }
+            job.append(
+                &line[position..token.start],
+                0.0,
+                egui::TextFormat::simple(font.clone(), text_color),
+            );
+            let color = self.syntax_highlighter.get_token_color(&token.token_type);
+            job.append(
+                &token.text,
+                0.0,
+                egui::TextFormat::simple(font.clone(), color),
+            );
+            position = token.end;
+        }
+        job.append(
+            &line[position..],
+            0.0,
+            egui::TextFormat::simple(font, text_color),
+        );
+        job
+    }
+
+    /// Name both endpoints of a compared diff, commits with their summaries
+    fn show_compared_header(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
+        let Some(ref compared) = state.compared_diff else {
+            return;
+        };
+        let diffs = &compared.diffs;
+        let insertions: usize = diffs.iter().map(|diff| diff.stats.insertions).sum();
+        let deletions: usize = diffs.iter().map(|diff| diff.stats.deletions).sum();
+        let mut close = false;
+        let mut refresh = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Diff from");
+            show_endpoint(ui, &compared.from, state);
+            ui.label("to");
+            show_endpoint(ui, &compared.to, state);
+            ui.separator();
+            ui.label(match diffs.len() {
+                1 => "1 file".to_string(),
+                count => format!("{count} files"),
+            });
+            ui.colored_label(
+                egui::Color32::from_rgb(100, 200, 100),
+                format!("+{insertions}"),
+            );
+            ui.colored_label(
+                egui::Color32::from_rgb(220, 100, 100),
+                format!("-{deletions}"),
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("✖ Close").clicked() {
+                    close = true;
+                }
+                if ui
+                    .button("🔄")
+                    .on_hover_text("Diff the endpoints again")
+                    .clicked()
+                {
+                    refresh = true;
+                }
+            });
+        });
+
+        if close {
+            state.close_compared_diff();
+            self.current_file_index = 0;
+        } else if refresh {
+            state.refresh_compared_diff();
+        }
+    }
+
+    /// Show the submodule's commit-range log in place of a gitlink diff
+    fn show_submodule_summary(
+        &mut self,
+        ui: &mut egui::Ui,
+        change: &SubmoduleChange,
+        state: &AppState,
+    ) {
+        if !self.submodule_summaries.contains_key(change) {
+            let summary = state
+                .repository
+                .as_ref()
+                .ok_or_else(|| "No repository".to_string())
+                .and_then(|repo| {
+                    repo.get_submodule_log_summary(change)
+                        .map_err(|e| e.to_string())
+                });
+            self.submodule_summaries.insert(change.clone(), summary);
+        }
+
+        let short = |id: &Option<String>| {
+            id.as_deref().map_or_else(
+                || "0000000".to_string(),
+                |id| id[..id.len().min(7)].to_string(),
+            )
+        };
+
+        ui.indent("submodule_summary", |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("📦").size(self.font_size));
+                ui.monospace(format!(
+                    "Submodule {} {}..{}",
+                    change.path,
+                    short(&change.old_commit),
+                    short(&change.new_commit)
+                ));
+
+                if let Some(target) = change.new_commit.as_ref().or(change.old_commit.as_ref()) {
+                    if ui
+                        .button("📂 Open submodule")
+                        .on_hover_text(format!("Browse {} at {}", change.path, target))
+                        .clicked()
+                    {
+                        self.pending_submodule = Some(change.clone());
+                    }
+                }
+            });
+
+            match self.submodule_summaries.get(change) {
+                Some(Ok(summary)) => {
+                    if summary.added.is_empty() && summary.removed.is_empty() {
+                        ui.label("No commits between these revisions");
+                    }
+                    for commit in &summary.added {
+                        ui.colored_label(
+                            egui::Color32::GREEN,
+                            format!("  > {} {}", commit.short_id, commit.summary),
+                        );
+                    }
+                    if !summary.is_fast_forward() {
+                        ui.colored_label(egui::Color32::ORANGE, "Rewound past:");
+                    }
+                    for commit in &summary.removed {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            format!("  < {} {}", commit.short_id, commit.summary),
+                        );
+                    }
+                    if summary.truncated {
+                        ui.label("  …");
+                    }
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GRAY, format!("Log unavailable: {e}"));
+                }
+                None => {}
+            }
+        });
+        ui.separator();
+    }
+
     /// Show binary file information
     fn show_binary_file_info(&self, ui: &mut egui::Ui, diff: &GitDiff) {
         ui.indent("binary_info", |ui| {
@@ -1745,3 +2352,19 @@ impl WordDiffEngine {
         lcs
     }
 }
+
+/// An endpoint's label, with the commit summary on hover
+fn show_endpoint(ui: &mut egui::Ui, endpoint: &DiffEndpoint, state: &AppState) {
+    let label = ui.strong(endpoint.label());
+    let Some(commit_id) = endpoint.commit_id() else {
+        return;
+    };
+    match state.commits.iter().find(|commit| commit.id == commit_id) {
+        Some(commit) => {
+            label.on_hover_text(format!("{}\n{}", commit.id, commit.summary));
+        }
+        None => {
+            label.on_hover_text(commit_id);
+        }
+    }
+}
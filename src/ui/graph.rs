@@ -1,8 +1,12 @@
+use crate::git::{CommitGraphIndex, LaneLayout};
 use crate::models::GitCommit;
 use crate::state::AppState;
 use eframe::egui;
 use std::collections::HashMap;
 
+mod export;
+pub use export::*;
+
 /// Advanced commit graph rendering system
 /// Based on the original gitk's sophisticated branch layout algorithm
 pub struct CommitGraphRenderer {
@@ -10,8 +14,6 @@ pub struct CommitGraphRenderer {
     layout_cache: HashMap<String, GraphLayout>,
     /// Color palette for branches
     branch_colors: Vec<egui::Color32>,
-    /// Maximum number of parallel branches to display
-    max_branches: usize,
     /// Row height for commits
     row_height: f32,
     /// Column width for branches
@@ -242,7 +244,6 @@ impl CommitGraphRenderer {
         Self {
             layout_cache: HashMap::new(),
             branch_colors: Self::create_color_palette(),
-            max_branches: 32,
             row_height: 40.0,
             column_width: 24.0,
             zoom_level: 1.0,
@@ -300,10 +301,10 @@ impl CommitGraphRenderer {
         let layout = self.compute_graph_layout(commits, available_rect.size());
 
         // Handle user interactions
-        let interaction_result = self.handle_interactions(ui, &layout);
+        let interaction_result = self.handle_interactions(ui, &layout, &state.commit_index);
 
         // Render the graph
-        self.render_graph(ui, &layout, commits, available_rect);
+        self.render_graph(ui, &layout, commits, state, available_rect);
 
         // Render commit details on hover
         self.render_hover_tooltip(ui, commits);
@@ -338,18 +339,22 @@ impl CommitGraphRenderer {
         let (parent_map, child_map) = self.build_relationship_maps(commits);
 
         // Step 2: Assign lanes to commits using the gitk algorithm
-        let lane_assignments = self.assign_commit_lanes(commits, &parent_map, &child_map);
+        let lane_assignments = Self::assign_commit_lanes(commits);
 
         // Step 3: Calculate positions for each commit
         // Add margin for better visibility
         let graph_margin_x = 20.0;
         let graph_margin_y = 10.0;
-        
+
         for (row, commit) in commits.iter().enumerate() {
             let lane = lane_assignments.get(&commit.id).unwrap_or(&0);
             let pos = egui::Pos2 {
-                x: graph_margin_x + (*lane as f32) * self.column_width * self.zoom_level + self.pan_offset.x,
-                y: graph_margin_y + (row as f32) * self.row_height * self.zoom_level + self.pan_offset.y,
+                x: graph_margin_x
+                    + (*lane as f32) * self.column_width * self.zoom_level
+                    + self.pan_offset.x,
+                y: graph_margin_y
+                    + (row as f32) * self.row_height * self.zoom_level
+                    + self.pan_offset.y,
             };
 
             let color = self.branch_colors[*lane % self.branch_colors.len()];
@@ -415,130 +420,9 @@ impl CommitGraphRenderer {
         (parent_map, child_map)
     }
 
-    /// Assign lane positions using the sophisticated gitk algorithm
-    fn assign_commit_lanes(
-        &self,
-        commits: &[GitCommit],
-        parent_map: &HashMap<String, Vec<String>>,
-        child_map: &HashMap<String, Vec<String>>,
-    ) -> HashMap<String, usize> {
-        let mut lane_assignments: HashMap<String, usize> = HashMap::new();
-        let mut active_lanes: Vec<Option<String>> = vec![None; self.max_branches];
-        let mut next_free_lane = 0;
-
-        for commit in commits {
-            let commit_id = &commit.id;
-            let parents = parent_map.get(commit_id).cloned().unwrap_or_default();
-
-            // Determine the best lane for this commit
-            let assigned_lane = if parents.is_empty() {
-                // Root commit - assign to next free lane
-                let lane = self.find_free_lane(&active_lanes);
-                if lane >= active_lanes.len() {
-                    active_lanes.resize(lane + 1, None);
-                }
-                active_lanes[lane] = Some(commit_id.clone());
-                lane
-            } else if parents.len() == 1 {
-                // Single parent - try to continue on parent's lane
-                let parent_id = &parents[0];
-                if let Some(parent_lane) = lane_assignments.get(parent_id) {
-                    // Check if parent's lane is available and within bounds
-                    if *parent_lane < active_lanes.len() && active_lanes[*parent_lane].as_ref() == Some(parent_id) {
-                        active_lanes[*parent_lane] = Some(commit_id.clone());
-                        *parent_lane
-                    } else {
-                        // Parent's lane is occupied, find new lane
-                        let lane = self.find_free_lane(&active_lanes);
-                        if lane >= active_lanes.len() {
-                            active_lanes.resize(lane + 1, None);
-                        }
-                        active_lanes[lane] = Some(commit_id.clone());
-                        lane
-                    }
-                } else {
-                    // Parent not found, assign new lane
-                    let lane = self.find_free_lane(&active_lanes);
-                    if lane >= active_lanes.len() {
-                        active_lanes.resize(lane + 1, None);
-                    }
-                    active_lanes[lane] = Some(commit_id.clone());
-                    lane
-                }
-            } else {
-                // Merge commit - complex lane assignment
-                self.assign_merge_commit_lane(
-                    commit_id,
-                    &parents,
-                    &mut active_lanes,
-                    &lane_assignments,
-                )
-            };
-
-            lane_assignments.insert(commit_id.clone(), assigned_lane);
-
-            // Update active lanes based on children
-            self.update_active_lanes_for_children(commit_id, &mut active_lanes, child_map);
-        }
-
-        lane_assignments
-    }
-
-    /// Find the next available lane
-    fn find_free_lane(&self, active_lanes: &[Option<String>]) -> usize {
-        for (i, lane) in active_lanes.iter().enumerate() {
-            if lane.is_none() {
-                return i;
-            }
-        }
-        active_lanes.len() // Expand if needed
-    }
-
-    /// Handle merge commit lane assignment
-    fn assign_merge_commit_lane(
-        &self,
-        commit_id: &str,
-        parents: &[String],
-        active_lanes: &mut Vec<Option<String>>,
-        lane_assignments: &HashMap<String, usize>,
-    ) -> usize {
-        // For merge commits, try to use the lane of the first parent
-        if let Some(first_parent) = parents.first() {
-            if let Some(parent_lane) = lane_assignments.get(first_parent) {
-                if *parent_lane < active_lanes.len()
-                    && active_lanes[*parent_lane].as_ref() == Some(first_parent)
-                {
-                    active_lanes[*parent_lane] = Some(commit_id.to_string());
-                    return *parent_lane;
-                }
-            }
-        }
-
-        // Fallback: assign to free lane
-        let lane = self.find_free_lane(active_lanes);
-        if lane >= active_lanes.len() {
-            active_lanes.resize(lane + 1, None);
-        }
-        active_lanes[lane] = Some(commit_id.to_string());
-        lane
-    }
-
-    /// Update active lanes based on commit children
-    fn update_active_lanes_for_children(
-        &self,
-        commit_id: &str,
-        active_lanes: &mut Vec<Option<String>>,
-        child_map: &HashMap<String, Vec<String>>,
-    ) {
-        // If this commit has no children, its lane becomes free
-        if !child_map.contains_key(commit_id) {
-            for lane in active_lanes.iter_mut() {
-                if lane.as_ref() == Some(&commit_id.to_string()) {
-                    *lane = None;
-                    break;
-                }
-            }
-        }
+    /// Assign lane positions with the layout engine shared with the CLI
+    fn assign_commit_lanes(commits: &[GitCommit]) -> HashMap<String, usize> {
+        LaneLayout::compute(commits).lanes()
     }
 
     /// Create connection lines between parent and child commits
@@ -752,6 +636,7 @@ impl CommitGraphRenderer {
         &mut self,
         ui: &mut egui::Ui,
         layout: &GraphLayout,
+        index: &CommitGraphIndex,
     ) -> GraphInteractionResult {
         let response = ui.interact(
             ui.available_rect_before_wrap(),
@@ -805,7 +690,7 @@ impl CommitGraphRenderer {
                 // Auto-highlight path for single selection
                 if self.interaction_state.selected_commits.len() == 1 {
                     let commit_id_clone = commit_id.clone();
-                    self.auto_highlight_commit_path(&commit_id_clone, layout);
+                    self.auto_highlight_commit_path(&commit_id_clone, layout, index);
                 } else if self.interaction_state.selected_commits.is_empty() {
                     self.highlighted_path = None;
                 }
@@ -816,7 +701,7 @@ impl CommitGraphRenderer {
         if response.double_clicked() {
             if let Some(ref commit_id) = self.interaction_state.hovered_commit {
                 let commit_id_clone = commit_id.clone();
-                self.trace_commit_ancestry(&commit_id_clone, layout);
+                self.trace_commit_ancestry(&commit_id_clone, layout, index);
                 interaction_result.path_traced = true;
             }
         }
@@ -932,6 +817,7 @@ impl CommitGraphRenderer {
         ui: &mut egui::Ui,
         layout: &GraphLayout,
         commits: &[GitCommit],
+        state: &AppState,
         rect: egui::Rect,
     ) {
         let painter = ui.painter();
@@ -961,6 +847,7 @@ impl CommitGraphRenderer {
                         .hovered_commit
                         .as_ref()
                         .unwrap_or(&String::new()),
+                state.highlight_match(commit_id),
             );
         }
 
@@ -1034,21 +921,42 @@ impl CommitGraphRenderer {
         egui::Pos2::new(x, y)
     }
 
-    /// Draw a commit circle
-    fn draw_commit(&self, painter: &egui::Painter, commit_pos: &CommitPosition, is_hovered: bool) {
-        let radius = if is_hovered {
+    /// Draw a commit circle; `highlight` is the highlight layer's verdict, if one is active
+    fn draw_commit(
+        &self,
+        painter: &egui::Painter,
+        commit_pos: &CommitPosition,
+        is_hovered: bool,
+        highlight: Option<bool>,
+    ) {
+        let mut radius = if is_hovered {
             commit_pos.radius * 1.3
         } else {
             commit_pos.radius
         };
         let stroke_width = if is_hovered { 3.0 } else { 2.0 };
+        let (fill, outline) = match highlight {
+            Some(false) => (
+                commit_pos.color.gamma_multiply(0.3),
+                egui::Color32::BLACK.gamma_multiply(0.3),
+            ),
+            _ => (commit_pos.color, egui::Color32::BLACK),
+        };
 
         // Draw commit circle
+        if highlight == Some(true) {
+            radius *= 1.25;
+            painter.circle_stroke(
+                commit_pos.pos,
+                radius + 2.5,
+                egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 200, 0)),
+            );
+        }
         painter.circle(
             commit_pos.pos,
             radius,
-            commit_pos.color,
-            egui::Stroke::new(stroke_width, egui::Color32::BLACK),
+            fill,
+            egui::Stroke::new(stroke_width, outline),
         );
 
         // Draw selection indicator if selected
@@ -1714,7 +1622,12 @@ impl CommitGraphRenderer {
     }
 
     /// Auto-highlight path from a commit to its ancestors
-    fn auto_highlight_commit_path(&mut self, commit_id: &str, layout: &GraphLayout) {
+    fn auto_highlight_commit_path(
+        &mut self,
+        commit_id: &str,
+        layout: &GraphLayout,
+        index: &CommitGraphIndex,
+    ) {
         let mut path = Vec::new();
         let mut current_id = commit_id.to_string();
 
@@ -1723,6 +1636,26 @@ impl CommitGraphRenderer {
             // Limit path length to prevent infinite loops
             path.push(current_id.clone());
 
+            // Prefer the commit index over matching line positions
+            let first_parent = git2::Oid::from_str(&current_id)
+                .ok()
+                .filter(|oid| index.contains(*oid))
+                .map(|oid| {
+                    index
+                        .parents(oid)
+                        .first()
+                        .map(std::string::ToString::to_string)
+                });
+            if let Some(first_parent) = first_parent {
+                match first_parent {
+                    Some(parent_id) => {
+                        current_id = parent_id;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
             // Find commit position to get parent information
             if let Some(commit_pos) = layout.commit_positions.get(&current_id) {
                 // For simplicity, follow the first parent line if available
@@ -1749,7 +1682,24 @@ impl CommitGraphRenderer {
     }
 
     /// Trace commit ancestry for comprehensive path highlighting
-    fn trace_commit_ancestry(&mut self, commit_id: &str, layout: &GraphLayout) {
+    fn trace_commit_ancestry(
+        &mut self,
+        commit_id: &str,
+        layout: &GraphLayout,
+        index: &CommitGraphIndex,
+    ) {
+        if let Ok(oid) = git2::Oid::from_str(commit_id) {
+            if index.contains(oid) {
+                let ancestry_path: Vec<String> = index
+                    .ancestors(oid, 50)
+                    .into_iter()
+                    .map(|ancestor| ancestor.to_string())
+                    .collect();
+                self.highlighted_path = (ancestry_path.len() > 1).then_some(ancestry_path);
+                return;
+            }
+        }
+
         let mut ancestry_path = Vec::new();
         let mut to_visit = vec![commit_id.to_string()];
         let mut visited = std::collections::HashSet::new();
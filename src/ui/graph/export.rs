@@ -0,0 +1,651 @@
+use super::CommitGraphRenderer;
+use crate::models::GitCommit;
+use crate::state::AppState;
+use anyhow::{Result, anyhow};
+use eframe::egui;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+const ROW_HEIGHT: f64 = 24.0;
+const LANE_WIDTH: f64 = 16.0;
+const MARGIN: f64 = 8.0;
+const DOT_RADIUS: f64 = 4.5;
+const FONT_SIZE: f64 = 12.0;
+const CHAR_WIDTH: f64 = FONT_SIZE * 0.6; // Monospace advance; text is fitted without font metrics
+const COLUMN_GAP: usize = 2; // In characters
+const AUTHOR_CHARS: usize = 18;
+const DATE_CHARS: usize = 16;
+const HASH_CHARS: usize = 8;
+const MAX_SUMMARY_CHARS: usize = 72; // When the width follows the text
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphExportFormat {
+    Svg,
+    Png,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphExportTheme {
+    Light,
+    Dark,
+}
+
+/// A text column drawn next to the graph lanes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphExportColumn {
+    Refs, // Branch and tag labels before the summary
+    Summary,
+    Author,
+    Date,
+    Hash,
+}
+
+impl GraphExportColumn {
+    pub const ALL: [Self; 5] = [
+        Self::Refs,
+        Self::Summary,
+        Self::Author,
+        Self::Date,
+        Self::Hash,
+    ];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Refs => "Refs",
+            Self::Summary => "Summary",
+            Self::Author => "Author",
+            Self::Date => "Date",
+            Self::Hash => "Hash",
+        }
+    }
+}
+
+/// Configuration for exporting the commit graph as an image
+#[derive(Debug, Clone)]
+pub struct GraphExportConfig {
+    pub rows: Range<usize>, // Rows of the commit list to draw
+    pub width: Option<u32>, // Image width in pixels; None fits the text
+    pub theme: GraphExportTheme,
+    pub columns: Vec<GraphExportColumn>, // Shown columns, each in its fixed position
+    pub scale: f32,                      // PNG pixels per SVG pixel, 2.0 for high-DPI screens
+}
+
+impl Default for GraphExportConfig {
+    fn default() -> Self {
+        Self {
+            rows: 0..usize::MAX,
+            width: None,
+            theme: GraphExportTheme::Light,
+            columns: GraphExportColumn::ALL.to_vec(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl GraphExportConfig {
+    #[must_use]
+    pub fn shows(&self, column: GraphExportColumn) -> bool {
+        self.columns.contains(&column)
+    }
+}
+
+struct ExportPalette {
+    background: &'static str,
+    text: &'static str,
+    secondary: &'static str,
+}
+
+impl GraphExportTheme {
+    const fn palette(self) -> ExportPalette {
+        match self {
+            Self::Light => ExportPalette {
+                background: "#ffffff",
+                text: "#1f2328",
+                secondary: "#656d76",
+            },
+            Self::Dark => ExportPalette {
+                background: "#1e1e1e",
+                text: "#d4d4d4",
+                secondary: "#9da5b4",
+            },
+        }
+    }
+}
+
+/// A branch or tag label drawn before a commit's summary
+struct ExportLabel {
+    text: String,
+    background: egui::Color32,
+    text_color: egui::Color32,
+}
+
+/// A line from a commit's dot to one of its parents
+struct ExportEdge {
+    row: usize,
+    lane: usize,
+    parent_row: usize, // Past the last commit when the parent is not loaded
+    parent_lane: usize,
+}
+
+/// Where the rows, lanes and text columns of an export go
+struct ExportLayout {
+    lanes: HashMap<String, usize>, // Commit id -> lane, as on screen
+    first_row: usize,
+    text_x: f64,       // Left edge of the text columns
+    main_chars: usize, // Room for the ref labels and the summary
+    palette: ExportPalette,
+}
+
+impl ExportLayout {
+    fn lane_of(&self, commit: &GitCommit) -> usize {
+        self.lanes.get(&commit.id).copied().unwrap_or(0)
+    }
+
+    fn lane_x(lane: usize) -> f64 {
+        coordinate(lane).mul_add(LANE_WIDTH, MARGIN) + LANE_WIDTH / 2.0
+    }
+
+    fn row_y(&self, row: usize) -> f64 {
+        (coordinate(row) - coordinate(self.first_row)).mul_add(ROW_HEIGHT, ROW_HEIGHT / 2.0)
+    }
+}
+
+impl CommitGraphRenderer {
+    /// Render rows of the commit list as a standalone SVG document, using
+    /// the same lanes and colors as the on-screen graph. Lines to commits
+    /// outside the rows run off the top or bottom edge.
+    pub fn export_svg(
+        &self,
+        commits: &[GitCommit],
+        state: &AppState,
+        config: &GraphExportConfig,
+    ) -> String {
+        let rows = config.rows.start.min(commits.len())..config.rows.end.min(commits.len());
+        let lanes = Self::assign_commit_lanes(commits);
+        let lane_of = |commit: &GitCommit| lanes.get(&commit.id).copied().unwrap_or(0);
+
+        let edges = export_edges(commits, &rows, lane_of);
+        let lane_count = edges
+            .iter()
+            .flat_map(|edge| [edge.lane, edge.parent_lane])
+            .chain(commits[rows.clone()].iter().map(&lane_of))
+            .max()
+            .map_or(1, |lane| lane + 1);
+
+        // Text columns, right of the lanes
+        let labels: Vec<Vec<ExportLabel>> = commits[rows.clone()]
+            .iter()
+            .map(|commit| self.export_labels(commit, state, config))
+            .collect();
+        let text_x = coordinate(lane_count).mul_add(LANE_WIDTH, MARGIN) + MARGIN;
+        let fixed_chars: usize = [
+            (GraphExportColumn::Author, AUTHOR_CHARS),
+            (GraphExportColumn::Date, DATE_CHARS),
+            (GraphExportColumn::Hash, HASH_CHARS),
+        ]
+        .iter()
+        .filter(|(column, _)| config.shows(*column))
+        .map(|(_, chars)| chars + COLUMN_GAP)
+        .sum();
+        let main_chars = config.width.map_or_else(
+            || {
+                commits[rows.clone()]
+                    .iter()
+                    .zip(&labels)
+                    .map(|(commit, labels)| {
+                        let summary = if config.shows(GraphExportColumn::Summary) {
+                            commit.summary.chars().count().min(MAX_SUMMARY_CHARS)
+                        } else {
+                            0
+                        };
+                        label_chars(labels) + summary
+                    })
+                    .max()
+                    .unwrap_or(0)
+            },
+            |width| {
+                let fixed_width = coordinate(fixed_chars) * CHAR_WIDTH;
+                chars_within(f64::from(width) - text_x - MARGIN - fixed_width)
+            },
+        );
+        let width = config.width.map_or_else(
+            || coordinate(main_chars + fixed_chars).mul_add(CHAR_WIDTH, text_x) + MARGIN,
+            f64::from,
+        );
+        let height = coordinate(rows.len()) * ROW_HEIGHT;
+
+        let layout = ExportLayout {
+            lanes,
+            first_row: rows.start,
+            text_x,
+            main_chars,
+            palette: config.theme.palette(),
+        };
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="monospace" font-size="{f}">"#,
+            w = width.ceil(),
+            h = height.ceil(),
+            f = FONT_SIZE,
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            layout.palette.background
+        );
+
+        // Lines first so the commit dots sit on top
+        let _ = writeln!(
+            svg,
+            r#"<g fill="none" stroke-width="2" stroke-linecap="round">"#
+        );
+        for edge in &edges {
+            self.write_edge(&mut svg, edge, &layout);
+        }
+        let _ = writeln!(svg, "</g>");
+
+        for ((row, commit), labels) in rows.clone().zip(&commits[rows]).zip(&labels) {
+            self.write_commit_row(&mut svg, commit, row, labels, &layout, config);
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Render rows of the commit list to PNG on the CPU, without egui
+    pub fn export_png(
+        &self,
+        commits: &[GitCommit],
+        state: &AppState,
+        config: &GraphExportConfig,
+    ) -> Result<Vec<u8>> {
+        rasterize_svg(&self.export_svg(commits, state, config), config.scale)
+    }
+
+    /// Export in either format, ready to be written to a file
+    pub fn export_graph(
+        &self,
+        commits: &[GitCommit],
+        state: &AppState,
+        config: &GraphExportConfig,
+        format: GraphExportFormat,
+    ) -> Result<Vec<u8>> {
+        match format {
+            GraphExportFormat::Svg => Ok(self.export_svg(commits, state, config).into_bytes()),
+            GraphExportFormat::Png => self.export_png(commits, state, config),
+        }
+    }
+
+    fn export_labels(
+        &self,
+        commit: &GitCommit,
+        state: &AppState,
+        config: &GraphExportConfig,
+    ) -> Vec<ExportLabel> {
+        if !config.shows(GraphExportColumn::Refs) {
+            return Vec::new();
+        }
+        self.create_ref_labels(commit, state)
+            .into_iter()
+            .map(|label| ExportLabel {
+                text: short_ref_name(&label.text),
+                background: label.background,
+                text_color: label.text_color,
+            })
+            .collect()
+    }
+
+    fn write_edge(&self, svg: &mut String, edge: &ExportEdge, layout: &ExportLayout) {
+        let (x1, y1) = (ExportLayout::lane_x(edge.lane), layout.row_y(edge.row));
+        let (x2, y2) = (
+            ExportLayout::lane_x(edge.parent_lane),
+            layout.row_y(edge.parent_row),
+        );
+        let half = ROW_HEIGHT / 2.0;
+        // Like gitk: branch out right below the child, join right above the parent
+        let (path, color_lane) = match edge.lane.cmp(&edge.parent_lane) {
+            Ordering::Equal => (format!("M{x1:.1},{y1:.1} L{x2:.1},{y2:.1}"), edge.lane),
+            Ordering::Less => {
+                let bend = y1 + ROW_HEIGHT;
+                (
+                    format!(
+                        "M{x1:.1},{y1:.1} C{x1:.1},{:.1} {x2:.1},{:.1} {x2:.1},{bend:.1} L{x2:.1},{y2:.1}",
+                        y1 + half,
+                        y1 + half
+                    ),
+                    edge.parent_lane,
+                )
+            }
+            Ordering::Greater => {
+                let bend = y2 - ROW_HEIGHT;
+                (
+                    format!(
+                        "M{x1:.1},{y1:.1} L{x1:.1},{bend:.1} C{x1:.1},{:.1} {x2:.1},{:.1} {x2:.1},{y2:.1}",
+                        y2 - half,
+                        y2 - half
+                    ),
+                    edge.lane,
+                )
+            }
+        };
+        let _ = writeln!(
+            svg,
+            r#"<path d="{}" stroke="{}"/>"#,
+            path,
+            hex_color(self.lane_color(color_lane))
+        );
+    }
+
+    /// The dot, ref labels and text columns of one commit
+    fn write_commit_row(
+        &self,
+        svg: &mut String,
+        commit: &GitCommit,
+        row: usize,
+        labels: &[ExportLabel],
+        layout: &ExportLayout,
+        config: &GraphExportConfig,
+    ) {
+        let palette = &layout.palette;
+        let lane = layout.lane_of(commit);
+        let (x, y) = (ExportLayout::lane_x(lane), layout.row_y(row));
+        let color = hex_color(self.lane_color(lane));
+        // Merges are drawn hollow
+        let (fill, stroke) = if commit.parent_ids.len() > 1 {
+            (palette.background, color.as_str())
+        } else {
+            (color.as_str(), palette.background)
+        };
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{x:.1}" cy="{y:.1}" r="{DOT_RADIUS}" fill="{fill}" stroke="{stroke}" stroke-width="1.5"/>"#
+        );
+
+        let baseline = FONT_SIZE.mul_add(0.35, y);
+        let mut x = layout.text_x;
+        let mut chars_left = layout.main_chars;
+        for label in labels {
+            let chars = label.text.chars().count() + 1;
+            if chars + 1 > chars_left {
+                break;
+            }
+            let label_width = coordinate(chars) * CHAR_WIDTH;
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x:.1}" y="{:.1}" width="{label_width:.1}" height="{:.1}" rx="3" fill="{}"/>"#,
+                FONT_SIZE.mul_add(-0.7, y),
+                FONT_SIZE * 1.4,
+                hex_color(label.background)
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{baseline:.1}" fill="{}">{}</text>"#,
+                x + CHAR_WIDTH / 2.0,
+                hex_color(label.text_color),
+                escape_xml(&label.text)
+            );
+            x += coordinate(chars + 1) * CHAR_WIDTH;
+            chars_left -= chars + 1;
+        }
+        if config.shows(GraphExportColumn::Summary) && chars_left > 0 {
+            let summary = truncate_chars(&commit.summary, chars_left);
+            let _ = writeln!(
+                svg,
+                r#"<text x="{x:.1}" y="{baseline:.1}" fill="{}">{}</text>"#,
+                palette.text,
+                escape_xml(&summary)
+            );
+        }
+
+        let mut x = coordinate(layout.main_chars + COLUMN_GAP).mul_add(CHAR_WIDTH, layout.text_x);
+        let date = commit.author.when.format("%Y-%m-%d %H:%M").to_string();
+        for (column, text, chars) in [
+            (
+                GraphExportColumn::Author,
+                commit.author.name.as_str(),
+                AUTHOR_CHARS,
+            ),
+            (GraphExportColumn::Date, date.as_str(), DATE_CHARS),
+            (
+                GraphExportColumn::Hash,
+                commit.short_id.as_str(),
+                HASH_CHARS,
+            ),
+        ] {
+            if !config.shows(column) {
+                continue;
+            }
+            let _ = writeln!(
+                svg,
+                r#"<text x="{x:.1}" y="{baseline:.1}" fill="{}">{}</text>"#,
+                palette.secondary,
+                escape_xml(&truncate_chars(text, chars))
+            );
+            x += coordinate(chars + COLUMN_GAP) * CHAR_WIDTH;
+        }
+    }
+
+    fn lane_color(&self, lane: usize) -> egui::Color32 {
+        self.branch_colors[lane % self.branch_colors.len()]
+    }
+}
+
+/// Every edge with an end inside the rows or passing through them
+fn export_edges(
+    commits: &[GitCommit],
+    rows: &Range<usize>,
+    lane_of: impl Fn(&GitCommit) -> usize,
+) -> Vec<ExportEdge> {
+    let commit_rows: HashMap<&str, usize> = commits
+        .iter()
+        .enumerate()
+        .map(|(row, commit)| (commit.id.as_str(), row))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (row, commit) in commits.iter().enumerate().take(rows.end) {
+        for parent_id in &commit.parent_ids {
+            let (parent_row, parent_lane) = match commit_rows.get(parent_id.as_str()) {
+                Some(&parent_row) => (parent_row, lane_of(&commits[parent_row])),
+                None => (commits.len(), lane_of(commit)), // Not loaded: straight down
+            };
+            if parent_row >= rows.start {
+                edges.push(ExportEdge {
+                    row,
+                    lane: lane_of(commit),
+                    parent_row,
+                    parent_lane,
+                });
+            }
+        }
+    }
+    edges
+}
+
+/// Rasterize an SVG document to PNG. The monospace family resolves to the
+/// font egui bundles, so images look the same with or without system fonts.
+pub fn rasterize_svg(svg: &str, scale: f32) -> Result<Vec<u8>> {
+    let mut options = resvg::usvg::Options::default();
+    let fontdb = options.fontdb_mut();
+    for font in egui::FontDefinitions::default().font_data.values() {
+        fontdb.load_font_data(font.font.to_vec());
+    }
+    fontdb.set_monospace_family("Hack");
+    fontdb.load_system_fonts(); // Fallback for scripts the bundled fonts lack
+
+    let tree = resvg::usvg::Tree::from_str(svg, &options)?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| anyhow!("Invalid image scale: {scale}"))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| anyhow!("Image too large: {}x{}", size.width(), size.height()))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap.encode_png()?)
+}
+
+/// Rows, lanes and character counts as SVG coordinates, without rounding
+fn coordinate(count: usize) -> f64 {
+    f64::from(u32::try_from(count).unwrap_or(u32::MAX))
+}
+
+/// Whole characters of text that fit in `width` pixels, counted up to a
+/// width no image reaches
+fn chars_within(width: f64) -> usize {
+    (1..=usize::from(u16::MAX))
+        .take_while(|&chars| coordinate(chars) * CHAR_WIDTH <= width)
+        .count()
+}
+
+fn label_chars(labels: &[ExportLabel]) -> usize {
+    labels
+        .iter()
+        .map(|label| label.text.chars().count() + 2)
+        .sum()
+}
+
+fn hex_color(color: egui::Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn short_ref_name(name: &str) -> String {
+    ["refs/heads/", "refs/remotes/", "refs/tags/"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name)
+        .to_string()
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Escape markup and drop the control characters XML 1.0 does not allow,
+/// which commit messages and author names can still carry
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .filter(|&c| c >= ' ' || c == '\t' || c == '\n')
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GitSignature;
+    use chrono::Utc;
+
+    fn commit(id: &str, parents: &[&str], summary: &str) -> GitCommit {
+        let signature = GitSignature {
+            name: "Test \u{1b}User".to_string(),
+            email: "test@example.com".to_string(),
+            when: Utc::now(),
+        };
+        GitCommit {
+            id: id.repeat(40),
+            short_id: id.repeat(7),
+            author: signature.clone(),
+            committer: signature,
+            message: summary.to_string(),
+            summary: summary.to_string(),
+            parent_ids: parents.iter().map(|parent| parent.repeat(40)).collect(),
+            tree_id: String::new(),
+            is_signed: false,
+            notes: Vec::new(),
+        }
+    }
+
+    fn test_commits() -> Vec<GitCommit> {
+        vec![
+            commit("d", &["b", "c"], "Merge <topic> & \"friends\""),
+            commit("c", &["a"], "Bell\u{7} and form\u{c} feed"),
+            commit("b", &["a"], "Tab\tseparated"),
+            commit("a", &[], "Initial commit"),
+        ]
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml("a < b & \"c\" > d"),
+            "a &lt; b &amp; &quot;c&quot; &gt; d"
+        );
+        assert_eq!(
+            escape_xml("nul\0 esc\u{1b} cr\r del\u{7f}"),
+            "nul esc cr del\u{7f}"
+        );
+        assert_eq!(escape_xml("tab\tnewline\n"), "tab\tnewline\n");
+    }
+
+    #[test]
+    fn test_export_svg_parses() -> Result<()> {
+        let renderer = CommitGraphRenderer::new();
+        let state = AppState::new();
+        let commits = test_commits();
+
+        for theme in [GraphExportTheme::Light, GraphExportTheme::Dark] {
+            let config = GraphExportConfig {
+                theme,
+                ..GraphExportConfig::default()
+            };
+            let svg = renderer.export_svg(&commits, &state, &config);
+            let tree = resvg::usvg::Tree::from_str(&svg, &resvg::usvg::Options::default())?;
+            assert_eq!(
+                f64::from(tree.size().height()),
+                coordinate(commits.len()) * ROW_HEIGHT
+            );
+            assert!(svg.contains("Merge &lt;topic&gt; &amp; &quot;friends&quot;"));
+            assert!(!svg.contains('\u{7}') && !svg.contains('\u{1b}'));
+        }
+
+        // Rows cut out of the middle still form a valid document
+        let config = GraphExportConfig {
+            rows: 1..3,
+            ..GraphExportConfig::default()
+        };
+        let svg = renderer.export_svg(&commits, &state, &config);
+        let tree = resvg::usvg::Tree::from_str(&svg, &resvg::usvg::Options::default())?;
+        assert_eq!(f64::from(tree.size().height()), 2.0 * ROW_HEIGHT);
+        assert!(!svg.contains("Initial commit"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_png_size() -> Result<()> {
+        let renderer = CommitGraphRenderer::new();
+        let state = AppState::new();
+        let commits = test_commits();
+        let config = GraphExportConfig {
+            width: Some(600),
+            scale: 2.0,
+            ..GraphExportConfig::default()
+        };
+
+        let png = renderer.export_png(&commits, &state, &config)?;
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let pixmap = tiny_skia::Pixmap::decode_png(&png)?;
+        assert_eq!(pixmap.width(), 1200);
+        assert_eq!(
+            f64::from(pixmap.height()),
+            coordinate(commits.len()) * ROW_HEIGHT * 2.0
+        );
+        Ok(())
+    }
+}
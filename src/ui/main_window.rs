@@ -1,5 +1,8 @@
 use crate::state::{AppConfig, AppState};
-use crate::ui::{CommitGraph, DiffViewer, ReferencesPanel, SearchPanel, ViewsPanel};
+use crate::ui::{
+    ActivityPanel, CommitGraph, DiffViewer, ReferencesPanel, SearchPanel, StatisticsPanel,
+    TreeBrowser, ViewsPanel,
+};
 use eframe::egui;
 
 pub struct MainWindow {
@@ -8,6 +11,9 @@ pub struct MainWindow {
     search_panel: SearchPanel,
     references_panel: ReferencesPanel,
     views_panel: ViewsPanel,
+    statistics_panel: StatisticsPanel,
+    activity_panel: ActivityPanel,
+    tree_browser: TreeBrowser,
     left_panel_width: f32,
     right_panel_width: f32,
     show_references: bool,
@@ -49,6 +55,8 @@ pub struct PanelVisibility {
     pub references: bool,
     pub views: bool,
     pub search: bool,
+    pub statistics: bool, // Floating dashboard window
+    pub activity: bool,   // Commit heatmap below the main panes
     pub auto_hide_empty: bool,
 }
 
@@ -60,6 +68,9 @@ impl MainWindow {
             search_panel: SearchPanel::new(),
             references_panel: ReferencesPanel::new(),
             views_panel: ViewsPanel::new(),
+            statistics_panel: StatisticsPanel::new(),
+            activity_panel: ActivityPanel::new(),
+            tree_browser: TreeBrowser::new(),
             left_panel_width: 500.0,
             right_panel_width: 350.0,
             show_references: true,
@@ -78,6 +89,8 @@ impl MainWindow {
                 references: true,
                 views: true,
                 search: true,
+                statistics: false,
+                activity: false,
                 auto_hide_empty: false,
             },
             // Menu and toolbar state
@@ -108,6 +121,14 @@ impl MainWindow {
             self.show_toolbar(ui, state, config);
         }
 
+        if self.panel_visibility.activity {
+            egui::TopBottomPanel::bottom("activity_heatmap")
+                .resizable(false)
+                .show_inside(ui, |ui| {
+                    self.activity_panel.show(ui, state);
+                });
+        }
+
         // Main content area with layout-specific rendering
         match self.layout_mode {
             LayoutMode::ThreePaneClassic => {
@@ -133,9 +154,40 @@ impl MainWindow {
         self.handle_context_menus(ui, state);
     }
 
+    /// Only the diff viewer, for a patch file opened without a repository
+    pub fn show_patch_viewer(
+        &mut self,
+        ui: &mut egui::Ui,
+        state: &mut AppState,
+        config: &AppConfig,
+    ) {
+        self.diff_viewer.show(ui, state, config);
+    }
+
     pub fn show_dialogs(&mut self, ctx: &egui::Context, state: &mut AppState) {
         // Show views dialogs (create/edit view dialogs)
         self.views_panel.show_dialogs(ctx, state);
+
+        if self.panel_visibility.statistics {
+            let mut open = true;
+            egui::Window::new("📈 Statistics")
+                .open(&mut open)
+                .default_width(700.0)
+                .default_height(600.0)
+                .show(ctx, |ui| {
+                    self.statistics_panel.show(ui, state);
+                });
+            self.panel_visibility.statistics = open;
+        }
+    }
+
+    /// Open the statistics dashboard, computing it for the current view the
+    /// first time
+    pub fn open_statistics(&mut self, state: &mut AppState) {
+        self.panel_visibility.statistics = true;
+        if state.statistics.is_none() && !state.is_computing_statistics() {
+            state.start_statistics();
+        }
     }
 
     /// Handle keyboard shortcuts for the main window
@@ -247,6 +299,14 @@ impl MainWindow {
                 ui.checkbox(&mut self.panel_visibility.references, "🏷️ References");
                 ui.checkbox(&mut self.panel_visibility.views, "👁️ Views");
                 ui.checkbox(&mut self.panel_visibility.search, "🔍 Search");
+                ui.checkbox(&mut self.panel_visibility.activity, "🗓 Activity");
+                if ui
+                    .checkbox(&mut self.panel_visibility.statistics, "📈 Statistics")
+                    .changed()
+                    && self.panel_visibility.statistics
+                {
+                    self.open_statistics(state);
+                }
 
                 ui.separator();
                 ui.heading("Interface");
@@ -304,6 +364,17 @@ impl MainWindow {
                 state.refresh_commits();
             }
 
+            if let Some(parent) = state.parent_sessions.last() {
+                let parent_name = parent.repository.info().name.clone();
+                if ui
+                    .button("⬆")
+                    .on_hover_text(format!("Back to superproject '{parent_name}'"))
+                    .clicked()
+                {
+                    state.return_to_parent_repository();
+                }
+            }
+
             ui.separator();
 
             // Layout buttons
@@ -671,7 +742,7 @@ impl MainWindow {
     }
 
     /// Handle context menus
-    fn handle_context_menus(&mut self, ui: &mut egui::Ui, _state: &mut AppState) {
+    fn handle_context_menus(&mut self, ui: &egui::Ui, state: &mut AppState) {
         if self.show_file_context_menu {
             // Use a simpler context menu approach
             let popup_id = egui::Id::new("file_context_menu");
@@ -687,11 +758,14 @@ impl MainWindow {
                             self.show_file_context_menu = false;
                         }
                         if ui.button("👁️ View File").clicked() {
-                            // Open file view
+                            if let Some(commit_id) = state.selected_commit.clone() {
+                                state.view_tree_file(&commit_id, &self.context_file_path);
+                            }
                             self.show_file_context_menu = false;
                         }
                         if ui.button("📝 View Diff").clicked() {
-                            // Show file diff
+                            state.close_tree_file();
+                            state.selected_files = vec![self.context_file_path.clone()];
                             self.show_file_context_menu = false;
                         }
 
@@ -705,52 +779,10 @@ impl MainWindow {
     }
 
     fn show_file_tree(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
-        ui.heading("Files");
-        ui.separator();
-
-        if let Some(selected_commit) = state.get_selected_commit().cloned() {
-            ui.label(format!("Commit: {}", &selected_commit.short_id));
-            ui.separator();
-
-            if let Some(ref repo) = state.repository {
-                match repo.get_commit_tree_entries(&selected_commit.id) {
-                    Ok(entries) => {
-                        egui::ScrollArea::vertical().show(ui, |ui| {
-                            for entry in entries {
-                                let icon = if entry.is_tree { "📁" } else { "📄" };
-                                let response = ui.selectable_label(
-                                    state.selected_files.contains(&entry.path),
-                                    format!("{} {}", icon, entry.name),
-                                );
-
-                                if response.clicked() {
-                                    // Handle file selection
-                                    if !entry.is_tree {
-                                        state.selected_files = vec![entry.path.clone()];
-                                        if let Ok(diff) =
-                                            repo.get_file_diff(&selected_commit.id, &entry.path)
-                                        {
-                                            state.current_diff = Some(diff);
-                                        }
-                                    }
-                                }
-
-                                // Context menu
-                                if response.secondary_clicked() {
-                                    self.context_file_path = entry.path.clone();
-                                    self.context_menu_pos = response.rect.left_bottom();
-                                    self.show_file_context_menu = true;
-                                }
-                            }
-                        });
-                    }
-                    Err(e) => {
-                        ui.label(format!("Error loading files: {}", e));
-                    }
-                }
-            }
-        } else {
-            ui.label("Select a commit to view files");
+        if let Some((path, pos)) = self.tree_browser.show(ui, state) {
+            self.context_file_path = path;
+            self.context_menu_pos = pos;
+            self.show_file_context_menu = true;
         }
     }
 
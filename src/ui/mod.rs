@@ -1,15 +1,21 @@
+pub mod activity;
 pub mod commit_graph;
 pub mod diff_viewer;
 pub mod graph;
 pub mod main_window;
 pub mod references;
 pub mod search;
+pub mod statistics;
+pub mod tree_browser;
 pub mod views;
 
+pub use activity::*;
 pub use commit_graph::*;
 pub use diff_viewer::*;
 pub use graph::*;
 pub use main_window::*;
 pub use references::*;
 pub use search::*;
+pub use statistics::*;
+pub use tree_browser::*;
 pub use views::*;
@@ -1,11 +1,16 @@
+use crate::git::{WorktreeCreateConfig, WorktreeInfo};
 use crate::state::AppState;
+use crate::ui::signature_color;
 use eframe::egui;
+use std::path::{Path, PathBuf};
 
 pub struct ReferencesPanel {
     show_local_branches: bool,
     show_remote_branches: bool,
     show_tags: bool,
+    show_worktrees: bool,
     filter_text: String,
+    worktree_dialog: CreateWorktreeDialog,
 }
 
 impl ReferencesPanel {
@@ -14,7 +19,9 @@ impl ReferencesPanel {
             show_local_branches: true,
             show_remote_branches: false,
             show_tags: true,
+            show_worktrees: true,
             filter_text: String::new(),
+            worktree_dialog: CreateWorktreeDialog::new(),
         }
     }
 
@@ -36,6 +43,7 @@ impl ReferencesPanel {
             ui.checkbox(&mut self.show_local_branches, "Local");
             ui.checkbox(&mut self.show_remote_branches, "Remote");
             ui.checkbox(&mut self.show_tags, "Tags");
+            ui.checkbox(&mut self.show_worktrees, "Worktrees");
         });
 
         // Update state based on UI settings
@@ -70,7 +78,16 @@ impl ReferencesPanel {
             if self.show_tags {
                 self.show_tags_section(ui, state);
             }
+
+            // Worktrees
+            if self.show_worktrees {
+                self.show_worktrees_section(ui, state);
+            }
         });
+
+        if let Some((name, path, config)) = self.worktree_dialog.show(ui.ctx()) {
+            state.create_worktree(&name, &path, config);
+        }
     }
 
     fn show_local_branches_section(&self, ui: &mut egui::Ui, state: &mut AppState) {
@@ -78,6 +95,8 @@ impl ReferencesPanel {
             let branches = state.get_branches();
             let current_branch = state.get_current_branch().cloned();
             let mut branch_to_switch = None;
+            let mut branch_to_checkout = None;
+            let mut worktree_to_open = None;
 
             for branch in branches.iter() {
                 if !self.matches_filter(branch) {
@@ -89,6 +108,12 @@ impl ReferencesPanel {
                     continue;
                 }
 
+                // Branches checked out in another worktree cannot be checked out here
+                let holder = state
+                    .get_branch_worktree(branch)
+                    .filter(|worktree| !worktree.is_current)
+                    .cloned();
+
                 ui.horizontal(|ui| {
                     let is_current = current_branch
                         .as_ref()
@@ -107,6 +132,14 @@ impl ReferencesPanel {
                         branch_to_switch = Some(branch.clone());
                     }
 
+                    if let Some(ref holder) = holder {
+                        ui.colored_label(egui::Color32::LIGHT_GREEN, "🌲")
+                            .on_hover_text(format!(
+                                "Checked out in worktree {}",
+                                holder.describe()
+                            ));
+                    }
+
                     response.context_menu(|ui| {
                         if ui.button("Switch to branch").clicked() {
                             branch_to_switch = Some(branch.clone());
@@ -119,6 +152,26 @@ impl ReferencesPanel {
                             ui.close_menu();
                         }
 
+                        let checkout = ui.add_enabled(
+                            holder.is_none() && !is_current,
+                            egui::Button::new("Check out branch"),
+                        );
+                        if checkout.clicked() {
+                            branch_to_checkout = Some(branch.clone());
+                            ui.close();
+                        }
+                        if let Some(ref holder) = holder {
+                            checkout.on_disabled_hover_text(format!(
+                                "Already checked out in worktree {}",
+                                holder.describe()
+                            ));
+
+                            if ui.button("Switch to worktree").clicked() {
+                                worktree_to_open = Some(holder.path.clone());
+                                ui.close();
+                            }
+                        }
+
                         ui.separator();
 
                         if ui.button("Delete branch").clicked() {
@@ -133,6 +186,12 @@ impl ReferencesPanel {
             if let Some(branch) = branch_to_switch {
                 state.switch_to_branch(&branch);
             }
+            if let Some(branch) = branch_to_checkout {
+                state.checkout_branch(&branch);
+            }
+            if let Some(path) = worktree_to_open {
+                state.switch_worktree(&path);
+            }
         });
     }
 
@@ -165,7 +224,7 @@ impl ReferencesPanel {
         });
     }
 
-    fn show_tags_section(&self, ui: &mut egui::Ui, state: &AppState) {
+    fn show_tags_section(&self, ui: &mut egui::Ui, state: &mut AppState) {
         ui.collapsing("Tags", |ui| {
             let tags = state.get_tags();
 
@@ -178,6 +237,24 @@ impl ReferencesPanel {
                     ui.colored_label(egui::Color32::YELLOW, "🏷️");
                     let response = ui.selectable_label(false, tag);
 
+                    if ui.is_rect_visible(response.rect) {
+                        state.ensure_tag_signature(tag);
+                    }
+
+                    if let Some(verification) = state.get_tag_signature(tag) {
+                        if verification.status.is_signed() {
+                            let mut details = verification.output.clone();
+                            if let Some(ref key) = verification.key {
+                                details = format!("Key: {key}\n{details}");
+                            }
+                            ui.colored_label(
+                                signature_color(verification.status),
+                                format!("🔏 {}", verification.status.label()),
+                            )
+                            .on_hover_text(details);
+                        }
+                    }
+
                     response.context_menu(|ui| {
                         if ui.button("View commit").clicked() {
                             // TODO: Implement jumping to tag commit
@@ -201,6 +278,114 @@ impl ReferencesPanel {
         });
     }
 
+    fn show_worktrees_section(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
+        ui.collapsing("Worktrees", |ui| {
+            let worktrees = state.worktrees.clone();
+            let mut worktree_to_open = None;
+            let mut lock_change = None;
+            let mut worktree_to_remove = None;
+
+            for worktree in &worktrees {
+                let label = worktree.head_branch.as_deref().unwrap_or("(detached HEAD)");
+                if !self.matches_filter(&worktree.name) && !self.matches_filter(label) {
+                    continue;
+                }
+
+                ui.horizontal(|ui| {
+                    if worktree.is_current {
+                        ui.colored_label(egui::Color32::GREEN, "📍");
+                    } else {
+                        ui.label("  ");
+                    }
+
+                    let response = ui
+                        .selectable_label(
+                            worktree.is_current,
+                            format!("🌲 {} [{}]", worktree.name, label),
+                        )
+                        .on_hover_text(worktree.path.display().to_string());
+
+                    if response.double_clicked() && !worktree.is_current && !worktree.is_prunable {
+                        worktree_to_open = Some(worktree.path.clone());
+                    }
+
+                    if worktree.is_locked() {
+                        ui.colored_label(egui::Color32::YELLOW, "🔒")
+                            .on_hover_text(worktree.lock_reason().unwrap_or("Locked"));
+                    }
+                    if worktree.is_prunable {
+                        ui.colored_label(egui::Color32::RED, "⚠")
+                            .on_hover_text("Working directory is missing");
+                    }
+
+                    response.context_menu(|ui| {
+                        if ui
+                            .add_enabled(
+                                !worktree.is_current && !worktree.is_prunable,
+                                egui::Button::new("Switch to worktree"),
+                            )
+                            .clicked()
+                        {
+                            worktree_to_open = Some(worktree.path.clone());
+                            ui.close();
+                        }
+
+                        if worktree.is_main {
+                            return;
+                        }
+
+                        ui.separator();
+
+                        let lock_label = if worktree.is_locked() {
+                            "Unlock worktree"
+                        } else {
+                            "Lock worktree"
+                        };
+                        if ui.button(lock_label).clicked() {
+                            lock_change = Some((worktree.name.clone(), !worktree.is_locked()));
+                            ui.close();
+                        }
+
+                        if ui
+                            .add_enabled(!worktree.is_current, egui::Button::new("Remove worktree"))
+                            .clicked()
+                        {
+                            worktree_to_remove = Some((worktree.name.clone(), false));
+                            ui.close();
+                        }
+
+                        if ui
+                            .add_enabled(
+                                !worktree.is_current,
+                                egui::Button::new("Force remove worktree"),
+                            )
+                            .on_hover_text("Discard local changes and ignore the lock")
+                            .clicked()
+                        {
+                            worktree_to_remove = Some((worktree.name.clone(), true));
+                            ui.close();
+                        }
+                    });
+                });
+            }
+
+            if state.repository.is_some() && ui.button("➕ Add worktree").clicked() {
+                self.worktree_dialog.show_dialog(&worktrees);
+            }
+
+            // Handle worktree actions outside the loop
+            if let Some(path) = worktree_to_open {
+                state.switch_worktree(&path);
+            }
+            if let Some((name, locked)) = lock_change {
+                state.set_worktree_locked(&name, locked);
+            }
+            if let Some((name, force)) = worktree_to_remove {
+                state.remove_worktree(&name, force);
+            }
+        });
+    }
+
     fn matches_filter(&self, name: &str) -> bool {
         if self.filter_text.is_empty() {
             return true;
@@ -397,3 +582,141 @@ impl CreateTagDialog {
         result
     }
 }
+
+/// Name, path and settings of a worktree the user asked to add
+pub type WorktreeRequest = (String, PathBuf, WorktreeCreateConfig);
+
+/// Dialog for adding linked worktrees
+pub struct CreateWorktreeDialog {
+    name: String,
+    path: String,
+    branch: String,
+    create_branch: bool,
+    lock: bool,
+    base_dir: Option<PathBuf>, // Where new worktrees go by default
+    show: bool,
+}
+
+impl Default for CreateWorktreeDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CreateWorktreeDialog {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            name: String::new(),
+            path: String::new(),
+            branch: String::new(),
+            create_branch: true,
+            lock: false,
+            base_dir: None,
+            show: false,
+        }
+    }
+
+    pub fn show_dialog(&mut self, worktrees: &[WorktreeInfo]) {
+        self.show = true;
+        self.name.clear();
+        self.path.clear();
+        self.branch.clear();
+        self.create_branch = true;
+        self.lock = false;
+        // Default to siblings of the main worktree, like `git worktree add ../name`
+        self.base_dir = worktrees
+            .iter()
+            .find(|worktree| worktree.is_main)
+            .and_then(|worktree| worktree.path.parent())
+            .map(Path::to_path_buf);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<WorktreeRequest> {
+        if !self.show {
+            return None;
+        }
+
+        let mut result = None;
+        let mut keep_open = true;
+
+        egui::Window::new("Add Worktree")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut self.name);
+                    });
+
+                    let default_path = self
+                        .base_dir
+                        .as_ref()
+                        .filter(|_| !self.name.is_empty())
+                        .map(|base| base.join(&self.name));
+
+                    ui.horizontal(|ui| {
+                        ui.label("Path:");
+                        let hint = default_path
+                            .as_ref()
+                            .map(|path| path.display().to_string())
+                            .unwrap_or_default();
+                        ui.add(egui::TextEdit::singleline(&mut self.path).hint_text(hint));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Branch:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.branch)
+                                .hint_text(self.name.clone()),
+                        );
+                    });
+
+                    ui.checkbox(&mut self.create_branch, "Create new branch at HEAD");
+                    ui.checkbox(&mut self.lock, "Lock worktree");
+
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        let path = if self.path.trim().is_empty() {
+                            default_path
+                        } else {
+                            Some(PathBuf::from(self.path.trim()))
+                        };
+                        let can_create = !self.name.is_empty()
+                            && !self.name.contains(' ')
+                            && !self.name.contains('/')
+                            && path.is_some()
+                            && (self.create_branch || !self.branch.trim().is_empty());
+
+                        if ui
+                            .add_enabled(can_create, egui::Button::new("Create"))
+                            .clicked()
+                        {
+                            let branch = self.branch.trim();
+                            let config = WorktreeCreateConfig {
+                                branch: (!branch.is_empty()).then(|| branch.to_string()),
+                                create_branch: self.create_branch,
+                                lock: self.lock,
+                            };
+                            if let Some(path) = path {
+                                result = Some((self.name.clone(), path, config));
+                            }
+                            keep_open = false;
+                        }
+
+                        if ui.button("Cancel").clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            });
+
+        if !keep_open {
+            self.show = false;
+        }
+
+        result
+    }
+}
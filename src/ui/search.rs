@@ -1,9 +1,46 @@
+use crate::git::{PickaxeMode, PickaxeQuery};
 use crate::state::AppState;
 use eframe::egui;
 
+const QUERY_HELP: &str = "Terms are ANDed: author: committer: msg: path: ref: after: before: \
+parent:N\nmerge alone selects merge commits; quote it (\"merge\") to search for the word\n\
+OR or | for either, -term or NOT to exclude, ( ) to group, /regex/ values";
+
+/// What the search box matches against, as in gitk's find modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    Commit,          // Message, author or commit id
+    AddsOrRemoves,   // Adding/removing string (-S)
+    ChangesMatching, // Changing lines matching (-G)
+    TouchingPaths,   // Highlight commits touching pathspecs
+}
+
+impl SearchMode {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Commit => "Containing",
+            Self::AddsOrRemoves => "Adding/removing string",
+            Self::ChangesMatching => "Changing lines matching",
+            Self::TouchingPaths => "Touching paths",
+        }
+    }
+
+    const fn pickaxe_mode(self) -> Option<PickaxeMode> {
+        match self {
+            Self::Commit | Self::TouchingPaths => None,
+            Self::AddsOrRemoves => Some(PickaxeMode::AddsOrRemoves),
+            Self::ChangesMatching => Some(PickaxeMode::ChangesMatching),
+        }
+    }
+}
+
 pub struct SearchPanel {
     search_text: String,
     search_focused: bool,
+    mode: SearchMode,
+    regex: bool,
+    ignore_case: bool,
+    highlight: bool, // Emphasize query matches in place instead of filtering
 }
 
 impl SearchPanel {
@@ -11,24 +48,159 @@ impl SearchPanel {
         Self {
             search_text: String::new(),
             search_focused: false,
+            mode: SearchMode::Commit,
+            regex: false,
+            ignore_case: false,
+            highlight: false,
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
         ui.horizontal(|ui| {
-            let search_response = ui.text_edit_singleline(&mut self.search_text);
+            egui::ComboBox::from_id_salt("search_mode")
+                .selected_text(self.mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in [
+                        SearchMode::Commit,
+                        SearchMode::AddsOrRemoves,
+                        SearchMode::ChangesMatching,
+                        SearchMode::TouchingPaths,
+                    ] {
+                        ui.selectable_value(&mut self.mode, mode, mode.label());
+                    }
+                });
+
+            let search_response = ui
+                .text_edit_singleline(&mut self.search_text)
+                .on_hover_text(QUERY_HELP);
 
-            if search_response.changed() {
-                state.search_commits(&self.search_text);
+            // Diff searches are expensive, so they only run on demand
+            if search_response.changed() && self.mode == SearchMode::Commit {
+                self.apply_commit_query(state);
             }
+            let submitted =
+                search_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
 
-            if ui.button("🔍").clicked() {
-                state.search_commits(&self.search_text);
+            if ui.button("🔍").clicked() || (submitted && self.mode != SearchMode::Commit) {
+                self.run_search(state);
             }
 
             if ui.button("Clear").clicked() {
                 self.search_text.clear();
-                state.search_commits("");
+                if self.mode == SearchMode::TouchingPaths {
+                    state.clear_path_search();
+                } else {
+                    self.apply_commit_query(state);
+                }
+            }
+
+            if self.mode == SearchMode::Commit {
+                if ui
+                    .checkbox(&mut self.highlight, "Highlight")
+                    .on_hover_text("Keep every commit and emphasize the matches")
+                    .changed()
+                {
+                    // Swap which layer the query drives
+                    if self.highlight {
+                        state.search_commits("");
+                    } else {
+                        state.clear_highlight();
+                    }
+                    self.apply_commit_query(state);
+                }
+
+                let error = if self.highlight {
+                    &state.highlight_error
+                } else {
+                    &state.query_error
+                };
+                if let Some(error) = error {
+                    ui.colored_label(egui::Color32::RED, format!("⚠ {error}"));
+                } else if self.highlight {
+                    if state.highlight_query.is_some() {
+                        ui.label(format!("{} highlighted", state.highlight_matches.len()));
+                        if ui.button("⬆").on_hover_text("Previous match").clicked() {
+                            state.select_highlight_match(false);
+                        }
+                        if ui.button("⬇").on_hover_text("Next match").clicked() {
+                            state.select_highlight_match(true);
+                        }
+                    }
+                } else if !state.search_query.is_empty() && ui.button("💾 Save as view").clicked()
+                {
+                    let name = state.search_query.clone();
+                    state.save_query_as_view(&name);
+                }
+            }
+
+            if self.mode == SearchMode::AddsOrRemoves {
+                ui.checkbox(&mut self.regex, "Regex");
+            }
+            if self.mode.pickaxe_mode().is_some() {
+                ui.checkbox(&mut self.ignore_case, "Ignore case");
+            }
+
+            if let Some(ref search) = state.path_search {
+                ui.separator();
+                if search.is_complete() {
+                    ui.label(format!(
+                        "{} commits touch {}",
+                        search.matched_count(),
+                        search.query().join(" ")
+                    ));
+                } else {
+                    ui.spinner();
+                    ui.label(format!(
+                        "{} matches, {} commits scanned",
+                        search.matched_count(),
+                        search.scanned_count()
+                    ));
+                    if ui.button("⏹ Stop").clicked() {
+                        state.cancel_path_search();
+                    }
+                }
+                if ui.button("⬆").on_hover_text("Previous match").clicked() {
+                    state.select_path_match(false);
+                }
+                if ui.button("⬇").on_hover_text("Next match").clicked() {
+                    state.select_path_match(true);
+                }
+            }
+
+            if let Some(ref search) = state.pickaxe_search {
+                ui.separator();
+                if search.is_complete() {
+                    ui.label(format!(
+                        "{} matches in {} commits",
+                        search.matched_count(),
+                        search.scanned_count()
+                    ));
+                } else {
+                    ui.spinner();
+                    ui.label(format!(
+                        "{} matches, {} commits scanned",
+                        search.matched_count(),
+                        search.scanned_count()
+                    ));
+                    if ui.button("⏹ Stop").clicked() {
+                        state.cancel_pickaxe_search();
+                    }
+                }
+            }
+
+            if let Some(ref search) = state.query_search {
+                if !search.is_complete() {
+                    ui.separator();
+                    ui.spinner();
+                    ui.label(format!(
+                        "{} matches, {} commits scanned",
+                        search.matched_count(),
+                        search.scanned_count()
+                    ));
+                    if ui.button("⏹ Stop").clicked() {
+                        state.cancel_query_search();
+                    }
+                }
             }
 
             ui.separator();
@@ -49,9 +221,45 @@ impl SearchPanel {
         });
     }
 
+    fn run_search(&self, state: &mut AppState) {
+        match self.mode.pickaxe_mode() {
+            Some(mode) => {
+                let mut query = PickaxeQuery::new(&self.search_text, mode);
+                query.regex = self.regex;
+                query.ignore_case = self.ignore_case;
+                query.max_matches = Some(state.commit_limit);
+                state.start_pickaxe_search(query);
+            }
+            None if self.mode == SearchMode::TouchingPaths => {
+                state.start_path_search(&self.search_text);
+            }
+            None => self.apply_commit_query(state),
+        }
+    }
+
+    /// Filter the list by the query, or highlight its matches in place
+    fn apply_commit_query(&self, state: &mut AppState) {
+        let query = self.query_text(state);
+        if self.highlight {
+            state.set_highlight_query(&query);
+        } else {
+            state.search_commits(&query);
+        }
+    }
+
+    /// The search box query narrowed by the author field
+    fn query_text(&self, state: &AppState) -> String {
+        let author = state.filter_author.trim().replace('"', "");
+        if author.is_empty() {
+            self.search_text.clone()
+        } else if self.search_text.trim().is_empty() {
+            format!("author:\"{author}\"")
+        } else {
+            format!("({}) author:\"{}\"", self.search_text, author)
+        }
+    }
+
     fn apply_filters(&self, state: &mut AppState) {
-        // This would implement filtering logic
-        // For now, we'll just refresh commits
-        state.start_streaming_commits();
+        self.apply_commit_query(state);
     }
 }
@@ -0,0 +1,326 @@
+use crate::git::{PathChurn, RepositoryStatistics};
+use crate::state::AppState;
+use chrono::{Datelike, NaiveDate};
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Legend, Plot};
+
+/// Authors charted on their own; the rest are stacked as "Others"
+const CHARTED_AUTHORS: usize = 6;
+/// Rows in the author, file and directory tables
+const TABLE_ROWS: usize = 15;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const AUTHOR_COLORS: [egui::Color32; CHARTED_AUTHORS + 1] = [
+    egui::Color32::from_rgb(100, 150, 255),
+    egui::Color32::from_rgb(255, 150, 100),
+    egui::Color32::from_rgb(100, 200, 120),
+    egui::Color32::from_rgb(220, 120, 220),
+    egui::Color32::from_rgb(240, 200, 80),
+    egui::Color32::from_rgb(90, 200, 210),
+    egui::Color32::GRAY,
+];
+
+/// Label and commit count of one histogram bar
+type Bucket = (String, usize);
+
+/// Dashboard of who changed what and when, for the commits of the current view
+pub struct StatisticsPanel {
+    show_directories: bool, // Churn table lists directories instead of files
+}
+
+impl Default for StatisticsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatisticsPanel {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            show_directories: false,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut AppState) {
+        ui.horizontal(|ui| {
+            let view_name = state
+                .view_manager
+                .as_ref()
+                .map_or("All commits", |views| views.get_current_view_name());
+            ui.label(format!(
+                "View: {} ({} commits)",
+                view_name,
+                state.get_filtered_commits().len()
+            ));
+
+            if let Some(ref job) = state.statistics_job {
+                if !job.is_complete() {
+                    ui.spinner();
+                    ui.label(format!(
+                        "{}/{} commits",
+                        job.scanned_count(),
+                        job.total_count().unwrap_or(0)
+                    ));
+                    if ui.button("Cancel").clicked() {
+                        job.cancel();
+                    }
+                    return;
+                }
+            }
+            if ui
+                .button("🔄 Recompute")
+                .on_hover_text("Compute again for the commits now in the view")
+                .clicked()
+            {
+                state.start_statistics();
+            }
+        });
+        ui.separator();
+
+        let Some(ref statistics) = state.statistics else {
+            if !state.is_computing_statistics() {
+                ui.label("No statistics computed yet");
+            }
+            return;
+        };
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.label(format!(
+                "{} commits ({} merges) by {} authors",
+                statistics.commit_count,
+                statistics.merge_count,
+                statistics.authors.len()
+            ));
+
+            ui.heading("Commits per author over time");
+            show_timeline(ui, statistics);
+
+            ui.heading("Authors");
+            show_authors(ui, statistics);
+
+            ui.heading("Activity");
+            ui.columns(2, |columns| {
+                let hours: Vec<Bucket> = statistics
+                    .commits_by_hour
+                    .iter()
+                    .enumerate()
+                    .map(|(hour, count)| (format!("{hour:02}"), *count))
+                    .collect();
+                show_histogram(&mut columns[0], "by_hour", "Commits by hour", &hours);
+                let weekdays: Vec<Bucket> = WEEKDAYS
+                    .iter()
+                    .zip(statistics.commits_by_weekday)
+                    .map(|(day, count)| (day.to_string(), count))
+                    .collect();
+                show_histogram(
+                    &mut columns[1],
+                    "by_weekday",
+                    "Commits by weekday",
+                    &weekdays,
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.heading("Most changed");
+                ui.radio_value(&mut self.show_directories, false, "Files");
+                ui.radio_value(&mut self.show_directories, true, "Directories");
+            });
+            let paths = if self.show_directories {
+                &statistics.directories
+            } else {
+                &statistics.files
+            };
+            show_churn(ui, statistics, paths);
+
+            ui.heading("Bus factor by directory");
+            ui.weak("Fewest authors who made half of the changes in a directory");
+            show_bus_factors(ui, statistics);
+        });
+    }
+}
+
+/// Stacked monthly commit counts of the most active authors
+fn show_timeline(ui: &mut egui::Ui, statistics: &RepositoryStatistics) {
+    let charted: Vec<&str> = statistics
+        .authors
+        .iter()
+        .take(CHARTED_AUTHORS)
+        .map(|author| author.email.as_str())
+        .collect();
+
+    let mut charts: Vec<BarChart> = Vec::new();
+    for series in 0..=charted.len() {
+        let bars = statistics
+            .commits_by_month
+            .iter()
+            .map(|(month, by_author)| {
+                let count: usize = charted.get(series).map_or_else(
+                    || {
+                        by_author
+                            .iter()
+                            .filter(|(email, _)| !charted.contains(&email.as_str()))
+                            .map(|(_, count)| count)
+                            .sum()
+                    },
+                    |email| by_author.get(*email).copied().unwrap_or(0),
+                );
+                Bar::new(month_index(*month), bar_height(count)).width(0.8)
+            })
+            .collect();
+        let name = charted.get(series).map_or_else(
+            || "Others".to_string(),
+            |email| statistics.author_name(email).to_string(),
+        );
+        let previous: Vec<&BarChart> = charts.iter().collect();
+        let chart = BarChart::new(name, bars)
+            .color(AUTHOR_COLORS[series.min(CHARTED_AUTHORS)])
+            .stack_on(&previous);
+        charts.push(chart);
+    }
+
+    Plot::new("commits_over_time")
+        .height(200.0)
+        .legend(Legend::default())
+        .allow_scroll(false)
+        .x_axis_formatter(|mark, _| month_label(mark.value))
+        .show(ui, |plot_ui| {
+            for chart in charts {
+                plot_ui.bar_chart(chart);
+            }
+        });
+}
+
+fn show_authors(ui: &mut egui::Ui, statistics: &RepositoryStatistics) {
+    egui::Grid::new("statistics_authors")
+        .striped(true)
+        .num_columns(4)
+        .show(ui, |ui| {
+            ui.strong("Author");
+            ui.strong("Commits");
+            ui.strong("Added");
+            ui.strong("Removed");
+            ui.end_row();
+
+            for author in statistics.authors.iter().take(TABLE_ROWS) {
+                ui.label(&author.name).on_hover_text(&author.email);
+                ui.label(author.commits.to_string());
+                ui.colored_label(
+                    egui::Color32::from_rgb(100, 200, 100),
+                    format!("+{}", author.insertions),
+                );
+                ui.colored_label(
+                    egui::Color32::from_rgb(220, 100, 100),
+                    format!("-{}", author.deletions),
+                );
+                ui.end_row();
+            }
+        });
+}
+
+fn show_histogram(ui: &mut egui::Ui, id: &str, title: &str, buckets: &[Bucket]) {
+    ui.label(title);
+    let bars = (0..)
+        .zip(buckets)
+        .map(|(index, (label, count))| Bar::new(f64::from(index), bar_height(*count)).name(label))
+        .collect();
+    let labels: Vec<String> = buckets.iter().map(|(label, _)| label.clone()).collect();
+
+    Plot::new(id)
+        .height(150.0)
+        .allow_scroll(false)
+        .allow_zoom(false)
+        .allow_drag(false)
+        .x_axis_formatter(move |mark, _| {
+            (0..)
+                .zip(&labels)
+                .find(|(index, _)| (mark.value - f64::from(*index)).abs() <= f64::EPSILON)
+                .map(|(_, label)| label.clone())
+                .unwrap_or_default()
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(title, bars).color(AUTHOR_COLORS[0]));
+        });
+}
+
+fn show_churn(ui: &mut egui::Ui, statistics: &RepositoryStatistics, paths: &[PathChurn]) {
+    egui::Grid::new("statistics_churn")
+        .striped(true)
+        .num_columns(5)
+        .show(ui, |ui| {
+            ui.strong("Path");
+            ui.strong("Commits");
+            ui.strong("Added");
+            ui.strong("Removed");
+            ui.strong("Top author");
+            ui.end_row();
+
+            for path in paths.iter().take(TABLE_ROWS) {
+                ui.monospace(&path.path);
+                ui.label(path.commits.to_string());
+                ui.label(format!("+{}", path.insertions));
+                ui.label(format!("-{}", path.deletions));
+                ui.label(top_author(statistics, path));
+                ui.end_row();
+            }
+        });
+}
+
+fn show_bus_factors(ui: &mut egui::Ui, statistics: &RepositoryStatistics) {
+    egui::Grid::new("statistics_bus_factor")
+        .striped(true)
+        .num_columns(3)
+        .show(ui, |ui| {
+            ui.strong("Directory");
+            ui.strong("Bus factor");
+            ui.strong("Top author");
+            ui.end_row();
+
+            for directory in statistics.directories.iter().take(TABLE_ROWS) {
+                let bus_factor = directory.bus_factor();
+                ui.monospace(&directory.path);
+                if bus_factor <= 1 {
+                    ui.colored_label(egui::Color32::from_rgb(220, 100, 100), "1")
+                        .on_hover_text("One person made most of these changes");
+                } else {
+                    ui.label(bus_factor.to_string());
+                }
+                ui.label(top_author(statistics, directory));
+                ui.end_row();
+            }
+        });
+}
+
+/// Author with the most changed lines and their share, e.g. "Alice (72%)"
+fn top_author(statistics: &RepositoryStatistics, path: &PathChurn) -> String {
+    let Some((email, lines)) = path.lines_by_author.iter().max_by_key(|(_, lines)| **lines) else {
+        return String::new();
+    };
+    let share = if path.churn() == 0 {
+        100
+    } else {
+        lines * 100 / path.churn()
+    };
+    format!("{} ({}%)", statistics.author_name(email), share)
+}
+
+/// Commit count as a bar height, saturating at counts no view reaches
+fn bar_height(count: usize) -> f64 {
+    f64::from(u32::try_from(count).unwrap_or(u32::MAX))
+}
+
+/// Months since year 0, so consecutive months are one apart on the axis
+fn month_index(month: NaiveDate) -> f64 {
+    f64::from(month.year()).mul_add(12.0, f64::from(month.month0()))
+}
+
+fn month_label(value: f64) -> String {
+    let index = value.round();
+    if (value - index).abs() > f64::EPSILON {
+        return String::new();
+    }
+    format!(
+        "{}-{:02}",
+        index.div_euclid(12.0),
+        index.rem_euclid(12.0) + 1.0
+    )
+}
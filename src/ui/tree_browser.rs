@@ -0,0 +1,261 @@
+use crate::models::{SubmoduleChange, TreeEntry};
+use crate::state::AppState;
+use eframe::egui;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+const CHANGED_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 160, 60);
+
+/// What the file list shows, like gitk's "Patch" and "Tree" buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileListMode {
+    Patch, // Files the selected commit changed; clicking one shows its diff
+    Tree,  // The commit's whole tree; clicking a file shows its content
+}
+
+/// Entries of each listed directory, by its path
+type DirectoryListings = HashMap<String, Vec<TreeEntry>>;
+
+/// Path and screen position of a requested context menu
+pub type ContextMenuRequest = (String, egui::Pos2);
+
+/// What a click in the list asked for, applied once drawing is done
+enum FileAction {
+    ShowDiff(String),
+    ViewFile(String),
+    OpenSubmodule(SubmoduleChange),
+    ContextMenu(String, egui::Pos2),
+}
+
+/// Files of the selected commit, as a patch file list or a browsable tree
+pub struct TreeBrowser {
+    mode: FileListMode,
+    commit_id: Option<String>,      // Commit the listings below belong to
+    directories: DirectoryListings, // Listed on first expansion
+    expanded: HashSet<String>,      // Kept across commits, like gitk
+    changed: BTreeSet<String>,      // Paths the commit changed
+    changed_directories: HashSet<String>, // Directories holding any of them
+    error: Option<String>,
+}
+
+impl Default for TreeBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TreeBrowser {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            mode: FileListMode::Patch,
+            commit_id: None,
+            directories: HashMap::new(),
+            expanded: HashSet::new(),
+            changed: BTreeSet::new(),
+            changed_directories: HashSet::new(),
+            error: None,
+        }
+    }
+
+    /// Returns a right-clicked path and where to open its context menu
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut AppState) -> Option<ContextMenuRequest> {
+        ui.horizontal(|ui| {
+            ui.heading("Files");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.radio_value(&mut self.mode, FileListMode::Tree, "Tree");
+                if ui
+                    .radio_value(&mut self.mode, FileListMode::Patch, "Patch")
+                    .changed()
+                {
+                    state.close_tree_file();
+                }
+            });
+        });
+        ui.separator();
+
+        let Some(commit) = state.get_selected_commit().cloned() else {
+            ui.label("Select a commit to view files");
+            return None;
+        };
+        if self.commit_id.as_deref() != Some(commit.id.as_str()) {
+            self.load(state, &commit.id);
+        }
+
+        ui.label(format!("Commit: {}", &commit.short_id));
+        if let Some(ref error) = self.error {
+            ui.colored_label(egui::Color32::RED, error);
+            return None;
+        }
+        ui.separator();
+
+        let mut action = None;
+        egui::ScrollArea::vertical().show(ui, |ui| match self.mode {
+            FileListMode::Patch => self.show_changed_files(ui, state, &mut action),
+            FileListMode::Tree => self.show_directory(ui, state, "", &mut action),
+        });
+
+        match action? {
+            FileAction::ShowDiff(path) => {
+                state.close_tree_file();
+                state.selected_files = vec![path];
+            }
+            FileAction::ViewFile(path) => {
+                state.view_tree_file(&commit.id, &path);
+                state.selected_files = vec![path];
+            }
+            FileAction::OpenSubmodule(change) => state.open_submodule(&change),
+            FileAction::ContextMenu(path, pos) => return Some((path, pos)),
+        }
+        None
+    }
+
+    fn load(&mut self, state: &AppState, commit_id: &str) {
+        self.commit_id = Some(commit_id.to_string());
+        self.directories.clear();
+        self.changed.clear();
+        self.changed_directories.clear();
+        self.error = None;
+
+        let Some(ref repo) = state.repository else {
+            return;
+        };
+        match repo.get_commit_changed_paths(commit_id) {
+            Ok(paths) => {
+                for path in paths {
+                    for (index, _) in path.match_indices('/') {
+                        self.changed_directories.insert(path[..index].to_string());
+                    }
+                    self.changed.insert(path);
+                }
+            }
+            Err(e) => self.error = Some(format!("Error loading files: {e}")),
+        }
+    }
+
+    fn show_changed_files(
+        &self,
+        ui: &mut egui::Ui,
+        state: &AppState,
+        action: &mut Option<FileAction>,
+    ) {
+        if self.changed.is_empty() {
+            ui.label("No files changed");
+        }
+
+        for path in &self.changed {
+            let response =
+                ui.selectable_label(state.selected_files.contains(path), format!("📄 {path}"));
+            if response.clicked() {
+                *action = Some(FileAction::ShowDiff(path.clone()));
+            }
+            if response.secondary_clicked() {
+                *action = Some(FileAction::ContextMenu(
+                    path.clone(),
+                    response.rect.left_bottom(),
+                ));
+            }
+        }
+    }
+
+    /// List one directory, recursing into the expanded ones
+    fn show_directory(
+        &mut self,
+        ui: &mut egui::Ui,
+        state: &AppState,
+        directory: &str,
+        action: &mut Option<FileAction>,
+    ) {
+        if !self.directories.contains_key(directory) {
+            let entries = match (&state.repository, &self.commit_id) {
+                (Some(repo), Some(commit_id)) => repo
+                    .get_tree_directory(commit_id, directory)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to list {}: {}", directory, e);
+                        Vec::new()
+                    }),
+                _ => Vec::new(),
+            };
+            self.directories.insert(directory.to_string(), entries);
+        }
+
+        let entries = self.directories[directory].clone();
+        for entry in &entries {
+            if entry.is_tree {
+                let is_expanded = self.expanded.contains(&entry.path);
+                let icon = if is_expanded { "📂" } else { "📁" };
+                let text = self.entry_text(entry, format!("{} {}", icon, entry.name));
+                if ui.selectable_label(false, text).clicked() {
+                    if is_expanded {
+                        self.expanded.remove(&entry.path);
+                    } else {
+                        self.expanded.insert(entry.path.clone());
+                    }
+                }
+
+                if is_expanded {
+                    ui.indent(format!("tree_{}", entry.path), |ui| {
+                        self.show_directory(ui, state, &entry.path, action);
+                    });
+                }
+            } else {
+                self.show_file(ui, state, entry, action);
+            }
+        }
+    }
+
+    fn show_file(
+        &self,
+        ui: &mut egui::Ui,
+        state: &AppState,
+        entry: &TreeEntry,
+        action: &mut Option<FileAction>,
+    ) {
+        let icon = if entry.is_submodule() { "📦" } else { "📄" };
+        let is_viewed = state
+            .tree_file
+            .as_ref()
+            .is_some_and(|file| file.path == entry.path);
+        let mut response = ui.selectable_label(
+            is_viewed,
+            self.entry_text(entry, format!("{} {}", icon, entry.name)),
+        );
+
+        if entry.is_submodule() {
+            response = response.on_hover_text(format!(
+                "Submodule at {} (double-click to open)",
+                &entry.id[..7]
+            ));
+            if response.double_clicked() {
+                *action = Some(FileAction::OpenSubmodule(SubmoduleChange {
+                    path: entry.path.clone(),
+                    old_commit: None,
+                    new_commit: Some(entry.id.clone()),
+                }));
+            }
+        } else if response.clicked() {
+            *action = Some(FileAction::ViewFile(entry.path.clone()));
+        }
+
+        if response.secondary_clicked() {
+            *action = Some(FileAction::ContextMenu(
+                entry.path.clone(),
+                response.rect.left_bottom(),
+            ));
+        }
+    }
+
+    /// An entry's label, marked when the commit changed it or, for a
+    /// directory, anything below it
+    fn entry_text(&self, entry: &TreeEntry, label: String) -> egui::RichText {
+        let changed = if entry.is_tree {
+            self.changed_directories.contains(&entry.path)
+        } else {
+            self.changed.contains(&entry.path)
+        };
+        if changed {
+            egui::RichText::new(format!("{label} ●")).color(CHANGED_COLOR)
+        } else {
+            egui::RichText::new(label)
+        }
+    }
+}
@@ -326,7 +326,14 @@ impl CreateViewDialog {
                             }
                         });
 
-                        ui.weak("Use formats like '2023-01-01', '1.week.ago', 'yesterday'");
+                        match self.filter.date_range() {
+                            Ok(_) => {
+                                ui.weak("Use formats like '2023-01-01', '1.week.ago', 'yesterday'");
+                            }
+                            Err(e) => {
+                                ui.colored_label(egui::Color32::RED, format!("⚠ {e}"));
+                            }
+                        }
                     });
 
                     ui.collapsing("Other Options", |ui| {
@@ -365,11 +372,29 @@ impl CreateViewDialog {
                         ui.checkbox(&mut self.filter.include_merges, "Include merge commits");
                     });
 
+                    ui.collapsing("Query", |ui| {
+                        let mut query = self.filter.query.clone().unwrap_or_default();
+                        if ui.text_edit_singleline(&mut query).changed() {
+                            self.filter.query = if query.is_empty() { None } else { Some(query) };
+                        }
+
+                        match self.filter.parse_query() {
+                            Ok(_) => {
+                                ui.weak("e.g. author:alice after:2026-01-01 path:src -merge");
+                            }
+                            Err(e) => {
+                                ui.colored_label(egui::Color32::RED, format!("⚠ {e}"));
+                            }
+                        }
+                    });
+
                     ui.separator();
 
                     // Buttons
                     ui.horizontal(|ui| {
-                        let can_create = !self.filter.name.is_empty();
+                        let can_create = !self.filter.name.is_empty()
+                            && self.filter.parse_query().is_ok()
+                            && self.filter.date_range().is_ok();
 
                         if ui
                             .add_enabled(can_create, egui::Button::new("Create"))